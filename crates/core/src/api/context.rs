@@ -5,7 +5,11 @@ use crate::{
 	sql::{Bytesize, Duration},
 };
 
-use super::middleware::{invoke::InvokeMiddleware, CollectedMiddleware};
+use super::middleware::{
+	api::{auth::AuthRequirement, Cors, RateLimit},
+	invoke::InvokeMiddleware,
+	CollectedMiddleware,
+};
 
 #[derive(Default, Debug)]
 pub struct InvocationContext {
@@ -14,6 +18,9 @@ pub struct InvocationContext {
 	pub response_body_raw: bool,
 	pub response_headers: Option<HeaderMap>,
 	pub timeout: Option<Duration>,
+	pub cors: Option<Cors>,
+	pub rate_limit: Option<RateLimit>,
+	pub auth: Option<AuthRequirement>,
 }
 
 impl InvocationContext {
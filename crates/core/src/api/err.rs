@@ -21,6 +21,9 @@ pub enum ApiError {
 	#[error("Invalid API response: {0}")]
 	InvalidApiResponse(String),
 
+	#[error("Invalid result from middleware '{0}': {1}")]
+	InvalidMiddlewareResult(String, String),
+
 	#[error("Invalid Accept or Content-Type header")]
 	InvalidFormat,
 
@@ -45,6 +48,7 @@ impl ApiError {
 			Self::BodyDecodeFailure => StatusCode::BAD_REQUEST,
 			Self::BodyEncodeFailure => StatusCode::INTERNAL_SERVER_ERROR,
 			Self::InvalidApiResponse(_) => StatusCode::INTERNAL_SERVER_ERROR,
+			Self::InvalidMiddlewareResult(_, _) => StatusCode::INTERNAL_SERVER_ERROR,
 			Self::InvalidFormat => StatusCode::BAD_REQUEST,
 			Self::MissingFormat => StatusCode::BAD_REQUEST,
 			Self::Unreachable(_) => StatusCode::INTERNAL_SERVER_ERROR,
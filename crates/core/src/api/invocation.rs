@@ -1,13 +1,17 @@
 use std::{collections::BTreeMap, sync::Arc};
 
-use http::HeaderMap;
+use http::{header::RETRY_AFTER, HeaderMap, StatusCode};
 use reblessive::{tree::Stk, TreeStack};
 
 use super::{
 	body::ApiBody,
 	context::InvocationContext,
 	method::Method,
-	middleware::CollectMiddleware,
+	middleware::{
+		api::{auth::AuthRequirement, RateLimit, RateLimitKey},
+		custom::{self, MiddlewareOutcome},
+		CollectMiddleware,
+	},
 	response::{ApiResponse, ResponseInstruction},
 };
 use crate::{
@@ -17,8 +21,8 @@ use crate::{
 	err::Error,
 	kvs::{Datastore, Transaction},
 	sql::{
-		statements::define::{config::api::ApiConfig, ApiDefinition},
-		FlowResultExt as _, Object, Value,
+		statements::define::{config::api::ApiConfig, ApiDefinition, DefineConfigStatement},
+		FlowResultExt as _, Object, Part, Value,
 	},
 };
 
@@ -57,12 +61,69 @@ impl ApiInvocation {
 
 		let mut ctx = ds.setup_ctx()?;
 		ctx.set_transaction(tx);
+		sess.context(&mut ctx);
 		let ctx = &ctx.freeze();
 
 		let mut stack = TreeStack::new();
 		stack.enter(|stk| self.invoke_with_context(stk, ctx, &opt, api, body)).finish().await
 	}
 
+	/// Responds to a CORS preflight request, where the method of this invocation is the
+	/// method which was requested. Returns `None` if the endpoint does not allow CORS.
+	pub async fn preflight_with_transaction(
+		self,
+		tx: Arc<Transaction>,
+		ds: Arc<Datastore>,
+		sess: &Session,
+		api: &ApiDefinition,
+	) -> Result<Option<ApiResponse>, Error> {
+		let opt = ds.setup_options(sess);
+
+		let mut ctx = ds.setup_ctx()?;
+		ctx.set_transaction(tx);
+		let ctx = ctx.freeze();
+
+		let Some((_, action_config)) = self.find_action(api) else {
+			return Ok(None);
+		};
+
+		let global = ctx.tx().get_db_optional_config(opt.ns()?, opt.db()?, "api").await?;
+		let configs = Self::configs(&global, api, action_config)?;
+
+		let middleware: Vec<&RequestMiddleware> =
+			configs.into_iter().filter_map(|v| v.middleware.as_ref()).collect();
+		let (builtin, _) = middleware.collect()?;
+
+		let mut inv_ctx = InvocationContext::default();
+		inv_ctx.apply_middleware(builtin)?;
+
+		inv_ctx.cors.map(|cors| cors.preflight(&self.headers)).transpose()
+	}
+
+	/// Finds the action which handles this invocation, along with its configuration
+	fn find_action<'a>(
+		&self,
+		api: &'a ApiDefinition,
+	) -> Option<(&'a Value, &'a Option<ApiConfig>)> {
+		match api.actions.iter().find(|x| x.methods.contains(&self.method)) {
+			Some(v) => Some((&v.action, &v.config)),
+			None => api.fallback.as_ref().map(|v| (v, &None)),
+		}
+	}
+
+	/// Collects the configuration for an action, from the least to the most specific
+	fn configs<'a>(
+		global: &'a Option<Arc<DefineConfigStatement>>,
+		api: &'a ApiDefinition,
+		action_config: &'a Option<ApiConfig>,
+	) -> Result<Vec<&'a ApiConfig>, Error> {
+		let mut configs: Vec<&ApiConfig> = Vec::new();
+		configs.extend(global.as_ref().map(|v| v.inner.try_into_api()).transpose()?);
+		configs.extend(api.config.as_ref());
+		configs.extend(action_config);
+		Ok(configs)
+	}
+
 	// The `invoke` method accepting a parameter like `Option<&mut Stk>`
 	// causes issues with axum, hence the separation
 	pub async fn invoke_with_context(
@@ -73,24 +134,16 @@ impl ApiInvocation {
 		api: &ApiDefinition,
 		body: ApiBody,
 	) -> Result<Option<(ApiResponse, ResponseInstruction)>, Error> {
-		let (action, action_config) =
-			match api.actions.iter().find(|x| x.methods.contains(&self.method)) {
-				Some(v) => (&v.action, &v.config),
-				None => match &api.fallback {
-					Some(v) => (v, &None),
-					None => return Ok(None),
-				},
-			};
+		let Some((action, action_config)) = self.find_action(api) else {
+			return Ok(None);
+		};
 
-		let mut configs: Vec<&ApiConfig> = Vec::new();
 		let global = ctx.tx().get_db_optional_config(opt.ns()?, opt.db()?, "api").await?;
-		configs.extend(global.as_ref().map(|v| v.inner.try_into_api()).transpose()?);
-		configs.extend(api.config.as_ref());
-		configs.extend(action_config);
+		let configs = Self::configs(&global, api, action_config)?;
 
 		let middleware: Vec<&RequestMiddleware> =
 			configs.into_iter().filter_map(|v| v.middleware.as_ref()).collect();
-		let builtin = middleware.collect()?;
+		let (builtin, custom) = middleware.collect()?;
 
 		let mut inv_ctx = InvocationContext::default();
 		inv_ctx.apply_middleware(builtin)?;
@@ -104,8 +157,20 @@ impl ApiInvocation {
 			ResponseInstruction::for_format(&self)?
 		};
 
+		// Check the authentication and rate limits before processing the request
+		if let Some(mut res) = self.guard(ctx, opt, api, &inv_ctx)? {
+			if let Some(cors) = &inv_ctx.cors {
+				cors.apply(&self.headers, &mut res.headers)?;
+			}
+
+			return Ok(Some((res, res_instruction)));
+		}
+
 		let body = body.process(&inv_ctx, &self).await?;
 
+		// Keep the request headers, which are needed for the CORS response headers
+		let req_headers = inv_ctx.cors.as_ref().map(|_| self.headers.clone());
+
 		// Edit the options
 		let opt = opt.new_with_perms(false);
 
@@ -113,8 +178,8 @@ impl ApiInvocation {
 		let mut ctx = MutableContext::new_isolated(ctx);
 
 		// Set the request variable
-		let vars = self.vars(body)?;
-		ctx.add_value("request", vars.into());
+		let mut vars = self.vars(body)?;
+		ctx.add_value("request", vars.clone().into());
 
 		// Possibly set the timeout
 		if let Some(timeout) = inv_ctx.timeout {
@@ -122,11 +187,31 @@ impl ApiInvocation {
 		}
 
 		// Freeze the context
-		let ctx = ctx.freeze();
+		let mut ctx = ctx.freeze();
 
-		// Compute the action
+		// Run the custom middleware, which can modify the request or respond early
+		let mut response = None;
+		for entry in custom {
+			match custom::invoke(stk, &ctx, &opt, entry, vars).await? {
+				MiddlewareOutcome::Continue(request) => {
+					// Expose the modified request to the next middleware and the action
+					let mut inner = MutableContext::new(&ctx);
+					inner.add_value("request", request.clone().into());
+					ctx = inner.freeze();
+					vars = request;
+				}
+				MiddlewareOutcome::Respond(res) => {
+					response = Some(res);
+					break;
+				}
+			}
+		}
 
-		let res = action.compute(stk, &ctx, &opt, None).await.catch_return()?;
+		// Compute the action, unless a middleware responded already
+		let res = match response {
+			Some(res) => res,
+			None => action.compute(stk, &ctx, &opt, None).await.catch_return()?,
+		};
 
 		let mut res = ApiResponse::try_from(res)?;
 		if let Some(headers) = inv_ctx.response_headers {
@@ -135,6 +220,80 @@ impl ApiInvocation {
 			res.headers = headers;
 		}
 
+		if let (Some(cors), Some(req_headers)) = (&inv_ctx.cors, &req_headers) {
+			cors.apply(req_headers, &mut res.headers)?;
+		}
+
 		Ok(Some((res, res_instruction)))
 	}
+
+	/// Enforces the authentication and rate limiting middleware, returning
+	/// the response to send instead when the request is not allowed
+	fn guard(
+		&self,
+		ctx: &Context,
+		opt: &Options,
+		api: &ApiDefinition,
+		inv_ctx: &InvocationContext,
+	) -> Result<Option<ApiResponse>, Error> {
+		if let Some(AuthRequirement {
+			access,
+		}) = &inv_ctx.auth
+		{
+			// The request must be authenticated
+			if opt.auth.is_anon() {
+				return Ok(Some(ApiResponse::status(StatusCode::UNAUTHORIZED)));
+			}
+			// The request must use one of the specified access methods
+			if !access.is_empty() {
+				let allowed = match ctx.value("access") {
+					Some(Value::Strand(ac)) => access.iter().any(|x| x == ac.as_str()),
+					_ => false,
+				};
+
+				if !allowed {
+					return Ok(Some(ApiResponse::status(StatusCode::FORBIDDEN)));
+				}
+			}
+		}
+
+		if let Some(RateLimit {
+			limit,
+			per,
+			by,
+		}) = &inv_ctx.rate_limit
+		{
+			let Some(limiter) = ctx.get_rate_limiter() else {
+				return Ok(None);
+			};
+			// Identify the client by its IP address, or by the authenticated subject
+			let ip = match ctx.value("session").map(|v| v.pick(&[Part::from("ip")])) {
+				Some(Value::Strand(ip)) if !ip.is_empty() => Some(ip.0),
+				_ => None,
+			};
+			let subject = (!opt.auth.is_anon())
+				.then(|| format!("auth:{}:{}", opt.auth.level(), opt.auth.id()));
+			let client = match (by, ip) {
+				(RateLimitKey::Auth, _) if subject.is_some() => subject,
+				(_, Some(ip)) => Some(format!("ip:{ip}")),
+				// Without an IP address, such as for an invocation from a query,
+				// the client can only be identified by the authenticated subject
+				(_, None) => subject,
+			};
+			// Anonymous clients without an IP address can't be told apart, so they are not limited
+			if let Some(client) = client {
+				// Each endpoint is limited separately
+				let key = format!("{}/{}{}/{client}", opt.ns()?, opt.db()?, api.path);
+				if let Err(retry) = limiter.check(key, *limit, **per) {
+					let mut res = ApiResponse::status(StatusCode::TOO_MANY_REQUESTS);
+					// Round up, so that the client does not retry too early
+					let secs = retry.as_secs() + u64::from(retry.subsec_nanos() > 0);
+					res.headers.insert(RETRY_AFTER, secs.into());
+					return Ok(Some(res));
+				}
+			}
+		}
+
+		Ok(None)
+	}
 }
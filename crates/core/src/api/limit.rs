@@ -0,0 +1,118 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+use dashmap::DashMap;
+use trice::Instant;
+
+/// The number of tracked clients above which fully refilled buckets are discarded
+const PRUNE_THRESHOLD: usize = 10_000;
+
+/// The token bucket of a single rate limited client
+struct Bucket {
+	/// The number of requests which can currently be made
+	tokens: f64,
+	/// When the number of tokens was last calculated
+	updated: Instant,
+	/// When the bucket will be full again, if no further requests are made
+	full_at: Instant,
+}
+
+/// Tracks the request rate of clients for API endpoints on this node.
+///
+/// The buckets are held in memory, and are shared by every request to the
+/// datastore on this node, but not with the other nodes in a cluster. Each
+/// node therefore enforces the limit separately, and the state is lost when
+/// the node restarts.
+#[derive(Default)]
+pub struct NodeRateLimiter {
+	buckets: DashMap<String, Bucket>,
+	/// The number of checks since the buckets were last pruned
+	checks: AtomicUsize,
+}
+
+impl NodeRateLimiter {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Takes a token from the bucket for the given key, allowing at most `limit` requests
+	/// within every `per` period. When the limit has been reached, this returns the time
+	/// after which the next request will be allowed.
+	pub fn check(&self, key: String, limit: u64, per: Duration) -> Result<(), Duration> {
+		let now = Instant::now();
+		let limit = limit as f64;
+		// The number of tokens which are added back every second
+		let rate = limit / per.as_secs_f64().max(f64::EPSILON);
+		// Discard the buckets of clients which have not made requests recently,
+		// at most once for every tracked client, so pruning is constant amortised
+		let checks = self.checks.fetch_add(1, Ordering::Relaxed) + 1;
+		if checks >= self.buckets.len().max(PRUNE_THRESHOLD) {
+			self.checks.store(0, Ordering::Relaxed);
+			self.buckets.retain(|_, v| v.full_at > now);
+		}
+		// Fetch or create the bucket for this client
+		let mut bucket = self.buckets.entry(key).or_insert_with(|| Bucket {
+			tokens: limit,
+			updated: now,
+			full_at: now,
+		});
+		// Refill the bucket for the time which has elapsed
+		let elapsed = now.duration_since(bucket.updated).as_secs_f64();
+		bucket.tokens = (bucket.tokens + elapsed * rate).min(limit);
+		bucket.updated = now;
+		// Check if a request can be made
+		if bucket.tokens < 1.0 {
+			return Err(Duration::from_secs_f64((1.0 - bucket.tokens) / rate));
+		}
+		bucket.tokens -= 1.0;
+		bucket.full_at = now + Duration::from_secs_f64((limit - bucket.tokens) / rate);
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn limits_requests_per_key() {
+		let limiter = NodeRateLimiter::new();
+		let per = Duration::from_secs(60);
+
+		assert!(limiter.check("a".into(), 2, per).is_ok());
+		assert!(limiter.check("a".into(), 2, per).is_ok());
+
+		let retry = limiter.check("a".into(), 2, per).unwrap_err();
+		assert!(retry > Duration::from_secs(25) && retry <= Duration::from_secs(30));
+
+		// Other clients have their own bucket
+		assert!(limiter.check("b".into(), 2, per).is_ok());
+	}
+
+	#[test]
+	fn prunes_full_buckets() {
+		let limiter = NodeRateLimiter::new();
+		let per = Duration::from_millis(10);
+		for i in 1..PRUNE_THRESHOLD {
+			assert!(limiter.check(i.to_string(), 1, per).is_ok());
+		}
+		// Buckets are kept until enough checks have been made
+		std::thread::sleep(Duration::from_millis(20));
+		assert_eq!(limiter.buckets.len(), PRUNE_THRESHOLD - 1);
+		// The buckets which have refilled are then discarded
+		assert!(limiter.check("a".into(), 1, per).is_ok());
+		assert_eq!(limiter.buckets.len(), 1);
+	}
+
+	#[test]
+	fn refills_over_time() {
+		let limiter = NodeRateLimiter::new();
+		let per = Duration::from_millis(50);
+
+		assert!(limiter.check("a".into(), 1, per).is_ok());
+		assert!(limiter.check("a".into(), 1, per).is_err());
+
+		std::thread::sleep(Duration::from_millis(60));
+		assert!(limiter.check("a".into(), 1, per).is_ok());
+	}
+}
@@ -0,0 +1,18 @@
+use crate::{api::context::InvocationContext, err::Error, fnc::args::Rest};
+
+/// The authentication requirements for an API endpoint
+#[derive(Debug, Default)]
+pub struct AuthRequirement {
+	/// The access methods which are allowed, or any access method when empty
+	pub access: Vec<String>,
+}
+
+pub fn require(
+	context: &mut InvocationContext,
+	(Rest(access),): (Rest<String>,),
+) -> Result<(), Error> {
+	context.auth = Some(AuthRequirement {
+		access,
+	});
+	Ok(())
+}
@@ -0,0 +1,164 @@
+use http::{
+	header::{
+		ACCESS_CONTROL_ALLOW_CREDENTIALS, ACCESS_CONTROL_ALLOW_HEADERS,
+		ACCESS_CONTROL_ALLOW_METHODS, ACCESS_CONTROL_ALLOW_ORIGIN, ACCESS_CONTROL_EXPOSE_HEADERS,
+		ACCESS_CONTROL_MAX_AGE, ACCESS_CONTROL_REQUEST_HEADERS, ACCESS_CONTROL_REQUEST_METHOD,
+		ORIGIN, VARY,
+	},
+	HeaderMap, HeaderValue, StatusCode,
+};
+
+use crate::{
+	api::{context::InvocationContext, response::ApiResponse},
+	err::Error,
+	fnc::args::Optional,
+	sql::{Duration, Object, Value},
+};
+
+/// The Cross-Origin Resource Sharing configuration for an API endpoint
+#[derive(Debug, Default)]
+pub struct Cors {
+	/// The allowed origins, or `None` if any origin is allowed
+	pub origins: Option<Vec<String>>,
+	/// The allowed methods, or `None` to allow the requested method
+	pub methods: Option<Vec<String>>,
+	/// The allowed request headers, or `None` to allow the requested headers
+	pub headers: Option<Vec<String>>,
+	/// The response headers which are exposed to the client
+	pub expose: Vec<String>,
+	/// Whether credentials are allowed to be sent with requests
+	pub credentials: bool,
+	/// How long the result of a preflight request can be cached for
+	pub max_age: Option<Duration>,
+}
+
+impl Cors {
+	/// Returns the value of the `Access-Control-Allow-Origin` header, if the origin is allowed
+	fn allow_origin(&self, req: &HeaderMap) -> Option<HeaderValue> {
+		let origin = req.get(ORIGIN)?;
+		match &self.origins {
+			// Credentials can not be used with a wildcard origin, so the origin is reflected
+			None if self.credentials => Some(origin.clone()),
+			None => Some(HeaderValue::from_static("*")),
+			Some(origins) => {
+				let value = origin.to_str().ok()?;
+				origins.iter().any(|x| x == value).then(|| origin.clone())
+			}
+		}
+	}
+
+	/// Adds the headers for an actual cross-origin request to a response
+	pub fn apply(&self, req: &HeaderMap, res: &mut HeaderMap) -> Result<(), Error> {
+		// The response varies based on the origin, unless any origin is allowed
+		if self.origins.is_some() || self.credentials {
+			res.append(VARY, HeaderValue::from_static("origin"));
+		}
+
+		let Some(origin) = self.allow_origin(req) else {
+			return Ok(());
+		};
+
+		res.insert(ACCESS_CONTROL_ALLOW_ORIGIN, origin);
+
+		if self.credentials {
+			res.insert(ACCESS_CONTROL_ALLOW_CREDENTIALS, HeaderValue::from_static("true"));
+		}
+
+		if !self.expose.is_empty() {
+			res.insert(ACCESS_CONTROL_EXPOSE_HEADERS, self.expose.join(", ").parse()?);
+		}
+
+		Ok(())
+	}
+
+	/// Creates the response to a preflight request
+	pub fn preflight(&self, req: &HeaderMap) -> Result<ApiResponse, Error> {
+		let mut headers = HeaderMap::new();
+
+		self.apply(req, &mut headers)?;
+
+		// Only describe the allowed request if the origin is allowed
+		if headers.contains_key(ACCESS_CONTROL_ALLOW_ORIGIN) {
+			let methods = match &self.methods {
+				Some(v) => Some(v.join(", ").parse()?),
+				None => req.get(ACCESS_CONTROL_REQUEST_METHOD).cloned(),
+			};
+
+			if let Some(methods) = methods {
+				headers.insert(ACCESS_CONTROL_ALLOW_METHODS, methods);
+			}
+
+			let allowed = match &self.headers {
+				Some(v) => Some(v.join(", ").parse()?),
+				None => req.get(ACCESS_CONTROL_REQUEST_HEADERS).cloned(),
+			};
+
+			if let Some(allowed) = allowed {
+				headers.insert(ACCESS_CONTROL_ALLOW_HEADERS, allowed);
+			}
+
+			if let Some(max_age) = &self.max_age {
+				headers.insert(ACCESS_CONTROL_MAX_AGE, max_age.as_secs().into());
+			}
+		}
+
+		Ok(ApiResponse {
+			raw: None,
+			status: StatusCode::NO_CONTENT,
+			body: None,
+			headers,
+		})
+	}
+}
+
+/// Parses a list of strings, where `"*"` allows any value
+fn parse_list(value: Value) -> Result<Option<Vec<String>>, Error> {
+	match value {
+		Value::Strand(v) if v.as_str() == "*" => Ok(None),
+		v => Ok(Some(v.coerce_to::<Vec<String>>()?)),
+	}
+}
+
+pub fn cors(
+	context: &mut InvocationContext,
+	(Optional(opts),): (Optional<Object>,),
+) -> Result<(), Error> {
+	let mut cors = Cors::default();
+
+	if let Some(mut opts) = opts {
+		if let Some(v) = opts.remove("origins") {
+			cors.origins = parse_list(v)?;
+		}
+
+		if let Some(v) = opts.remove("methods") {
+			cors.methods =
+				parse_list(v)?.map(|x| x.into_iter().map(|m| m.to_uppercase()).collect());
+		}
+
+		if let Some(v) = opts.remove("headers") {
+			cors.headers = parse_list(v)?;
+		}
+
+		if let Some(v) = opts.remove("expose") {
+			cors.expose = v.coerce_to::<Vec<String>>()?;
+		}
+
+		if let Some(v) = opts.remove("credentials") {
+			cors.credentials = v.coerce_to::<bool>()?;
+		}
+
+		if let Some(v) = opts.remove("max_age") {
+			cors.max_age = Some(v.coerce_to::<Duration>()?);
+		}
+
+		if let Some(k) = opts.keys().next() {
+			return Err(Error::InvalidArguments {
+				name: "api::cors".to_string(),
+				message: format!("Unexpected option '{k}'"),
+			});
+		}
+	}
+
+	context.cors = Some(cors);
+	Ok(())
+}
@@ -1,6 +1,12 @@
+pub mod auth;
+mod cors;
+mod rate_limit;
 pub mod req;
 pub mod res;
 
+pub use cors::{cors, Cors};
+pub use rate_limit::{rate_limit, RateLimit, RateLimitKey};
+
 use crate::{api::context::InvocationContext, err::Error, sql::Duration};
 
 pub fn timeout(context: &mut InvocationContext, (timeout,): (Duration,)) -> Result<(), Error> {
@@ -0,0 +1,66 @@
+use crate::{
+	api::context::InvocationContext,
+	err::Error,
+	fnc::args::Optional,
+	sql::{Duration, Strand},
+};
+
+/// What requests are grouped by when they are rate limited
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum RateLimitKey {
+	/// Limit requests per client IP address,
+	/// or per authenticated subject for requests without an IP address
+	#[default]
+	Ip,
+	/// Limit requests per authenticated subject, or per IP address for anonymous requests
+	Auth,
+}
+
+/// The rate limiting configuration for an API endpoint, which each node enforces separately
+#[derive(Debug)]
+pub struct RateLimit {
+	/// The number of requests which are allowed within the period
+	pub limit: u64,
+	/// The period over which requests are counted
+	pub per: Duration,
+	/// What requests are grouped by
+	pub by: RateLimitKey,
+}
+
+pub fn rate_limit(
+	context: &mut InvocationContext,
+	(limit, per, Optional(by)): (i64, Duration, Optional<Strand>),
+) -> Result<(), Error> {
+	let limit =
+		u64::try_from(limit).ok().filter(|x| *x > 0).ok_or_else(|| Error::InvalidArguments {
+			name: "api::rate_limit".to_string(),
+			message: "Argument 1 was the wrong type, expected a positive number of requests"
+				.to_string(),
+		})?;
+
+	if per.is_zero() {
+		return Err(Error::InvalidArguments {
+			name: "api::rate_limit".to_string(),
+			message: "Argument 2 was the wrong type, expected a non-zero duration".to_string(),
+		});
+	}
+
+	let by = match by.as_ref().map(|x| x.as_str()) {
+		None | Some("ip") => RateLimitKey::Ip,
+		Some("auth") => RateLimitKey::Auth,
+		Some(_) => {
+			return Err(Error::InvalidArguments {
+				name: "api::rate_limit".to_string(),
+				message: "Argument 3 was the wrong type, expected either 'ip' or 'auth'"
+					.to_string(),
+			})
+		}
+	};
+
+	context.rate_limit = Some(RateLimit {
+		limit,
+		per,
+		by,
+	});
+	Ok(())
+}
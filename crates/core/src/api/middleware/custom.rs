@@ -0,0 +1,67 @@
+use reblessive::tree::Stk;
+
+use crate::{
+	api::err::ApiError,
+	ctx::Context,
+	dbs::Options,
+	err::Error,
+	sql::{FlowResultExt as _, Function, Value},
+};
+
+/// The outcome of invoking a custom middleware function
+pub enum MiddlewareOutcome {
+	/// Continue processing, with a possibly modified request
+	Continue(Value),
+	/// Stop processing, and respond with the returned response
+	Respond(Value),
+}
+
+/// Invokes a custom middleware function, passing the request as the first argument.
+///
+/// A function can return `NONE` to continue with the request unchanged, `{ request: {...} }`
+/// to continue with a modified request, or `{ respond: {...} }` to respond directly without
+/// running the remaining middleware or the action itself.
+pub async fn invoke(
+	stk: &mut Stk,
+	ctx: &Context,
+	opt: &Options,
+	(name, args): (&String, &Vec<Value>),
+	request: Value,
+) -> Result<MiddlewareOutcome, Error> {
+	// The name was validated as a custom function when collected
+	let Some(("fn", function)) = name.split_once("::") else {
+		return Err(Error::Unreachable("Expected a custom middleware function".into()));
+	};
+	// The request is passed before any of the configured arguments
+	let mut a = Vec::with_capacity(args.len() + 1);
+	a.push(request.clone());
+	a.extend(args.iter().cloned());
+	// Invoke the function
+	let res = Function::Custom(function.to_string(), a)
+		.compute(stk, ctx, opt, None)
+		.await
+		.catch_return()?;
+	// Process the result
+	match res {
+		Value::None => return Ok(MiddlewareOutcome::Continue(request)),
+		// The outcome is explicit, as a request can itself contain any field
+		Value::Object(ref obj) if obj.len() == 1 => match obj.iter().next() {
+			Some((k, v @ Value::Object(_))) if k == "request" => {
+				return Ok(MiddlewareOutcome::Continue(v.clone()))
+			}
+			Some((k, v @ Value::Object(_))) if k == "respond" => {
+				return Ok(MiddlewareOutcome::Respond(v.clone()))
+			}
+			_ => {}
+		},
+		_ => {}
+	}
+	Err(ApiError::InvalidMiddlewareResult(
+		name.to_owned(),
+		format!(
+			"Expected NONE, {{ request: object }} or {{ respond: object }}, but found {}",
+			res.kindof()
+		),
+	)
+	.into())
+}
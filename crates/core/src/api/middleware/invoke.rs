@@ -42,6 +42,10 @@ impl<'a> InvokeMiddleware<'a> for (&'a String, &'a Vec<Value>) {
 			"api::res::headers" => api::res::headers,
 			"api::res::header" => api::res::header,
 			//
+			"api::auth::require" => api::auth::require,
+			//
+			"api::cors" => api::cors,
+			"api::rate_limit" => api::rate_limit,
 			"api::timeout" => api::timeout,
 		)
 	}
@@ -1,6 +1,7 @@
 use std::ops::Deref;
 
 pub mod api;
+pub(super) mod custom;
 pub(super) mod invoke;
 
 use revision::revisioned;
@@ -33,22 +34,22 @@ impl Deref for RequestMiddleware {
 pub type CollectedMiddleware<'a> = Vec<(&'a String, &'a Vec<Value>)>;
 
 pub trait CollectMiddleware<'a> {
-	fn collect(&'a self) -> Result<CollectedMiddleware<'a>, Error>;
+	/// Collects the builtin and the custom middleware separately, each in the order it was defined.
+	/// The builtin middleware configures the invocation before any custom middleware runs,
+	/// whatever their relative order in the definition.
+	fn collect(&'a self) -> Result<(CollectedMiddleware<'a>, CollectedMiddleware<'a>), Error>;
 }
 
 impl<'a> CollectMiddleware<'a> for Vec<&'a RequestMiddleware> {
-	fn collect(&'a self) -> Result<CollectedMiddleware<'a>, Error> {
-		let mut middleware: CollectedMiddleware<'a> = Vec::new();
+	fn collect(&'a self) -> Result<(CollectedMiddleware<'a>, CollectedMiddleware<'a>), Error> {
+		let mut builtin: CollectedMiddleware<'a> = Vec::new();
+		let mut custom: CollectedMiddleware<'a> = Vec::new();
 
 		for map in self.iter() {
 			for (k, v) in map.iter() {
 				match k.split_once("::") {
-					Some(("api", _)) => middleware.push((k, v)),
-					Some(("fn", _)) => {
-						return Err(Error::Unreachable(
-							"Custom middleware are not yet supported".into(),
-						))
-					}
+					Some(("api", _)) => builtin.push((k, v)),
+					Some(("fn", _)) => custom.push((k, v)),
 					_ => {
 						return Err(Error::Unreachable(
 							"Found a middleware which is unparsable".into(),
@@ -58,6 +59,6 @@ impl<'a> CollectMiddleware<'a> for Vec<&'a RequestMiddleware> {
 			}
 		}
 
-		Ok(middleware)
+		Ok((builtin, custom))
 	}
 }
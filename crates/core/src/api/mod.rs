@@ -2,6 +2,7 @@ pub mod body;
 pub mod context;
 pub mod err;
 pub mod invocation;
+pub mod limit;
 pub mod method;
 pub mod middleware;
 pub mod path;
@@ -19,6 +19,18 @@ pub struct ApiResponse {
 	pub headers: HeaderMap,
 }
 
+impl ApiResponse {
+	/// Creates a response with an empty body and the given status
+	pub fn status(status: StatusCode) -> Self {
+		Self {
+			raw: None,
+			status,
+			body: None,
+			headers: HeaderMap::new(),
+		}
+	}
+}
+
 impl TryFrom<Value> for ApiResponse {
 	type Error = Error;
 	fn try_from(value: Value) -> Result<Self, Self::Error> {
@@ -4,6 +4,8 @@ use std::sync::Arc;
 
 #[cfg(not(target_arch = "wasm32"))]
 use super::store::file::FileStore;
+#[cfg(all(feature = "enterprise", not(target_arch = "wasm32")))]
+use super::store::s3::S3Store;
 
 pub(crate) async fn connect(
 	url: &str,
@@ -19,5 +21,10 @@ pub(crate) async fn connect(
 		return Ok(Arc::new(FileStore::new(opts)));
 	}
 
+	#[cfg(all(feature = "enterprise", not(target_arch = "wasm32")))]
+	if let Some(opts) = S3Store::parse_url(url, _global)? {
+		return Ok(Arc::new(S3Store::new(opts)?));
+	}
+
 	Err(Error::UnsupportedBackend)
 }
@@ -8,9 +8,12 @@ use crate::{
 };
 use core::fmt;
 use reblessive::tree::Stk;
-use std::sync::Arc;
+use std::{ops::Range, sync::Arc};
 
-use super::store::{ListOptions, ObjectKey, ObjectMeta, ObjectStore};
+use super::store::{
+	util::clamp_range, GetOptions, ListOptions, ObjectKey, ObjectMeta, ObjectStore, ObjectStream,
+	PutOptions,
+};
 
 fn accept_payload(value: Value) -> Result<bytes::Bytes, Error> {
 	value.cast_to::<Bytes>().map(|x| bytes::Bytes::from(x.0)).map_err(Error::from)
@@ -64,13 +67,36 @@ impl<'a> BucketController<'a> {
 	/// Attempt to put a file
 	/// `Bytes` and `Strand` values are supported, and will be converted into `Bytes`
 	/// Create or update permissions will be used, based on if the remote file already exists
-	pub(crate) async fn put(&mut self, key: &ObjectKey, value: Value) -> Result<(), Error> {
+	pub(crate) async fn put(
+		&mut self,
+		key: &ObjectKey,
+		value: Value,
+		opts: &PutOptions,
+	) -> Result<(), Error> {
 		let payload = accept_payload(value)?;
 		self.require_writeable()?;
 		self.check_permission(BucketOperation::Put, Some(key), None).await?;
 
 		self.store
-			.put(key, payload)
+			.put(key, payload, opts)
+			.await
+			.map_err(|e| Error::ObjectStoreFailure(self.bucket.name.to_raw(), e.to_string()))?;
+
+		Ok(())
+	}
+
+	/// Attempt to put a file from a stream of chunks, without buffering the whole file
+	pub(crate) async fn put_stream(
+		&mut self,
+		key: &ObjectKey,
+		stream: ObjectStream,
+		opts: &PutOptions,
+	) -> Result<(), Error> {
+		self.require_writeable()?;
+		self.check_permission(BucketOperation::Put, Some(key), None).await?;
+
+		self.store
+			.put_stream(key, stream, opts)
 			.await
 			.map_err(|e| Error::ObjectStoreFailure(self.bucket.name.to_raw(), e.to_string()))?;
 
@@ -84,13 +110,14 @@ impl<'a> BucketController<'a> {
 		&mut self,
 		key: &ObjectKey,
 		value: Value,
+		opts: &PutOptions,
 	) -> Result<(), Error> {
 		let payload = accept_payload(value)?;
 		self.require_writeable()?;
 		self.check_permission(BucketOperation::Put, Some(key), None).await?;
 
 		self.store
-			.put_if_not_exists(key, payload)
+			.put_if_not_exists(key, payload, opts)
 			.await
 			.map_err(|e| Error::ObjectStoreFailure(self.bucket.name.to_raw(), e.to_string()))?;
 
@@ -106,13 +133,19 @@ impl<'a> BucketController<'a> {
 			.map_err(|e| Error::ObjectStoreFailure(self.bucket.name.to_raw(), e.to_string()))
 	}
 
-	pub(crate) async fn get(&mut self, key: &ObjectKey) -> Result<Option<Bytes>, Error> {
+	pub(crate) async fn get(
+		&mut self,
+		key: &ObjectKey,
+		opts: &GetOptions,
+	) -> Result<Option<Bytes>, Error> {
 		self.check_permission(BucketOperation::Get, Some(key), None).await?;
 
-		let bytes = match self
-			.store
-			.get(key)
-			.await
+		let res = match opts.range {
+			Some(ref range) => self.store.get_range(key, range.clone()).await,
+			None => self.store.get(key).await,
+		};
+
+		let bytes = match res
 			.map_err(|e| Error::ObjectStoreFailure(self.bucket.name.to_raw(), e.to_string()))?
 		{
 			Some(v) => v,
@@ -122,6 +155,41 @@ impl<'a> BucketController<'a> {
 		Ok(Some(bytes.to_vec().into()))
 	}
 
+	/// Attempt to get a file as a stream of chunks, optionally limited to a byte range
+	pub(crate) async fn get_stream(
+		&mut self,
+		key: &ObjectKey,
+		range: Option<FileRange>,
+	) -> Result<Option<FileStream>, Error> {
+		self.check_permission(BucketOperation::Get, Some(key), None).await?;
+
+		let map_err = |e: String| Error::ObjectStoreFailure(self.bucket.name.to_raw(), e);
+
+		// Fetch the size of the file, to resolve the requested range
+		let Some(meta) = self.store.head(key).await.map_err(map_err)? else {
+			return Ok(None);
+		};
+
+		let range = match range {
+			Some(range) => {
+				Some(range.resolve(meta.size).ok_or(Error::FileRangeNotSatisfiable(meta.size))?)
+			}
+			None => None,
+		};
+
+		let Some(stream) = self.store.get_stream(key, range.clone()).await.map_err(map_err)? else {
+			return Ok(None);
+		};
+
+		Ok(Some(FileStream {
+			size: meta.size,
+			range: range.unwrap_or(0..meta.size),
+			etag: meta.etag,
+			content_type: meta.attributes.content_type,
+			stream,
+		}))
+	}
+
 	pub(crate) async fn delete(&mut self, key: &ObjectKey) -> Result<(), Error> {
 		self.require_writeable()?;
 		self.check_permission(BucketOperation::Delete, Some(key), None).await?;
@@ -273,6 +341,50 @@ impl<'a> BucketController<'a> {
 	}
 }
 
+/// A byte range of a file, as requested by a client
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum FileRange {
+	/// All bytes from the start offset, up to and excluding the end offset
+	Bounded(u64, u64),
+	/// All bytes from the start offset until the end of the file
+	From(u64),
+	/// The last number of bytes of the file
+	Suffix(u64),
+}
+
+impl FileRange {
+	/// Resolve the range against the size of a file, returning `None` when
+	/// the range can not be satisfied
+	pub fn resolve(self, size: u64) -> Option<Range<u64>> {
+		let range = match self {
+			Self::Bounded(start, end) if start < end => start..end,
+			Self::From(start) => start..size,
+			Self::Suffix(len) if len > 0 => size.saturating_sub(len)..size,
+			_ => return None,
+		};
+
+		if range.start >= size {
+			return None;
+		}
+
+		Some(clamp_range(range, size))
+	}
+}
+
+/// A file which is streamed from a bucket
+pub struct FileStream {
+	/// The total size of the file in bytes
+	pub size: u64,
+	/// The byte range of the file which is contained in the stream
+	pub range: Range<u64>,
+	/// The entity tag of the file, if known
+	pub etag: Option<String>,
+	/// The content type of the file, if one was specified when it was stored
+	pub content_type: Option<String>,
+	/// The contents of the file
+	pub stream: ObjectStream,
+}
+
 #[derive(Clone, Copy, Debug)]
 pub enum BucketOperation {
 	Put,
@@ -6,6 +6,7 @@ mod connection;
 pub(crate) use connection::{connect, connect_global, BucketConnectionKey, BucketConnections};
 mod controller;
 pub(crate) use controller::BucketController;
-pub use controller::BucketOperation;
+pub use controller::{BucketOperation, FileRange, FileStream};
 
 pub(crate) mod store;
+pub use store::ObjectStream;
@@ -1,17 +1,61 @@
 use std::{
 	future::Future,
+	io::SeekFrom,
+	ops::Range,
 	path::{Path as OsPath, PathBuf},
 	pin::Pin,
 };
 
 use bytes::Bytes;
+use futures::{StreamExt, TryStreamExt};
 use path_clean::PathClean;
-use tokio::{fs::File, io::AsyncWriteExt};
+use serde::{Deserialize, Serialize};
+use tokio::{
+	fs::File,
+	io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt},
+};
+use tokio_util::io::ReaderStream;
 use url::Url;
 
 use crate::{cnf::BUCKET_FOLDER_ALLOWLIST, err::Error, sql::Datetime};
 
-use super::{ListOptions, ObjectKey, ObjectMeta, ObjectStore};
+use super::{
+	util::{clamp_range, etag, ETagHasher},
+	ListOptions, ObjectAttributes, ObjectKey, ObjectMeta, ObjectStore, ObjectStream, PutOptions,
+};
+
+/// The folder, within the root of the store, where object attributes are kept
+const META_FOLDER: &str = ".surreal-meta";
+
+/// The prefix of the temporary files which uploads are streamed into
+const UPLOAD_PREFIX: &str = ".surreal-upload-";
+
+/// A temporary file which is removed when dropped, unless it has been kept
+struct TempFile(Option<PathBuf>);
+
+impl TempFile {
+	/// Keep the file, once it has been moved into place
+	fn keep(&mut self) {
+		self.0 = None;
+	}
+}
+
+impl Drop for TempFile {
+	fn drop(&mut self) {
+		// The upload failed or was cancelled, so the partial data is discarded
+		if let Some(path) = self.0.take() {
+			let _ = std::fs::remove_file(path);
+		}
+	}
+}
+
+/// The attributes of an object, stored in a sidecar file next to the object data
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Sidecar {
+	etag: String,
+	#[serde(flatten)]
+	attributes: ObjectAttributes,
+}
 
 /// Options for configuring the FileStore
 #[derive(Clone, Debug)]
@@ -111,6 +155,26 @@ impl FileStore {
 
 	/// Convert a Path to an OsPath, checking against the allowlist
 	async fn to_os_path(&self, path: &ObjectKey) -> Result<PathBuf, String> {
+		// Get the relative path components
+		let relative_path = path.as_str().trim_start_matches('/');
+
+		// The folder holding object attributes can not be accessed directly
+		if relative_path.split('/').next().is_some_and(|x| x.eq_ignore_ascii_case(META_FOLDER)) {
+			return Err(format!("Path is reserved for object metadata: {}", path));
+		}
+
+		self.resolve_path(path, relative_path).await
+	}
+
+	/// Get the OsPath of the sidecar file holding the attributes for an object
+	async fn to_meta_path(&self, path: &ObjectKey) -> Result<PathBuf, String> {
+		let relative_path = path.as_str().trim_start_matches('/');
+		let relative_path = format!("{META_FOLDER}/{relative_path}.json");
+		self.resolve_path(path, &relative_path).await
+	}
+
+	/// Resolve a path relative to the root of the store, checking against the allowlist
+	async fn resolve_path(&self, path: &ObjectKey, relative_path: &str) -> Result<PathBuf, String> {
 		let root = PathBuf::from(self.options.root.as_str());
 
 		// First canonicalize the root (which should exist)
@@ -118,9 +182,6 @@ impl FileStore {
 			.await
 			.map_err(|e| format!("Failed to canonicalize root path: {}", e))?;
 
-		// Get the relative path components
-		let relative_path = path.as_str().trim_start_matches('/');
-
 		// Handle case sensitivity
 		let relative_path = if self.options.lowercase_paths {
 			&relative_path.to_lowercase()
@@ -140,6 +201,150 @@ impl FileStore {
 		Ok(full_path)
 	}
 
+	/// Open a file for reading, limited to the given byte range
+	async fn open_range(
+		path: &OsPath,
+		range: Option<Range<u64>>,
+	) -> Result<tokio::io::Take<File>, String> {
+		let mut file = File::open(path).await.map_err(|e| format!("Failed to open file: {}", e))?;
+
+		let size =
+			file.metadata().await.map_err(|e| format!("Failed to get metadata: {}", e))?.len();
+
+		let range = clamp_range(range.unwrap_or(0..size), size);
+
+		file.seek(SeekFrom::Start(range.start))
+			.await
+			.map_err(|e| format!("Failed to seek file: {}", e))?;
+
+		Ok(file.take(range.end - range.start))
+	}
+
+	/// Write the data for an object, along with its attributes
+	async fn write_object(
+		&self,
+		key: &ObjectKey,
+		os_path: &OsPath,
+		data: &[u8],
+		opts: &PutOptions,
+	) -> Result<(), String> {
+		Self::ensure_parent_dirs(os_path).await?;
+
+		let mut file =
+			File::create(os_path).await.map_err(|e| format!("Failed to create file: {}", e))?;
+
+		file.write_all(data).await.map_err(|e| format!("Failed to write to file: {}", e))?;
+
+		file.flush().await.map_err(|e| format!("Failed to flush file: {}", e))?;
+
+		self.write_sidecar(key, etag(data), opts).await
+	}
+
+	/// Store the attributes of an object in its sidecar file
+	async fn write_sidecar(
+		&self,
+		key: &ObjectKey,
+		etag: String,
+		opts: &PutOptions,
+	) -> Result<(), String> {
+		let meta_path = self.to_meta_path(key).await?;
+		Self::ensure_parent_dirs(&meta_path).await?;
+
+		let sidecar = Sidecar {
+			etag,
+			attributes: opts.attributes.clone(),
+		};
+
+		let data = serde_json::to_vec(&sidecar)
+			.map_err(|e| format!("Failed to serialize metadata: {}", e))?;
+
+		tokio::fs::write(&meta_path, data)
+			.await
+			.map_err(|e| format!("Failed to write metadata: {}", e))
+	}
+
+	/// Read the attributes of an object from its sidecar file, if it has one
+	async fn read_sidecar(&self, key: &ObjectKey) -> Result<Option<Sidecar>, String> {
+		let meta_path = self.to_meta_path(key).await?;
+
+		// Files which were not written through the store have no sidecar
+		if !Self::path_exists(&meta_path).await? {
+			return Ok(None);
+		}
+
+		let data = tokio::fs::read(&meta_path)
+			.await
+			.map_err(|e| format!("Failed to read metadata: {}", e))?;
+
+		serde_json::from_slice(&data)
+			.map(Some)
+			.map_err(|e| format!("Failed to deserialize metadata: {}", e))
+	}
+
+	/// Remove the sidecar file of an object, if it has one
+	async fn remove_sidecar(&self, key: &ObjectKey) -> Result<(), String> {
+		let meta_path = self.to_meta_path(key).await?;
+
+		if !Self::path_exists(&meta_path).await? {
+			return Ok(());
+		}
+
+		tokio::fs::remove_file(&meta_path)
+			.await
+			.map_err(|e| format!("Failed to delete metadata: {}", e))
+	}
+
+	/// Copy or move the sidecar file of an object, so that the attributes follow the data
+	async fn transfer_sidecar(
+		&self,
+		key: &ObjectKey,
+		target: &ObjectKey,
+		remove_source: bool,
+	) -> Result<(), String> {
+		let source_path = self.to_meta_path(key).await?;
+		let target_path = self.to_meta_path(target).await?;
+
+		// Ensure stale attributes are not left behind for the target
+		if !Self::path_exists(&source_path).await? {
+			return self.remove_sidecar(target).await;
+		}
+
+		Self::ensure_parent_dirs(&target_path).await?;
+
+		if remove_source {
+			tokio::fs::rename(&source_path, &target_path)
+				.await
+				.map_err(|e| format!("Failed to rename metadata: {}", e))
+		} else {
+			tokio::fs::copy(&source_path, &target_path)
+				.await
+				.map(|_| ())
+				.map_err(|e| format!("Failed to copy metadata: {}", e))
+		}
+	}
+
+	/// Build the metadata for an object from its file metadata and sidecar file
+	async fn object_meta(
+		&self,
+		key: ObjectKey,
+		metadata: &std::fs::Metadata,
+	) -> Result<ObjectMeta, String> {
+		let size = metadata.len();
+
+		// Get modified time if available
+		let updated = metadata.modified().map(|time| Datetime(time.into())).unwrap_or_default();
+
+		let sidecar = self.read_sidecar(&key).await?;
+
+		Ok(ObjectMeta {
+			size,
+			updated,
+			etag: sidecar.as_ref().map(|x| x.etag.clone()),
+			attributes: sidecar.map(|x| x.attributes).unwrap_or_default(),
+			key,
+		})
+	}
+
 	/// Create parent directories for a path if they don't exist
 	async fn ensure_parent_dirs(path: &OsPath) -> Result<(), String> {
 		if let Some(parent) = path.parent() {
@@ -177,20 +382,55 @@ impl ObjectStore for FileStore {
 		&'a self,
 		key: &'a ObjectKey,
 		data: Bytes,
+		opts: &'a PutOptions,
+	) -> Pin<Box<dyn Future<Output = Result<(), String>> + Send + 'a>> {
+		Box::pin(async move {
+			let os_path = self.to_os_path(key).await?;
+			self.write_object(key, &os_path, &data, opts).await
+		})
+	}
+
+	fn put_stream<'a>(
+		&'a self,
+		key: &'a ObjectKey,
+		mut stream: ObjectStream,
+		opts: &'a PutOptions,
 	) -> Pin<Box<dyn Future<Output = Result<(), String>> + Send + 'a>> {
 		Box::pin(async move {
 			let os_path = self.to_os_path(key).await?;
 			Self::ensure_parent_dirs(&os_path).await?;
 
-			let mut file = File::create(&os_path)
+			// Stream into a temporary file in the same directory, so that the
+			// existing object is left intact if the upload does not complete
+			let tmp_path =
+				os_path.with_file_name(format!("{UPLOAD_PREFIX}{}", uuid::Uuid::new_v4()));
+			let mut tmp = TempFile(Some(tmp_path.clone()));
+
+			let mut file = File::create(&tmp_path)
 				.await
 				.map_err(|e| format!("Failed to create file: {}", e))?;
 
-			file.write_all(&data).await.map_err(|e| format!("Failed to write to file: {}", e))?;
+			// Write the chunks as they arrive, so the data is never fully buffered
+			let mut hasher = ETagHasher::default();
+			while let Some(chunk) = stream.next().await {
+				let chunk = chunk?;
+				hasher.update(&chunk);
+				file.write_all(&chunk)
+					.await
+					.map_err(|e| format!("Failed to write to file: {}", e))?;
+			}
 
 			file.flush().await.map_err(|e| format!("Failed to flush file: {}", e))?;
+			file.sync_all().await.map_err(|e| format!("Failed to sync file: {}", e))?;
+			drop(file);
 
-			Ok(())
+			// Replace the existing object only once all of the data is written
+			tokio::fs::rename(&tmp_path, &os_path)
+				.await
+				.map_err(|e| format!("Failed to rename file: {}", e))?;
+			tmp.keep();
+
+			self.write_sidecar(key, hasher.finish(), opts).await
 		})
 	}
 
@@ -198,6 +438,7 @@ impl ObjectStore for FileStore {
 		&'a self,
 		key: &'a ObjectKey,
 		data: Bytes,
+		opts: &'a PutOptions,
 	) -> Pin<Box<dyn Future<Output = Result<(), String>> + Send + 'a>> {
 		Box::pin(async move {
 			let os_path = self.to_os_path(key).await?;
@@ -207,23 +448,34 @@ impl ObjectStore for FileStore {
 				return Ok(());
 			}
 
-			Self::ensure_parent_dirs(&os_path).await?;
+			self.write_object(key, &os_path, &data, opts).await
+		})
+	}
 
-			let mut file = File::create(&os_path)
-				.await
-				.map_err(|e| format!("Failed to create file: {}", e))?;
+	fn get<'a>(
+		&'a self,
+		key: &'a ObjectKey,
+	) -> Pin<Box<dyn Future<Output = Result<Option<Bytes>, String>> + Send + 'a>> {
+		Box::pin(async move {
+			let os_path = self.to_os_path(key).await?;
 
-			file.write_all(&data).await.map_err(|e| format!("Failed to write to file: {}", e))?;
+			// Check if the file exists
+			if !Self::path_exists(&os_path).await? {
+				return Ok(None);
+			}
 
-			file.flush().await.map_err(|e| format!("Failed to flush file: {}", e))?;
+			let data = tokio::fs::read(&os_path)
+				.await
+				.map_err(|e| format!("Failed to read file: {}", e))?;
 
-			Ok(())
+			Ok(Some(Bytes::from(data)))
 		})
 	}
 
-	fn get<'a>(
+	fn get_range<'a>(
 		&'a self,
 		key: &'a ObjectKey,
+		range: Range<u64>,
 	) -> Pin<Box<dyn Future<Output = Result<Option<Bytes>, String>> + Send + 'a>> {
 		Box::pin(async move {
 			let os_path = self.to_os_path(key).await?;
@@ -233,7 +485,10 @@ impl ObjectStore for FileStore {
 				return Ok(None);
 			}
 
-			let data = tokio::fs::read(&os_path)
+			let mut data = Vec::new();
+			Self::open_range(&os_path, Some(range))
+				.await?
+				.read_to_end(&mut data)
 				.await
 				.map_err(|e| format!("Failed to read file: {}", e))?;
 
@@ -241,6 +496,28 @@ impl ObjectStore for FileStore {
 		})
 	}
 
+	fn get_stream<'a>(
+		&'a self,
+		key: &'a ObjectKey,
+		range: Option<Range<u64>>,
+	) -> Pin<Box<dyn Future<Output = Result<Option<ObjectStream>, String>> + Send + 'a>> {
+		Box::pin(async move {
+			let os_path = self.to_os_path(key).await?;
+
+			// Check if the file exists
+			if !Self::path_exists(&os_path).await? {
+				return Ok(None);
+			}
+
+			let reader = Self::open_range(&os_path, range).await?;
+			let stream = ReaderStream::new(reader)
+				.map_err(|e| format!("Failed to read file: {}", e))
+				.boxed();
+
+			Ok(Some(stream))
+		})
+	}
+
 	fn head<'a>(
 		&'a self,
 		key: &'a ObjectKey,
@@ -257,16 +534,7 @@ impl ObjectStore for FileStore {
 				.await
 				.map_err(|e| format!("Failed to get metadata: {}", e))?;
 
-			let size = metadata.len();
-
-			// Get modified time if available
-			let updated = metadata.modified().map(|time| Datetime(time.into())).unwrap_or_default();
-
-			Ok(Some(ObjectMeta {
-				size,
-				updated,
-				key: key.to_owned(),
-			}))
+			self.object_meta(key.to_owned(), &metadata).await.map(Some)
 		})
 	}
 
@@ -286,7 +554,7 @@ impl ObjectStore for FileStore {
 				.await
 				.map_err(|e| format!("Failed to delete file: {}", e))?;
 
-			Ok(())
+			self.remove_sidecar(key).await
 		})
 	}
 
@@ -311,8 +579,7 @@ impl ObjectStore for FileStore {
 
 			// Check if the source file exists
 			if !Self::path_exists(&source_key).await? {
-				// Silently ignore operations on non-existent source files
-				return Ok(());
+				return Err(format!("Source object does not exist: {}", key));
 			}
 
 			Self::ensure_parent_dirs(&target_key).await?;
@@ -321,7 +588,7 @@ impl ObjectStore for FileStore {
 				.await
 				.map_err(|e| format!("Failed to copy file: {}", e))?;
 
-			Ok(())
+			self.transfer_sidecar(key, target, false).await
 		})
 	}
 
@@ -341,8 +608,7 @@ impl ObjectStore for FileStore {
 
 			// Check if the source file exists
 			if !Self::path_exists(&source_key).await? {
-				// Silently ignore operations on non-existent source files
-				return Ok(());
+				return Err(format!("Source object does not exist: {}", key));
 			}
 
 			Self::ensure_parent_dirs(&target_key).await?;
@@ -351,7 +617,7 @@ impl ObjectStore for FileStore {
 				.await
 				.map_err(|e| format!("Failed to copy file: {}", e))?;
 
-			Ok(())
+			self.transfer_sidecar(key, target, false).await
 		})
 	}
 
@@ -366,7 +632,7 @@ impl ObjectStore for FileStore {
 
 			// Check if the source file exists
 			if !Self::path_exists(&source_key).await? {
-				return Err(format!("Source file does not exist: {}", source_key.display()));
+				return Err(format!("Source object does not exist: {}", key));
 			}
 
 			Self::ensure_parent_dirs(&target_key).await?;
@@ -375,7 +641,7 @@ impl ObjectStore for FileStore {
 				.await
 				.map_err(|e| format!("Failed to rename file: {}", e))?;
 
-			Ok(())
+			self.transfer_sidecar(key, target, true).await
 		})
 	}
 
@@ -395,7 +661,7 @@ impl ObjectStore for FileStore {
 
 			// Check if the source file exists
 			if !Self::path_exists(&source_key).await? {
-				return Err(format!("Source file does not exist: {}", source_key.display()));
+				return Err(format!("Source object does not exist: {}", key));
 			}
 
 			Self::ensure_parent_dirs(&target_key).await?;
@@ -404,7 +670,7 @@ impl ObjectStore for FileStore {
 				.await
 				.map_err(|e| format!("Failed to rename file: {}", e))?;
 
-			Ok(())
+			self.transfer_sidecar(key, target, true).await
 		})
 	}
 
@@ -437,14 +703,7 @@ impl ObjectStore for FileStore {
 					}
 				}
 
-				let size = metadata.len();
-				let updated =
-					metadata.modified().map(|time| Datetime(time.into())).unwrap_or_default();
-				return Ok(vec![ObjectMeta {
-					key: base_key,
-					size,
-					updated,
-				}]);
+				return Ok(vec![self.object_meta(base_key, &metadata).await?]);
 			}
 
 			// If it's a directory, read its contents
@@ -472,6 +731,11 @@ impl ObjectStore for FileStore {
 					continue;
 				}
 
+				// Skip the temporary files of uploads which are in progress
+				if entry.file_name().to_string_lossy().starts_with(UPLOAD_PREFIX) {
+					continue;
+				}
+
 				// Convert the path to a relative Key
 				let rel_path = path
 					.strip_prefix(&os_path)
@@ -503,19 +767,10 @@ impl ObjectStore for FileStore {
 			};
 
 			// Convert to ObjectMeta
-			let objects = limited_entries
-				.into_iter()
-				.map(|(entry_key, metadata)| {
-					let size = metadata.len();
-					let updated =
-						metadata.modified().map(|time| Datetime(time.into())).unwrap_or_default();
-					ObjectMeta {
-						key: entry_key,
-						size,
-						updated,
-					}
-				})
-				.collect();
+			let mut objects = Vec::with_capacity(limited_entries.len());
+			for (entry_key, metadata) in limited_entries {
+				objects.push(self.object_meta(entry_key, &metadata).await?);
+			}
 
 			Ok(objects)
 		})
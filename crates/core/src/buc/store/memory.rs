@@ -1,26 +1,44 @@
-use std::{future::Future, pin::Pin};
+use std::{future::Future, ops::Range, pin::Pin};
 
-use bytes::Bytes;
+use bytes::{Bytes, BytesMut};
 use dashmap::DashMap;
+use futures::StreamExt;
 use url::Url;
 
 use crate::sql::Datetime;
 
-use super::{ListOptions, ObjectKey, ObjectMeta, ObjectStore};
+use super::{
+	util::{clamp_range, etag},
+	ListOptions, ObjectAttributes, ObjectKey, ObjectMeta, ObjectStore, ObjectStream, PutOptions,
+};
 
 #[derive(Clone, Debug, Default)]
 pub struct Entry {
 	bytes: Bytes,
 	updated: Datetime,
+	etag: String,
+	attributes: ObjectAttributes,
 }
 
-impl From<Bytes> for Entry {
-	fn from(bytes: Bytes) -> Self {
+impl Entry {
+	fn new(bytes: Bytes, opts: &PutOptions) -> Self {
 		Self {
+			etag: etag(&bytes),
 			bytes,
+			attributes: opts.attributes.clone(),
 			..Default::default()
 		}
 	}
+
+	fn meta(&self, key: &ObjectKey) -> ObjectMeta {
+		ObjectMeta {
+			size: self.bytes.len() as u64,
+			updated: self.updated.clone(),
+			key: key.to_owned(),
+			etag: Some(self.etag.clone()),
+			attributes: self.attributes.clone(),
+		}
+	}
 }
 
 #[derive(Clone, Debug, Default)]
@@ -51,9 +69,28 @@ impl ObjectStore for MemoryStore {
 		&'a self,
 		key: &'a ObjectKey,
 		data: Bytes,
+		opts: &'a PutOptions,
 	) -> Pin<Box<dyn Future<Output = Result<(), String>> + Send + 'a>> {
 		Box::pin(async move {
-			self.store.insert(key.clone(), data.into());
+			self.store.insert(key.clone(), Entry::new(data, opts));
+			Ok(())
+		})
+	}
+
+	fn put_stream<'a>(
+		&'a self,
+		key: &'a ObjectKey,
+		mut stream: ObjectStream,
+		opts: &'a PutOptions,
+	) -> Pin<Box<dyn Future<Output = Result<(), String>> + Send + 'a>> {
+		Box::pin(async move {
+			// The data is kept in memory anyway, so collect the chunks
+			let mut data = BytesMut::new();
+			while let Some(chunk) = stream.next().await {
+				data.extend_from_slice(&chunk?);
+			}
+
+			self.store.insert(key.clone(), Entry::new(data.freeze(), opts));
 			Ok(())
 		})
 	}
@@ -62,9 +99,10 @@ impl ObjectStore for MemoryStore {
 		&'a self,
 		key: &'a ObjectKey,
 		data: Bytes,
+		opts: &'a PutOptions,
 	) -> Pin<Box<dyn Future<Output = Result<(), String>> + Send + 'a>> {
 		Box::pin(async move {
-			self.store.entry(key.clone()).or_insert_with(|| data.into());
+			self.store.entry(key.clone()).or_insert_with(|| Entry::new(data, opts));
 
 			Ok(())
 		})
@@ -80,16 +118,41 @@ impl ObjectStore for MemoryStore {
 		})
 	}
 
+	fn get_range<'a>(
+		&'a self,
+		key: &'a ObjectKey,
+		range: Range<u64>,
+	) -> Pin<Box<dyn Future<Output = Result<Option<Bytes>, String>> + Send + 'a>> {
+		Box::pin(async move {
+			let data = self.store.get(key).map(|v| {
+				let range = clamp_range(range, v.bytes.len() as u64);
+				v.bytes.slice(range.start as usize..range.end as usize)
+			});
+			Ok(data)
+		})
+	}
+
+	fn get_stream<'a>(
+		&'a self,
+		key: &'a ObjectKey,
+		range: Option<Range<u64>>,
+	) -> Pin<Box<dyn Future<Output = Result<Option<ObjectStream>, String>> + Send + 'a>> {
+		Box::pin(async move {
+			let data = match range {
+				Some(range) => self.get_range(key, range).await?,
+				None => self.get(key).await?,
+			};
+			// The data is already in memory, so stream it as a single chunk
+			Ok(data.map(|x| futures::stream::once(async move { Ok(x) }).boxed()))
+		})
+	}
+
 	fn head<'a>(
 		&'a self,
 		key: &'a ObjectKey,
 	) -> Pin<Box<dyn Future<Output = Result<Option<ObjectMeta>, String>> + Send + 'a>> {
 		Box::pin(async move {
-			let data = self.store.get(key).map(|v| ObjectMeta {
-				size: v.bytes.len() as u64,
-				updated: v.updated.clone(),
-				key: key.to_owned(),
-			});
+			let data = self.store.get(key).map(|v| v.meta(key));
 
 			Ok(data)
 		})
@@ -121,10 +184,10 @@ impl ObjectStore for MemoryStore {
 		target: &'a ObjectKey,
 	) -> Pin<Box<dyn Future<Output = Result<(), String>> + Send + 'a>> {
 		Box::pin(async move {
-			if let Some(x) = self.store.get(key) {
-				let data = x.clone();
-				self.store.insert(target.clone(), data);
-			}
+			let Some(data) = self.store.get(key).map(|x| x.clone()) else {
+				return Err(format!("Source object does not exist: {}", key));
+			};
+			self.store.insert(target.clone(), data);
 
 			Ok(())
 		})
@@ -137,10 +200,10 @@ impl ObjectStore for MemoryStore {
 	) -> Pin<Box<dyn Future<Output = Result<(), String>> + Send + 'a>> {
 		Box::pin(async move {
 			if !self.store.contains_key(target) {
-				if let Some(x) = self.store.get(key) {
-					let data = x.clone();
-					self.store.insert(target.clone(), data);
-				}
+				let Some(data) = self.store.get(key).map(|x| x.clone()) else {
+					return Err(format!("Source object does not exist: {}", key));
+				};
+				self.store.insert(target.clone(), data);
 			}
 
 			Ok(())
@@ -153,9 +216,10 @@ impl ObjectStore for MemoryStore {
 		target: &'a ObjectKey,
 	) -> Pin<Box<dyn Future<Output = Result<(), String>> + Send + 'a>> {
 		Box::pin(async move {
-			if let Some((_, data)) = self.store.remove(key) {
-				self.store.insert(target.clone(), data);
-			}
+			let Some((_, data)) = self.store.remove(key) else {
+				return Err(format!("Source object does not exist: {}", key));
+			};
+			self.store.insert(target.clone(), data);
 
 			Ok(())
 		})
@@ -168,9 +232,10 @@ impl ObjectStore for MemoryStore {
 	) -> Pin<Box<dyn Future<Output = Result<(), String>> + Send + 'a>> {
 		Box::pin(async move {
 			if !self.store.contains_key(target) {
-				if let Some((_, data)) = self.store.remove(key) {
-					self.store.insert(target.clone(), data);
-				}
+				let Some((_, data)) = self.store.remove(key) else {
+					return Err(format!("Source object does not exist: {}", key));
+				};
+				self.store.insert(target.clone(), data);
 			}
 
 			Ok(())
@@ -223,12 +288,7 @@ impl ObjectStore for MemoryStore {
 
 			// Convert to ObjectMeta
 			for x in limited_keys {
-				let entry = x.value();
-				objects.push(ObjectMeta {
-					key: x.key().clone(),
-					size: entry.bytes.len() as u64,
-					updated: entry.updated.clone(),
-				});
+				objects.push(x.value().meta(x.key()));
 			}
 
 			Ok(objects)
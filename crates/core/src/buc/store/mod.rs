@@ -1,36 +1,118 @@
 use crate::{
 	err::Error,
-	sql::{Datetime, File, Object, Value},
+	sql::{Datetime, File, Number, Object, Value},
 };
 use bytes::Bytes;
-use std::{future::Future, pin::Pin, sync::Arc};
+use futures::Stream;
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, future::Future, ops::Range, pin::Pin, sync::Arc};
 
 #[cfg(not(target_arch = "wasm32"))]
 pub(crate) mod file;
 pub(crate) mod memory;
 pub(crate) mod prefixed;
+#[cfg(all(feature = "enterprise", not(target_arch = "wasm32")))]
+pub(crate) mod s3;
 pub(crate) mod util;
 pub(crate) use util::ObjectKey;
 
+/// A stream of chunks of object data
+pub type ObjectStream = Pin<Box<dyn Stream<Item = Result<Bytes, String>> + Send + 'static>>;
+
 pub(crate) struct ObjectMeta {
 	pub size: u64,
 	pub updated: Datetime,
 	pub key: ObjectKey,
+	pub etag: Option<String>,
+	pub attributes: ObjectAttributes,
 }
 
 impl ObjectMeta {
 	pub fn into_value(self, bucket: String) -> Value {
+		let metadata = self
+			.attributes
+			.metadata
+			.into_iter()
+			.map(|(k, v)| (k, Value::from(v)))
+			.collect::<BTreeMap<_, _>>();
+
 		Value::from(map! {
 			"updated" => Value::from(self.updated),
 			"size" => Value::from(self.size),
 			"file" => Value::File(File {
 				bucket,
 				key: self.key.to_string(),
-			})
+			}),
+			"etag" => Value::from(self.etag),
+			"content_type" => Value::from(self.attributes.content_type),
+			"metadata" => Value::from(Object::from(metadata)),
 		})
 	}
 }
 
+/// Descriptive attributes which are stored alongside the contents of an object
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct ObjectAttributes {
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub content_type: Option<String>,
+	#[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+	pub metadata: BTreeMap<String, String>,
+}
+
+/// The options which can be specified when putting an object
+#[derive(Clone, Debug, Default)]
+pub(crate) struct PutOptions {
+	pub attributes: ObjectAttributes,
+}
+
+impl TryFrom<Object> for PutOptions {
+	type Error = Error;
+	fn try_from(mut obj: Object) -> Result<Self, Self::Error> {
+		let mut opts = PutOptions::default();
+
+		if let Some(content_type) = obj.remove("content_type") {
+			let content_type = content_type.coerce_to::<String>()?;
+			if !is_header_value(&content_type) {
+				return Err(Error::InvalidFileMetadata(format!(
+					"The content type '{content_type}' is not valid"
+				)));
+			}
+
+			opts.attributes.content_type = Some(content_type);
+		}
+
+		if let Some(metadata) = obj.remove("metadata") {
+			for (key, value) in metadata.coerce_to::<Object>()? {
+				// Metadata keys are case-insensitive, as they are stored as headers in some backends
+				let key = key.to_lowercase();
+				if key.is_empty()
+					|| !key.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+				{
+					return Err(Error::InvalidFileMetadata(format!(
+						"The metadata key '{key}' may only contain alphanumeric characters, dashes and underscores"
+					)));
+				}
+
+				let value = value.coerce_to::<String>()?;
+				if !is_header_value(&value) {
+					return Err(Error::InvalidFileMetadata(format!(
+						"The value for metadata key '{key}' may only contain printable ASCII characters"
+					)));
+				}
+
+				opts.attributes.metadata.insert(key, value);
+			}
+		}
+
+		Ok(opts)
+	}
+}
+
+/// Checks if a string can be stored as an HTTP header value
+fn is_header_value(value: &str) -> bool {
+	value.chars().all(|c| c == ' ' || c.is_ascii_graphic())
+}
+
 #[derive(Default)]
 pub(crate) struct ListOptions {
 	pub start: Option<ObjectKey>,
@@ -60,17 +142,70 @@ impl TryFrom<Object> for ListOptions {
 	}
 }
 
+#[derive(Default)]
+pub(crate) struct GetOptions {
+	pub range: Option<Range<u64>>,
+}
+
+impl TryFrom<Object> for GetOptions {
+	type Error = Error;
+	fn try_from(mut obj: Object) -> Result<Self, Self::Error> {
+		let mut opts = GetOptions::default();
+
+		if let Some(range) = obj.remove("range") {
+			// The range is specified as `[start, end]`, with an exclusive end
+			let Value::Array(range) = range else {
+				return Err(Error::InvalidFileRange(format!(
+					"Expected an array of a start and end offset, but found {}",
+					range.kindof()
+				)));
+			};
+
+			let (start, end) = match range.0.as_slice() {
+				[Value::Number(Number::Int(start)), Value::Number(Number::Int(end))] => {
+					(*start, *end)
+				}
+				_ => {
+					return Err(Error::InvalidFileRange(
+						"Expected an array of a start and end offset".to_string(),
+					))
+				}
+			};
+
+			if start < 0 || end < start {
+				return Err(Error::InvalidFileRange(format!(
+					"The range {start}..{end} is not a valid byte range"
+				)));
+			}
+
+			opts.range = Some(start as u64..end as u64);
+		}
+
+		Ok(opts)
+	}
+}
+
 pub(crate) trait ObjectStore: Send + Sync + 'static {
 	fn put<'a>(
 		&'a self,
 		key: &'a ObjectKey,
 		data: Bytes,
+		opts: &'a PutOptions,
+	) -> Pin<Box<dyn Future<Output = Result<(), String>> + Send + 'a>>;
+
+	/// Writes an object from a stream of chunks, without buffering the whole object
+	fn put_stream<'a>(
+		&'a self,
+		key: &'a ObjectKey,
+		stream: ObjectStream,
+		opts: &'a PutOptions,
 	) -> Pin<Box<dyn Future<Output = Result<(), String>> + Send + 'a>>;
 
 	fn put_if_not_exists<'a>(
 		&'a self,
 		key: &'a ObjectKey,
 		data: Bytes,
+		opts: &'a PutOptions,
 	) -> Pin<Box<dyn Future<Output = Result<(), String>> + Send + 'a>>;
 
 	fn get<'a>(
@@ -78,6 +213,21 @@ pub(crate) trait ObjectStore: Send + Sync + 'static {
 		key: &'a ObjectKey,
 	) -> Pin<Box<dyn Future<Output = Result<Option<Bytes>, String>> + Send + 'a>>;
 
+	/// Reads a byte range of an object, where the end of the range is
+	/// exclusive. Ranges extending beyond the end of the object are truncated.
+	fn get_range<'a>(
+		&'a self,
+		key: &'a ObjectKey,
+		range: Range<u64>,
+	) -> Pin<Box<dyn Future<Output = Result<Option<Bytes>, String>> + Send + 'a>>;
+
+	/// Reads an object, or a byte range of an object, as a stream of chunks
+	fn get_stream<'a>(
+		&'a self,
+		key: &'a ObjectKey,
+		range: Option<Range<u64>>,
+	) -> Pin<Box<dyn Future<Output = Result<Option<ObjectStream>, String>> + Send + 'a>>;
+
 	fn head<'a>(
 		&'a self,
 		key: &'a ObjectKey,
@@ -128,16 +278,27 @@ impl ObjectStore for Arc<dyn ObjectStore> {
 		&'a self,
 		key: &'a ObjectKey,
 		data: Bytes,
+		opts: &'a PutOptions,
+	) -> Pin<Box<dyn Future<Output = Result<(), String>> + Send + 'a>> {
+		(**self).put(key, data, opts)
+	}
+
+	fn put_stream<'a>(
+		&'a self,
+		key: &'a ObjectKey,
+		stream: ObjectStream,
+		opts: &'a PutOptions,
 	) -> Pin<Box<dyn Future<Output = Result<(), String>> + Send + 'a>> {
-		(**self).put(key, data)
+		(**self).put_stream(key, stream, opts)
 	}
 
 	fn put_if_not_exists<'a>(
 		&'a self,
 		key: &'a ObjectKey,
 		data: Bytes,
+		opts: &'a PutOptions,
 	) -> Pin<Box<dyn Future<Output = Result<(), String>> + Send + 'a>> {
-		(**self).put_if_not_exists(key, data)
+		(**self).put_if_not_exists(key, data, opts)
 	}
 
 	fn get<'a>(
@@ -147,6 +308,22 @@ impl ObjectStore for Arc<dyn ObjectStore> {
 		(**self).get(key)
 	}
 
+	fn get_range<'a>(
+		&'a self,
+		key: &'a ObjectKey,
+		range: Range<u64>,
+	) -> Pin<Box<dyn Future<Output = Result<Option<Bytes>, String>> + Send + 'a>> {
+		(**self).get_range(key, range)
+	}
+
+	fn get_stream<'a>(
+		&'a self,
+		key: &'a ObjectKey,
+		range: Option<Range<u64>>,
+	) -> Pin<Box<dyn Future<Output = Result<Option<ObjectStream>, String>> + Send + 'a>> {
+		(**self).get_stream(key, range)
+	}
+
 	fn head<'a>(
 		&'a self,
 		key: &'a ObjectKey,
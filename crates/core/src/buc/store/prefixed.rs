@@ -1,6 +1,6 @@
-use super::{ListOptions, ObjectKey, ObjectMeta, ObjectStore};
+use super::{ListOptions, ObjectKey, ObjectMeta, ObjectStore, ObjectStream, PutOptions};
 use bytes::Bytes;
-use std::{future::Future, pin::Pin};
+use std::{future::Future, ops::Range, pin::Pin};
 
 #[derive(Clone, Debug)]
 pub struct PrefixedStore<T: ObjectStore> {
@@ -22,20 +22,33 @@ impl<T: ObjectStore> ObjectStore for PrefixedStore<T> {
 		&'a self,
 		key: &'a ObjectKey,
 		data: Bytes,
+		opts: &'a PutOptions,
 	) -> Pin<Box<dyn Future<Output = Result<(), String>> + Send + 'a>> {
 		let full_key = self.prefix.join(key);
 
-		Box::pin(async move { self.store.put(&full_key, data).await })
+		Box::pin(async move { self.store.put(&full_key, data, opts).await })
+	}
+
+	fn put_stream<'a>(
+		&'a self,
+		key: &'a ObjectKey,
+		stream: ObjectStream,
+		opts: &'a PutOptions,
+	) -> Pin<Box<dyn Future<Output = Result<(), String>> + Send + 'a>> {
+		let full_key = self.prefix.join(key);
+
+		Box::pin(async move { self.store.put_stream(&full_key, stream, opts).await })
 	}
 
 	fn put_if_not_exists<'a>(
 		&'a self,
 		key: &'a ObjectKey,
 		data: Bytes,
+		opts: &'a PutOptions,
 	) -> Pin<Box<dyn Future<Output = Result<(), String>> + Send + 'a>> {
 		let full_key = self.prefix.join(key);
 
-		Box::pin(async move { self.store.put_if_not_exists(&full_key, data).await })
+		Box::pin(async move { self.store.put_if_not_exists(&full_key, data, opts).await })
 	}
 
 	fn get<'a>(
@@ -47,6 +60,26 @@ impl<T: ObjectStore> ObjectStore for PrefixedStore<T> {
 		Box::pin(async move { self.store.get(&full_key).await })
 	}
 
+	fn get_range<'a>(
+		&'a self,
+		key: &'a ObjectKey,
+		range: Range<u64>,
+	) -> Pin<Box<dyn Future<Output = Result<Option<Bytes>, String>> + Send + 'a>> {
+		let full_key = self.prefix.join(key);
+
+		Box::pin(async move { self.store.get_range(&full_key, range).await })
+	}
+
+	fn get_stream<'a>(
+		&'a self,
+		key: &'a ObjectKey,
+		range: Option<Range<u64>>,
+	) -> Pin<Box<dyn Future<Output = Result<Option<ObjectStream>, String>> + Send + 'a>> {
+		let full_key = self.prefix.join(key);
+
+		Box::pin(async move { self.store.get_stream(&full_key, range).await })
+	}
+
 	fn head<'a>(
 		&'a self,
 		key: &'a ObjectKey,
@@ -0,0 +1,809 @@
+use std::{borrow::Cow, future::Future, ops::Range, pin::Pin};
+
+use bytes::{Bytes, BytesMut};
+use chrono::{DateTime, Utc};
+use futures::{StreamExt, TryStreamExt};
+use percent_encoding::percent_decode_str;
+use s3::{
+	command::Command,
+	creds::Credentials,
+	error::S3Error,
+	request::{tokio_backend::HyperRequest, Request},
+	Bucket, Region,
+};
+use tokio_util::io::StreamReader;
+use url::Url;
+
+use crate::{err::Error, sql::Datetime};
+
+use super::{
+	ListOptions, ObjectAttributes, ObjectKey, ObjectMeta, ObjectStore, ObjectStream, PutOptions,
+};
+
+/// The maximum number of keys requested from S3 in a single list call
+const LIST_PAGE_SIZE: usize = 1000;
+
+/// The content type S3 assumes when none is specified
+const DEFAULT_CONTENT_TYPE: &str = "application/octet-stream";
+
+/// Options for configuring the S3Store
+#[derive(Clone, Debug)]
+pub struct S3StoreOptions {
+	bucket: String,
+	root: ObjectKey,
+	region: Region,
+	credentials: Credentials,
+	path_style: bool,
+}
+
+/// A store implementation that uses an S3-compatible object storage service
+#[derive(Clone, Debug)]
+pub struct S3Store {
+	bucket: Box<Bucket>,
+	root: ObjectKey,
+}
+
+impl S3Store {
+	/// Create a new S3Store with the given options
+	pub fn new(options: S3StoreOptions) -> Result<Self, Error> {
+		let bucket = Bucket::new(&options.bucket, options.region, options.credentials)
+			.map_err(|e| Error::BucketConnectionFailed(e.to_string()))?;
+
+		let bucket = if options.path_style {
+			bucket.with_path_style()
+		} else {
+			bucket
+		};
+
+		Ok(S3Store {
+			bucket,
+			root: options.root,
+		})
+	}
+
+	/// Parse a URL into S3StoreOptions
+	///
+	/// The URL takes the form `s3://bucket/optional/prefix`, and accepts
+	/// the `region`, `endpoint` and `path_style` query options. Credentials
+	/// are only accepted in the URL for the global bucket, which is part of
+	/// the server configuration. Otherwise they are read from the standard
+	/// AWS environment variables or profile of the server process.
+	pub fn parse_url(url: &str, global: bool) -> Result<Option<S3StoreOptions>, Error> {
+		let Ok(url) = Url::parse(url) else {
+			return Ok(None);
+		};
+
+		if url.scheme() != "s3" {
+			return Ok(None);
+		}
+
+		// The host portion of the URL is the bucket name
+		let bucket = match url.host_str() {
+			Some(bucket) if !bucket.is_empty() => bucket.to_string(),
+			_ => {
+				return Err(Error::InvalidBucketUrl(
+					"Expected to find a bucket name in the S3 url".to_string(),
+				))
+			}
+		};
+
+		// The path portion of the URL is an optional key prefix
+		let root = match url.path().trim_matches('/') {
+			"" => ObjectKey::from(""),
+			path => ObjectKey::from(path.to_string()),
+		};
+
+		let mut region = None;
+		let mut endpoint = None;
+		let mut path_style = None;
+
+		for (key, value) in url.query_pairs() {
+			match key.as_ref() {
+				"region" => region = Some(value.to_string()),
+				"endpoint" => endpoint = Some(value.to_string()),
+				"path_style" => {
+					path_style = Some(if value.is_empty() {
+						true
+					} else {
+						value.parse().map_err(|_| {
+							Error::InvalidBucketUrl(
+								"Expected to find a bool for query option `path_style`".to_string(),
+							)
+						})?
+					})
+				}
+				_ => {
+					return Err(Error::InvalidBucketUrl(format!(
+						"Unknown query option `{key}` for S3 url"
+					)))
+				}
+			}
+		}
+
+		// Custom endpoints are typically S3-compatible services, which
+		// rarely support virtual-hosted style requests, so default to
+		// path-style requests when an endpoint is specified
+		let path_style = path_style.unwrap_or(endpoint.is_some());
+
+		let region = match endpoint {
+			Some(endpoint) => Region::Custom {
+				region: region.unwrap_or_else(|| "us-east-1".to_string()),
+				endpoint,
+			},
+			None => region
+				.as_deref()
+				.unwrap_or("us-east-1")
+				.parse()
+				.map_err(|e| Error::InvalidBucketUrl(format!("Invalid S3 region: {e}")))?,
+		};
+
+		let credentials = if !url.username().is_empty() || url.password().is_some() {
+			// Credentials in a bucket definition would be stored in plaintext
+			if !global {
+				return Err(Error::InvalidBucketUrl(
+					"Credentials can only be specified in the url of the global bucket".to_string(),
+				));
+			}
+
+			let access_key = percent_decode(url.username());
+			let secret_key = url.password().map(percent_decode);
+
+			Credentials::new(Some(&access_key), secret_key.as_deref(), None, None, None)
+				.map_err(|e| Error::InvalidBucketUrl(format!("Invalid S3 credentials: {e}")))?
+		} else {
+			// Fall back to anonymous access when no credentials are configured
+			Credentials::from_env()
+				.or_else(|_| Credentials::from_profile(None))
+				.or_else(|_| Credentials::anonymous())
+				.map_err(|e| Error::BucketConnectionFailed(e.to_string()))?
+		};
+
+		Ok(Some(S3StoreOptions {
+			bucket,
+			root,
+			region,
+			credentials,
+			path_style,
+		}))
+	}
+
+	/// Convert an object key into the path of the object in the S3 bucket
+	fn to_s3_path(&self, key: &ObjectKey) -> String {
+		self.root.join(key).to_string()
+	}
+
+	/// Convert a key from the S3 bucket back into an object key
+	fn to_object_key(&self, key: &str) -> ObjectKey {
+		let key = ObjectKey::from(key);
+		key.strip_prefix(&self.root).unwrap_or(key)
+	}
+
+	/// Get a bucket handle which sends the user metadata of an object as headers
+	fn bucket_for(&self, opts: &PutOptions) -> Cow<'_, Bucket> {
+		if opts.attributes.metadata.is_empty() {
+			return Cow::Borrowed(&self.bucket);
+		}
+
+		// The metadata keys and values were validated as header-safe when parsed
+		let mut bucket = self.bucket.as_ref().clone();
+		for (key, value) in opts.attributes.metadata.iter() {
+			bucket.add_header(&format!("x-amz-meta-{key}"), value);
+		}
+
+		Cow::Owned(bucket)
+	}
+}
+
+/// Decode a percent-encoded URL component
+fn percent_decode(input: &str) -> String {
+	percent_decode_str(input).decode_utf8_lossy().into_owned()
+}
+
+/// Check if an S3 error represents a missing object
+fn is_not_found(err: &S3Error) -> bool {
+	matches!(err, S3Error::HttpFailWithBody(404, _))
+}
+
+/// Check if an S3 error represents a conditional write whose target already exists
+fn is_precondition_failed(err: &S3Error) -> bool {
+	matches!(err, S3Error::HttpFailWithBody(412, _))
+}
+
+/// Get a bucket handle whose writes are rejected if the target object already exists.
+///
+/// The check is performed atomically by the backend, so that concurrent writers can't
+/// overwrite each other. Backends which do not support conditional writes ignore it.
+fn if_not_exists(bucket: &Bucket) -> Bucket {
+	let mut bucket = bucket.clone();
+	bucket.add_header("If-None-Match", "*");
+	bucket
+}
+
+/// Strip the quotes from an entity tag returned by S3
+fn parse_etag(input: &str) -> String {
+	input.trim_matches('"').to_string()
+}
+
+/// Parse a timestamp returned by S3, which can be either RFC 3339 or RFC 2822
+fn parse_timestamp(input: Option<&str>) -> Datetime {
+	input
+		.and_then(|x| {
+			DateTime::parse_from_rfc3339(x).or_else(|_| DateTime::parse_from_rfc2822(x)).ok()
+		})
+		.map(|x| Datetime(x.with_timezone(&Utc)))
+		.unwrap_or_default()
+}
+
+impl ObjectStore for S3Store {
+	fn put<'a>(
+		&'a self,
+		key: &'a ObjectKey,
+		data: Bytes,
+		opts: &'a PutOptions,
+	) -> Pin<Box<dyn Future<Output = Result<(), String>> + Send + 'a>> {
+		Box::pin(async move {
+			let content_type =
+				opts.attributes.content_type.as_deref().unwrap_or(DEFAULT_CONTENT_TYPE);
+
+			self.bucket_for(opts)
+				.put_object_with_content_type(self.to_s3_path(key), &data, content_type)
+				.await
+				.map_err(|e| format!("Failed to put object: {}", e))?;
+
+			Ok(())
+		})
+	}
+
+	fn put_stream<'a>(
+		&'a self,
+		key: &'a ObjectKey,
+		stream: ObjectStream,
+		opts: &'a PutOptions,
+	) -> Pin<Box<dyn Future<Output = Result<(), String>> + Send + 'a>> {
+		Box::pin(async move {
+			// Large objects are uploaded in parts as the stream is consumed
+			let mut reader = StreamReader::new(
+				stream.map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e)),
+			);
+
+			let content_type =
+				opts.attributes.content_type.as_deref().unwrap_or(DEFAULT_CONTENT_TYPE);
+
+			self.bucket_for(opts)
+				.put_object_stream_with_content_type(
+					&mut reader,
+					self.to_s3_path(key),
+					content_type,
+				)
+				.await
+				.map_err(|e| format!("Failed to put object: {}", e))?;
+
+			Ok(())
+		})
+	}
+
+	fn put_if_not_exists<'a>(
+		&'a self,
+		key: &'a ObjectKey,
+		data: Bytes,
+		opts: &'a PutOptions,
+	) -> Pin<Box<dyn Future<Output = Result<(), String>> + Send + 'a>> {
+		Box::pin(async move {
+			let content_type =
+				opts.attributes.content_type.as_deref().unwrap_or(DEFAULT_CONTENT_TYPE);
+
+			// The backend rejects the write if the object already exists
+			match if_not_exists(&self.bucket_for(opts))
+				.put_object_with_content_type(self.to_s3_path(key), &data, content_type)
+				.await
+			{
+				Ok(_) => Ok(()),
+				Err(e) if is_precondition_failed(&e) => Ok(()),
+				Err(e) => Err(format!("Failed to put object: {}", e)),
+			}
+		})
+	}
+
+	fn get<'a>(
+		&'a self,
+		key: &'a ObjectKey,
+	) -> Pin<Box<dyn Future<Output = Result<Option<Bytes>, String>> + Send + 'a>> {
+		Box::pin(async move {
+			match self.bucket.get_object(self.to_s3_path(key)).await {
+				Ok(res) => Ok(Some(res.bytes().clone())),
+				Err(e) if is_not_found(&e) => Ok(None),
+				Err(e) => Err(format!("Failed to get object: {}", e)),
+			}
+		})
+	}
+
+	fn get_range<'a>(
+		&'a self,
+		key: &'a ObjectKey,
+		range: Range<u64>,
+	) -> Pin<Box<dyn Future<Output = Result<Option<Bytes>, String>> + Send + 'a>> {
+		Box::pin(async move {
+			let Some(mut stream) = self.get_stream(key, Some(range)).await? else {
+				return Ok(None);
+			};
+
+			let mut data = BytesMut::new();
+			while let Some(chunk) = stream.next().await {
+				data.extend_from_slice(&chunk?);
+			}
+
+			Ok(Some(data.freeze()))
+		})
+	}
+
+	fn get_stream<'a>(
+		&'a self,
+		key: &'a ObjectKey,
+		range: Option<Range<u64>>,
+	) -> Pin<Box<dyn Future<Output = Result<Option<ObjectStream>, String>> + Send + 'a>> {
+		Box::pin(async move {
+			let command = match range {
+				// An empty range can not be expressed in a Range header
+				Some(range) if range.is_empty() => {
+					return Ok(self.exists(key).await?.then(|| futures::stream::empty().boxed()));
+				}
+				// S3 ranges are inclusive of the end offset
+				Some(range) => Command::GetObjectRange {
+					start: range.start,
+					end: Some(range.end - 1),
+				},
+				None => Command::GetObject,
+			};
+
+			let path = self.to_s3_path(key);
+			let res = match HyperRequest::new(&self.bucket, &path, command).await {
+				Ok(req) => req.response_data_to_stream().await,
+				Err(e) => Err(e),
+			};
+
+			match res {
+				Ok(res) => {
+					Ok(Some(res.bytes.map_err(|e| format!("Failed to read object: {}", e)).boxed()))
+				}
+				Err(e) if is_not_found(&e) => Ok(None),
+				// The requested range starts beyond the end of the object
+				Err(S3Error::HttpFailWithBody(416, _)) => {
+					Ok(Some(futures::stream::empty().boxed()))
+				}
+				Err(e) => Err(format!("Failed to get object: {}", e)),
+			}
+		})
+	}
+
+	fn head<'a>(
+		&'a self,
+		key: &'a ObjectKey,
+	) -> Pin<Box<dyn Future<Output = Result<Option<ObjectMeta>, String>> + Send + 'a>> {
+		Box::pin(async move {
+			match self.bucket.head_object(self.to_s3_path(key)).await {
+				Ok((res, _)) => Ok(Some(ObjectMeta {
+					size: res.content_length.unwrap_or_default() as u64,
+					updated: parse_timestamp(res.last_modified.as_deref()),
+					key: key.to_owned(),
+					etag: res.e_tag.as_deref().map(parse_etag),
+					attributes: ObjectAttributes {
+						content_type: res.content_type,
+						metadata: res
+							.metadata
+							.unwrap_or_default()
+							.into_iter()
+							.map(|(k, v)| (k.to_lowercase(), v))
+							.collect(),
+					},
+				})),
+				Err(e) if is_not_found(&e) => Ok(None),
+				Err(e) => Err(format!("Failed to get metadata: {}", e)),
+			}
+		})
+	}
+
+	fn delete<'a>(
+		&'a self,
+		key: &'a ObjectKey,
+	) -> Pin<Box<dyn Future<Output = Result<(), String>> + Send + 'a>> {
+		Box::pin(async move {
+			match self.bucket.delete_object(self.to_s3_path(key)).await {
+				Ok(_) => Ok(()),
+				Err(e) if is_not_found(&e) => Ok(()),
+				Err(e) => Err(format!("Failed to delete object: {}", e)),
+			}
+		})
+	}
+
+	fn exists<'a>(
+		&'a self,
+		key: &'a ObjectKey,
+	) -> Pin<Box<dyn Future<Output = Result<bool, String>> + Send + 'a>> {
+		Box::pin(async move { Ok(self.head(key).await?.is_some()) })
+	}
+
+	fn copy<'a>(
+		&'a self,
+		key: &'a ObjectKey,
+		target: &'a ObjectKey,
+	) -> Pin<Box<dyn Future<Output = Result<(), String>> + Send + 'a>> {
+		Box::pin(async move {
+			match self
+				.bucket
+				.copy_object_internal(self.to_s3_path(key), self.to_s3_path(target))
+				.await
+			{
+				Ok(_) => Ok(()),
+				Err(e) if is_not_found(&e) => Err(format!("Source object does not exist: {}", key)),
+				Err(e) => Err(format!("Failed to copy object: {}", e)),
+			}
+		})
+	}
+
+	fn copy_if_not_exists<'a>(
+		&'a self,
+		key: &'a ObjectKey,
+		target: &'a ObjectKey,
+	) -> Pin<Box<dyn Future<Output = Result<(), String>> + Send + 'a>> {
+		Box::pin(async move {
+			// Check if target already exists, for backends without conditional copies
+			if self.exists(target).await? {
+				return Ok(());
+			}
+
+			// The backend rejects the copy if the target was created in the meantime
+			match if_not_exists(&self.bucket)
+				.copy_object_internal(self.to_s3_path(key), self.to_s3_path(target))
+				.await
+			{
+				Ok(_) => Ok(()),
+				Err(e) if is_precondition_failed(&e) => Ok(()),
+				Err(e) if is_not_found(&e) => Err(format!("Source object does not exist: {}", key)),
+				Err(e) => Err(format!("Failed to copy object: {}", e)),
+			}
+		})
+	}
+
+	fn rename<'a>(
+		&'a self,
+		key: &'a ObjectKey,
+		target: &'a ObjectKey,
+	) -> Pin<Box<dyn Future<Output = Result<(), String>> + Send + 'a>> {
+		Box::pin(async move {
+			// S3 has no native rename, so copy the object and remove the source
+			match self
+				.bucket
+				.copy_object_internal(self.to_s3_path(key), self.to_s3_path(target))
+				.await
+			{
+				Ok(_) => (),
+				Err(e) if is_not_found(&e) => {
+					return Err(format!("Source object does not exist: {}", key));
+				}
+				Err(e) => return Err(format!("Failed to rename object: {}", e)),
+			}
+
+			self.delete(key).await
+		})
+	}
+
+	fn rename_if_not_exists<'a>(
+		&'a self,
+		key: &'a ObjectKey,
+		target: &'a ObjectKey,
+	) -> Pin<Box<dyn Future<Output = Result<(), String>> + Send + 'a>> {
+		Box::pin(async move {
+			// Check if target already exists, for backends without conditional copies
+			if self.exists(target).await? {
+				return Ok(());
+			}
+
+			// The backend rejects the copy if the target was created in the meantime,
+			// in which case the source is left in place
+			match if_not_exists(&self.bucket)
+				.copy_object_internal(self.to_s3_path(key), self.to_s3_path(target))
+				.await
+			{
+				Ok(_) => (),
+				Err(e) if is_precondition_failed(&e) => return Ok(()),
+				Err(e) if is_not_found(&e) => {
+					return Err(format!("Source object does not exist: {}", key));
+				}
+				Err(e) => return Err(format!("Failed to rename object: {}", e)),
+			}
+
+			self.delete(key).await
+		})
+	}
+
+	fn list<'a>(
+		&'a self,
+		opts: &'a ListOptions,
+	) -> Pin<Box<dyn Future<Output = Result<Vec<ObjectMeta>, String>> + Send + 'a>> {
+		Box::pin(async move {
+			// Combine the store's root with the request prefix
+			let prefix = match opts.prefix {
+				Some(ref prefix) => self.root.join(prefix),
+				None => self.root.join(&ObjectKey::from("")),
+			};
+			let prefix = prefix.trim_start_matches('/').to_string();
+
+			// S3 lists keys after the start key, which matches our semantics
+			let mut start_after =
+				opts.start.as_ref().map(|s| self.to_s3_path(s).trim_start_matches('/').to_string());
+
+			let mut objects = Vec::new();
+			let mut continuation_token = None;
+
+			loop {
+				// Only request as many keys as we still need
+				let max_keys = match opts.limit {
+					Some(limit) => (limit - objects.len()).min(LIST_PAGE_SIZE),
+					None => LIST_PAGE_SIZE,
+				};
+
+				if max_keys == 0 {
+					break;
+				}
+
+				let (res, _) = self
+					.bucket
+					.list_page(
+						prefix.clone(),
+						None,
+						continuation_token.take(),
+						start_after.take(),
+						Some(max_keys),
+					)
+					.await
+					.map_err(|e| format!("Failed to list objects: {}", e))?;
+
+				// Listings do not include the content type or user metadata of objects
+				objects.extend(res.contents.into_iter().map(|x| ObjectMeta {
+					size: x.size,
+					updated: parse_timestamp(Some(&x.last_modified)),
+					key: self.to_object_key(&x.key),
+					etag: x.e_tag.as_deref().map(parse_etag),
+					attributes: ObjectAttributes::default(),
+				}));
+
+				match res.next_continuation_token {
+					Some(token) if res.is_truncated => continuation_token = Some(token),
+					_ => break,
+				}
+			}
+
+			Ok(objects)
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use wiremock::matchers::{header, method, path, query_param};
+	use wiremock::{Mock, MockServer, ResponseTemplate};
+
+	async fn store(server: &MockServer, root: &str) -> S3Store {
+		let url = format!("s3://test-bucket{root}?endpoint={}", server.uri());
+		let opts = S3Store::parse_url(&url, false).unwrap().unwrap();
+		S3Store::new(opts).unwrap()
+	}
+
+	#[test]
+	fn parse_url_ignores_other_schemes() {
+		assert!(S3Store::parse_url("memory", false).unwrap().is_none());
+		assert!(S3Store::parse_url("file:///tmp/bucket", false).unwrap().is_none());
+	}
+
+	#[test]
+	fn parse_url_options() {
+		let opts =
+			S3Store::parse_url("s3://bucket/some/prefix?region=eu-west-2", false).unwrap().unwrap();
+		assert_eq!(opts.bucket, "bucket");
+		assert_eq!(opts.root.as_str(), "/some/prefix");
+		assert_eq!(opts.region, Region::EuWest2);
+		assert!(!opts.path_style);
+
+		let opts = S3Store::parse_url("s3://bucket?endpoint=http://127.0.0.1:9000", false)
+			.unwrap()
+			.unwrap();
+		assert_eq!(opts.root.as_str(), "/");
+		assert!(opts.path_style);
+		assert!(matches!(opts.region, Region::Custom { .. }));
+
+		let res = S3Store::parse_url("s3://bucket?unknown=1", false);
+		assert!(matches!(res, Err(Error::InvalidBucketUrl(_))));
+
+		let res = S3Store::parse_url("s3:///prefix", false);
+		assert!(matches!(res, Err(Error::InvalidBucketUrl(_))));
+	}
+
+	#[test]
+	fn parse_url_credentials() {
+		// Credentials are rejected for buckets defined in the database
+		let res = S3Store::parse_url("s3://key:secret@bucket", false);
+		assert!(matches!(res, Err(Error::InvalidBucketUrl(_))));
+		// Credentials are accepted for the global bucket
+		let opts = S3Store::parse_url("s3://key:s%2Fcret@bucket", true).unwrap().unwrap();
+		assert_eq!(opts.credentials.access_key.as_deref(), Some("key"));
+		assert_eq!(opts.credentials.secret_key.as_deref(), Some("s/cret"));
+	}
+
+	#[tokio::test]
+	async fn get_and_head() {
+		let server = MockServer::start().await;
+		Mock::given(method("GET"))
+			.and(path("/test-bucket/root/file.txt"))
+			.respond_with(ResponseTemplate::new(200).set_body_bytes("hello"))
+			.mount(&server)
+			.await;
+		Mock::given(method("HEAD"))
+			.and(path("/test-bucket/root/file.txt"))
+			.respond_with(
+				ResponseTemplate::new(200)
+					.insert_header("content-length", "5")
+					.insert_header("last-modified", "Wed, 21 Oct 2015 07:28:00 GMT")
+					.insert_header("etag", "\"5d41402abc4b2a76b9719d911017c592\"")
+					.insert_header("content-type", "text/plain")
+					.insert_header("x-amz-meta-author", "tobie"),
+			)
+			.mount(&server)
+			.await;
+		Mock::given(method("GET"))
+			.and(path("/test-bucket/root/missing.txt"))
+			.respond_with(ResponseTemplate::new(404))
+			.mount(&server)
+			.await;
+		Mock::given(method("HEAD"))
+			.and(path("/test-bucket/root/missing.txt"))
+			.respond_with(ResponseTemplate::new(404))
+			.mount(&server)
+			.await;
+
+		let store = store(&server, "/root").await;
+		let key = ObjectKey::from("file.txt");
+		let missing = ObjectKey::from("missing.txt");
+
+		assert_eq!(store.get(&key).await.unwrap(), Some(Bytes::from("hello")));
+		assert_eq!(store.get(&missing).await.unwrap(), None);
+
+		let meta = store.head(&key).await.unwrap().unwrap();
+		assert_eq!(meta.size, 5);
+		assert_eq!(meta.key.as_str(), "/file.txt");
+		assert_eq!(meta.updated.to_raw(), "2015-10-21T07:28:00Z");
+		assert_eq!(meta.etag.as_deref(), Some("5d41402abc4b2a76b9719d911017c592"));
+		assert_eq!(meta.attributes.content_type.as_deref(), Some("text/plain"));
+		assert_eq!(meta.attributes.metadata.get("author").map(String::as_str), Some("tobie"));
+		assert!(store.head(&missing).await.unwrap().is_none());
+		assert!(!store.exists(&missing).await.unwrap());
+	}
+
+	#[tokio::test]
+	async fn put_sends_attributes() {
+		let server = MockServer::start().await;
+		Mock::given(method("PUT"))
+			.and(path("/test-bucket/file.txt"))
+			.and(header("content-type", "text/plain"))
+			.and(header("x-amz-meta-author", "tobie"))
+			.respond_with(ResponseTemplate::new(200))
+			.expect(1)
+			.mount(&server)
+			.await;
+
+		let store = store(&server, "").await;
+		let key = ObjectKey::from("file.txt");
+		let mut opts = PutOptions::default();
+		opts.attributes.content_type = Some("text/plain".to_string());
+		opts.attributes.metadata.insert("author".to_string(), "tobie".to_string());
+		store.put(&key, Bytes::from("hello"), &opts).await.unwrap();
+	}
+
+	#[tokio::test]
+	async fn put_if_not_exists_skips_existing() {
+		let server = MockServer::start().await;
+		// The backend rejects the conditional write, as the object exists
+		Mock::given(method("PUT"))
+			.and(path("/test-bucket/file.txt"))
+			.and(header("if-none-match", "*"))
+			.respond_with(ResponseTemplate::new(412))
+			.expect(1)
+			.mount(&server)
+			.await;
+
+		let store = store(&server, "").await;
+		let key = ObjectKey::from("file.txt");
+		store.put_if_not_exists(&key, Bytes::from("hello"), &PutOptions::default()).await.unwrap();
+	}
+
+	#[tokio::test]
+	async fn rename_if_not_exists_keeps_source() {
+		let server = MockServer::start().await;
+		Mock::given(method("HEAD"))
+			.and(path("/test-bucket/target.txt"))
+			.respond_with(ResponseTemplate::new(404))
+			.mount(&server)
+			.await;
+		// The target is created by another writer before the copy is made
+		Mock::given(method("PUT"))
+			.and(path("/test-bucket/target.txt"))
+			.and(header("if-none-match", "*"))
+			.respond_with(ResponseTemplate::new(412))
+			.expect(2)
+			.mount(&server)
+			.await;
+		Mock::given(method("DELETE"))
+			.respond_with(ResponseTemplate::new(204))
+			.expect(0)
+			.mount(&server)
+			.await;
+
+		let store = store(&server, "").await;
+		let key = ObjectKey::from("source.txt");
+		let target = ObjectKey::from("target.txt");
+		store.copy_if_not_exists(&key, &target).await.unwrap();
+		store.rename_if_not_exists(&key, &target).await.unwrap();
+	}
+
+	#[tokio::test]
+	async fn copy_and_rename_missing_source() {
+		let server = MockServer::start().await;
+		Mock::given(method("HEAD"))
+			.and(path("/test-bucket/target.txt"))
+			.respond_with(ResponseTemplate::new(404))
+			.mount(&server)
+			.await;
+		Mock::given(method("PUT"))
+			.and(path("/test-bucket/target.txt"))
+			.respond_with(ResponseTemplate::new(404).set_body_string("NoSuchKey"))
+			.mount(&server)
+			.await;
+
+		let store = store(&server, "").await;
+		let key = ObjectKey::from("source.txt");
+		let target = ObjectKey::from("target.txt");
+		// Every operation reports the missing source in the same way as the other backends
+		let err = "Source object does not exist: /source.txt";
+		assert_eq!(store.copy(&key, &target).await.unwrap_err(), err);
+		assert_eq!(store.copy_if_not_exists(&key, &target).await.unwrap_err(), err);
+		assert_eq!(store.rename(&key, &target).await.unwrap_err(), err);
+		assert_eq!(store.rename_if_not_exists(&key, &target).await.unwrap_err(), err);
+	}
+
+	#[tokio::test]
+	async fn list_strips_root() {
+		let server = MockServer::start().await;
+		let body = r#"<?xml version="1.0" encoding="UTF-8"?>
+<ListBucketResult>
+	<Name>test-bucket</Name>
+	<Prefix>root/dir</Prefix>
+	<IsTruncated>false</IsTruncated>
+	<Contents>
+		<Key>root/dir/a.txt</Key>
+		<LastModified>2015-10-21T07:28:00.000Z</LastModified>
+		<Size>3</Size>
+	</Contents>
+	<Contents>
+		<Key>root/dir/b.txt</Key>
+		<LastModified>2015-10-21T07:28:00.000Z</LastModified>
+		<Size>4</Size>
+	</Contents>
+</ListBucketResult>"#;
+		Mock::given(method("GET"))
+			.and(path("/test-bucket/"))
+			.and(query_param("prefix", "root/dir"))
+			.respond_with(ResponseTemplate::new(200).set_body_string(body))
+			.mount(&server)
+			.await;
+
+		let store = store(&server, "/root").await;
+		let opts = ListOptions {
+			prefix: Some(ObjectKey::from("dir")),
+			..Default::default()
+		};
+		let list = store.list(&opts).await.unwrap();
+		let keys: Vec<_> = list.iter().map(|x| x.key.as_str()).collect();
+		assert_eq!(keys, vec!["/dir/a.txt", "/dir/b.txt"]);
+		assert_eq!(list[1].size, 4);
+	}
+}
@@ -0,0 +1,38 @@
+use md5::{Digest, Md5};
+
+/// Incrementally computes the entity tag of an object, as the hex encoded
+/// MD5 digest of its contents, matching the ETag of single-part S3 uploads
+#[derive(Default)]
+pub struct ETagHasher(Md5);
+
+impl ETagHasher {
+	pub fn update(&mut self, data: &[u8]) {
+		self.0.update(data);
+	}
+
+	pub fn finish(self) -> String {
+		format!("{:x}", self.0.finalize())
+	}
+}
+
+/// Computes the entity tag for the full contents of an object
+pub fn etag(data: &[u8]) -> String {
+	let mut hasher = ETagHasher::default();
+	hasher.update(data);
+	hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn etag_matches_incremental_hash() {
+		assert_eq!(etag(b"hello"), "5d41402abc4b2a76b9719d911017c592");
+
+		let mut hasher = ETagHasher::default();
+		hasher.update(b"hel");
+		hasher.update(b"lo");
+		assert_eq!(hasher.finish(), etag(b"hello"));
+	}
+}
@@ -1,2 +1,6 @@
+mod etag;
+pub use etag::{etag, ETagHasher};
 mod path;
 pub use path::ObjectKey;
+mod range;
+pub use range::clamp_range;
@@ -0,0 +1,20 @@
+use std::ops::Range;
+
+/// Truncate a byte range so that it fits within an object of the given size
+pub fn clamp_range(range: Range<u64>, size: u64) -> Range<u64> {
+	let end = range.end.min(size);
+	range.start.min(end)..end
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn clamp_range_truncates() {
+		assert_eq!(clamp_range(0..10, 20), 0..10);
+		assert_eq!(clamp_range(5..30, 20), 5..20);
+		assert_eq!(clamp_range(25..30, 20), 20..20);
+		assert_eq!(clamp_range(0..0, 20), 0..0);
+	}
+}
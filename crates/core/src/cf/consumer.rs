@@ -0,0 +1,118 @@
+use crate::cf::{self, ChangeSet};
+use crate::err::Error;
+use crate::key::database::cu;
+use crate::kvs::Transaction;
+use crate::sql::statements::show::ShowSince;
+use crate::vs::VersionStamp;
+use chrono::Utc;
+use revision::revisioned;
+use serde::{Deserialize, Serialize};
+
+/// The acknowledged position of a named change feed consumer
+#[revisioned(revision = 1)]
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct Cursor {
+	/// The versionstamp of the last acknowledged change set
+	pub vs: u64,
+	/// The time, in seconds, at which the consumer last read changes
+	pub ts: u64,
+}
+
+// reset moves the cursor of a consumer to the latest versionstamp of the database,
+// so that the consumer only sees changes which are made after this point.
+pub(crate) async fn reset(tx: &Transaction, ns: &str, db: &str, cn: &str) -> Result<(), Error> {
+	let vs = latest(tx, ns, db).await?;
+	save(tx, ns, db, cn, vs).await
+}
+
+// read returns the change sets for the table of a consumer which it has not yet acknowledged.
+// A read does not acknowledge the changes it returns, so they are returned again until the
+// consumer confirms it has processed them, which gives at-least-once delivery. The consumer
+// confirms by reading since the versionstamp following the last change it has processed,
+// as returned with the changes, which acknowledges every change before that versionstamp.
+// A `since` of 0 acknowledges nothing. Changes which only touched other tables are
+// acknowledged automatically, as the consumer never sees them.
+pub(crate) async fn read(
+	tx: &Transaction,
+	ns: &str,
+	db: &str,
+	cn: &str,
+	since: u64,
+	limit: Option<u32>,
+) -> Result<Vec<ChangeSet>, Error> {
+	// Fetch the consumer definition
+	let cn = tx.get_db_consumer(ns, db, cn).await?;
+	// Fetch the acknowledged position
+	let mut cur = match tx.get(cu::new(ns, db, &cn.name), None).await? {
+		Some(v) => revision::from_slice::<Cursor>(&v)?,
+		None => Cursor::default(),
+	};
+	// Acknowledge the changes which the consumer has processed. The position never moves
+	// backwards, as acknowledged changes may have been garbage collected, and never moves
+	// past the latest change, so that changes which have not been made yet are not skipped.
+	if since > 0 {
+		let processed = VersionStamp::try_from_u128(since as u128 - 1)?.into_u64_lossy();
+		let latest = latest(tx, ns, db).await?;
+		cur.vs = cur.vs.max(processed.min(latest));
+	}
+	// Read the next batch of complete change sets to the table of the consumer
+	let since = ShowSince::Versionstamp(cur.vs + 1);
+	let batch = cf::read_batch(tx, ns, db, Some(&cn.what.0), since, limit).await?;
+	// The change sets before the first one which is returned only changed other tables, so
+	// they are acknowledged, otherwise the consumer could never move past a batch which only
+	// contains changes to other tables, and would hold back garbage collection of the database.
+	let skipped = match batch.changes.first() {
+		Some(ChangeSet(vs, _)) => vs.into_u64_lossy().checked_sub(1),
+		None => batch.last.map(|vs| vs.into_u64_lossy()),
+	};
+	if let Some(vs) = skipped {
+		cur.vs = cur.vs.max(vs);
+	}
+	// Persist the acknowledged position, and record that the consumer is active
+	save(tx, ns, db, &cn.name, cur.vs).await?;
+	// Return the changes to the table of the consumer
+	let changes = batch.changes;
+	Ok(changes)
+}
+
+// watermark returns the versionstamp before which all changes have been acknowledged
+// by every active consumer in the database. A consumer which has not read any
+// changes within the changefeed expiry is inactive, and does not hold back garbage collection.
+pub(crate) async fn watermark(
+	tx: &Transaction,
+	ns: &str,
+	db: &str,
+	ts: u64,
+	expiry: u64,
+) -> Result<Option<VersionStamp>, Error> {
+	let mut res: Option<u64> = None;
+	for cn in tx.all_db_consumers(ns, db).await?.iter() {
+		if let Some(v) = tx.get(cu::new(ns, db, &cn.name), None).await? {
+			let cur: Cursor = revision::from_slice(&v)?;
+			// Ignore any inactive consumers
+			if cur.ts.saturating_add(expiry) < ts {
+				continue;
+			}
+			let vs = cur.vs + 1;
+			res = Some(res.map_or(vs, |v| v.min(vs)));
+		}
+	}
+	Ok(res.map(VersionStamp::from_u64))
+}
+
+// latest returns the versionstamp of the latest change to the database
+async fn latest(tx: &Transaction, ns: &str, db: &str) -> Result<u64, Error> {
+	match tx.get(crate::key::database::vs::new(ns, db), None).await? {
+		Some(v) => Ok(VersionStamp::from_slice(&v)?.into_u64_lossy()),
+		None => Ok(0),
+	}
+}
+
+async fn save(tx: &Transaction, ns: &str, db: &str, cn: &str, vs: u64) -> Result<(), Error> {
+	let cur = Cursor {
+		vs,
+		ts: Utc::now().timestamp() as u64,
+	};
+	tx.set(cu::new(ns, db, cn), revision::to_vec(&cur)?, None).await
+}
@@ -1,3 +1,4 @@
+use crate::cf::{consumer, sink};
 use crate::err::Error;
 use crate::key::change;
 use crate::key::debug::Sprintable;
@@ -57,6 +58,18 @@ pub async fn gc_ns(tx: &Transaction, ts: u64, ns: &str) -> Result<(), Error> {
 		// Calculate the watermark versionstamp
 		let watermark_vs =
 			tx.lock().await.get_versionstamp_from_timestamp(watermark_ts, ns, &db.name).await?;
+		// Never collect changes which active consumers have not yet acknowledged
+		let consumer_vs = consumer::watermark(tx, ns, &db.name, ts, cf_expiry).await?;
+		let watermark_vs = match (watermark_vs, consumer_vs) {
+			(Some(w), Some(c)) if c.into_u64_lossy() < w.into_u64_lossy() => Some(c),
+			(w, _) => w,
+		};
+		// Never collect changes which sinks have not yet delivered
+		let sink_vs = sink::watermark(tx, ns, &db.name).await?;
+		let watermark_vs = match (watermark_vs, sink_vs) {
+			(Some(w), Some(s)) if s.into_u64_lossy() < w.into_u64_lossy() => Some(s),
+			(w, _) => w,
+		};
 		// If a versionstamp exists, then garbage collect
 		if let Some(watermark_vs) = watermark_vs {
 			gc_range(tx, ns, &db.name, watermark_vs).await?;
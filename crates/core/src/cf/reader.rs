@@ -23,6 +23,35 @@ pub async fn read(
 	start: ShowSince,
 	limit: Option<u32>,
 ) -> Result<Vec<ChangeSet>, Error> {
+	Ok(read_batch(tx, ns, db, tb, start, limit).await?.changes)
+}
+
+/// A batch of complete change sets read from the change feed
+#[derive(Debug, Default)]
+pub struct ChangeBatch {
+	/// The change sets, only including the mutations of the requested table
+	pub changes: Vec<ChangeSet>,
+	/// The last versionstamp which was entirely read, whichever tables it changed.
+	/// The next batch should be read from the versionstamp following this one.
+	pub last: Option<VersionStamp>,
+	/// Whether the read was limited, so that further change sets may follow
+	pub more: bool,
+}
+
+// Reads the next batch of change sets, like `read`, but never splits a change set.
+//
+// The limit is the number of change feed entries which are scanned, whichever table
+// they belong to. When the limit is reached, the partly read last versionstamp is left
+// for the next batch, unless it is the only versionstamp in the batch, in which case
+// it is read entirely, even if it holds more entries than the limit.
+pub async fn read_batch(
+	tx: &Transaction,
+	ns: &str,
+	db: &str,
+	tb: Option<&str>,
+	start: ShowSince,
+	limit: Option<u32>,
+) -> Result<ChangeBatch, Error> {
 	// Calculate the start of the changefeed range
 	let beg = match start {
 		ShowSince::Versionstamp(x) => change::prefix_ts(ns, db, VersionStamp::from_u64(x))?,
@@ -42,19 +71,55 @@ pub async fn read(
 	// Calculate the end of the changefeed range
 	let end = change::suffix(ns, db)?;
 	// Limit the changefeed results with a default
-	let limit = limit.unwrap_or(100).min(1000);
+	let limit = limit.unwrap_or(100).clamp(1, 1000);
+	// Scan the changefeed entries
+	let mut entries = tx.scan(beg..end.clone(), limit, None).await?;
+	let more = entries.len() >= limit as usize;
+	if more {
+		let vs_of = |k: &[u8]| crate::key::change::Cf::decode(k).map(|c| c.vs);
+		let first = vs_of(&entries[0].0)?;
+		let last = vs_of(&entries[entries.len() - 1].0)?;
+		if first == last {
+			// A single change set fills the batch, so read the rest of it
+			let end = match last.next() {
+				Some(vs) => change::prefix_ts(ns, db, vs)?,
+				None => end,
+			};
+			loop {
+				let mut from = entries[entries.len() - 1].0.clone();
+				from.push(0x00);
+				let page = tx.scan(from..end.clone(), limit, None).await?;
+				let done = page.len() < limit as usize;
+				entries.extend(page);
+				if done {
+					break;
+				}
+			}
+		} else {
+			// The last change set may be incomplete, so leave it for the next batch
+			while let Some((k, _)) = entries.last() {
+				if vs_of(k)? != last {
+					break;
+				}
+				entries.pop();
+			}
+		}
+	}
 	// Create an empty buffer for the versionstamp
 	let mut vs: Option<VersionStamp> = None;
 	// Create an empty buffer for the table mutations
 	let mut buf: Vec<TableMutations> = Vec::new();
 	// Create an empty buffer for the final changesets
 	let mut res = Vec::<ChangeSet>::new();
+	// Keep track of the last versionstamp which was read
+	let mut last = None;
 	// iterate over _x and put decoded elements to r
-	for (k, v) in tx.scan(beg..end, limit, None).await? {
+	for (k, v) in entries {
 		#[cfg(debug_assertions)]
 		trace!("Reading change feed entry: {}", k.sprint());
 		// Decode the changefeed entry key
-		let dec = crate::key::change::Cf::decode(&k).unwrap();
+		let dec = crate::key::change::Cf::decode(&k)?;
+		last = Some(dec.vs);
 		// Check the change is for the desired table
 		if tb.is_some_and(|tb| tb != dec.tb) {
 			continue;
@@ -83,5 +148,9 @@ pub async fn read(
 		res.push(ChangeSet(vs.unwrap(), db_mut));
 	}
 	// Return the results
-	Ok(res)
+	Ok(ChangeBatch {
+		changes: res,
+		last,
+		more,
+	})
 }
@@ -0,0 +1,278 @@
+use crate::cf::consumer::Cursor;
+use crate::cf::{ChangeSet, DatabaseMutation};
+use crate::cnf::SINK_LEASE_DURATION;
+use crate::ctx::MutableContext;
+use crate::err::Error;
+use crate::fnc::args::Optional;
+use crate::key::database::{sl, sp};
+use crate::kvs::Datastore;
+use crate::kvs::LockType::Optimistic;
+use crate::kvs::Transaction;
+use crate::kvs::TransactionType::{Read, Write};
+use crate::sql::statements::define::DefineSinkStatement;
+use crate::sql::statements::show::ShowSince;
+use crate::sql::Value;
+use crate::vs::VersionStamp;
+use chrono::Utc;
+use revision::revisioned;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use uuid::Uuid;
+
+/// The default number of change feed entries read for each request
+const DEFAULT_BATCH: u32 = 100;
+/// The default number of times a failed request is retried
+const DEFAULT_RETRY: u32 = 3;
+/// The default time to wait for each request to complete
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The node which delivers the changes of a sink
+#[revisioned(revision = 1)]
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct Lease {
+	/// The id of the node which holds the lease
+	pub nd: Uuid,
+	/// The time, in seconds, at which the lease expires
+	pub expiry: u64,
+}
+
+// reset moves the position of a sink to the latest versionstamp of the database,
+// so that the sink only delivers changes which are made after this point.
+pub(crate) async fn reset(tx: &Transaction, ns: &str, db: &str, sk: &str) -> Result<(), Error> {
+	let vs = latest(tx, ns, db).await?;
+	save(tx, ns, db, sk, vs).await
+}
+
+// watermark returns the versionstamp before which all changes have been
+// delivered by every sink in the database. Unlike consumers, sinks never
+// become inactive, so undelivered changes are kept until the sink is either
+// removed, or reset to the latest change by redefining it with OVERWRITE.
+pub(crate) async fn watermark(
+	tx: &Transaction,
+	ns: &str,
+	db: &str,
+) -> Result<Option<VersionStamp>, Error> {
+	let mut res: Option<u64> = None;
+	for sk in tx.all_db_sinks(ns, db).await?.iter() {
+		if let Some(v) = tx.get(sp::new(ns, db, &sk.name), None).await? {
+			let cur: Cursor = revision::from_slice(&v)?;
+			let vs = cur.vs + 1;
+			res = Some(res.map_or(vs, |v| v.min(vs)));
+		}
+	}
+	Ok(res.map(VersionStamp::from_u64))
+}
+
+// acquire takes the lease for delivering the changes of a sink, unless another
+// node holds a lease which has not yet expired, so that the changes of a sink
+// are only delivered from a single node at a time. The node which holds the
+// lease renews it each time, returning the time at which the lease expires.
+async fn acquire(
+	tx: &Transaction,
+	ns: &str,
+	db: &str,
+	sk: &str,
+	nd: Uuid,
+	ts: u64,
+) -> Result<Option<u64>, Error> {
+	// Check that the sink has not been removed
+	tx.get_db_sink(ns, db, sk).await?;
+	if let Some(v) = tx.get(sl::new(ns, db, sk), None).await? {
+		let lease: Lease = revision::from_slice(&v)?;
+		if lease.nd != nd && lease.expiry > ts {
+			return Ok(None);
+		}
+	}
+	let lease = Lease {
+		nd,
+		expiry: ts + *SINK_LEASE_DURATION,
+	};
+	tx.set(sl::new(ns, db, sk), revision::to_vec(&lease)?, None).await?;
+	Ok(Some(lease.expiry))
+}
+
+// deliver sends all undelivered changes for the table of a sink to its endpoint,
+// in versionstamp order. The position only advances once a batch has been
+// accepted, so a change set may be delivered more than once, but never skipped.
+// Changes are only delivered by the node which holds the lease for the sink,
+// and no further batches are started once half of the lease has elapsed, so
+// that a sink with a large backlog is not still delivering once it expires.
+pub(crate) async fn deliver(ds: &Datastore, ns: &str, db: &str, sk: &str) -> Result<(), Error> {
+	// Take the lease for the sink
+	let now = Utc::now().timestamp() as u64;
+	let txn = ds.transaction(Write, Optimistic).await?;
+	let res = acquire(&txn, ns, db, sk, ds.id(), now).await;
+	let expiry = match res {
+		Ok(v) => {
+			txn.commit().await?;
+			v
+		}
+		Err(e) => {
+			txn.cancel().await?;
+			return Err(e);
+		}
+	};
+	// Another node is delivering the changes
+	let Some(expiry) = expiry else {
+		return Ok(());
+	};
+	let deadline = expiry - *SINK_LEASE_DURATION / 2;
+	loop {
+		// Leave the remaining changes for the next run
+		if Utc::now().timestamp() as u64 >= deadline {
+			return Ok(());
+		}
+		// Read the sink definition and the next batch of changes
+		let txn = ds.transaction(Read, Optimistic).await?;
+		let res = async {
+			let sk = txn.get_db_sink(ns, db, sk).await?;
+			let cur = match txn.get(sp::new(ns, db, &sk.name), None).await? {
+				Some(v) => revision::from_slice::<Cursor>(&v)?,
+				None => Cursor::default(),
+			};
+			// Read the changes to the whole database, so that the
+			// position advances past changes to any other tables
+			let since = ShowSince::Versionstamp(cur.vs + 1);
+			let limit = sk.batch.unwrap_or(DEFAULT_BATCH);
+			let batch = txn.changes(ns, db, None, since, limit).await?;
+			Ok::<_, Error>((sk, cur, batch))
+		}
+		.await;
+		txn.cancel().await?;
+		let (sk, cur, batch) = res?;
+		// Check if there is anything to process. The batch only holds complete
+		// change sets, so the position never moves past a partly read one.
+		let Some(last) = batch.last else {
+			return Ok(());
+		};
+		let vs = last.into_u64_lossy();
+		// Only send the changes to the table of the sink
+		let changes: Vec<Value> = batch
+			.changes
+			.into_iter()
+			.filter_map(|ChangeSet(vs, DatabaseMutation(tbs))| {
+				let tbs: Vec<_> = tbs.into_iter().filter(|tb| tb.0 == sk.what.0).collect();
+				(!tbs.is_empty()).then_some(ChangeSet(vs, DatabaseMutation(tbs)))
+			})
+			.map(|change| {
+				// Output the versionstamp which the change feed can be read from
+				let vs = change.0.into_u64_lossy();
+				let mut value = change.into_value();
+				if let Value::Object(ref mut o) = value {
+					o.insert("versionstamp".to_owned(), vs.into());
+				}
+				value
+			})
+			.collect();
+		// Send the changes, if any were made to this table
+		if !changes.is_empty() {
+			send(ds, &sk, changes).await?;
+		}
+		// Save the position, unless the sink was removed in the meantime
+		let txn = ds.transaction(Write, Optimistic).await?;
+		let res = async {
+			if txn.get_db_sink(ns, db, &sk.name).await.is_ok()
+				&& txn.get(sp::new(ns, db, &sk.name), None).await? == Some(revision::to_vec(&cur)?)
+			{
+				save(&txn, ns, db, &sk.name, vs).await?;
+			}
+			Ok::<_, Error>(())
+		}
+		.await;
+		match res {
+			Ok(_) => txn.commit().await?,
+			Err(e) => {
+				txn.cancel().await?;
+				return Err(e);
+			}
+		}
+		// Stop once all changes have been delivered
+		if !batch.more {
+			return Ok(());
+		}
+	}
+}
+
+// send posts a batch of change sets to the endpoint of a sink, retrying
+// with an exponential backoff if the request fails or is not accepted.
+async fn send(ds: &Datastore, sk: &DefineSinkStatement, changes: Vec<Value>) -> Result<(), Error> {
+	let timeout = sk.timeout.as_ref().map(|v| v.0).unwrap_or(DEFAULT_TIMEOUT);
+	let retry = sk.retry.unwrap_or(DEFAULT_RETRY);
+	let body = Value::from(changes);
+	let mut attempt = 0;
+	loop {
+		// Each request has its own timeout
+		let mut ctx = MutableContext::new(&ds.setup_ctx()?.freeze());
+		ctx.add_timeout(timeout)?;
+		let ctx = ctx.freeze();
+		// Send the request, which checks the network capabilities
+		let args = (Value::from(sk.url.as_str()), Optional(Some(body.clone())), Optional(None));
+		match crate::fnc::http::post(&ctx, args).await {
+			Ok(_) => return Ok(()),
+			Err(e) if attempt >= retry => return Err(e),
+			Err(e) => {
+				warn!("Failed to deliver changes to sink {}, retrying: {e}", sk.name);
+			}
+		}
+		// Wait before retrying the request
+		let dur = Duration::from_millis(100 << attempt.min(10));
+		#[cfg(target_family = "wasm")]
+		wasmtimer::tokio::sleep(dur).await;
+		#[cfg(not(target_family = "wasm"))]
+		tokio::time::sleep(dur).await;
+		attempt += 1;
+	}
+}
+
+// latest returns the versionstamp of the latest change to the database
+async fn latest(tx: &Transaction, ns: &str, db: &str) -> Result<u64, Error> {
+	match tx.get(crate::key::database::vs::new(ns, db), None).await? {
+		Some(v) => Ok(VersionStamp::from_slice(&v)?.into_u64_lossy()),
+		None => Ok(0),
+	}
+}
+
+async fn save(tx: &Transaction, ns: &str, db: &str, sk: &str, vs: u64) -> Result<(), Error> {
+	let cur = Cursor {
+		vs,
+		ts: Utc::now().timestamp() as u64,
+	};
+	tx.set(sp::new(ns, db, sk), revision::to_vec(&cur)?, None).await
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::dbs::{Capabilities, Session};
+
+	#[tokio::test]
+	async fn sink_lease() {
+		let ds = Datastore::new("memory").await.unwrap().with_capabilities(Capabilities::all());
+		let ses = Session::owner().with_ns("test").with_db("test");
+		let sql = "
+			DEFINE TABLE user CHANGEFEED 1h;
+			DEFINE SINK hook ON TABLE user URL 'http://localhost/hook';
+		";
+		for res in ds.execute(sql, &ses, None).await.unwrap() {
+			res.result.unwrap();
+		}
+		let (one, two) = (Uuid::new_v4(), Uuid::new_v4());
+		let ds = &ds;
+		let lease = |nd, ts| async move {
+			let tx = ds.transaction(Write, Optimistic).await.unwrap();
+			let res = acquire(&tx, "test", "test", "hook", nd, ts).await.unwrap();
+			tx.commit().await.unwrap();
+			res
+		};
+		// Only one node holds the lease at a time
+		assert_eq!(lease(one, 100).await, Some(100 + *SINK_LEASE_DURATION));
+		assert_eq!(lease(two, 101).await, None);
+		// The node which holds the lease renews it
+		assert_eq!(lease(one, 102).await, Some(102 + *SINK_LEASE_DURATION));
+		// Another node takes the lease once it expires
+		let ts = 102 + *SINK_LEASE_DURATION;
+		assert_eq!(lease(two, ts).await, Some(ts + *SINK_LEASE_DURATION));
+		assert_eq!(lease(one, ts).await, None);
+	}
+}
@@ -379,6 +379,90 @@ mod tests {
 		assert_eq!(r.len(), 2);
 	}
 
+	#[tokio::test]
+	async fn filtered_read_skips_other_tables() {
+		// Given more change feed entries for another table than a batch can hold
+		let ds = init(false).await;
+		for i in 0..1100 {
+			record_table_change(ds.transaction(Write, Optimistic).await.unwrap(), "other", i).await;
+		}
+		record_table_change(ds.transaction(Write, Optimistic).await.unwrap(), TB, 0).await;
+
+		// When we read the table changes in batches, resuming after the last versionstamp read
+		let mut since = ShowSince::Versionstamp(0);
+		let mut found = Vec::new();
+		for _ in 0..3 {
+			let tx = ds.transaction(Read, Optimistic).await.unwrap();
+			let batch = crate::cf::read_batch(&tx, NS, DB, Some(TB), since.clone(), Some(1000))
+				.await
+				.unwrap();
+			tx.cancel().await.unwrap();
+			found.extend(batch.changes);
+			if !batch.more {
+				break;
+			}
+			since = ShowSince::versionstamp(&batch.last.unwrap().next().unwrap());
+		}
+
+		// Then the change of the table is reached
+		assert_eq!(found.len(), 1, "{found:?}");
+		assert_eq!(found[0].1 .0[0].0, TB);
+	}
+
+	#[tokio::test]
+	async fn oversized_change_set_is_read_entirely() {
+		// Given a versionstamp changing more tables than the batch size
+		let ds = init(false).await;
+		let tx = ds.transaction(Write, Optimistic).await.unwrap();
+		for (vs, tb) in
+			[(1, "before"), (2, "a"), (2, "b"), (2, "c"), (2, "d"), (2, "e"), (3, "after")]
+		{
+			let key = crate::key::change::Cf::new(NS, DB, VersionStamp::from_u64(vs), tb);
+			let muts = TableMutations(
+				tb.to_string(),
+				vec![TableMutation::Set(Thing::from((tb, "a")), Value::from("a"))],
+			);
+			tx.set(key, revision::to_vec(&muts).unwrap(), None).await.unwrap();
+		}
+		tx.commit().await.unwrap();
+
+		// When we read with a limit which splits the versionstamp
+		let tx = ds.transaction(Read, Optimistic).await.unwrap();
+		let first = crate::cf::read_batch(&tx, NS, DB, None, ShowSince::Versionstamp(0), Some(3))
+			.await
+			.unwrap();
+		// Then the partly read versionstamp is left for the next batch
+		assert!(first.more);
+		assert_eq!(first.changes.len(), 1);
+		assert_eq!(first.last, Some(VersionStamp::from_u64(1)));
+		// And the next batch reads all of it, even beyond the limit
+		let since = ShowSince::versionstamp(&first.last.unwrap().next().unwrap());
+		let second = crate::cf::read_batch(&tx, NS, DB, None, since, Some(3)).await.unwrap();
+		tx.cancel().await.unwrap();
+		assert!(second.more);
+		assert_eq!(second.changes.len(), 1);
+		assert_eq!(second.changes[0].1 .0.len(), 5);
+		assert_eq!(second.last, Some(VersionStamp::from_u64(2)));
+	}
+
+	async fn record_table_change(tx: Transaction, tb: &str, id: i64) {
+		let thing = Thing {
+			tb: tb.to_owned(),
+			id: Id::from(id),
+		};
+		tx.lock().await.record_change(
+			NS,
+			DB,
+			tb,
+			&thing,
+			Value::None.into(),
+			Value::from("a").into(),
+			DONT_STORE_PREVIOUS,
+		);
+		tx.lock().await.complete_changes(true).await.unwrap();
+		tx.commit().await.unwrap();
+	}
+
 	async fn change_feed_vs(tx: Transaction, vs: &VersionStamp) -> Vec<ChangeSet> {
 		let r = crate::cf::read(
 			&tx,
@@ -51,6 +51,10 @@ pub static REGEX_CACHE_SIZE: LazyLock<usize> =
 pub static TRANSACTION_CACHE_SIZE: LazyLock<usize> =
 	lazy_env_parse!("SURREAL_TRANSACTION_CACHE_SIZE", usize, 10_000);
 
+/// The number of seconds after which an idle interactive transaction is cancelled (default: 10)
+pub static INTERACTIVE_TRANSACTION_IDLE_TIMEOUT: LazyLock<u64> =
+	lazy_env_parse!("SURREAL_INTERACTIVE_TRANSACTION_IDLE_TIMEOUT", u64, 10);
+
 /// Specifies the number of definitions which can be cached across transactions (default: 1,000)
 pub static DATASTORE_CACHE_SIZE: LazyLock<usize> =
 	lazy_env_parse!("SURREAL_DATASTORE_CACHE_SIZE", usize, 1_000);
@@ -63,6 +67,14 @@ pub static NORMAL_FETCH_SIZE: LazyLock<u32> =
 pub static EXPORT_BATCH_SIZE: LazyLock<u32> =
 	lazy_env_parse!("SURREAL_EXPORT_BATCH_SIZE", u32, 1000);
 
+/// How often to check for new changes when streaming a change feed, in milliseconds (default: 100)
+pub static CHANGEFEED_STREAM_INTERVAL: LazyLock<u64> =
+	lazy_env_parse!("SURREAL_CHANGEFEED_STREAM_INTERVAL", u64, 100);
+
+/// How long a node holds the lease for delivering the changes of a sink, in seconds (default: 30)
+pub static SINK_LEASE_DURATION: LazyLock<u64> =
+	lazy_env_parse!("SURREAL_SINK_LEASE_DURATION", u64, 30);
+
 /// The maximum number of keys that should be scanned at once for count queries (default: 10,000)
 pub static COUNT_BATCH_SIZE: LazyLock<u32> =
 	lazy_env_parse!("SURREAL_COUNT_BATCH_SIZE", u32, 10_000);
@@ -71,6 +83,23 @@ pub static COUNT_BATCH_SIZE: LazyLock<u32> =
 pub static INDEXING_BATCH_SIZE: LazyLock<u32> =
 	lazy_env_parse!("SURREAL_INDEXING_BATCH_SIZE", u32, 250);
 
+/// The maximum number of histogram buckets computed by ANALYZE for standard and unique indexes (default: 32)
+pub static INDEX_HISTOGRAM_BUCKETS: LazyLock<usize> =
+	lazy_env_parse!("SURREAL_INDEX_HISTOGRAM_BUCKETS", usize, 32);
+
+/// Whether the query of the matches operator is parsed with the full-text query syntax,
+/// or analyzed as plain text, as it was before the syntax was introduced (default: true)
+pub static FULLTEXT_QUERY_SYNTAX: LazyLock<bool> =
+	lazy_env_parse!("SURREAL_FULLTEXT_QUERY_SYNTAX", bool, true);
+
+/// The maximum number of terms a prefix of a full-text query expands to (default: 1024)
+pub static FULLTEXT_MAX_PREFIX_EXPANSIONS: LazyLock<usize> =
+	lazy_env_parse!("SURREAL_FULLTEXT_MAX_PREFIX_EXPANSIONS", usize, 1024);
+
+/// The maximum number of terms a fuzzy term of a full-text query expands to (default: 64)
+pub static FULLTEXT_MAX_FUZZY_EXPANSIONS: LazyLock<usize> =
+	lazy_env_parse!("SURREAL_FULLTEXT_MAX_FUZZY_EXPANSIONS", usize, 64);
+
 /// The maximum size of the priority queue triggering usage of the priority queue for the result collector.
 pub static MAX_ORDER_LIMIT_PRIORITY_QUEUE_SIZE: LazyLock<u32> =
 	lazy_env_parse!("SURREAL_MAX_ORDER_LIMIT_PRIORITY_QUEUE_SIZE", u32, 1000);
@@ -1,3 +1,4 @@
+use crate::api::limit::NodeRateLimiter;
 use crate::buc::store::ObjectStore;
 use crate::buc::{self, BucketConnectionKey, BucketConnections};
 use crate::cnf::PROTECTED_PARAM_NAMES;
@@ -70,6 +71,8 @@ pub struct MutableContext {
 	isolated: bool,
 	// A map of bucket connections
 	buckets: Option<Arc<BucketConnections>>,
+	// The rate limiter for API endpoints on this node
+	rate_limiter: Option<Arc<NodeRateLimiter>>,
 }
 
 impl Default for MutableContext {
@@ -120,6 +123,7 @@ impl MutableContext {
 			transaction: None,
 			isolated: false,
 			buckets: None,
+			rate_limiter: None,
 		}
 	}
 
@@ -145,6 +149,7 @@ impl MutableContext {
 			isolated: false,
 			parent: Some(parent.clone()),
 			buckets: parent.buckets.clone(),
+			rate_limiter: parent.rate_limiter.clone(),
 		}
 	}
 
@@ -172,6 +177,7 @@ impl MutableContext {
 			isolated: true,
 			parent: Some(parent.clone()),
 			buckets: parent.buckets.clone(),
+			rate_limiter: parent.rate_limiter.clone(),
 		}
 	}
 
@@ -199,6 +205,7 @@ impl MutableContext {
 			isolated: false,
 			parent: None,
 			buckets: from.buckets.clone(),
+			rate_limiter: from.rate_limiter.clone(),
 		}
 	}
 
@@ -213,6 +220,7 @@ impl MutableContext {
 		cache: Arc<DatastoreCache>,
 		#[cfg(storage)] temporary_directory: Option<Arc<PathBuf>>,
 		buckets: Arc<BucketConnections>,
+		rate_limiter: Arc<NodeRateLimiter>,
 	) -> Result<MutableContext, Error> {
 		let mut ctx = Self {
 			values: HashMap::default(),
@@ -234,6 +242,7 @@ impl MutableContext {
 			transaction: None,
 			isolated: false,
 			buckets: Some(buckets),
+			rate_limiter: Some(rate_limiter),
 		};
 		if let Some(timeout) = time_out {
 			ctx.add_timeout(timeout)?;
@@ -515,6 +524,11 @@ impl MutableContext {
 		self.buckets.clone()
 	}
 
+	/// Get the rate limiter for API endpoints on this node
+	pub(crate) fn get_rate_limiter(&self) -> Option<Arc<NodeRateLimiter>> {
+		self.rate_limiter.clone()
+	}
+
 	/// Obtain the connection for a bucket
 	pub(crate) async fn get_bucket_store(
 		&self,
@@ -310,9 +310,12 @@ pub enum RouteTarget {
 	Health,
 	Export,
 	Import,
+	Backup,
+	Restore,
 	Rpc,
 	Version,
 	Sync,
+	Changes,
 	Sql,
 	Signin,
 	Signup,
@@ -320,6 +323,7 @@ pub enum RouteTarget {
 	Ml,
 	GraphQL,
 	Api,
+	Files,
 }
 
 // impl display
@@ -329,9 +333,12 @@ impl fmt::Display for RouteTarget {
 			RouteTarget::Health => write!(f, "health"),
 			RouteTarget::Export => write!(f, "export"),
 			RouteTarget::Import => write!(f, "import"),
+			RouteTarget::Backup => write!(f, "backup"),
+			RouteTarget::Restore => write!(f, "restore"),
 			RouteTarget::Rpc => write!(f, "rpc"),
 			RouteTarget::Version => write!(f, "version"),
 			RouteTarget::Sync => write!(f, "sync"),
+			RouteTarget::Changes => write!(f, "changes"),
 			RouteTarget::Sql => write!(f, "sql"),
 			RouteTarget::Signin => write!(f, "signin"),
 			RouteTarget::Signup => write!(f, "signup"),
@@ -339,6 +346,7 @@ impl fmt::Display for RouteTarget {
 			RouteTarget::Ml => write!(f, "ml"),
 			RouteTarget::GraphQL => write!(f, "graphql"),
 			RouteTarget::Api => write!(f, "api"),
+			RouteTarget::Files => write!(f, "files"),
 		}
 	}
 }
@@ -367,9 +375,12 @@ impl std::str::FromStr for RouteTarget {
 			"health" => Ok(RouteTarget::Health),
 			"export" => Ok(RouteTarget::Export),
 			"import" => Ok(RouteTarget::Import),
+			"backup" => Ok(RouteTarget::Backup),
+			"restore" => Ok(RouteTarget::Restore),
 			"rpc" => Ok(RouteTarget::Rpc),
 			"version" => Ok(RouteTarget::Version),
 			"sync" => Ok(RouteTarget::Sync),
+			"changes" => Ok(RouteTarget::Changes),
 			"sql" => Ok(RouteTarget::Sql),
 			"signin" => Ok(RouteTarget::Signin),
 			"signup" => Ok(RouteTarget::Signup),
@@ -377,6 +388,7 @@ impl std::str::FromStr for RouteTarget {
 			"ml" => Ok(RouteTarget::Ml),
 			"graphql" => Ok(RouteTarget::GraphQL),
 			"api" => Ok(RouteTarget::Api),
+			"files" => Ok(RouteTarget::Files),
 			_ => Err(ParseRouteTargetError),
 		}
 	}
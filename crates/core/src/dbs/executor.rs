@@ -594,6 +594,69 @@ impl Executor {
 		}
 		Ok(this.results)
 	}
+
+	/// Execute a query within a transaction which is held open between queries.
+	///
+	/// The transaction is neither committed nor cancelled here. If any
+	/// statement fails, the remaining statements are not executed, and
+	/// the caller is expected to cancel the transaction.
+	#[instrument(level = "debug", name = "executor", target = "surrealdb::core::dbs", skip_all)]
+	pub(crate) async fn execute_in_transaction(
+		ctx: Context,
+		opt: Options,
+		qry: Query,
+		txn: Arc<Transaction>,
+	) -> Result<Vec<Response>, Error> {
+		let mut this = Executor::new(ctx, opt);
+		let mut failed = false;
+
+		for stmt in qry.into_iter() {
+			yield_now!();
+
+			if failed {
+				this.results.push(Response {
+					time: Duration::ZERO,
+					result: Err(Error::QueryNotExecuted),
+					query_type: QueryType::Other,
+				});
+				continue;
+			}
+
+			trace!(target: TARGET, statement = %stmt, "Executing statement");
+
+			let query_type: QueryType = (&stmt).into();
+			let before = Instant::now();
+			let result = match stmt {
+				Statement::Begin(_) | Statement::Cancel(_) | Statement::Commit(_) => {
+					Err(Error::QueryNotExecutedDetail {
+						message: "Transaction statements can not be used within an interactive transaction".to_string(),
+					})
+				}
+				Statement::Option(stmt) => match this.execute_option_statement(stmt) {
+					// Option statements don't produce a result
+					Ok(_) => continue,
+					Err(e) => Err(e),
+				},
+				Statement::Use(stmt) => this.execute_use_statement(stmt).map(|_| Value::None),
+				stmt => match this.execute_transaction_statement(txn.clone(), stmt).await {
+					Ok(value) | Err(ControlFlow::Return(value)) => Ok(value),
+					Err(ControlFlow::Break) | Err(ControlFlow::Continue) => {
+						Err(Error::InvalidControlFlow)
+					}
+					Err(ControlFlow::Err(e)) => Err(*e),
+				},
+			};
+
+			failed = result.is_err();
+			this.results.push(Response {
+				time: before.elapsed(),
+				result,
+				query_type,
+			});
+		}
+
+		Ok(this.results)
+	}
 }
 
 #[cfg(test)]
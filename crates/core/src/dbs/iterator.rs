@@ -384,7 +384,12 @@ impl Iterator {
 					c.set_iteration_stage(s);
 					cancel_ctx = c.freeze();
 					if !is_last {
-						self.clone().iterate(stk, &cancel_ctx, opt, stm, sp, None).await?;
+						let mut collect = self.clone();
+						collect.iterate(stk, &cancel_ctx, opt, stm, sp, None).await?;
+						// The errors of the collecting iteration cancel the whole iteration
+						if let Some(e) = collect.error.take() {
+							return Err(e);
+						}
 					};
 				}
 				sp
@@ -28,6 +28,9 @@ impl Plan {
 					for reason in qp.fallbacks() {
 						exp.add_fallback(reason.to_string());
 					}
+					for (tb, candidates) in qp.costs() {
+						exp.add_cost(tb, candidates.clone());
+					}
 				}
 				results.explain(&mut exp);
 				(e.0, Some(exp))
@@ -64,6 +67,10 @@ impl Explanation {
 		self.0.push(ExplainItem::new_fallback(reason));
 	}
 
+	fn add_cost(&mut self, tb: &str, candidates: Value) {
+		self.0.push(ExplainItem::new_cost(tb, candidates));
+	}
+
 	pub(super) fn add_record_strategy(&mut self, rs: RecordStrategy) {
 		self.0.push(ExplainItem::new_record_strategy(rs));
 	}
@@ -100,6 +107,13 @@ impl ExplainItem {
 		}
 	}
 
+	fn new_cost(tb: &str, candidates: Value) -> Self {
+		Self {
+			name: "Cost".into(),
+			details: vec![("table", tb.into()), ("candidates", candidates)],
+		}
+	}
+
 	fn new_iter(ctx: &Context, iter: &Iterable) -> Self {
 		match iter {
 			Iterable::Value(v) => Self {
@@ -85,6 +85,16 @@ pub enum Error {
 	#[error("Transaction is too large")]
 	TxTooLarge,
 
+	/// The interactive transaction does not exist, or has expired
+	#[error("The transaction '{id}' does not exist, or has expired")]
+	TxNotFound {
+		id: String,
+	},
+
+	/// A statement in the interactive transaction failed, so it was rolled back
+	#[error("The transaction was cancelled because a previous statement failed")]
+	TxAborted,
+
 	/// No namespace has been selected
 	#[error("Specify a namespace to use")]
 	NsEmpty,
@@ -366,6 +376,18 @@ pub enum Error {
 		name: String,
 	},
 
+	/// The requested consumer does not exist
+	#[error("The consumer '{name}' does not exist")]
+	CnNotFound {
+		name: String,
+	},
+
+	/// The requested sink does not exist
+	#[error("The sink '{name}' does not exist")]
+	SkNotFound {
+		name: String,
+	},
+
 	/// The requested config does not exist
 	#[error("The config for {name} does not exist")]
 	CgNotFound {
@@ -1003,6 +1025,18 @@ pub enum Error {
 		name: String,
 	},
 
+	/// The requested consumer already exists
+	#[error("The consumer '{name}' already exists")]
+	CnAlreadyExists {
+		name: String,
+	},
+
+	/// The requested sink already exists
+	#[error("The sink '{name}' already exists")]
+	SkAlreadyExists {
+		name: String,
+	},
+
 	/// The requested table already exists
 	#[error("The table '{name}' already exists")]
 	TbAlreadyExists {
@@ -1190,6 +1224,10 @@ pub enum Error {
 	#[error("The underlying datastore does not support versioned queries")]
 	UnsupportedVersionedQueries,
 
+	/// The storage engine can not remove old versions, so a retention policy can not be enforced
+	#[error("The underlying datastore does not support version retention policies")]
+	UnsupportedVersionRetention,
+
 	#[doc(hidden)]
 	#[error("The underlying datastore does not support reversed scans")]
 	UnsupportedReversedScans,
@@ -1241,6 +1279,18 @@ pub enum Error {
 	#[error("Encountered an issue while processed export config: found {0}, but expected {1}.")]
 	InvalidExportConfig(Value, String),
 
+	/// The backup could not be restored because it is invalid or corrupted
+	#[error("The backup is invalid: {0}")]
+	InvalidBackup(String),
+
+	/// A backup can only be restored into a datastore which contains no data
+	#[error("A backup can only be restored into an empty datastore")]
+	RestoreNotEmpty,
+
+	/// A datastore can not be recovered to a point before its backup was taken
+	#[error("The recovery point is before the backup of the database '{0}' was taken")]
+	RecoveryBeforeBackup(String),
+
 	/// Found an unexpected value in a range
 	#[error("Found {found} for bound but expected {expected}.")]
 	InvalidBound {
@@ -1349,6 +1399,15 @@ pub enum Error {
 
 	#[error("Failed to connect to bucket: {0}")]
 	BucketConnectionFailed(String),
+
+	#[error("Invalid byte range for file: {0}")]
+	InvalidFileRange(String),
+
+	#[error("The requested byte range is not satisfiable for a file of {0} bytes")]
+	FileRangeNotSatisfiable(u64),
+
+	#[error("Invalid metadata for file: {0}")]
+	InvalidFileMetadata(String),
 }
 
 impl From<Error> for String {
@@ -12,30 +12,33 @@ use super::{args::Optional, CursorDoc};
 
 pub async fn put(
 	(stk, ctx, opt, doc): (&mut Stk, &Context, &Options, Option<&CursorDoc>),
-	(file, value): (File, Value),
+	(file, value, Optional(opts)): (File, Value, Optional<Object>),
 ) -> Result<Value, Error> {
 	let mut controller = BucketController::new(stk, ctx, opt, doc, &file.bucket).await?;
-	controller.put(&file.key.into(), value).await?;
+	let opts = opts.map(|v| v.try_into()).transpose()?.unwrap_or_default();
+	controller.put(&file.key.into(), value, &opts).await?;
 
 	Ok(Value::None)
 }
 
 pub async fn put_if_not_exists(
 	(stk, ctx, opt, doc): (&mut Stk, &Context, &Options, Option<&CursorDoc>),
-	(file, value): (File, Value),
+	(file, value, Optional(opts)): (File, Value, Optional<Object>),
 ) -> Result<Value, Error> {
 	let mut controller = BucketController::new(stk, ctx, opt, doc, &file.bucket).await?;
-	controller.put_if_not_exists(&file.key.into(), value).await?;
+	let opts = opts.map(|v| v.try_into()).transpose()?.unwrap_or_default();
+	controller.put_if_not_exists(&file.key.into(), value, &opts).await?;
 
 	Ok(Value::None)
 }
 
 pub async fn get(
 	(stk, ctx, opt, doc): (&mut Stk, &Context, &Options, Option<&CursorDoc>),
-	(file,): (File,),
+	(file, Optional(opts)): (File, Optional<Object>),
 ) -> Result<Value, Error> {
 	let mut controller = BucketController::new(stk, ctx, opt, doc, &file.bucket).await?;
-	let res = controller.get(&file.key.into()).await?;
+	let opts = opts.map(|v| v.try_into()).transpose()?.unwrap_or_default();
+	let res = controller.get(&file.key.into(), &opts).await?;
 	Ok(res.map(Value::Bytes).unwrap_or_default())
 }
 
@@ -577,6 +577,8 @@ pub async fn asynchronous(
 		"search::score" => search::score((ctx, doc)).await,
 		"search::highlight" => search::highlight((ctx, doc)).await,
 		"search::offsets" => search::offsets((ctx, doc)).await,
+		"search::rrf" => search::rrf((ctx, doc)).await,
+		"search::linear" => search::linear((ctx, doc)).await,
 		//
 		"sleep" => sleep::sleep(ctx).await,
 		//
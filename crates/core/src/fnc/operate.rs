@@ -223,7 +223,9 @@ pub(crate) async fn matches(
 	let res = match get_executor_option(ctx, doc, exp) {
 		ExecutorOption::PreMatch => true,
 		ExecutorOption::None => false,
-		ExecutorOption::Execute(exe, thg) => exe.matches(stk, ctx, opt, thg, exp, l, r).await?,
+		ExecutorOption::Execute(exe, thg) => {
+			exe.matches(stk, ctx, opt, thg, doc, exp, l, r).await?
+		}
 	};
 	Ok(res.into())
 }
@@ -5,7 +5,9 @@ use crate::err::Error;
 use crate::fnc::get_execution_context;
 use crate::idx::ft::analyzer::Analyzer;
 use crate::idx::ft::highlighter::HighlightParams;
-use crate::sql::Value;
+use crate::idx::planner::executor::QueryExecutor;
+use crate::idx::planner::hybrid::{Fusion, LINEAR_DEFAULT_WEIGHT, RRF_DEFAULT_K};
+use crate::sql::{Number, Value};
 use reblessive::tree::Stk;
 
 use super::args::Optional;
@@ -61,3 +63,53 @@ pub async fn offsets(
 	}
 	Ok(Value::None)
 }
+
+/// Fuses the full-text ranking of the match reference with the KNN rankings by reciprocal rank fusion
+pub async fn rrf(
+	(ctx, doc): (&Context, Option<&CursorDoc>),
+	(match_ref, Optional(k)): (Value, Optional<Number>),
+) -> Result<Value, Error> {
+	let k = k.map(|k| k.to_float()).unwrap_or(RRF_DEFAULT_K);
+	if !(k > 0.0 && k.is_finite()) {
+		return Err(Error::InvalidArguments {
+			name: String::from("search::rrf"),
+			message: String::from("The ranking constant must be a positive number."),
+		});
+	}
+	if let Some((exe, _, thg)) = get_execution_context(ctx, doc) {
+		check_indexed_knn(exe, "search::rrf")?;
+		return exe.hybrid_score(ctx, &match_ref, thg, Fusion::Rrf(k)).await;
+	}
+	Ok(Value::None)
+}
+
+/// Fuses the full-text score of the match reference with the KNN distances by a weighted sum
+pub async fn linear(
+	(ctx, doc): (&Context, Option<&CursorDoc>),
+	(match_ref, Optional(weight)): (Value, Optional<Number>),
+) -> Result<Value, Error> {
+	let weight = weight.map(|w| w.to_float()).unwrap_or(LINEAR_DEFAULT_WEIGHT);
+	if !(0.0..=1.0).contains(&weight) {
+		return Err(Error::InvalidArguments {
+			name: String::from("search::linear"),
+			message: String::from("The weight of the full-text score must be between 0 and 1."),
+		});
+	}
+	if let Some((exe, _, thg)) = get_execution_context(ctx, doc) {
+		check_indexed_knn(exe, "search::linear")?;
+		return exe.hybrid_score(ctx, &match_ref, thg, Fusion::Linear(weight)).await;
+	}
+	Ok(Value::None)
+}
+
+/// A brute force KNN search only ranks the records for which the condition evaluates it,
+/// so its ranking can't be fused.
+fn check_indexed_knn(exe: &QueryExecutor, name: &str) -> Result<(), Error> {
+	if exe.has_bruteforce_knn() {
+		return Err(Error::InvalidArguments {
+			name: name.to_owned(),
+			message: String::from("The KNN searches of a hybrid search must use a vector index."),
+		});
+	}
+	Ok(())
+}
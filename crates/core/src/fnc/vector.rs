@@ -77,12 +77,16 @@ pub mod distance {
 		(ctx, doc): (&Context, Option<&CursorDoc>),
 		(Optional(knn_ref),): (Optional<Value>,),
 	) -> Result<Value, Error> {
-		if let Some((_exe, doc, thg)) = get_execution_context(ctx, doc) {
+		if let Some((exe, doc, thg)) = get_execution_context(ctx, doc) {
 			if let Some(ir) = &doc.ir {
 				if let Some(d) = ir.dist() {
 					return Ok(d.into());
 				}
 			}
+			// The record may have been found by another index, such as in a hybrid search
+			if let Some(d) = exe.knn_distance(thg) {
+				return Ok(d.into());
+			}
 			if let Some(IterationStage::Iterate(Some(results))) = ctx.get_iteration_stage() {
 				let n = if let Some(Value::Number(n)) = knn_ref {
 					n.as_usize()
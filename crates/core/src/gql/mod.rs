@@ -4,10 +4,13 @@ pub mod cache;
 pub mod error;
 mod ext;
 mod functions;
+mod mutations;
 pub mod schema;
+mod subscriptions;
 mod tables;
 mod utils;
 
 pub use error::GqlError;
+pub use subscriptions::Subscriptions;
 
 pub use cache::*;
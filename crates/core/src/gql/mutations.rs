@@ -0,0 +1,337 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use crate::dbs::Session;
+use crate::gql::ext::IntoExt;
+use crate::gql::schema::{gql_to_sql_kind, kind_to_input_type, unwrap_type};
+use crate::gql::utils::{field_val_erase_owned, GQLTx, GqlValueUtils};
+use crate::kvs::{Datastore, Transaction};
+use crate::sql::statements::{
+	CreateStatement, DefineFieldStatement, DefineTableStatement, DeleteStatement, UpdateStatement,
+	UpsertStatement,
+};
+use crate::sql::Value as SqlValue;
+use crate::sql::{self, Data, Fields, Id, Idiom, Kind, Output, Statement, Thing};
+use async_graphql::dynamic::{Field, FieldFuture, FieldValue, ResolverContext};
+use async_graphql::dynamic::{InputObject, InputValue, Object, Type, TypeRef};
+use async_graphql::Value as GqlValue;
+
+use super::error::{resolver_error, GqlError};
+
+macro_rules! id_input {
+	() => {
+		InputValue::new("id", TypeRef::named_nn(TypeRef::ID))
+	};
+}
+
+#[derive(Clone, Copy)]
+enum MutationKind {
+	Create,
+	Update,
+	Upsert,
+	Delete,
+}
+
+#[expect(clippy::too_many_arguments)]
+pub async fn process_mutations(
+	tbs: Arc<[DefineTableStatement]>,
+	mut mutation: Object,
+	types: &mut Vec<Type>,
+	tx: &Transaction,
+	ns: &str,
+	db: &str,
+	session: &Session,
+	datastore: &Arc<Datastore>,
+) -> Result<Option<Object>, GqlError> {
+	let mut generated = false;
+
+	for tb in tbs.iter() {
+		// Views are computed from other tables, so can't be written to
+		if tb.view.is_some() {
+			continue;
+		}
+		trace!("Adding mutations for table: {}", tb.name);
+		let tb_name = tb.name.to_string();
+		let fds = tx.all_tb_fields(ns, db, &tb.name.0, None).await?;
+
+		let create_input_name = format!("_create_{tb_name}");
+		let mut create_input = InputObject::new(&create_input_name).description(format!(
+			"Generated from `{}` the data which can be used to create a record",
+			tb.name
+		));
+		let update_input_name = format!("_update_{tb_name}");
+		let mut update_input = InputObject::new(&update_input_name).description(format!(
+			"Generated from `{}` the data which can be merged into a record",
+			tb.name
+		));
+		let mut create_fields = false;
+		let mut update_fields = false;
+
+		for fd in fds.iter() {
+			let Some(ref kind) = fd.kind else {
+				continue;
+			};
+			// The id is passed as an argument, and nested
+			// fields are set through their parent field
+			if fd.name.is_id() || fd.name.len() != 1 {
+				continue;
+			}
+			// References are computed from the linking records
+			if matches!(kind, Kind::References(_, _)) {
+				continue;
+			}
+			let fd_name = fd.name.to_string();
+			let fd_type = kind_to_input_type(kind.clone(), types)?;
+			// Fields which are computed or have a default don't need to be provided
+			let create_type = match fd.value.is_some() || fd.default.is_some() {
+				true => unwrap_type(fd_type.clone()),
+				false => fd_type.clone(),
+			};
+			create_input = create_input.field(InputValue::new(&fd_name, create_type));
+			create_fields = true;
+			if !fd.readonly {
+				update_input = update_input.field(InputValue::new(&fd_name, unwrap_type(fd_type)));
+				update_fields = true;
+			}
+		}
+
+		// Tables without typed fields accept arbitrary objects
+		let create_data = match create_fields {
+			true => TypeRef::named(&create_input_name),
+			false => TypeRef::named("object"),
+		};
+		let update_data = match update_fields {
+			true => {
+				types.push(Type::InputObject(update_input));
+				TypeRef::named_nn(&update_input_name)
+			}
+			false => TypeRef::named_nn("object"),
+		};
+
+		let fds: Arc<[DefineFieldStatement]> = fds;
+
+		// Relations can only be created with a RELATE statement
+		if !tb.is_relation() {
+			if create_fields {
+				types.push(Type::InputObject(create_input));
+			}
+			mutation = mutation.field(
+				Field::new(
+					mutation_name("create", &tb_name),
+					TypeRef::named(&tb_name),
+					make_mutation_resolver(
+						MutationKind::Create,
+						&tb_name,
+						fds.clone(),
+						session,
+						datastore,
+					),
+				)
+				.description(format!(
+					"Generated from table `{}`\nallows creating a record",
+					tb.name
+				))
+				.argument(InputValue::new("id", TypeRef::named(TypeRef::ID)))
+				.argument(InputValue::new("data", create_data)),
+			);
+			mutation = mutation.field(
+				Field::new(
+					mutation_name("upsert", &tb_name),
+					TypeRef::named(&tb_name),
+					make_mutation_resolver(
+						MutationKind::Upsert,
+						&tb_name,
+						fds.clone(),
+						session,
+						datastore,
+					),
+				)
+				.description(format!(
+					"Generated from table `{}`\nallows creating or updating a record by ID",
+					tb.name
+				))
+				.argument(id_input!())
+				.argument(InputValue::new("data", update_data.clone())),
+			);
+		}
+
+		mutation = mutation.field(
+			Field::new(
+				mutation_name("update", &tb_name),
+				TypeRef::named(&tb_name),
+				make_mutation_resolver(
+					MutationKind::Update,
+					&tb_name,
+					fds.clone(),
+					session,
+					datastore,
+				),
+			)
+			.description(format!(
+				"Generated from table `{}`\nallows updating an existing record by ID",
+				tb.name
+			))
+			.argument(id_input!())
+			.argument(InputValue::new("data", update_data)),
+		);
+
+		mutation = mutation.field(
+			Field::new(
+				mutation_name("delete", &tb_name),
+				TypeRef::named_nn(TypeRef::BOOLEAN),
+				make_mutation_resolver(MutationKind::Delete, &tb_name, fds, session, datastore),
+			)
+			.description(format!(
+				"Generated from table `{}`\nallows deleting a record by ID, returning whether it existed",
+				tb.name
+			))
+			.argument(id_input!()),
+		);
+
+		generated = true;
+	}
+
+	Ok(generated.then_some(mutation))
+}
+
+/// The name of a mutation of a table, such as `createPerson` for `person`
+fn mutation_name(action: &str, tb_name: &str) -> String {
+	let mut chars = tb_name.chars();
+	match chars.next() {
+		Some(c) => format!("{action}{}{}", c.to_uppercase(), chars.as_str()),
+		None => action.to_string(),
+	}
+}
+
+fn make_mutation_resolver(
+	kind: MutationKind,
+	tb_name: &str,
+	fds: Arc<[DefineFieldStatement]>,
+	session: &Session,
+	datastore: &Arc<Datastore>,
+) -> impl for<'a> Fn(ResolverContext<'a>) -> FieldFuture<'a> + Send + Sync + 'static {
+	let tb_name = tb_name.to_string();
+	let sess = session.to_owned();
+	let kvs = datastore.to_owned();
+	move |ctx: ResolverContext| {
+		let tb_name = tb_name.clone();
+		let fds = fds.clone();
+		let sess = sess.clone();
+		let kvs = kvs.clone();
+		FieldFuture::new(async move {
+			let args = ctx.args.as_index_map();
+			trace!("received mutation with args: {args:?}");
+
+			let what = match args.get("id").and_then(GqlValueUtils::as_string) {
+				Some(id) => SqlValue::Thing(thing_from_id(&tb_name, id)?),
+				None => SqlValue::Table(tb_name.intox()),
+			};
+			let data = match args.get("data") {
+				None | Some(GqlValue::Null) => None,
+				Some(data) => Some(data_from_input(data, &fds)?),
+			};
+
+			// RETURN VALUE id
+			let output = Some(Output::Fields(Fields(
+				vec![sql::Field::Single {
+					expr: SqlValue::Idiom(Idiom::from("id")),
+					alias: None,
+				}],
+				true,
+			)));
+
+			let ast = match kind {
+				MutationKind::Create => Statement::Create(CreateStatement {
+					what: vec![what].into(),
+					data: data.map(Data::ContentExpression),
+					output,
+					..Default::default()
+				}),
+				MutationKind::Update => Statement::Update(UpdateStatement {
+					what: vec![what].into(),
+					data: data.map(Data::MergeExpression),
+					output,
+					..Default::default()
+				}),
+				MutationKind::Upsert => Statement::Upsert(UpsertStatement {
+					what: vec![what].into(),
+					data: data.map(Data::MergeExpression),
+					output,
+					..Default::default()
+				}),
+				MutationKind::Delete => Statement::Delete(DeleteStatement {
+					what: vec![what].into(),
+					output: Some(Output::Before),
+					..Default::default()
+				}),
+			};
+
+			trace!("generated mutation ast: {ast:?}");
+
+			let gtx = GQLTx::new_writeable(&kvs, &sess).await?;
+			let res = match gtx.process_stmt(ast).await {
+				Ok(res) => {
+					gtx.commit().await?;
+					res
+				}
+				Err(e) => {
+					gtx.cancel().await?;
+					return Err(e.into());
+				}
+			};
+
+			let first = match res {
+				SqlValue::Array(a) => a.0.into_iter().next(),
+				v => Some(v),
+			};
+
+			match (kind, first) {
+				(MutationKind::Delete, first) => {
+					Ok(Some(FieldValue::value(GqlValue::Boolean(first.is_some()))))
+				}
+				(_, Some(SqlValue::Thing(t))) => {
+					// The changes are committed, so the returned
+					// record is read back in a fresh transaction
+					let gtx = GQLTx::new(&kvs, &sess).await?;
+					Ok(Some(field_val_erase_owned((gtx, t))))
+				}
+				_ => Ok(None),
+			}
+		})
+	}
+}
+
+fn thing_from_id(tb_name: &str, id: String) -> Result<Thing, GqlError> {
+	let thing = match Thing::try_from(id.as_str()) {
+		Ok(t) => t,
+		// Bare keys are parsed as if they were prefixed with the table
+		Err(_) => match Thing::try_from(format!("{tb_name}:{id}")) {
+			Ok(t) => t,
+			Err(_) => return Ok(Thing::from((tb_name.to_string(), id))),
+		},
+	};
+	// A mutation by id must only ever affect a single record
+	if let Id::Range(_) = thing.id {
+		return Err(resolver_error(format!("Record ranges are not supported, found `{thing}`")));
+	}
+	if thing.tb != tb_name {
+		return Err(resolver_error(format!("Record `{thing}` is not in table `{tb_name}`")));
+	}
+	Ok(thing)
+}
+
+fn data_from_input(data: &GqlValue, fds: &[DefineFieldStatement]) -> Result<SqlValue, GqlError> {
+	let GqlValue::Object(obj) = data else {
+		return gql_to_sql_kind(data, Kind::Object);
+	};
+	let mut out = BTreeMap::new();
+	for (k, v) in obj.iter() {
+		let kind = fds
+			.iter()
+			.find(|fd| fd.name.to_string() == k.as_str())
+			.and_then(|fd| fd.kind.clone())
+			.unwrap_or_default();
+		out.insert(k.to_string(), gql_to_sql_kind(v, kind)?);
+	}
+	Ok(SqlValue::Object(out.into()))
+}
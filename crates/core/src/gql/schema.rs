@@ -3,6 +3,8 @@ use std::sync::Arc;
 
 use crate::dbs::Session;
 use crate::gql::functions::process_fns;
+use crate::gql::mutations::process_mutations;
+use crate::gql::subscriptions::process_subscriptions;
 use crate::gql::tables::process_tbs;
 use crate::kvs::Datastore;
 use crate::sql;
@@ -14,6 +16,7 @@ use async_graphql::dynamic::Interface;
 use async_graphql::dynamic::InterfaceField;
 use async_graphql::dynamic::Object;
 use async_graphql::dynamic::Schema;
+use async_graphql::dynamic::Subscription;
 use async_graphql::dynamic::{Enum, Type, Union};
 use async_graphql::dynamic::{Scalar, TypeRef};
 use async_graphql::Name;
@@ -93,9 +96,35 @@ pub async fn generate_schema(
 
 	trace!(ns, db, ?tbs, ?fns, "generating schema");
 
+	let mut mutation = None;
+	let mut subscription = None;
+
 	match tbs {
 		Some(tbs) if !tbs.is_empty() => {
-			query = process_tbs(tbs, query, &mut types, &tx, ns, db, session, datastore).await?;
+			query = process_tbs(tbs.clone(), query, &mut types, &tx, ns, db, session, datastore)
+				.await?;
+			mutation = process_mutations(
+				tbs.clone(),
+				Object::new("Mutation"),
+				&mut types,
+				&tx,
+				ns,
+				db,
+				session,
+				datastore,
+			)
+			.await?;
+			subscription = process_subscriptions(
+				tbs,
+				Subscription::new("Subscription"),
+				&mut types,
+				&tx,
+				ns,
+				db,
+				session,
+				datastore,
+			)
+			.await?;
 		}
 		_ => {}
 	}
@@ -105,8 +134,20 @@ pub async fn generate_schema(
 	}
 
 	trace!("current Query object for schema: {:?}", query);
-
-	let mut schema = Schema::build("Query", None, None).register(query);
+	trace!("current Mutation object for schema: {:?}", mutation);
+
+	let mut schema = Schema::build(
+		"Query",
+		mutation.as_ref().map(|_| "Mutation"),
+		subscription.as_ref().map(|_| "Subscription"),
+	)
+	.register(query);
+	if let Some(mutation) = mutation {
+		schema = schema.register(mutation);
+	}
+	if let Some(subscription) = subscription {
+		schema = schema.register(subscription);
+	}
 	for ty in types {
 		trace!("adding type: {ty:?}");
 		schema = schema.register(ty);
@@ -336,6 +377,36 @@ pub fn kind_to_type(kind: Kind, types: &mut Vec<Type>) -> Result<TypeRef, GqlErr
 	Ok(out)
 }
 
+/// Converts a field kind into a type which can be used as a mutation input.
+///
+/// Output types such as record objects and unions are not valid as inputs, so
+/// record links are accepted as IDs and mixed unions fall back to `any`.
+pub fn kind_to_input_type(kind: Kind, types: &mut Vec<Type>) -> Result<TypeRef, GqlError> {
+	let (optional, match_kind) = match kind {
+		Kind::Option(op_ty) => (true, *op_ty),
+		_ => (false, kind),
+	};
+	let out_ty = match match_kind {
+		Kind::Option(t) => return kind_to_input_type(Kind::Option(t), types),
+		Kind::Record(_) => TypeRef::named(TypeRef::ID),
+		Kind::Array(k, _) => TypeRef::List(Box::new(kind_to_input_type(*k, types)?)),
+		Kind::Either(ks) => {
+			if ks.iter().all(|k| matches!(k, Kind::Literal(Literal::String(_)))) {
+				unwrap_type(kind_to_type(Kind::Either(ks), types)?)
+			} else {
+				TypeRef::named("any")
+			}
+		}
+		k => unwrap_type(kind_to_type(k, types)?),
+	};
+
+	let out = match optional {
+		true => out_ty,
+		false => TypeRef::NonNull(Box::new(out_ty)),
+	};
+	Ok(out)
+}
+
 pub fn unwrap_type(ty: TypeRef) -> TypeRef {
 	match ty {
 		TypeRef::NonNull(t) => unwrap_type(*t),
@@ -569,7 +640,7 @@ pub fn gql_to_sql_kind(val: &GqlValue, kind: Kind) -> Result<SqlValue, GqlError>
 		},
 		Kind::Record(ref ts) => match val {
 			GqlValue::String(s) => match syn::thing(s) {
-				Ok(t) => match ts.contains(&t.tb.as_str().into()) {
+				Ok(t) => match ts.is_empty() || ts.contains(&t.tb.as_str().into()) {
 					true => Ok(SqlValue::Thing(t)),
 					false => Err(type_error(kind, val)),
 				},
@@ -0,0 +1,287 @@
+use std::sync::Arc;
+
+use async_channel::{Receiver, Sender, TrySendError};
+use async_graphql::dynamic::{Enum, Field, FieldFuture, FieldValue, InputValue, Object};
+use async_graphql::dynamic::{Subscription, SubscriptionField, SubscriptionFieldFuture};
+use async_graphql::dynamic::{Type, TypeRef};
+use async_graphql::{Name, Value as GqlValue};
+use dashmap::DashMap;
+use futures::stream;
+use uuid::Uuid;
+
+use crate::dbs::{Action, Notification, Session};
+use crate::gql::ext::IntoExt;
+use crate::gql::tables::{cond_from_filter, filter_name_from_table};
+use crate::gql::utils::{ErasedSnapshot, GQLTx};
+use crate::kvs::{Datastore, Transaction};
+use crate::sql::statements::{DefineTableStatement, KillStatement, LiveStatement};
+use crate::sql::{Fields, Statement, Value as SqlValue};
+
+use super::error::{internal_error, resolver_error, GqlError};
+
+/// The number of notifications which are buffered for each subscription
+const SUBSCRIPTION_CHANNEL_SIZE: usize = 100;
+
+/// Routes live query notifications to the GraphQL subscriptions which started them.
+///
+/// A single instance should be shared between the task which receives
+/// notifications from the datastore, and the GraphQL service, which passes
+/// it to subscription resolvers as request data.
+#[derive(Debug, Default)]
+pub struct Subscriptions {
+	senders: DashMap<Uuid, Sender<Notification>>,
+}
+
+impl Subscriptions {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	fn register(&self, id: Uuid) -> Receiver<Notification> {
+		let (snd, rcv) = async_channel::bounded(SUBSCRIPTION_CHANNEL_SIZE);
+		self.senders.insert(id, snd);
+		rcv
+	}
+
+	fn unregister(&self, id: &Uuid) {
+		self.senders.remove(id);
+	}
+
+	/// Deliver a notification to the subscription which started the live query.
+	///
+	/// A subscription which does not keep up with its notifications is ended once
+	/// its buffer is full, rather than silently missing notifications, after which
+	/// the client can subscribe again. Returns the notification if no subscription
+	/// is registered for it.
+	pub fn deliver(&self, notification: Notification) -> Option<Notification> {
+		let id = notification.id.0;
+		let Some(snd) = self.senders.get(&id).map(|s| s.clone()) else {
+			return Some(notification);
+		};
+		match snd.try_send(notification) {
+			Ok(_) => (),
+			// The subscription has fallen behind
+			Err(TrySendError::Full(_)) => {
+				warn!("Ending GraphQL subscription {id}, as it is not receiving notifications");
+				self.senders.remove(&id);
+			}
+			// The subscription has been dropped
+			Err(TrySendError::Closed(_)) => {
+				self.senders.remove(&id);
+			}
+		}
+		None
+	}
+}
+
+/// The payload of a live notification, with the record snapshot it carries
+struct LiveNotification {
+	action: Action,
+	record: ErasedSnapshot,
+}
+
+/// Kills the live query of a subscription once the client stops listening
+struct LiveGuard {
+	id: Uuid,
+	subs: Arc<Subscriptions>,
+	kvs: Arc<Datastore>,
+	sess: Session,
+}
+
+impl Drop for LiveGuard {
+	fn drop(&mut self) {
+		self.subs.unregister(&self.id);
+		let Ok(handle) = tokio::runtime::Handle::try_current() else {
+			return;
+		};
+		let id = self.id;
+		let kvs = self.kvs.clone();
+		let sess = self.sess.clone();
+		handle.spawn(async move {
+			let ast = Statement::Kill(KillStatement {
+				id: SqlValue::Uuid(id.into()),
+			});
+			let res = match GQLTx::new_writeable(&kvs, &sess).await {
+				Ok(gtx) => match gtx.process_stmt(ast).await {
+					Ok(_) => gtx.commit().await,
+					Err(e) => gtx.cancel().await.and(Err(e)),
+				},
+				Err(e) => Err(e),
+			};
+			if let Err(e) = res {
+				trace!("failed to kill live query {id} for subscription: {e}");
+			}
+		});
+	}
+}
+
+#[expect(clippy::too_many_arguments)]
+pub async fn process_subscriptions(
+	tbs: Arc<[DefineTableStatement]>,
+	mut subscription: Subscription,
+	types: &mut Vec<Type>,
+	tx: &Transaction,
+	ns: &str,
+	db: &str,
+	session: &Session,
+	datastore: &Arc<Datastore>,
+) -> Result<Option<Subscription>, GqlError> {
+	if tbs.is_empty() {
+		return Ok(None);
+	}
+
+	types.push(Type::Enum(
+		Enum::new("_live_action")
+			.description("The action which caused a live notification")
+			.item("CREATE")
+			.item("UPDATE")
+			.item("DELETE"),
+	));
+
+	for tb in tbs.iter() {
+		trace!("Adding subscription for table: {}", tb.name);
+		let tb_name = tb.name.to_string();
+		let fds = tx.all_tb_fields(ns, db, &tb.name.0, None).await?;
+
+		let live_ty_name = format!("_live_{tb_name}");
+		let live_ty = Object::new(&live_ty_name)
+			.description(format!("Generated from `{}` a live notification", tb.name))
+			.field(Field::new("action", TypeRef::named_nn("_live_action"), |ctx| {
+				FieldFuture::new(async move {
+					let n = ctx
+						.parent_value
+						.downcast_ref::<LiveNotification>()
+						.ok_or_else(|| internal_error("failed to downcast"))?;
+					Ok(Some(FieldValue::value(GqlValue::Enum(Name::new(n.action.to_string())))))
+				})
+			}))
+			.field(Field::new("id", TypeRef::named_nn(TypeRef::ID), |ctx| {
+				FieldFuture::new(async move {
+					let n = ctx
+						.parent_value
+						.downcast_ref::<LiveNotification>()
+						.ok_or_else(|| internal_error("failed to downcast"))?;
+					Ok(Some(FieldValue::value(GqlValue::String(n.record.1.to_string()))))
+				})
+			}))
+			.field(Field::new("record", TypeRef::named_nn(&tb_name), |ctx| {
+				FieldFuture::new(async move {
+					let n = ctx
+						.parent_value
+						.downcast_ref::<LiveNotification>()
+						.ok_or_else(|| internal_error("failed to downcast"))?;
+					Ok(Some(FieldValue::owned_any(n.record.clone())))
+				})
+			}));
+		types.push(Type::Object(live_ty));
+
+		let sess1 = session.to_owned();
+		let kvs1 = datastore.to_owned();
+		let tb_name1 = tb_name.clone();
+		subscription = subscription.field(
+			SubscriptionField::new(&tb_name, TypeRef::named_nn(&live_ty_name), move |ctx| {
+				let tb_name = tb_name1.clone();
+				let fds = fds.clone();
+				// Live queries can only be started from realtime sessions
+				let sess = sess1.clone().with_rt(true);
+				let kvs = kvs1.clone();
+				SubscriptionFieldFuture::new(async move {
+					let subs = ctx
+						.data_opt::<Arc<Subscriptions>>()
+						.ok_or_else(|| {
+							resolver_error(
+								"Subscriptions are only available over WebSocket connections",
+							)
+						})?
+						.clone();
+
+					let cond = match ctx.args.as_index_map().get("filter") {
+						Some(GqlValue::Object(o)) => Some(cond_from_filter(o, &fds)?),
+						_ => None,
+					};
+
+					// LIVE SELECT * FROM ...
+					let live = LiveStatement::from_source_parts(
+						Fields::all(),
+						SqlValue::Table(tb_name.intox()),
+						cond,
+						None,
+					);
+					let id = live.id.0;
+
+					// Register before starting the live query, so no notifications are missed
+					let rcv = subs.register(id);
+					let guard = LiveGuard {
+						id,
+						subs,
+						kvs,
+						sess,
+					};
+
+					let gtx = GQLTx::new_writeable(&guard.kvs, &guard.sess).await?;
+					match gtx.process_stmt(Statement::Live(live)).await {
+						Ok(_) => gtx.commit().await?,
+						Err(e) => {
+							gtx.cancel().await?;
+							return Err(e.into());
+						}
+					}
+
+					let out = stream::unfold((rcv, guard), |(rcv, guard)| async move {
+						loop {
+							let notification = rcv.recv().await.ok()?;
+							let action = match notification.action {
+								Action::Killed => return None,
+								action => action,
+							};
+							let SqlValue::Thing(rid) = notification.record else {
+								continue;
+							};
+							let item = GQLTx::new(&guard.kvs, &guard.sess).await.map(|gtx| {
+								FieldValue::owned_any(LiveNotification {
+									action,
+									record: (gtx, rid, notification.result),
+								})
+							});
+							return Some((item.map_err(Into::into), (rcv, guard)));
+						}
+					});
+
+					Ok(out)
+				})
+			})
+			.description(format!(
+				"Generated from table `{}`\nallows listening to changes to records in a table",
+				tb.name
+			))
+			.argument(InputValue::new("filter", TypeRef::named(filter_name_from_table(&tb_name)))),
+		);
+	}
+
+	Ok(Some(subscription))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn deliver_ends_lagging_subscriptions() {
+		let subs = Subscriptions::new();
+		let id = Uuid::new_v4();
+		let rcv = subs.register(id);
+		let notify =
+			|| Notification::new(id.into(), Action::Create, SqlValue::None, SqlValue::None);
+		for _ in 0..SUBSCRIPTION_CHANNEL_SIZE {
+			assert!(subs.deliver(notify()).is_none());
+		}
+		// The subscription is ended once its buffer is full
+		assert!(subs.deliver(notify()).is_none());
+		assert!(!subs.senders.contains_key(&id));
+		// The buffered notifications can still be received
+		assert_eq!(rcv.len(), SUBSCRIPTION_CHANNEL_SIZE);
+		assert!(rcv.is_closed());
+		// Later notifications are not routed to the subscription
+		assert!(subs.deliver(notify()).is_some());
+	}
+}
@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::fmt::Display;
 use std::sync::Arc;
 
@@ -7,10 +8,10 @@ use crate::gql::schema::{kind_to_type, unwrap_type};
 use crate::kvs::{Datastore, Transaction};
 use crate::sql::order::{OrderList, Ordering};
 use crate::sql::statements::{DefineFieldStatement, DefineTableStatement, SelectStatement};
-use crate::sql::{self, Table};
+use crate::sql::{self, Dir, Graph, Table, TableType};
 use crate::sql::{Cond, Fields};
 use crate::sql::{Expression, Value as SqlValue};
-use crate::sql::{Idiom, Kind};
+use crate::sql::{Idiom, Kind, Part};
 use crate::sql::{Statement, Thing};
 use async_graphql::dynamic::indexmap::IndexMap;
 use async_graphql::dynamic::FieldFuture;
@@ -26,7 +27,9 @@ use super::error::{resolver_error, GqlError};
 use super::ext::IntoExt;
 use super::schema::{gql_to_sql_kind, sql_value_to_gql_value};
 use crate::gql::error::internal_error;
-use crate::gql::utils::{field_val_erase_owned, ErasedRecord, GQLTx, GqlValueUtils};
+use crate::gql::utils::{
+	field_val_erase_owned, ErasedRecord, ErasedSnapshot, GQLTx, GqlValueUtils,
+};
 
 macro_rules! order {
 	(asc, $field:expr) => {{
@@ -60,7 +63,7 @@ macro_rules! id_input {
 	};
 }
 
-fn filter_name_from_table(tb_name: impl Display) -> String {
+pub fn filter_name_from_table(tb_name: impl Display) -> String {
 	format!("_filter_{tb_name}")
 }
 
@@ -75,6 +78,25 @@ pub async fn process_tbs(
 	session: &Session,
 	datastore: &Arc<Datastore>,
 ) -> Result<Object, GqlError> {
+	// The fields of every table are fetched up front, so that
+	// related tables can be filtered by their own fields
+	let mut tb_fds = HashMap::new();
+	for tb in tbs.iter() {
+		tb_fds.insert(tb.name.to_string(), tx.all_tb_fields(ns, db, &tb.name.0, None).await?);
+	}
+
+	let relations: Vec<RelationInfo> = tbs
+		.iter()
+		.filter_map(|tb| match &tb.kind {
+			TableType::Relation(rel) => Some(RelationInfo {
+				name: tb.name.to_string(),
+				from: relation_tables(&rel.from),
+				to: relation_tables(&rel.to),
+			}),
+			_ => None,
+		})
+		.collect();
+
 	for tb in tbs.iter() {
 		trace!("Adding table: {}", tb.name);
 		let tb_name = tb.name.to_string();
@@ -97,7 +119,7 @@ pub async fn process_tbs(
 			.field(InputValue::new("desc", TypeRef::named(&table_orderable_name)))
 			.field(InputValue::new("then", TypeRef::named(&table_order_name)));
 
-		let table_filter_name = filter_name_from_table(tb_name);
+		let table_filter_name = filter_name_from_table(&tb_name);
 		let mut table_filter = InputObject::new(&table_filter_name);
 		table_filter = table_filter
 			.field(InputValue::new("id", TypeRef::named("_filter_id")))
@@ -107,7 +129,7 @@ pub async fn process_tbs(
 		types.push(Type::InputObject(filter_id()));
 
 		let sess1 = session.to_owned();
-		let fds = tx.all_tb_fields(ns, db, &tb.name.0, None).await?;
+		let fds = tb_fds[&tb.name.0].clone();
 		let fds1 = fds.clone();
 		let kvs1 = datastore.clone();
 
@@ -246,7 +268,6 @@ pub async fn process_tbs(
         .argument(InputValue::new("order", TypeRef::named(&table_order_name)))
         .argument(InputValue::new("filter", TypeRef::named(&table_filter_name))),
     );
-
 		let sess2 = session.to_owned();
 		let kvs2 = datastore.to_owned();
 		query =
@@ -319,7 +340,10 @@ pub async fn process_tbs(
 			let fd_name = Name::new(fd.name.to_string());
 			let fd_type = kind_to_type(kind.clone(), types)?;
 			table_orderable = table_orderable.item(fd_name.to_string());
-			let type_filter_name = format!("_filter_{}", unwrap_type(fd_type.clone()));
+			let type_filter_name = match link_list_tables(kind) {
+				Some(_) => "_filter_id_list".to_string(),
+				None => format!("_filter_{}", unwrap_type(fd_type.clone())),
+			};
 
 			let type_filter =
 				Type::InputObject(filter_from_type(kind.clone(), type_filter_name.clone(), types)?);
@@ -329,17 +353,54 @@ pub async fn process_tbs(
 			table_filter = table_filter
 				.field(InputValue::new(fd.name.to_string(), TypeRef::named(type_filter_name)));
 
-			table_ty_obj = table_ty_obj
-				.field(Field::new(
+			// Lists of record links can be filtered and paginated like a table
+			let field = match link_list_tables(kind) {
+				Some(ts) => {
+					let mut field = Field::new(
+						fd.name.to_string(),
+						fd_type,
+						make_link_list_resolver(fd_name.as_str(), &ts, &tb_fds),
+					);
+					if let [ft] = ts.as_slice() {
+						if tb_fds.contains_key(ft) {
+							field = field
+								.argument(limit_input!())
+								.argument(start_input!())
+								.argument(InputValue::new(
+									"order",
+									TypeRef::named(format!("_order_{ft}")),
+								))
+								.argument(InputValue::new(
+									"filter",
+									TypeRef::named(filter_name_from_table(ft)),
+								));
+						}
+					}
+					field
+				}
+				None => Field::new(
 					fd.name.to_string(),
 					fd_type,
 					make_table_field_resolver(fd_name.as_str(), fd.kind.clone()),
-				))
-				.description(if let Some(ref c) = fd.comment {
-					format!("{c}")
-				} else {
-					"".to_string()
-				});
+				),
+			};
+
+			table_ty_obj = table_ty_obj.field(field).description(if let Some(ref c) = fd.comment {
+				format!("{c}")
+			} else {
+				"".to_string()
+			});
+		}
+
+		// Edges of relation tables which can start or end at this table
+		for rel in relations.iter() {
+			let rel_fds = tb_fds[&rel.name].clone();
+			if rel.from.as_ref().is_none_or(|ts| ts.contains(&tb_name)) {
+				table_ty_obj = table_ty_obj.field(relation_field(rel, Dir::Out, rel_fds.clone()));
+			}
+			if rel.to.as_ref().is_none_or(|ts| ts.contains(&tb_name)) {
+				table_ty_obj = table_ty_obj.field(relation_field(rel, Dir::In, rel_fds));
+			}
 		}
 
 		types.push(Type::Object(table_ty_obj));
@@ -392,6 +453,267 @@ pub async fn process_tbs(
 	Ok(query)
 }
 
+/// A relation table, and the tables which its edges can start and end at
+struct RelationInfo {
+	name: String,
+	from: Option<Vec<String>>,
+	to: Option<Vec<String>>,
+}
+
+/// The tables a relation is restricted to, or `None` if any table is allowed
+fn relation_tables(kind: &Option<Kind>) -> Option<Vec<String>> {
+	match kind {
+		Some(Kind::Record(ts)) if !ts.is_empty() => Some(ts.iter().map(|t| t.0.clone()).collect()),
+		_ => None,
+	}
+}
+
+/// The tables linked to by a field, if it is a list of record links
+fn link_list_tables(kind: &Kind) -> Option<Vec<String>> {
+	let kind = match kind {
+		Kind::Option(k) => k.as_ref(),
+		k => k,
+	};
+	match kind {
+		Kind::Array(k, _) | Kind::Set(k, _) => match k.as_ref() {
+			Kind::Record(ts) => Some(ts.iter().map(|t| t.0.clone()).collect()),
+			_ => None,
+		},
+		_ => None,
+	}
+}
+
+fn relation_field(rel: &RelationInfo, dir: Dir, fds: Arc<[DefineFieldStatement]>) -> Field {
+	let (name, desc) = match dir {
+		Dir::In => (format!("_in_{}", rel.name), format!("incoming `<-{}` edges", rel.name)),
+		_ => (format!("_out_{}", rel.name), format!("outgoing `->{}` edges", rel.name)),
+	};
+	let rel_name = rel.name.clone();
+	Field::new(name, TypeRef::named_nn_list_nn(&rel.name), move |ctx| {
+		let rel_name = rel_name.clone();
+		let dir = dir.clone();
+		let fds = fds.clone();
+		FieldFuture::new(async move {
+			let (gtx, rid) = parent_record(&ctx)?;
+
+			let args = ctx.args.as_index_map();
+			trace!("received relation request with args: {args:?}");
+
+			// $parent->rel or $parent<-rel
+			let what = SqlValue::Idiom(Idiom(vec![
+				Part::Start(SqlValue::Thing(rid.clone())),
+				Part::Graph(Graph {
+					dir,
+					what: Table::from(rel_name).into(),
+					..Default::default()
+				}),
+			]));
+			let ids = select_ids(gtx, what, None, args, &fds).await?;
+
+			Ok(Some(FieldValue::list(
+				ids.into_iter().map(|t| field_val_erase_owned((gtx.clone(), t))),
+			)))
+		})
+	})
+	.description(format!("Generated from relation `{}`\nallows traversing {desc}", rel.name))
+	.argument(limit_input!())
+	.argument(start_input!())
+	.argument(InputValue::new("order", TypeRef::named(format!("_order_{}", rel.name))))
+	.argument(InputValue::new("filter", TypeRef::named(filter_name_from_table(&rel.name))))
+}
+
+fn make_link_list_resolver(
+	fd_name: impl Into<String>,
+	tables: &[String],
+	tb_fds: &HashMap<String, Arc<[DefineFieldStatement]>>,
+) -> impl for<'a> Fn(ResolverContext<'a>) -> FieldFuture<'a> + Send + Sync + 'static {
+	let fd_name = fd_name.into();
+	// Links to a single table can be filtered using that table's fields
+	let fds = match tables {
+		[ft] => tb_fds.get(ft).cloned(),
+		_ => None,
+	};
+	let multi = tables.len() != 1;
+	move |ctx: ResolverContext| {
+		let fd_name = fd_name.clone();
+		let fds = fds.clone();
+		FieldFuture::new(async move {
+			let (gtx, val) = parent_field(&ctx, &fd_name).await?;
+
+			let links: Vec<Thing> = match val {
+				SqlValue::Array(a) => {
+					a.0.into_iter().filter_map(|v| v.try_as_thing().ok()).collect()
+				}
+				SqlValue::None | SqlValue::Null => return Ok(None),
+				v => return Err(internal_error(format!("expected array, found: {v:?}")).into()),
+			};
+
+			let args = ctx.args.as_index_map();
+			let ids = match (args.is_empty(), fds) {
+				(false, Some(fds)) => {
+					let what = SqlValue::Array(
+						links.into_iter().map(SqlValue::Thing).collect::<Vec<_>>().into(),
+					);
+					select_ids(gtx, what, None, args, &fds).await?
+				}
+				_ => links,
+			};
+
+			Ok(Some(FieldValue::list(ids.into_iter().map(|t| {
+				let ty = t.tb.clone();
+				let out = field_val_erase_owned((gtx.clone(), t));
+				match multi {
+					true => out.with_type(ty),
+					false => out,
+				}
+			}))))
+		})
+	}
+}
+
+/// Gets the record which a field is being resolved on
+fn parent_record<'a>(ctx: &ResolverContext<'a>) -> Result<(&'a GQLTx, &'a Thing), GqlError> {
+	if let Some((gtx, rid)) = ctx.parent_value.downcast_ref::<ErasedRecord>() {
+		Ok((gtx, rid))
+	} else if let Some((gtx, rid, _)) = ctx.parent_value.downcast_ref::<ErasedSnapshot>() {
+		Ok((gtx, rid))
+	} else {
+		Err(internal_error("failed to downcast"))
+	}
+}
+
+/// Gets the value of a field on the record which is being resolved
+async fn parent_field<'a>(
+	ctx: &ResolverContext<'a>,
+	fd_name: &str,
+) -> Result<(&'a GQLTx, SqlValue), GqlError> {
+	if let Some((gtx, rid)) = ctx.parent_value.downcast_ref::<ErasedRecord>() {
+		Ok((gtx, gtx.get_record_field(rid.clone(), fd_name).await?))
+	} else if let Some((gtx, _, doc)) = ctx.parent_value.downcast_ref::<ErasedSnapshot>() {
+		Ok((gtx, doc.pick(&[Part::from(fd_name)])))
+	} else {
+		Err(internal_error("failed to downcast"))
+	}
+}
+
+/// Parses the `order` argument of a query into a list of orderings
+fn orders_from_arg(order: Option<&GqlValue>) -> Result<Option<Vec<sql::Order>>, GqlError> {
+	let Some(GqlValue::Object(o)) = order else {
+		return Ok(None);
+	};
+	let mut orders = vec![];
+	let mut current = o;
+	loop {
+		let asc = current.get("asc");
+		let desc = current.get("desc");
+		match (asc, desc) {
+			(Some(_), Some(_)) => {
+				return Err(resolver_error("Found both ASC and DESC in order"));
+			}
+			(Some(GqlValue::Enum(a)), None) => orders.push(order!(asc, a.as_str())),
+			(None, Some(GqlValue::Enum(d))) => orders.push(order!(desc, d.as_str())),
+			(_, _) => {
+				break;
+			}
+		}
+		if let Some(GqlValue::Object(next)) = current.get("then") {
+			current = next;
+		} else {
+			break;
+		}
+	}
+	Ok(Some(orders))
+}
+
+/// Selects the ids of the records in `what` which match the filter,
+/// ordering, and pagination arguments of a relation or link list field
+async fn select_ids(
+	gtx: &GQLTx,
+	what: SqlValue,
+	cond: Option<SqlValue>,
+	args: &IndexMap<Name, GqlValue>,
+	fds: &[DefineFieldStatement],
+) -> Result<Vec<Thing>, GqlError> {
+	let start = args.get("start").and_then(|v| v.as_i64()).map(|s| s.intox());
+
+	let limit = args.get("limit").and_then(|v| v.as_i64()).map(|l| l.intox());
+
+	let orders = orders_from_arg(args.get("order"))?;
+
+	trace!("parsed orders: {orders:?}");
+
+	let filter = match args.get("filter") {
+		Some(GqlValue::Object(o)) => Some(val_from_filter(o, fds)?),
+		None | Some(GqlValue::Null) => None,
+		Some(f) => {
+			error!("Found filter {f}, which should be object and should have been rejected by async graphql.");
+			return Err(resolver_error("Value in cond doesn't fit schema"));
+		}
+	};
+
+	let cond = match (cond, filter) {
+		(Some(l), Some(r)) => Some(
+			Expression::Binary {
+				l,
+				o: sql::Operator::And,
+				r,
+			}
+			.into(),
+		),
+		(cond, filter) => cond.or(filter),
+	};
+
+	trace!("parsed filter: {cond:?}");
+
+	// Ordering is applied to the selected fields, so any
+	// ordered fields are selected alongside the record id
+	let mut fields = vec![sql::Field::Single {
+		expr: SqlValue::Idiom(Idiom::from("id")),
+		alias: None,
+	}];
+	for o in orders.iter().flatten() {
+		fields.push(sql::Field::Single {
+			expr: SqlValue::Idiom(o.value.clone()),
+			alias: None,
+		});
+	}
+
+	// SELECT id, ... FROM ...
+	let ast = Statement::Select({
+		SelectStatement {
+			what: vec![what].into(),
+			expr: Fields(fields, false),
+			order: orders.map(|x| Ordering::Order(OrderList(x))),
+			cond: cond.map(IntoExt::intox),
+			limit,
+			start,
+			..Default::default()
+		}
+	});
+
+	trace!("generated query ast: {ast:?}");
+
+	let res = gtx.process_stmt(ast).await?;
+
+	let res_vec = match res {
+		SqlValue::Array(a) => a,
+		v => {
+			error!("Found top level value, in result which should be array: {v:?}");
+			return Err(internal_error("Internal Error"));
+		}
+	};
+
+	res_vec
+		.0
+		.into_iter()
+		.map(|v| {
+			v.pick(&[Part::from("id")])
+				.try_as_thing()
+				.map_err(|v| internal_error(format!("expected thing, found: {v:?}")))
+		})
+		.collect()
+}
+
 fn make_table_field_resolver(
 	fd_name: impl Into<String>,
 	kind: Option<Kind>,
@@ -402,12 +724,7 @@ fn make_table_field_resolver(
 		let field_kind = kind.clone();
 		FieldFuture::new({
 			async move {
-				let (ref gtx, ref rid) = ctx
-					.parent_value
-					.downcast_ref::<ErasedRecord>()
-					.ok_or_else(|| internal_error("failed to downcast"))?;
-
-				let val = gtx.get_record_field(rid.clone(), fd_name.as_str()).await?;
+				let (gtx, val) = parent_field(&ctx, &fd_name).await?;
 
 				let out = match val {
 					SqlValue::Thing(rid) if fd_name != "id" => {
@@ -456,6 +773,9 @@ fn filter_from_type(
 	types: &mut Vec<Type>,
 ) -> Result<InputObject, GqlError> {
 	let ty = match &kind {
+		k if link_list_tables(k).is_some() => {
+			TypeRef::List(Box::new(TypeRef::named_nn(TypeRef::ID)))
+		}
 		Kind::Record(ts) => match ts.len() {
 			1 => TypeRef::named(filter_name_from_table(
 				ts.first().expect("ts should have exactly one element").as_str(),
@@ -500,7 +820,7 @@ fn filter_from_type(
 	Ok(filter)
 }
 
-fn cond_from_filter(
+pub fn cond_from_filter(
 	filter: &IndexMap<Name, GqlValue>,
 	fds: &[DefineFieldStatement],
 ) -> Result<Cond, GqlError> {
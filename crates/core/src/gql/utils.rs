@@ -68,6 +68,19 @@ pub struct GQLTx {
 
 impl GQLTx {
 	pub async fn new(kvs: &Arc<Datastore>, sess: &Session) -> Result<Self, GqlError> {
+		Self::with_type(kvs, sess, TransactionType::Read).await
+	}
+
+	/// Create a transaction which can be used to process mutations
+	pub async fn new_writeable(kvs: &Arc<Datastore>, sess: &Session) -> Result<Self, GqlError> {
+		Self::with_type(kvs, sess, TransactionType::Write).await
+	}
+
+	async fn with_type(
+		kvs: &Arc<Datastore>,
+		sess: &Session,
+		tt: TransactionType,
+	) -> Result<Self, GqlError> {
 		kvs.check_anon(sess).map_err(|_| {
 			Error::IamError(IamError::NotAllowed {
 				actor: "anonymous".to_string(),
@@ -76,7 +89,7 @@ impl GQLTx {
 			})
 		})?;
 
-		let tx = kvs.transaction(TransactionType::Read, LockType::Optimistic).await?;
+		let tx = kvs.transaction(tt, LockType::Optimistic).await?;
 		let tx = Arc::new(tx);
 		let mut ctx = kvs.setup_ctx()?;
 		ctx.set_transaction(tx);
@@ -89,6 +102,14 @@ impl GQLTx {
 		})
 	}
 
+	pub async fn commit(&self) -> Result<(), GqlError> {
+		self.ctx.tx().commit().await.map_err(Into::into)
+	}
+
+	pub async fn cancel(&self) -> Result<(), GqlError> {
+		self.ctx.tx().cancel().await.map_err(Into::into)
+	}
+
 	pub async fn get_record_field(
 		&self,
 		rid: Thing,
@@ -134,6 +155,10 @@ impl GQLTx {
 
 pub type ErasedRecord = (GQLTx, Thing);
 
+/// A record which is resolved from a snapshot of its contents, rather than
+/// being read from the datastore, such as a record in a live notification.
+pub type ErasedSnapshot = (GQLTx, Thing, SqlValue);
+
 pub fn field_val_erase_owned(val: ErasedRecord) -> FieldValue<'static> {
 	FieldValue::owned_any(val)
 }
@@ -1,14 +1,19 @@
 use crate::err::Error;
 use crate::idx::ft::analyzer::mapper::Mapper;
+use crate::idx::ft::analyzer::stopwords::language_stopwords;
+use crate::idx::ft::analyzer::synonyms::Synonyms;
 use crate::idx::ft::analyzer::tokenizer::Tokens;
 use crate::idx::ft::offsets::Position;
 use crate::idx::trees::store::IndexStores;
-use crate::sql::filter::Filter as SqlFilter;
+use crate::sql::filter::{
+	Filter as SqlFilter, FilterStage, Stopwords as SqlStopwords, Synonyms as SqlSynonyms,
+};
 use crate::sql::language::Language;
 use deunicode::deunicode;
 use rust_stemmers::{Algorithm, Stemmer};
+use std::collections::HashSet;
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, PartialEq)]
 pub(super) enum FilteringStage {
 	Indexing,
 	Querying,
@@ -21,6 +26,9 @@ pub(super) enum Filter {
 	Lowercase,
 	Uppercase,
 	Mapper(Mapper),
+	Stopwords(HashSet<String>),
+	/// The synonyms, and the only stage at which they are expanded, if any
+	Synonyms(Synonyms, Option<FilteringStage>),
 }
 
 impl Filter {
@@ -55,6 +63,22 @@ impl Filter {
 			}
 			SqlFilter::Uppercase => Filter::Uppercase,
 			SqlFilter::Mapper(path) => Filter::Mapper(ixs.mappers().get(path)?),
+			SqlFilter::Stopwords(s) => Filter::Stopwords(match s {
+				SqlStopwords::Language(l) => {
+					language_stopwords(*l).lines().map(String::from).collect()
+				}
+				SqlStopwords::List(l) => l.iter().cloned().collect(),
+			}),
+			SqlFilter::Synonyms(s, stage) => Filter::Synonyms(
+				match s {
+					SqlSynonyms::List(groups) => Synonyms::from_groups(groups),
+					SqlSynonyms::File(path) => ixs.mappers().get_synonyms(path)?,
+				},
+				stage.map(|s| match s {
+					FilterStage::Index => FilteringStage::Indexing,
+					FilterStage::Query => FilteringStage::Querying,
+				}),
+			),
 		};
 		Ok(f)
 	}
@@ -75,10 +99,13 @@ impl Filter {
 	}
 
 	fn is_stage(&self, stage: FilteringStage) -> bool {
-		if let FilteringStage::Querying = stage {
-			!matches!(self, Filter::EdgeNgram(_, _) | Filter::Ngram(_, _))
-		} else {
-			true
+		match self {
+			// Expanding the synonyms at both stages is redundant, but the default
+			Filter::Synonyms(_, Some(s)) => *s == stage,
+			Filter::EdgeNgram(_, _) | Filter::Ngram(_, _) => {
+				!matches!(stage, FilteringStage::Querying)
+			}
+			_ => true,
 		}
 	}
 
@@ -106,6 +133,8 @@ impl Filter {
 			Filter::Stemmer(s) => Self::stem(s, c),
 			Filter::Uppercase => Self::uppercase(c),
 			Filter::Mapper(m) => m.map(c),
+			Filter::Stopwords(s) => Self::stopwords(s, c),
+			Filter::Synonyms(s, _) => s.expand(c),
 		}
 	}
 
@@ -140,6 +169,15 @@ impl Filter {
 		Self::check_term(c, s.stem(&c.to_lowercase()).into())
 	}
 
+	#[inline]
+	fn stopwords(stopwords: &HashSet<String>, c: &str) -> FilterResult {
+		if stopwords.contains(c) {
+			FilterResult::Ignore
+		} else {
+			FilterResult::Term(Term::Unchanged)
+		}
+	}
+
 	#[inline]
 	fn ngram(c: &str, min: u16, max: u16) -> FilterResult {
 		let min = min as usize;
@@ -739,6 +777,54 @@ mod tests {
 		.await;
 	}
 
+	#[tokio::test]
+	async fn test_stopwords() {
+		test_analyzer(
+			"ANALYZER test TOKENIZERS blank,class FILTERS lowercase,stopwords(english);",
+			"The cat is on the mat",
+			&["cat", "mat"],
+		)
+		.await;
+		test_analyzer(
+			"ANALYZER test TOKENIZERS blank,class FILTERS stopwords(['cat', 'the']);",
+			"The cat is on the mat",
+			&["The", "is", "on", "mat"],
+		)
+		.await;
+	}
+
+	#[tokio::test]
+	async fn test_synonyms() {
+		test_analyzer(
+			"ANALYZER test TOKENIZERS blank,class FILTERS lowercase,synonyms([['tv', 'television'], ['car', 'auto', 'automobile']]);",
+			"TV in the Auto",
+			&["tv", "television", "in", "the", "auto", "car", "automobile"],
+		)
+		.await;
+	}
+
+	#[tokio::test]
+	async fn test_synonyms_tokens() {
+		test_analyzer_tokens(
+			"ANALYZER test TOKENIZERS blank FILTERS synonyms([['tv', 'television']]);",
+			"tv",
+			&[
+				Token::Ref {
+					chars: (0, 0, 2),
+					bytes: (0, 2),
+					len: 2,
+				},
+				Token::String {
+					chars: (0, 0, 2),
+					bytes: (0, 2),
+					term: "television".to_string(),
+					len: 10,
+				},
+			],
+		)
+		.await;
+	}
+
 	#[tokio::test]
 	async fn test_ngram() {
 		test_analyzer(
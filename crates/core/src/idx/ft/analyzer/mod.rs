@@ -2,10 +2,12 @@ use crate::ctx::Context;
 use crate::dbs::Options;
 use crate::err::Error;
 use crate::idx::ft::analyzer::filter::FilteringStage;
-use crate::idx::ft::analyzer::tokenizer::{Tokenizer, Tokens};
+use crate::idx::ft::analyzer::tokenizer::{Token, Tokenizer, Tokens};
+use crate::idx::ft::boosts::FieldBoosts;
 use crate::idx::ft::doclength::DocLength;
-use crate::idx::ft::offsets::{Offset, OffsetRecords};
+use crate::idx::ft::offsets::{Offset, OffsetRecords, Position};
 use crate::idx::ft::postings::TermFrequency;
+use crate::idx::ft::query::TermPositions;
 use crate::idx::ft::terms::{TermId, TermLen, Terms};
 use crate::idx::trees::store::IndexStores;
 use crate::sql::statements::DefineAnalyzerStatement;
@@ -14,11 +16,13 @@ use crate::sql::{Function, Strand};
 use filter::Filter;
 use reblessive::tree::Stk;
 use std::collections::hash_map::Entry;
-use std::collections::{HashMap, HashSet};
+use std::collections::HashMap;
 use std::sync::Arc;
 
 mod filter;
 pub(in crate::idx) mod mapper;
+mod stopwords;
+pub(in crate::idx) mod synonyms;
 mod tokenizer;
 
 #[derive(Clone)]
@@ -29,27 +33,6 @@ pub(crate) struct Analyzer {
 
 pub(in crate::idx) type TermsList = Vec<Option<(TermId, TermLen)>>;
 
-pub(in crate::idx) struct TermsSet {
-	set: HashSet<TermId>,
-	has_unknown_terms: bool,
-}
-
-impl TermsSet {
-	/// If the query TermsSet contains terms that are unknown in the index
-	/// of if there is no terms in the set then
-	/// we are sure that it does not match any document
-	pub(in crate::idx) fn is_matchable(&self) -> bool {
-		!(self.has_unknown_terms || self.set.is_empty())
-	}
-
-	pub(in crate::idx) fn is_subset(&self, other: &TermsSet) -> bool {
-		if self.has_unknown_terms {
-			return false;
-		}
-		self.set.is_subset(&other.set)
-	}
-}
-
 impl Analyzer {
 	pub(crate) fn new(ixs: &IndexStores, az: Arc<DefineAnalyzerStatement>) -> Result<Self, Error> {
 		Ok(Self {
@@ -58,142 +41,107 @@ impl Analyzer {
 		})
 	}
 
-	pub(super) async fn extract_querying_terms(
+	/// Analyzes a part of a query.
+	/// Returns the tokens with their position and their length.
+	pub(super) async fn extract_querying_tokens(
 		&self,
 		stk: &mut Stk,
 		ctx: &Context,
 		opt: &Options,
-		t: &Terms,
 		content: String,
-	) -> Result<(TermsList, TermsSet), Error> {
+	) -> Result<Vec<(Position, String, TermLen)>, Error> {
 		let tokens = self.generate_tokens(stk, ctx, opt, FilteringStage::Querying, content).await?;
-		// We extract the term ids
-		let mut list = Vec::with_capacity(tokens.list().len());
-		let mut unique_tokens = HashSet::new();
-		let mut set = HashSet::new();
-		let tx = ctx.tx();
-		let mut has_unknown_terms = false;
-		for token in tokens.list() {
-			// Tokens can contains duplicated, not need to evaluate them again
-			if unique_tokens.insert(token) {
-				// Is the term known in the index?
-				let opt_term_id = t.get_term_id(&tx, tokens.get_token_string(token)?).await?;
-				list.push(opt_term_id.map(|tid| (tid, token.get_char_len())));
-				if let Some(term_id) = opt_term_id {
-					set.insert(term_id);
-				} else {
-					has_unknown_terms = true;
-				}
-			}
+		let mut res = Vec::with_capacity(tokens.list().len());
+		for (pos, token) in Self::positions(&tokens) {
+			let s = tokens.get_token_string(token)?;
+			res.push((pos, s.to_owned(), token.get_char_len()));
 		}
-		drop(tx);
-		Ok((
-			list,
-			TermsSet {
-				set,
-				has_unknown_terms,
-			},
-		))
+		Ok(res)
 	}
 
-	pub(in crate::idx) async fn extract_indexing_terms(
+	/// Extracts the terms of a value known by the index, with their positions.
+	pub(in crate::idx) async fn extract_term_positions(
 		&self,
 		stk: &mut Stk,
 		ctx: &Context,
 		opt: &Options,
 		t: &Terms,
 		content: Value,
-	) -> Result<TermsSet, Error> {
+	) -> Result<TermPositions, Error> {
 		let mut tv = Vec::new();
 		self.analyze_value(stk, ctx, opt, content, FilteringStage::Indexing, &mut tv).await?;
-		let mut set = HashSet::new();
-		let mut has_unknown_terms = false;
+		let mut tp = TermPositions::default();
 		let tx = ctx.tx();
-		for tokens in tv {
-			for token in tokens.list() {
+		for (i, tokens) in tv.iter().enumerate() {
+			for (pos, token) in Self::positions(tokens) {
 				if let Some(term_id) = t.get_term_id(&tx, tokens.get_token_string(token)?).await? {
-					set.insert(term_id);
-				} else {
-					has_unknown_terms = true;
+					tp.add(term_id, i as u32, pos);
 				}
 			}
 		}
 		drop(tx);
-		Ok(TermsSet {
-			set,
-			has_unknown_terms,
-		})
+		Ok(tp)
 	}
 
-	/// This method is used for indexing.
-	/// It will create new term ids for non already existing terms.
-	pub(super) async fn extract_terms_with_frequencies(
-		&self,
-		stk: &mut Stk,
-		ctx: &Context,
-		opt: &Options,
-		terms: &mut Terms,
-		field_content: Vec<Value>,
-	) -> Result<(DocLength, Vec<(TermId, TermFrequency)>), Error> {
-		let mut dl = 0;
-		// Let's first collect all the inputs, and collect the tokens.
-		// We need to store them because everything after is zero-copy
-		let mut inputs = vec![];
-		self.analyze_content(stk, ctx, opt, field_content, FilteringStage::Indexing, &mut inputs)
-			.await?;
-		// We then collect every unique terms and count the frequency
-		let mut tf: HashMap<&str, TermFrequency> = HashMap::new();
-		for tks in &inputs {
-			for tk in tks.list() {
-				dl += 1;
-				let s = tks.get_token_string(tk)?;
-				match tf.entry(s) {
-					Entry::Vacant(e) => {
-						e.insert(1);
-					}
-					Entry::Occupied(mut e) => {
-						e.insert(*e.get() + 1);
-					}
-				}
+	/// Numbers the tokens by position.
+	/// The tokens generated from the same original token (eg. by a ngram filter) share the same position.
+	fn positions(tokens: &Tokens) -> impl Iterator<Item = (Position, &Token)> {
+		let mut pos = 0;
+		let mut last_start = None;
+		tokens.list().iter().map(move |token| {
+			let start = token.new_offset(0).start;
+			if last_start.is_some_and(|s| s != start) {
+				pos += 1;
 			}
-		}
-		// Now we can resolve the term ids
-		let mut tfid = Vec::with_capacity(tf.len());
-		let tx = ctx.tx();
-		for (t, f) in tf {
-			tfid.push((terms.resolve_term_id(&tx, t).await?, f));
-		}
-		drop(tx);
-		Ok((dl, tfid))
+			last_start = Some(start);
+			(pos, token)
+		})
 	}
 
 	/// This method is used for indexing.
 	/// It will create new term ids for non already existing terms.
+	/// The frequencies and the document length are weighted by the boosts of the fields.
 	pub(super) async fn extract_terms_with_frequencies_with_offsets(
 		&self,
 		stk: &mut Stk,
 		ctx: &Context,
 		opt: &Options,
 		terms: &mut Terms,
+		boosts: &FieldBoosts,
 		content: Vec<Value>,
 	) -> Result<(DocLength, Vec<(TermId, TermFrequency)>, Vec<(TermId, OffsetRecords)>), Error> {
 		let mut dl = 0;
 		// Let's first collect all the inputs, and collect the tokens.
 		// We need to store them because everything after is zero-copy
 		let mut inputs = Vec::with_capacity(content.len());
-		self.analyze_content(stk, ctx, opt, content, FilteringStage::Indexing, &mut inputs).await?;
+		let mut fields = Vec::with_capacity(content.len());
+		self.analyze_content(
+			stk,
+			ctx,
+			opt,
+			content,
+			FilteringStage::Indexing,
+			&mut inputs,
+			&mut fields,
+		)
+		.await?;
 		// We then collect every unique terms and count the frequency and extract the offsets
-		let mut tfos: HashMap<&str, Vec<Offset>> = HashMap::new();
-		for (i, tks) in inputs.iter().enumerate() {
-			for tk in tks.list() {
-				dl += 1;
+		let mut tfos: HashMap<&str, (TermFrequency, Vec<Offset>)> = HashMap::new();
+		for (i, (tks, field)) in inputs.iter().zip(fields).enumerate() {
+			let w = boosts.weight(field);
+			for (pos, tk) in Self::positions(tks) {
+				dl += w;
 				let s = tks.get_token_string(tk)?;
-				let o = tk.new_offset(i as u32);
+				let o = tk.new_offset(i as u32).with_position(pos);
 				match tfos.entry(s) {
 					Entry::Vacant(e) => {
-						e.insert(vec![o]);
+						e.insert((w, vec![o]));
+					}
+					Entry::Occupied(mut e) => {
+						let (f, os) = e.get_mut();
+						*f += w;
+						os.push(o);
 					}
-					Entry::Occupied(mut e) => e.get_mut().push(o),
 				}
 			}
 		}
@@ -202,9 +150,9 @@ impl Analyzer {
 		let mut tfid = Vec::with_capacity(tfos.len());
 		let mut osid = Vec::with_capacity(tfos.len());
 		let tx = ctx.tx();
-		for (t, o) in tfos {
+		for (t, (f, o)) in tfos {
 			let id = terms.resolve_term_id(&tx, t).await?;
-			tfid.push((id, o.len() as TermFrequency));
+			tfid.push((id, f));
 			osid.push((id, OffsetRecords(o)));
 		}
 		drop(tx);
@@ -212,6 +160,8 @@ impl Analyzer {
 	}
 
 	/// Was marked recursive
+	/// Records in `fields` the position of the field each of the analyzed values comes from.
+	#[expect(clippy::too_many_arguments)]
 	async fn analyze_content(
 		&self,
 		stk: &mut Stk,
@@ -220,9 +170,11 @@ impl Analyzer {
 		content: Vec<Value>,
 		stage: FilteringStage,
 		tks: &mut Vec<Tokens>,
+		fields: &mut Vec<usize>,
 	) -> Result<(), Error> {
-		for v in content {
+		for (field, v) in content.into_iter().enumerate() {
 			self.analyze_value(stk, ctx, opt, v, stage, tks).await?;
+			fields.resize(tks.len(), field);
 		}
 		Ok(())
 	}
@@ -0,0 +1,28 @@
+//! The built-in stop-words of the languages, one lowercase word per line.
+//!
+//! The lists are derived from the Snowball project stop-word lists,
+//! which are distributed under the BSD licence in `stopwords/LICENSE`.
+use crate::sql::language::Language;
+
+pub(super) fn language_stopwords(language: Language) -> &'static str {
+	match language {
+		Language::Arabic => include_str!("stopwords/arabic.txt"),
+		Language::Danish => include_str!("stopwords/danish.txt"),
+		Language::Dutch => include_str!("stopwords/dutch.txt"),
+		Language::English => include_str!("stopwords/english.txt"),
+		Language::Finnish => include_str!("stopwords/finnish.txt"),
+		Language::French => include_str!("stopwords/french.txt"),
+		Language::German => include_str!("stopwords/german.txt"),
+		Language::Greek => include_str!("stopwords/greek.txt"),
+		Language::Hungarian => include_str!("stopwords/hungarian.txt"),
+		Language::Italian => include_str!("stopwords/italian.txt"),
+		Language::Norwegian => include_str!("stopwords/norwegian.txt"),
+		Language::Portuguese => include_str!("stopwords/portuguese.txt"),
+		Language::Romanian => include_str!("stopwords/romanian.txt"),
+		Language::Russian => include_str!("stopwords/russian.txt"),
+		Language::Spanish => include_str!("stopwords/spanish.txt"),
+		Language::Swedish => include_str!("stopwords/swedish.txt"),
+		Language::Tamil => include_str!("stopwords/tamil.txt"),
+		Language::Turkish => include_str!("stopwords/turkish.txt"),
+	}
+}
@@ -0,0 +1,105 @@
+use crate::err::Error;
+use crate::iam::file::is_path_allowed;
+use crate::idx::ft::analyzer::filter::{FilterResult, Term};
+use std::collections::HashMap;
+#[cfg(target_family = "wasm")]
+use std::fs::File;
+#[cfg(target_family = "wasm")]
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+use std::sync::Arc;
+#[cfg(not(target_family = "wasm"))]
+use tokio::fs::File;
+#[cfg(not(target_family = "wasm"))]
+use tokio::io::{AsyncBufReadExt, BufReader};
+
+/// Expands the terms with their synonyms
+#[derive(Clone, Default)]
+pub(in crate::idx) struct Synonyms {
+	terms: Arc<HashMap<String, Vec<String>>>,
+}
+
+impl Synonyms {
+	pub(in crate::idx) fn from_groups(groups: &[Vec<String>]) -> Self {
+		let mut terms = HashMap::new();
+		for group in groups {
+			Self::add_group(&mut terms, group.iter().map(String::as_str));
+		}
+		Self {
+			terms: Arc::new(terms),
+		}
+	}
+
+	/// Loads a file with one group of comma separated synonyms per line.
+	/// Empty lines and lines starting with `#` are ignored.
+	pub(in crate::idx) async fn from_file(path: &Path) -> Result<Self, Error> {
+		let mut terms = HashMap::new();
+		let path = is_path_allowed(path)?;
+		Self::iterate_file(&mut terms, &path).await?;
+		Ok(Self {
+			terms: Arc::new(terms),
+		})
+	}
+
+	fn add_group<'a>(
+		terms: &mut HashMap<String, Vec<String>>,
+		group: impl Iterator<Item = &'a str>,
+	) {
+		let group: Vec<&str> = group.map(str::trim).filter(|t| !t.is_empty()).collect();
+		for &term in &group {
+			let synonyms = terms.entry(term.to_owned()).or_default();
+			for &s in &group {
+				if s != term && !synonyms.iter().any(|e| e == s) {
+					synonyms.push(s.to_owned());
+				}
+			}
+		}
+	}
+
+	fn add_line(terms: &mut HashMap<String, Vec<String>>, line: &str) {
+		let line = line.trim();
+		if !line.is_empty() && !line.starts_with('#') {
+			Self::add_group(terms, line.split(','));
+		}
+	}
+
+	#[cfg(not(target_family = "wasm"))]
+	async fn iterate_file(
+		terms: &mut HashMap<String, Vec<String>>,
+		path: &Path,
+	) -> Result<(), Error> {
+		let file = File::open(path).await?;
+		let reader = BufReader::new(file);
+		let mut lines = reader.lines();
+		while let Some(line) = lines.next_line().await? {
+			yield_now!();
+			Self::add_line(terms, &line);
+		}
+		Ok(())
+	}
+
+	#[cfg(target_family = "wasm")]
+	async fn iterate_file(
+		terms: &mut HashMap<String, Vec<String>>,
+		path: &Path,
+	) -> Result<(), Error> {
+		let file = File::open(path)?;
+		let reader = BufReader::new(file);
+		for line_result in reader.lines() {
+			Self::add_line(terms, &line_result?);
+		}
+		Ok(())
+	}
+
+	/// Returns the term followed by its synonyms, at the same position
+	pub(super) fn expand(&self, token: &str) -> FilterResult {
+		match self.terms.get(token) {
+			Some(synonyms) => FilterResult::Terms(
+				std::iter::once(Term::Unchanged)
+					.chain(synonyms.iter().map(|s| Term::NewTerm(s.clone(), 0)))
+					.collect(),
+			),
+			None => FilterResult::Term(Term::Unchanged),
+		}
+	}
+}
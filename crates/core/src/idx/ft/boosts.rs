@@ -0,0 +1,62 @@
+use crate::idx::ft::postings::TermFrequency;
+use crate::sql::Number;
+
+/// The weight of a token of the least boosted field.
+/// The weighted frequencies are fixed-point numbers with this unit.
+const BOOST_UNIT: TermFrequency = 100;
+
+/// The boosts of the fields of a multi-field search index.
+///
+/// The tokens of a boosted field count as a fraction of occurrences, in proportion to its boost.
+/// The term frequencies and the document lengths are then the weighted sums of the occurrences
+/// over every field (BM25F), and the index produces a single score for all the fields.
+/// Without boosts every token counts as one occurrence.
+#[derive(Clone, Default)]
+pub(super) struct FieldBoosts(Vec<TermFrequency>);
+
+impl FieldBoosts {
+	pub(super) fn new(boosts: &[Number]) -> Self {
+		// The boosts are relative: the least boosted field weighs a plain occurrence
+		let min = boosts.iter().map(Number::to_float).fold(f64::INFINITY, f64::min);
+		Self(
+			boosts
+				.iter()
+				.map(|b| ((b.to_float() / min) * BOOST_UNIT as f64).round() as TermFrequency)
+				.collect(),
+		)
+	}
+
+	/// The weight of a token of the field at the given position
+	pub(super) fn weight(&self, field: usize) -> TermFrequency {
+		self.0.get(field).copied().unwrap_or(1)
+	}
+
+	/// The weight of a plain occurrence, the scale of the weighted frequencies
+	pub(super) fn unit(&self) -> f32 {
+		if self.0.is_empty() {
+			1.0
+		} else {
+			BOOST_UNIT as f32
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::idx::ft::boosts::FieldBoosts;
+	use crate::sql::Number;
+
+	#[test]
+	fn test_field_boosts() {
+		let b = FieldBoosts::default();
+		assert_eq!(b.weight(0), 1);
+		assert_eq!(b.weight(3), 1);
+		assert_eq!(b.unit(), 1.0);
+
+		let b = FieldBoosts::new(&[Number::Int(3), Number::Float(0.5), Number::Int(1)]);
+		assert_eq!(b.weight(0), 600);
+		assert_eq!(b.weight(1), 100);
+		assert_eq!(b.weight(2), 200);
+		assert_eq!(b.unit(), 100.0);
+	}
+}
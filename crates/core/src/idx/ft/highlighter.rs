@@ -22,16 +22,19 @@ impl HighlightParams {
 pub(super) struct Highlighter {
 	prefix: Vec<char>,
 	suffix: Vec<char>,
-	fields: Vec<(Idiom, Value)>,
+	/// The values of each highlighted field
+	fields: Vec<(Idiom, Vec<(Idiom, Value)>)>,
 	offseter: Offseter,
 }
 
 impl Highlighter {
-	pub(super) fn new(hlp: HighlightParams, idiom: &Idiom, doc: &Value) -> Self {
+	/// The idioms are the fields of the index, in the order they were indexed.
+	/// A multi-field index reports the highlights of each field that matched.
+	pub(super) fn new(hlp: HighlightParams, idioms: &[Idiom], doc: &Value) -> Self {
 		let prefix = hlp.prefix.to_raw_string().chars().collect();
 		let suffix = hlp.suffix.to_raw_string().chars().collect();
 		// Extract the fields we want to highlight
-		let fields = doc.walk(idiom);
+		let fields = idioms.iter().map(|i| (i.clone(), doc.walk(i))).collect();
 		Self {
 			fields,
 			prefix,
@@ -62,22 +65,20 @@ impl Highlighter {
 			_ => {}
 		}
 	}
-}
-
-impl TryFrom<Highlighter> for Value {
-	type Error = Error;
 
-	fn try_from(hl: Highlighter) -> Result<Self, Error> {
-		if hl.fields.is_empty() {
-			return Ok(Self::None);
-		}
-		let mut vals = vec![];
-		for (_, f) in hl.fields {
-			Highlighter::extract(f, &mut vals);
-		}
+	/// Highlights the values of a field.
+	/// `idx` is the index of the first value, the values of the fields being numbered in sequence.
+	/// Returns the highlighted values, and if any of them matched.
+	fn highlight_values(
+		&self,
+		vals: Vec<String>,
+		idx: &mut u32,
+	) -> Result<(Vec<Value>, bool), Error> {
 		let mut res = Vec::with_capacity(vals.len());
-		for (idx, val) in vals.into_iter().enumerate() {
-			if let Some(m) = hl.offseter.offsets.get(&(idx as u32)) {
+		let mut matched = false;
+		for val in vals {
+			if let Some(m) = self.offseter.offsets.get(idx) {
+				matched = true;
 				let mut v: Vec<char> = val.chars().collect();
 				let mut l = v.len();
 				let mut d = 0;
@@ -98,8 +99,8 @@ impl TryFrom<Highlighter> for Value {
 				};
 
 				for (s, e) in m {
-					append(*s, &hl.prefix)?;
-					append(*e, &hl.suffix)?;
+					append(*s, &self.prefix)?;
+					append(*e, &self.suffix)?;
 				}
 
 				let s: String = v.iter().collect();
@@ -107,11 +108,49 @@ impl TryFrom<Highlighter> for Value {
 			} else {
 				res.push(Value::from(val));
 			}
+			*idx += 1;
 		}
-		Ok(match res.len() {
+		Ok((res, matched))
+	}
+
+	fn into_value(mut res: Vec<Value>) -> Value {
+		match res.len() {
 			0 => Value::None,
 			1 => res.remove(0),
 			_ => Value::from(res),
+		}
+	}
+}
+
+impl TryFrom<Highlighter> for Value {
+	type Error = Error;
+
+	fn try_from(mut hl: Highlighter) -> Result<Self, Error> {
+		if hl.fields.iter().all(|(_, f)| f.is_empty()) {
+			return Ok(Self::None);
+		}
+		let fields = std::mem::take(&mut hl.fields);
+		let single = fields.len() == 1;
+		let mut idx = 0;
+		let mut matches = BTreeMap::default();
+		for (field, values) in fields {
+			let mut vals = vec![];
+			for (_, f) in values {
+				Highlighter::extract(f, &mut vals);
+			}
+			let (res, matched) = hl.highlight_values(vals, &mut idx)?;
+			// A single field is highlighted whether or not it matched
+			if single {
+				return Ok(Highlighter::into_value(res));
+			}
+			if matched {
+				matches.insert(field.to_string(), Highlighter::into_value(res));
+			}
+		}
+		// Several fields are reported by name, only if they matched
+		Ok(match matches.len() {
+			0 => Value::None,
+			_ => Value::from(Object::from(matches)),
 		})
 	}
 }
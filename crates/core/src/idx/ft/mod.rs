@@ -1,8 +1,10 @@
 pub(crate) mod analyzer;
+mod boosts;
 mod doclength;
 pub(crate) mod highlighter;
 mod offsets;
 mod postings;
+pub(super) mod query;
 pub(super) mod scorer;
 pub(super) mod termdocs;
 pub(crate) mod terms;
@@ -11,13 +13,15 @@ use crate::ctx::Context;
 use crate::dbs::Options;
 use crate::err::Error;
 use crate::idx::docids::{DocId, DocIds};
-use crate::idx::ft::analyzer::{Analyzer, TermsList, TermsSet};
+use crate::idx::ft::analyzer::Analyzer;
+use crate::idx::ft::boosts::FieldBoosts;
 use crate::idx::ft::doclength::DocLengths;
 use crate::idx::ft::highlighter::{HighlightParams, Highlighter, Offseter};
 use crate::idx::ft::offsets::Offsets;
 use crate::idx::ft::postings::Postings;
+use crate::idx::ft::query::{FtQuery, Query};
 use crate::idx::ft::scorer::BM25Scorer;
-use crate::idx::ft::termdocs::{TermDocs, TermsDocs};
+use crate::idx::ft::termdocs::TermDocs;
 use crate::idx::ft::terms::{TermId, TermLen, Terms};
 use crate::idx::trees::btree::BStatistics;
 use crate::idx::trees::store::IndexStores;
@@ -33,7 +37,6 @@ use revision::revisioned;
 use roaring::treemap::IntoIter;
 use roaring::RoaringTreemap;
 use serde::{Deserialize, Serialize};
-use std::ops::BitAnd;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
@@ -46,6 +49,8 @@ pub(crate) struct FtIndex {
 	state: State,
 	bm25: Option<Bm25Params>,
 	highlighting: bool,
+	fuzzy: u8,
+	boosts: FieldBoosts,
 	doc_ids: Arc<RwLock<DocIds>>,
 	doc_lengths: Arc<RwLock<DocLengths>>,
 	postings: Arc<RwLock<Postings>>,
@@ -166,6 +171,8 @@ impl FtIndex {
 			index_key_base,
 			bm25,
 			highlighting: p.hl,
+			fuzzy: p.fuzzy,
+			boosts: FieldBoosts::new(&p.boosts),
 			analyzer,
 			doc_ids,
 			doc_lengths,
@@ -227,12 +234,10 @@ impl FtIndex {
 				}
 				drop(p);
 				drop(t);
-				// Remove the offsets if any
-				if self.highlighting {
-					for term_id in term_list {
-						// TODO?: Removal can be done with a prefix on doc_id
-						self.offsets.remove_offsets(&tx, doc_id, term_id).await?;
-					}
+				// Remove the offsets
+				for term_id in term_list {
+					// TODO?: Removal can be done with a prefix on doc_id
+					self.offsets.remove_offsets(&tx, doc_id, term_id).await?;
 				}
 			}
 		}
@@ -255,21 +260,20 @@ impl FtIndex {
 		drop(doc_ids);
 		let doc_id = *resolved.doc_id();
 
-		// Extract the doc_lengths, terms en frequencies (and offset)
+		// Extract the doc_lengths, terms en frequencies and offsets.
+		// The offsets are stored even without highlighting, as their positions verify the phrases.
 		let mut t = self.terms.write().await;
-		let (doc_length, terms_and_frequencies, offsets) = if self.highlighting {
-			let (dl, tf, ofs) = self
-				.analyzer
-				.extract_terms_with_frequencies_with_offsets(stk, ctx, opt, &mut t, content)
-				.await?;
-			(dl, tf, Some(ofs))
-		} else {
-			let (dl, tf) = self
-				.analyzer
-				.extract_terms_with_frequencies(stk, ctx, opt, &mut t, content)
-				.await?;
-			(dl, tf, None)
-		};
+		let (doc_length, terms_and_frequencies, offsets) = self
+			.analyzer
+			.extract_terms_with_frequencies_with_offsets(
+				stk,
+				ctx,
+				opt,
+				&mut t,
+				&self.boosts,
+				content,
+			)
+			.await?;
 
 		// Set the doc length
 		let tx = ctx.tx();
@@ -316,20 +320,14 @@ impl FtIndex {
 		drop(p);
 		drop(t);
 
-		if self.highlighting {
-			// Set the offset if any
-			if let Some(ofs) = offsets {
-				if !ofs.is_empty() {
-					for (tid, or) in ofs {
-						self.offsets.set_offsets(&tx, doc_id, tid, or).await?;
-					}
-				}
-			}
-			// In case of an update, w remove the offset for the terms that does not exist anymore
-			if let Some(old_term_ids) = old_term_ids {
-				for old_term_id in old_term_ids {
-					self.offsets.remove_offsets(&tx, doc_id, old_term_id).await?;
-				}
+		// Set the offsets
+		for (tid, or) in offsets {
+			self.offsets.set_offsets(&tx, doc_id, tid, or).await?;
+		}
+		// In case of an update, w remove the offset for the terms that does not exist anymore
+		if let Some(old_term_ids) = old_term_ids {
+			for old_term_id in old_term_ids {
+				self.offsets.remove_offsets(&tx, doc_id, old_term_id).await?;
 			}
 		}
 
@@ -350,72 +348,48 @@ impl FtIndex {
 		Ok(())
 	}
 
-	pub(super) async fn extract_querying_terms(
+	pub(super) async fn new_query(
 		&self,
 		stk: &mut Stk,
 		ctx: &Context,
 		opt: &Options,
-		query_string: String,
-	) -> Result<(TermsList, TermsSet), Error> {
+		query: &Query,
+	) -> Result<FtQuery, Error> {
 		let t = self.terms.read().await;
-		let res = self.analyzer.extract_querying_terms(stk, ctx, opt, &t, query_string).await?;
+		let res = FtQuery::new(
+			stk,
+			ctx,
+			opt,
+			&self.analyzer,
+			&t,
+			&self.term_docs,
+			&self.offsets,
+			self.fuzzy,
+			query.clone(),
+		)
+		.await;
 		drop(t);
-		Ok(res)
-	}
-
-	pub(super) async fn get_terms_docs(
-		&self,
-		tx: &Transaction,
-		terms: &TermsList,
-	) -> Result<Vec<Option<(TermId, RoaringTreemap)>>, Error> {
-		let mut terms_docs = Vec::with_capacity(terms.len());
-		for opt_term in terms {
-			if let Some((term_id, _)) = opt_term {
-				let docs = self.term_docs.get_docs(tx, *term_id).await?;
-				if let Some(docs) = docs {
-					terms_docs.push(Some((*term_id, docs)));
-				} else {
-					terms_docs.push(Some((*term_id, RoaringTreemap::new())));
-				}
-			} else {
-				terms_docs.push(None);
-			}
-		}
-		Ok(terms_docs)
+		res
 	}
 
-	pub(super) fn new_hits_iterator(
-		&self,
-		terms_docs: TermsDocs,
-	) -> Result<Option<HitsIterator>, Error> {
-		let mut hits: Option<RoaringTreemap> = None;
-		for opt_term_docs in terms_docs.iter() {
-			if let Some((_, term_docs)) = opt_term_docs {
-				if let Some(h) = hits {
-					hits = Some(h.bitand(term_docs));
-				} else {
-					hits = Some(term_docs.clone());
-				}
-			} else {
-				return Ok(None);
-			}
+	pub(super) fn new_hits_iterator(&self, query: &FtQuery) -> Option<HitsIterator> {
+		let hits = query.hits();
+		if hits.is_empty() {
+			return None;
 		}
-		if let Some(hits) = hits {
-			if !hits.is_empty() {
-				return Ok(Some(HitsIterator::new(self.doc_ids.clone(), hits)));
-			}
-		}
-		Ok(None)
+		Some(HitsIterator::new(self.doc_ids.clone(), hits))
 	}
 
-	pub(super) fn new_scorer(&self, terms_docs: TermsDocs) -> Result<Option<BM25Scorer>, Error> {
+	pub(super) fn new_scorer(&self, query: &FtQuery) -> Result<Option<BM25Scorer>, Error> {
 		if let Some(bm25) = &self.bm25 {
 			return Ok(Some(BM25Scorer::new(
 				self.postings.clone(),
-				terms_docs,
+				query.terms_docs(),
+				query.term_weights(),
 				self.doc_lengths.clone(),
 				self.state.total_docs_lengths,
 				self.state.doc_count,
+				self.boosts.unit(),
 				bm25.clone(),
 			)));
 		}
@@ -428,7 +402,7 @@ impl FtIndex {
 		thg: &Thing,
 		terms: &[Option<(TermId, TermLen)>],
 		hlp: HighlightParams,
-		idiom: &Idiom,
+		idioms: &[Idiom],
 		doc: &Value,
 	) -> Result<Value, Error> {
 		let doc_key: Key = revision::to_vec(thg)?;
@@ -436,7 +410,13 @@ impl FtIndex {
 		let doc_id = di.get_doc_id(tx, doc_key).await?;
 		drop(di);
 		if let Some(doc_id) = doc_id {
-			let mut hl = Highlighter::new(hlp, idiom, doc);
+			let mut hl = Highlighter::new(hlp, idioms, doc);
+			// Without HIGHLIGHTS the offsets are only stored for the phrases
+			let terms = if self.highlighting {
+				terms
+			} else {
+				&[]
+			};
 			for (term_id, term_len) in terms.iter().flatten() {
 				let o = self.offsets.get_offsets(tx, doc_id, *term_id).await?;
 				if let Some(o) = o {
@@ -461,6 +441,11 @@ impl FtIndex {
 		drop(di);
 		if let Some(doc_id) = doc_id {
 			let mut or = Offseter::new(partial);
+			let terms = if self.highlighting {
+				terms
+			} else {
+				&[]
+			};
 			for (term_id, term_len) in terms.iter().flatten() {
 				let o = self.offsets.get_offsets(tx, doc_id, *term_id).await?;
 				if let Some(o) = o {
@@ -532,6 +517,7 @@ impl HitsIterator {
 mod tests {
 	use crate::ctx::{Context, MutableContext};
 	use crate::dbs::Options;
+	use crate::idx::ft::query::Query;
 	use crate::idx::ft::scorer::{BM25Scorer, Score};
 	use crate::idx::ft::{FtIndex, HitsIterator};
 	use crate::idx::IndexKeyBase;
@@ -575,12 +561,9 @@ mod tests {
 		fti: &FtIndex,
 		qs: &str,
 	) -> (Option<HitsIterator>, BM25Scorer) {
-		let (term_list, _) =
-			fti.extract_querying_terms(stk, ctx, opt, qs.to_string()).await.unwrap();
-		let tx = ctx.tx();
-		let td = Arc::new(fti.get_terms_docs(&tx, &term_list).await.unwrap());
-		let scr = fti.new_scorer(td.clone()).unwrap().unwrap();
-		let hits = fti.new_hits_iterator(td).unwrap();
+		let query = fti.new_query(stk, ctx, opt, &Query::parse(qs)).await.unwrap();
+		let scr = fti.new_scorer(&query).unwrap().unwrap();
+		let hits = fti.new_hits_iterator(&query);
 		(hits, scr)
 	}
 
@@ -605,6 +588,8 @@ mod tests {
 			doc_lengths_cache: 100,
 			postings_cache: 100,
 			terms_cache: 100,
+			fuzzy: 0,
+			boosts: Vec::new(),
 		};
 		let fti = FtIndex::with_analyzer(
 			ctx.get_index_stores(),
@@ -6,6 +6,7 @@ use crate::kvs::{Transaction, Val};
 
 pub(super) type Position = u32;
 
+#[derive(Clone)]
 pub(super) struct Offsets {
 	index_key_base: IndexKeyBase,
 }
@@ -65,6 +66,9 @@ pub(super) struct Offset {
 	pub(super) gen_start: Position,
 	// End position of the original term
 	pub(super) end: Position,
+	// Position of the term among the terms of the value,
+	// unknown for the offsets stored before the positions were
+	pub(super) pos: Option<Position>,
 }
 
 impl Offset {
@@ -74,8 +78,14 @@ impl Offset {
 			start,
 			gen_start,
 			end,
+			pos: None,
 		}
 	}
+
+	pub(super) fn with_position(mut self, pos: Position) -> Self {
+		self.pos = Some(pos);
+		self
+	}
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -103,6 +113,10 @@ impl TryFrom<OffsetRecords> for Val {
 			decompressed.push(o.gen_start);
 			decompressed.push(o.end);
 		}
+		// The positions are likely to be ascending too
+		if offsets.0.iter().all(|o| o.pos.is_some()) {
+			decompressed.extend(offsets.0.iter().filter_map(|o| o.pos));
+		}
 		Ok(bincode::serialize(&decompressed)?)
 	}
 }
@@ -121,6 +135,8 @@ impl TryFrom<Val> for OffsetRecords {
 		// <= v1.4 the Offset contains only two field: start and end.
 		// We check the number of integers. If there is only 3 per offset this is the old format.
 		let without_gen_start = n_offsets * 3 + 1 == decompressed.len();
+		// Before v3.0 the positions of the terms are not stored.
+		let with_pos = n_offsets * 5 + 1 == decompressed.len();
 
 		let mut indexes = decompressed.into_iter().skip(1);
 		let mut tail = indexes.clone().skip(n_offsets);
@@ -137,6 +153,12 @@ impl TryFrom<Val> for OffsetRecords {
 			let end = tail.next().ok_or(Error::CorruptedIndex("OffsetRecords::try_from(5)"))?;
 			res.push(Offset::new(index, start, gen_start, end));
 		}
+		if with_pos {
+			for o in res.iter_mut() {
+				o.pos =
+					Some(tail.next().ok_or(Error::CorruptedIndex("OffsetRecords::try_from(6)"))?);
+			}
+		}
 		Ok(OffsetRecords(res))
 	}
 }
@@ -158,6 +180,18 @@ mod tests {
 		assert_eq!(o, o2)
 	}
 
+	#[test]
+	fn test_offset_records_with_positions() {
+		let o = OffsetRecords(vec![
+			Offset::new(0, 1, 2, 3).with_position(0),
+			Offset::new(0, 11, 13, 22).with_position(3),
+			Offset::new(1, 1, 3, 4).with_position(0),
+		]);
+		let v: Val = o.clone().try_into().unwrap();
+		let o2 = v.try_into().unwrap();
+		assert_eq!(o, o2)
+	}
+
 	#[test]
 	fn test_migrate_v1_offset_records() {
 		let decompressed = vec![3u32, 0, 0, 1, 1, 3, 11, 22, 1, 4];
@@ -0,0 +1,1062 @@
+//! The query syntax of the `@@` matches operator:
+//! - `a b` matches the documents containing both terms,
+//! - `a OR b` matches the documents containing either term,
+//! - `-a` excludes the documents containing the term,
+//! - `"a b"` matches the terms as a phrase, at consecutive positions,
+//! - `"a b"~2` matches the terms in order, with at most two other terms in between,
+//! - `pre*` matches any term starting with the prefix,
+//! - `word~` and `word~1` match the terms within an edit distance of the word,
+//!   either the distance of the index, or `2`, or the given distance,
+//! - `(a OR b) c` groups expressions,
+//! - `\` escapes the next character, which is then matched literally,
+//!   such as in `\-a`, `\"a b\"`, `a\*`, `a\~1`, `\OR` or `\(a\)`.
+//!
+//! Before this syntax, the whole query was analyzed as plain text. Queries relying on
+//! these characters being literal have to escape them, or the syntax can be disabled
+//! with [`FULLTEXT_QUERY_SYNTAX`].
+//!
+//! The syntax is lenient: unbalanced quotes or parentheses are closed at the end of the query.
+//! Groups and exclusions nested deeper than [`MAX_QUERY_DEPTH`] are ignored.
+//!
+//! When the index defines a fuzzy distance, every word of the query is matched with that distance,
+//! except in phrases and prefixes. `word~0` matches the exact word.
+use crate::cnf::{
+	FULLTEXT_MAX_FUZZY_EXPANSIONS, FULLTEXT_MAX_PREFIX_EXPANSIONS, FULLTEXT_QUERY_SYNTAX,
+};
+use crate::ctx::Context;
+use crate::dbs::Options;
+use crate::err::Error;
+use crate::idx::docids::DocId;
+use crate::idx::ft::analyzer::{Analyzer, TermsList};
+use crate::idx::ft::offsets::{Offsets, Position};
+use crate::idx::ft::scorer::{Score, TermWeights};
+use crate::idx::ft::termdocs::{TermDocs, TermsDocs};
+use crate::idx::ft::terms::{TermId, TermLen, Terms};
+use crate::kvs::Transaction;
+use crate::sql::index::MAX_FUZZY_DISTANCE;
+use reblessive::tree::Stk;
+use roaring::RoaringTreemap;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::iter::Peekable;
+use std::sync::Arc;
+use std::vec::IntoIter;
+
+/// The maximum nesting of groups and exclusions in a query.
+/// The query is walked recursively, so the nesting must be bounded.
+const MAX_QUERY_DEPTH: usize = 32;
+
+/// A parsed full-text query
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub(in crate::idx) enum Query {
+	/// Plain text, every term of which must be present
+	Text(String),
+	/// Terms which must be present in order, with at most the given number of other terms in between
+	Phrase(String, u32),
+	/// A term starting with the prefix must be present
+	Prefix(String),
+	/// A term within the edit distance of each term must be present.
+	/// Without distance, the distance of the index applies.
+	Fuzzy(String, Option<u8>),
+	/// Excludes the documents matching the query
+	Not(Box<Query>),
+	And(Vec<Query>),
+	Or(Vec<Query>),
+}
+
+#[derive(Debug, PartialEq)]
+enum QueryToken {
+	/// A word or a phrase
+	Term(Query),
+	Not,
+	Or,
+	Open,
+	Close,
+}
+
+impl Query {
+	/// Parses the query of a matches operator, unless the query syntax is disabled
+	pub(in crate::idx) fn new(query: &str) -> Self {
+		if *FULLTEXT_QUERY_SYNTAX {
+			Self::parse(query)
+		} else {
+			Self::Text(query.to_owned())
+		}
+	}
+
+	pub(in crate::idx) fn parse(query: &str) -> Self {
+		let mut parser = QueryParser {
+			tokens: Self::lex(query).into_iter().peekable(),
+			depth: 0,
+			skipped: 0,
+		};
+		let mut parts = vec![parser.parse_or()];
+		// A stray closing parenthesis is ignored
+		while parser.eat(&QueryToken::Close) {
+			parts.push(parser.parse_or());
+		}
+		Self::and(parts)
+	}
+
+	fn lex(query: &str) -> Vec<QueryToken> {
+		let mut tokens = vec![];
+		let mut chars = query.chars().peekable();
+		while let Some(c) = chars.next() {
+			match c {
+				'"' => {
+					let mut phrase = String::new();
+					while let Some(c) = chars.next() {
+						match c {
+							'"' => break,
+							'\\' => phrase.extend(chars.next()),
+							c => phrase.push(c),
+						}
+					}
+					// A phrase followed by `~N` is a proximity search
+					let mut slop = 0;
+					let mut proximity = chars.clone();
+					if proximity.next() == Some('~') {
+						let digits: String =
+							proximity.by_ref().take_while(|c| c.is_ascii_digit()).collect();
+						if !digits.is_empty() {
+							slop = digits.parse().unwrap_or(Position::MAX);
+							chars.nth(digits.len());
+						}
+					}
+					tokens.push(QueryToken::Term(Query::Phrase(phrase, slop)));
+				}
+				'(' => tokens.push(QueryToken::Open),
+				')' => tokens.push(QueryToken::Close),
+				// A minus is an exclusion when it prefixes a word, a phrase or a group
+				'-' => {
+					if chars.peek().is_some_and(|c| !c.is_whitespace()) {
+						tokens.push(QueryToken::Not);
+					}
+				}
+				c if c.is_whitespace() => {}
+				c => {
+					let mut word = String::new();
+					// The length of the word up to its last escaped character
+					let mut literal = 0;
+					let mut next = Some(c);
+					while let Some(c) = next {
+						if c == '\\' {
+							if let Some(c) = chars.next() {
+								word.push(c);
+								literal = word.len();
+							}
+						} else {
+							word.push(c);
+						}
+						next = chars.next_if(|c| !c.is_whitespace() && !"\"()".contains(*c));
+					}
+					match word.as_str() {
+						"" => {}
+						"OR" if literal == 0 => tokens.push(QueryToken::Or),
+						"AND" if literal == 0 => {}
+						_ => tokens.push(QueryToken::Term(Self::word(word, literal))),
+					}
+				}
+			}
+		}
+		tokens
+	}
+
+	/// Parses a word, whose first `literal` bytes were escaped and can't be operators
+	fn word(word: String, literal: usize) -> Self {
+		let (head, tail) = word.split_at(literal);
+		if let Some(p) = tail.strip_suffix('*') {
+			if !head.is_empty() || !p.is_empty() {
+				return Self::Prefix(format!("{head}{p}"));
+			}
+		}
+		if let Some((t, d)) = tail.rsplit_once('~') {
+			if (!head.is_empty() || !t.is_empty()) && d.chars().all(|c| c.is_ascii_digit()) {
+				// Distances are capped at the maximum distance
+				let d = (!d.is_empty()).then(|| d.parse().unwrap_or(MAX_FUZZY_DISTANCE));
+				return Self::Fuzzy(format!("{head}{t}"), d);
+			}
+		}
+		Self::Text(word)
+	}
+
+	fn and(mut queries: Vec<Query>) -> Self {
+		if queries.len() == 1 {
+			queries.remove(0)
+		} else {
+			Self::And(queries)
+		}
+	}
+
+	/// Returns `true` if the query contains a phrase,
+	/// which can only be verified on the content of the documents.
+	pub(in crate::idx) fn has_phrases(&self) -> bool {
+		match self {
+			Self::Phrase(..) => true,
+			Self::Text(_) | Self::Prefix(_) | Self::Fuzzy(..) => false,
+			Self::Not(q) => q.has_phrases(),
+			Self::And(qs) | Self::Or(qs) => qs.iter().any(Self::has_phrases),
+		}
+	}
+}
+
+struct QueryParser {
+	tokens: Peekable<IntoIter<QueryToken>>,
+	/// The nesting of the groups and exclusions being parsed
+	depth: usize,
+	/// The groups which were too deeply nested, and whose parentheses are ignored
+	skipped: usize,
+}
+
+impl QueryParser {
+	fn peek(&mut self) -> Option<&QueryToken> {
+		self.tokens.peek()
+	}
+
+	fn next(&mut self) -> Option<QueryToken> {
+		self.tokens.next()
+	}
+
+	fn eat(&mut self, token: &QueryToken) -> bool {
+		self.tokens.next_if_eq(token).is_some()
+	}
+
+	fn parse_or(&mut self) -> Query {
+		let mut queries = vec![self.parse_and()];
+		while self.eat(&QueryToken::Or) {
+			queries.push(self.parse_and());
+		}
+		if queries.len() == 1 {
+			queries.remove(0)
+		} else {
+			Query::Or(queries)
+		}
+	}
+
+	fn parse_and(&mut self) -> Query {
+		let mut queries = vec![];
+		loop {
+			let skipped = self.skipped;
+			match self.peek() {
+				None | Some(QueryToken::Or) => break,
+				// The closing parenthesis of an ignored group is consumed as a term
+				Some(QueryToken::Close) if skipped == 0 => break,
+				_ => {}
+			}
+			let Some(q) = self.parse_unary() else {
+				continue;
+			};
+			// Consecutive words are analyzed together
+			match (queries.last_mut(), q) {
+				(Some(Query::Text(t)), Query::Text(w)) => {
+					t.push(' ');
+					t.push_str(&w);
+				}
+				(_, q) => queries.push(q),
+			}
+		}
+		Query::and(queries)
+	}
+
+	fn parse_unary(&mut self) -> Option<Query> {
+		match self.next()? {
+			QueryToken::Term(q) => Some(q),
+			// Exclusions nested too deeply are ignored
+			QueryToken::Not if self.depth >= MAX_QUERY_DEPTH => None,
+			QueryToken::Not => {
+				self.depth += 1;
+				let q = self.parse_unary().map(|q| Query::Not(Box::new(q)));
+				self.depth -= 1;
+				q
+			}
+			// Groups nested too deeply are parsed as part of the enclosing group
+			QueryToken::Open if self.depth >= MAX_QUERY_DEPTH => {
+				self.skipped += 1;
+				None
+			}
+			QueryToken::Open => {
+				self.depth += 1;
+				let q = self.parse_or();
+				self.depth -= 1;
+				self.eat(&QueryToken::Close);
+				Some(q)
+			}
+			QueryToken::Close => {
+				self.skipped = self.skipped.saturating_sub(1);
+				None
+			}
+			QueryToken::Or => None,
+		}
+	}
+}
+
+/// A query whose terms have been resolved against the index
+enum Node {
+	/// Every term must be present, `None` being a term unknown to the index
+	Terms(Vec<Option<TermId>>),
+	/// The terms must be present at the given relative positions,
+	/// with at most the given number of extra positions in between.
+	/// The terms sharing a position, such as synonyms, are alternatives.
+	Phrase(Vec<(Position, Vec<TermId>)>, u32),
+	/// At least one of the terms must be present
+	AnyOf(Vec<TermId>),
+	Not(Box<Node>),
+	And(Vec<Node>),
+	Or(Vec<Node>),
+}
+
+impl Node {
+	fn collect_terms(&self, terms: &mut HashSet<TermId>) {
+		match self {
+			Self::Terms(ts) => terms.extend(ts.iter().flatten()),
+			Self::Phrase(ts, _) => terms.extend(ts.iter().flat_map(|(_, ts)| ts)),
+			Self::AnyOf(ts) => terms.extend(ts),
+			Self::Not(n) => n.collect_terms(terms),
+			Self::And(ns) | Self::Or(ns) => ns.iter().for_each(|n| n.collect_terms(terms)),
+		}
+	}
+
+	fn collect_phrase_terms(&self, terms: &mut HashSet<TermId>) {
+		match self {
+			Self::Phrase(ts, _) => terms.extend(ts.iter().flat_map(|(_, ts)| ts)),
+			Self::Terms(_) | Self::AnyOf(_) => {}
+			Self::Not(n) => n.collect_phrase_terms(terms),
+			Self::And(ns) | Self::Or(ns) => ns.iter().for_each(|n| n.collect_phrase_terms(terms)),
+		}
+	}
+
+	/// Returns the documents matching the node, and whether the result is exact.
+	/// Phrases return the documents containing every term, which have to be verified.
+	fn hits(&self, docs: &HashMap<TermId, RoaringTreemap>) -> (RoaringTreemap, bool) {
+		match self {
+			Self::Terms(ts) => (Self::intersect(ts.iter().copied(), docs), true),
+			Self::Phrase(ts, _) => {
+				(Self::intersect_alternatives(ts.iter().map(|(_, ts)| ts), docs), false)
+			}
+			Self::AnyOf(ts) => {
+				let mut hits = RoaringTreemap::new();
+				for t in ts {
+					if let Some(d) = docs.get(t) {
+						hits |= d;
+					}
+				}
+				(hits, true)
+			}
+			// An exclusion alone matches nothing
+			Self::Not(_) => (RoaringTreemap::new(), true),
+			Self::And(ns) => {
+				let mut hits: Option<RoaringTreemap> = None;
+				let mut exact = true;
+				for n in ns.iter().filter(|n| !matches!(n, Self::Not(_))) {
+					let (h, e) = n.hits(docs);
+					exact &= e;
+					hits = Some(match hits {
+						Some(hits) => hits & h,
+						None => h,
+					});
+				}
+				let Some(mut hits) = hits else {
+					return (RoaringTreemap::new(), true);
+				};
+				for n in ns {
+					if let Self::Not(n) = n {
+						let (h, e) = n.hits(docs);
+						// Only exact results can be excluded
+						if e {
+							hits -= h;
+						} else {
+							exact = false;
+						}
+					}
+				}
+				(hits, exact)
+			}
+			Self::Or(ns) => {
+				let mut hits = RoaringTreemap::new();
+				let mut exact = true;
+				for n in ns {
+					let (h, e) = n.hits(docs);
+					hits |= h;
+					exact &= e;
+				}
+				(hits, exact)
+			}
+		}
+	}
+
+	fn intersect(
+		terms: impl Iterator<Item = Option<TermId>>,
+		docs: &HashMap<TermId, RoaringTreemap>,
+	) -> RoaringTreemap {
+		let mut hits: Option<RoaringTreemap> = None;
+		for t in terms {
+			let Some(d) = t.and_then(|t| docs.get(&t)) else {
+				return RoaringTreemap::new();
+			};
+			hits = Some(match hits {
+				Some(h) => h & d,
+				None => d.clone(),
+			});
+		}
+		hits.unwrap_or_default()
+	}
+
+	/// Intersects the documents containing any of the alternatives of each position
+	fn intersect_alternatives<'a>(
+		positions: impl Iterator<Item = &'a Vec<TermId>>,
+		docs: &HashMap<TermId, RoaringTreemap>,
+	) -> RoaringTreemap {
+		let mut hits: Option<RoaringTreemap> = None;
+		for ts in positions {
+			let mut any = RoaringTreemap::new();
+			for t in ts {
+				if let Some(d) = docs.get(t) {
+					any |= d;
+				}
+			}
+			hits = Some(match hits {
+				Some(h) => h & any,
+				None => any,
+			});
+		}
+		hits.unwrap_or_default()
+	}
+
+	/// Checks if a document matches the node.
+	/// Phrases are only verified if the positions of the terms are given.
+	fn matches<F: Fn(TermId) -> bool>(
+		&self,
+		contains: &F,
+		positions: Option<&TermPositions>,
+	) -> bool {
+		match self {
+			Self::Terms(ts) => ts.iter().all(|t| t.is_some_and(contains)),
+			Self::Phrase(ts, slop) => {
+				ts.iter().all(|(_, ts)| ts.iter().any(|t| contains(*t)))
+					&& positions.is_none_or(|p| p.has_phrase(ts, *slop))
+			}
+			Self::AnyOf(ts) => ts.iter().any(|t| contains(*t)),
+			Self::Not(_) => false,
+			Self::And(ns) => {
+				let mut positives = false;
+				for n in ns {
+					match n {
+						Self::Not(n) => {
+							if n.matches(contains, positions) {
+								return false;
+							}
+						}
+						n => {
+							if !n.matches(contains, positions) {
+								return false;
+							}
+							positives = true;
+						}
+					}
+				}
+				positives
+			}
+			Self::Or(ns) => ns.iter().any(|n| n.matches(contains, positions)),
+		}
+	}
+}
+
+/// The positions of the terms of a document,
+/// as pairs of the index of the value and the position of the term in the value.
+#[derive(Default)]
+pub(in crate::idx) struct TermPositions(HashMap<TermId, BTreeSet<(u32, Position)>>);
+
+impl TermPositions {
+	pub(super) fn add(&mut self, term_id: TermId, index: u32, pos: Position) {
+		self.0.entry(term_id).or_default().insert((index, pos));
+	}
+
+	fn contains(&self, term_id: TermId) -> bool {
+		self.0.contains_key(&term_id)
+	}
+
+	/// Checks if the terms are present in the same value, in order, at their relative positions.
+	/// `slop` is the number of extra positions allowed between the first and the last term.
+	/// The terms sharing a relative position are alternatives.
+	fn has_phrase(&self, phrase: &[(Position, Vec<TermId>)], slop: u32) -> bool {
+		let Some(((_, first), rest)) = phrase.split_first() else {
+			return false;
+		};
+		first.iter().filter_map(|t| self.0.get(t)).flatten().any(|&(i, start)| {
+			// Each term is matched at its nearest position, which minimises the extra positions
+			let mut prev = (start, 0);
+			for (rel, ts) in rest {
+				let min = prev.0.saturating_add(rel - prev.1);
+				let nearest = ts
+					.iter()
+					.filter_map(|t| self.0.get(t)?.range((i, min)..=(i, Position::MAX)).next())
+					.map(|&(_, p)| p)
+					.min();
+				let Some(p) = nearest else {
+					return false;
+				};
+				prev = (p, *rel);
+			}
+			prev.0 - start - prev.1 <= slop
+		})
+	}
+}
+
+/// A full-text query resolved against an index
+pub(in crate::idx) struct FtQuery {
+	root: Node,
+	/// The terms which are not excluded, used for scoring and highlighting
+	terms_list: TermsList,
+	/// The documents containing each term of the query
+	docs: HashMap<TermId, RoaringTreemap>,
+	/// The weights of the terms matched by fuzzy terms
+	weights: TermWeights,
+	/// Phrases have to be verified on the positions of the terms in the documents
+	has_phrases: bool,
+	/// The terms of the phrases, whose stored positions verify the phrases
+	phrase_terms: HashSet<TermId>,
+	offsets: Offsets,
+}
+
+impl FtQuery {
+	#[expect(clippy::too_many_arguments)]
+	pub(super) async fn new(
+		stk: &mut Stk,
+		ctx: &Context,
+		opt: &Options,
+		analyzer: &Analyzer,
+		terms: &Terms,
+		term_docs: &TermDocs,
+		offsets: &Offsets,
+		fuzzy: u8,
+		query: Query,
+	) -> Result<Self, Error> {
+		let has_phrases = query.has_phrases();
+		let mut resolver = QueryResolver {
+			ctx,
+			opt,
+			analyzer,
+			terms,
+			fuzzy,
+			terms_list: vec![],
+			weights: TermWeights::default(),
+		};
+		let root = resolver.resolve(stk, query, false).await?.unwrap_or(Node::And(vec![]));
+		let mut term_ids = HashSet::new();
+		root.collect_terms(&mut term_ids);
+		let mut phrase_terms = HashSet::new();
+		root.collect_phrase_terms(&mut phrase_terms);
+		let mut docs = HashMap::with_capacity(term_ids.len());
+		let tx = ctx.tx();
+		for term_id in term_ids {
+			if let Some(d) = term_docs.get_docs(&tx, term_id).await? {
+				docs.insert(term_id, d);
+			}
+		}
+		drop(tx);
+		Ok(Self {
+			root,
+			terms_list: resolver.terms_list,
+			docs,
+			weights: resolver.weights,
+			has_phrases,
+			phrase_terms,
+			offsets: offsets.clone(),
+		})
+	}
+
+	pub(in crate::idx) fn terms_list(&self) -> &TermsList {
+		&self.terms_list
+	}
+
+	/// The documents of the terms which are not excluded, used for scoring
+	pub(in crate::idx) fn terms_docs(&self) -> TermsDocs {
+		Arc::new(
+			self.terms_list
+				.iter()
+				.flatten()
+				.map(|(t, _)| Some((*t, self.docs.get(t).cloned().unwrap_or_default())))
+				.collect(),
+		)
+	}
+
+	pub(super) fn term_weights(&self) -> TermWeights {
+		self.weights.clone()
+	}
+
+	/// Returns the candidate documents.
+	/// If the query contains phrases, they have to be verified with `matches_stored`.
+	pub(in crate::idx) fn hits(&self) -> RoaringTreemap {
+		self.root.hits(&self.docs).0
+	}
+
+	pub(in crate::idx) fn has_phrases(&self) -> bool {
+		self.has_phrases
+	}
+
+	/// Checks if an indexed document matches.
+	/// The positions of the terms are required to verify the phrases.
+	pub(in crate::idx) fn matches_doc_id(
+		&self,
+		doc_id: DocId,
+		positions: Option<&TermPositions>,
+	) -> bool {
+		let contains = |t| self.docs.get(&t).is_some_and(|d| d.contains(doc_id));
+		self.root.matches(&contains, positions)
+	}
+
+	/// Checks if an indexed document matches, the phrases being verified on the stored positions.
+	/// Returns `None` if the positions are not stored, the document having been indexed
+	/// by a version which did not store them.
+	pub(in crate::idx) async fn matches_stored(
+		&self,
+		tx: &Transaction,
+		doc_id: DocId,
+	) -> Result<Option<bool>, Error> {
+		if !self.has_phrases {
+			return Ok(Some(self.matches_doc_id(doc_id, None)));
+		}
+		let mut positions = TermPositions::default();
+		for t in &self.phrase_terms {
+			if !self.docs.get(t).is_some_and(|d| d.contains(doc_id)) {
+				continue;
+			}
+			let Some(offsets) = self.offsets.get_offsets(tx, doc_id, *t).await? else {
+				return Ok(None);
+			};
+			for o in offsets.0 {
+				let Some(pos) = o.pos else {
+					return Ok(None);
+				};
+				positions.add(*t, o.index, pos);
+			}
+		}
+		Ok(Some(self.matches_doc_id(doc_id, Some(&positions))))
+	}
+
+	/// Checks if a value, whose terms have been extracted, matches.
+	pub(in crate::idx) fn matches_terms(&self, positions: &TermPositions) -> bool {
+		self.root.matches(&|t| positions.contains(t), Some(positions))
+	}
+}
+
+struct QueryResolver<'a> {
+	ctx: &'a Context,
+	opt: &'a Options,
+	analyzer: &'a Analyzer,
+	terms: &'a Terms,
+	/// The default fuzzy distance of the index
+	fuzzy: u8,
+	terms_list: TermsList,
+	weights: TermWeights,
+}
+
+impl QueryResolver<'_> {
+	/// Resolves the terms of a query.
+	/// Returns `None` if the query does not contain any term.
+	async fn resolve(
+		&mut self,
+		stk: &mut Stk,
+		query: Query,
+		excluded: bool,
+	) -> Result<Option<Node>, Error> {
+		let node = match query {
+			Query::Text(t) if self.fuzzy > 0 => {
+				return self.resolve_fuzzy(stk, t, self.fuzzy, excluded).await;
+			}
+			Query::Fuzzy(t, d) => {
+				let d = d.unwrap_or(if self.fuzzy > 0 {
+					self.fuzzy
+				} else {
+					MAX_FUZZY_DISTANCE
+				});
+				if d > 0 {
+					return self.resolve_fuzzy(stk, t, d.min(MAX_FUZZY_DISTANCE), excluded).await;
+				}
+				let terms = self.resolve_terms(stk, t, excluded).await?;
+				match Self::terms_node(terms) {
+					Some(n) => n,
+					None => return Ok(None),
+				}
+			}
+			Query::Text(t) => {
+				let terms = self.resolve_terms(stk, t, excluded).await?;
+				match Self::terms_node(terms) {
+					Some(n) => n,
+					None => return Ok(None),
+				}
+			}
+			Query::Phrase(p, slop) => {
+				let terms = Self::group_by_position(self.resolve_terms(stk, p, excluded).await?);
+				match terms.first() {
+					None => return Ok(None),
+					Some((first, _)) => {
+						let first = *first;
+						let terms = terms
+							.into_iter()
+							.map(|(pos, ts)| (pos - first, ts.into_iter().flatten().collect()))
+							.collect();
+						Node::Phrase(terms, slop)
+					}
+				}
+			}
+			Query::Prefix(p) => {
+				let mut tokens =
+					self.analyzer.extract_querying_tokens(stk, self.ctx, self.opt, p).await?;
+				let Some((_, prefix, _)) = tokens.pop() else {
+					return Ok(None);
+				};
+				let tx = self.ctx.tx();
+				let expansions = self
+					.terms
+					.search_by_prefix(&tx, &prefix, *FULLTEXT_MAX_PREFIX_EXPANSIONS)
+					.await?;
+				let mut terms = Vec::with_capacity(tokens.len());
+				for (_, s, len) in tokens {
+					let term_id = self.terms.get_term_id(&tx, &s).await?;
+					terms.push(term_id);
+					self.add_term(term_id.map(|t| (t, len)), excluded);
+				}
+				drop(tx);
+				for (t, len) in &expansions {
+					self.add_term(Some((*t, *len)), excluded);
+				}
+				let any_of = Node::AnyOf(expansions.into_iter().map(|(t, _)| t).collect());
+				if terms.is_empty() {
+					any_of
+				} else {
+					Node::And(vec![Node::Terms(terms), any_of])
+				}
+			}
+			Query::Not(q) => match stk.run(|stk| self.resolve(stk, *q, !excluded)).await? {
+				Some(n) => Node::Not(Box::new(n)),
+				None => return Ok(None),
+			},
+			Query::And(qs) => match self.resolve_all(stk, qs, excluded).await? {
+				Some(ns) => Node::And(ns),
+				None => return Ok(None),
+			},
+			Query::Or(qs) => match self.resolve_all(stk, qs, excluded).await? {
+				Some(ns) => Node::Or(ns),
+				None => return Ok(None),
+			},
+		};
+		Ok(Some(node))
+	}
+
+	/// Every position of a text is required.
+	/// The terms sharing a position, such as the synonyms expanded at query time, are alternatives.
+	fn terms_node(terms: Vec<(Position, Option<TermId>)>) -> Option<Node> {
+		let mut required = vec![];
+		let mut nodes = vec![];
+		for (_, ts) in Self::group_by_position(terms) {
+			if let [t] = ts.as_slice() {
+				required.push(*t);
+			} else {
+				nodes.push(Node::AnyOf(ts.into_iter().flatten().collect()));
+			}
+		}
+		if !required.is_empty() {
+			nodes.insert(0, Node::Terms(required));
+		}
+		match nodes.len() {
+			0 => None,
+			1 => nodes.pop(),
+			_ => Some(Node::And(nodes)),
+		}
+	}
+
+	/// Groups the consecutive terms sharing the same position
+	fn group_by_position(
+		terms: Vec<(Position, Option<TermId>)>,
+	) -> Vec<(Position, Vec<Option<TermId>>)> {
+		let mut groups: Vec<(Position, Vec<Option<TermId>>)> = Vec::with_capacity(terms.len());
+		for (pos, t) in terms {
+			match groups.last_mut() {
+				Some((p, ts)) if *p == pos => ts.push(t),
+				_ => groups.push((pos, vec![t])),
+			}
+		}
+		groups
+	}
+
+	async fn resolve_all(
+		&mut self,
+		stk: &mut Stk,
+		queries: Vec<Query>,
+		excluded: bool,
+	) -> Result<Option<Vec<Node>>, Error> {
+		let mut nodes = Vec::with_capacity(queries.len());
+		for q in queries {
+			if let Some(n) = stk.run(|stk| self.resolve(stk, q, excluded)).await? {
+				nodes.push(n);
+			}
+		}
+		Ok(if nodes.is_empty() {
+			None
+		} else {
+			Some(nodes)
+		})
+	}
+
+	/// Analyzes a text and expands each of its terms to the terms within the edit distance
+	async fn resolve_fuzzy(
+		&mut self,
+		stk: &mut Stk,
+		text: String,
+		distance: u8,
+		excluded: bool,
+	) -> Result<Option<Node>, Error> {
+		let tokens = self.analyzer.extract_querying_tokens(stk, self.ctx, self.opt, text).await?;
+		let mut nodes = Vec::with_capacity(tokens.len());
+		let tx = self.ctx.tx();
+		for (_, s, _) in tokens {
+			let expansions =
+				self.terms.search_fuzzy(&tx, &s, distance, *FULLTEXT_MAX_FUZZY_EXPANSIONS).await?;
+			let mut terms = Vec::with_capacity(expansions.len());
+			for (t, len, d) in expansions {
+				// The more edits, the lower the score
+				self.add_weighted_term(Some((t, len)), 1.0 / (1.0 + d as Score), excluded);
+				terms.push(t);
+			}
+			nodes.push(Node::AnyOf(terms));
+		}
+		drop(tx);
+		Ok(match nodes.len() {
+			0 => None,
+			1 => nodes.pop(),
+			_ => Some(Node::And(nodes)),
+		})
+	}
+
+	/// Analyzes a text and resolves its terms, with their positions
+	async fn resolve_terms(
+		&mut self,
+		stk: &mut Stk,
+		text: String,
+		excluded: bool,
+	) -> Result<Vec<(Position, Option<TermId>)>, Error> {
+		let tokens = self.analyzer.extract_querying_tokens(stk, self.ctx, self.opt, text).await?;
+		let mut terms = Vec::with_capacity(tokens.len());
+		let tx = self.ctx.tx();
+		for (pos, s, len) in tokens {
+			let term_id = self.terms.get_term_id(&tx, &s).await?;
+			terms.push((pos, term_id));
+			self.add_term(term_id.map(|t| (t, len)), excluded);
+		}
+		drop(tx);
+		Ok(terms)
+	}
+
+	fn add_term(&mut self, term: Option<(TermId, TermLen)>, excluded: bool) {
+		self.add_weighted_term(term, 1.0, excluded);
+	}
+
+	/// Adds a term used for scoring and highlighting.
+	/// A term matched several times keeps its highest weight.
+	fn add_weighted_term(
+		&mut self,
+		term: Option<(TermId, TermLen)>,
+		weight: Score,
+		excluded: bool,
+	) {
+		let Some(term) = term else {
+			return;
+		};
+		if excluded {
+			return;
+		}
+		if !self.terms_list.contains(&Some(term)) {
+			self.terms_list.push(Some(term));
+		}
+		let w = self.weights.entry(term.0).or_insert(weight);
+		*w = w.max(weight);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{Node, Query, TermPositions, MAX_QUERY_DEPTH};
+	use roaring::RoaringTreemap;
+	use std::collections::HashMap;
+
+	fn text(s: &str) -> Query {
+		Query::Text(s.to_owned())
+	}
+
+	#[test]
+	fn parse_query() {
+		assert_eq!(Query::parse("hello world"), text("hello world"));
+		assert_eq!(Query::parse("hello OR world"), Query::Or(vec![text("hello"), text("world")]));
+		assert_eq!(
+			Query::parse("\"hello world\" -foo bar*"),
+			Query::And(vec![
+				Query::Phrase("hello world".to_owned(), 0),
+				Query::Not(Box::new(text("foo"))),
+				Query::Prefix("bar".to_owned()),
+			])
+		);
+		assert_eq!(
+			Query::parse("(a OR b) c AND d"),
+			Query::And(vec![Query::Or(vec![text("a"), text("b")]), text("c d")])
+		);
+		// Hyphens within words and lone symbols are not operators
+		assert_eq!(Query::parse("e-mail - *"), text("e-mail *"));
+		// Unbalanced quotes and parentheses are closed at the end
+		assert_eq!(
+			Query::parse("(a \"b c"),
+			Query::And(vec![text("a"), Query::Phrase("b c".to_owned(), 0)])
+		);
+		assert_eq!(Query::parse("a) b"), Query::And(vec![text("a"), text("b")]));
+		assert!(Query::parse("a -(b \"c d\")").has_phrases());
+		assert!(!Query::parse("a OR b*").has_phrases());
+		assert_eq!(
+			Query::parse("\"a b\"~2 \"c d\"~ e"),
+			Query::And(vec![
+				Query::Phrase("a b".to_owned(), 2),
+				Query::Phrase("c d".to_owned(), 0),
+				text("~ e"),
+			])
+		);
+		assert_eq!(
+			Query::parse("recieve~ relieve~1 word~0 word~9 ~ a~b"),
+			Query::And(vec![
+				Query::Fuzzy("recieve".to_owned(), None),
+				Query::Fuzzy("relieve".to_owned(), Some(1)),
+				Query::Fuzzy("word".to_owned(), Some(0)),
+				Query::Fuzzy("word".to_owned(), Some(9)),
+				text("~ a~b"),
+			])
+		);
+		assert_eq!(
+			Query::parse("-typo~999"),
+			Query::Not(Box::new(Query::Fuzzy("typo".to_owned(), Some(2))))
+		);
+	}
+
+	#[test]
+	fn parse_escaped_query() {
+		// Escaped operators are matched literally, as the query was before the syntax
+		assert_eq!(Query::parse(r"\-a \OR b"), text("-a OR b"));
+		assert_eq!(Query::parse(r#"\"a b\""#), text("\"a b\""));
+		assert_eq!(Query::parse(r"a\* b\~1 \(c\) d\\"), text(r"a* b~1 (c) d\"));
+		assert_eq!(Query::parse(r#""say \"hi\"""#), Query::Phrase("say \"hi\"".to_owned(), 0));
+		// Only the characters following the escaped ones can be operators
+		assert_eq!(
+			Query::parse(r"\** \~a~1 a\~~ \"),
+			Query::And(vec![
+				Query::Prefix("*".to_owned()),
+				Query::Fuzzy("~a".to_owned(), Some(1)),
+				Query::Fuzzy("a~".to_owned(), None),
+			])
+		);
+	}
+
+	#[test]
+	fn parse_deep_query() {
+		// Deeply nested groups and exclusions don't exhaust the stack
+		let query = Query::parse(&format!("{}a{}", "(".repeat(100_000), ")".repeat(100_000)));
+		assert_eq!(query, text("a"));
+		let query = Query::parse(&format!("{}a", "-".repeat(100_000)));
+		assert!(!query.has_phrases());
+		let query = Query::parse(&"(-)(".repeat(100_000));
+		assert!(!query.has_phrases());
+		// The nesting is ignored beyond the maximum depth
+		let mut query = Query::parse(&format!("{}a OR b c", "-(".repeat(100_000)));
+		let mut depth = 0;
+		while let Query::Not(q) = query {
+			query = *q;
+			depth += 1;
+		}
+		assert_eq!(depth, MAX_QUERY_DEPTH / 2);
+		assert_eq!(query, Query::Or(vec![text("a"), text("b c")]));
+	}
+
+	#[test]
+	fn query_hits() {
+		let docs = HashMap::from([
+			(1, RoaringTreemap::from_iter([1, 2, 3])),
+			(2, RoaringTreemap::from_iter([2, 3, 4])),
+			(3, RoaringTreemap::from_iter([3])),
+		]);
+		let hits = |n: Node| {
+			let (h, e) = n.hits(&docs);
+			(h.into_iter().collect::<Vec<_>>(), e)
+		};
+		assert_eq!(hits(Node::Terms(vec![Some(1), Some(2)])), (vec![2, 3], true));
+		assert_eq!(hits(Node::Terms(vec![Some(1), None])), (vec![], true));
+		assert_eq!(hits(Node::AnyOf(vec![1, 2])), (vec![1, 2, 3, 4], true));
+		assert_eq!(
+			hits(Node::And(vec![
+				Node::AnyOf(vec![1, 2]),
+				Node::Not(Box::new(Node::Terms(vec![Some(3)])))
+			])),
+			(vec![1, 2, 4], true)
+		);
+		// Excluding a phrase can't be resolved by the index
+		assert_eq!(
+			hits(Node::And(vec![
+				Node::Terms(vec![Some(1)]),
+				Node::Not(Box::new(Node::Phrase(vec![(0, vec![2]), (1, vec![3])], 0)))
+			])),
+			(vec![1, 2, 3], false)
+		);
+		assert_eq!(hits(Node::Not(Box::new(Node::Terms(vec![Some(1)])))), (vec![], true));
+	}
+
+	#[test]
+	fn phrase_positions() {
+		let mut tp = TermPositions::default();
+		// "a b c" and "c a"
+		tp.add(1, 0, 0);
+		tp.add(2, 0, 1);
+		tp.add(3, 0, 2);
+		tp.add(3, 1, 0);
+		tp.add(1, 1, 1);
+		assert!(tp.has_phrase(&[(0, vec![1]), (1, vec![2])], 0));
+		assert!(tp.has_phrase(&[(0, vec![2]), (1, vec![3])], 0));
+		assert!(tp.has_phrase(&[(0, vec![3]), (1, vec![1])], 0));
+		assert!(!tp.has_phrase(&[(0, vec![1]), (1, vec![3])], 0));
+		assert!(!tp.has_phrase(&[(0, vec![2]), (1, vec![1])], 0));
+		assert!(!tp.has_phrase(&[(0, vec![1]), (1, vec![])], 0));
+	}
+
+	#[test]
+	fn proximity_positions() {
+		let mut tp = TermPositions::default();
+		// "a b c d" and "d a"
+		tp.add(1, 0, 0);
+		tp.add(2, 0, 1);
+		tp.add(3, 0, 2);
+		tp.add(4, 0, 3);
+		tp.add(4, 1, 0);
+		tp.add(1, 1, 1);
+		assert!(!tp.has_phrase(&[(0, vec![1]), (1, vec![4])], 1));
+		assert!(tp.has_phrase(&[(0, vec![1]), (1, vec![4])], 2));
+		assert!(tp.has_phrase(&[(0, vec![1]), (1, vec![3]), (2, vec![4])], 1));
+		assert!(!tp.has_phrase(&[(0, vec![1]), (1, vec![4]), (2, vec![3])], 9));
+		assert!(tp.has_phrase(&[(0, vec![4]), (1, vec![1])], 0));
+		// The terms have to be in the same value
+		assert!(!tp.has_phrase(&[(0, vec![3]), (1, vec![1])], 9));
+	}
+
+	#[test]
+	fn phrase_alternatives() {
+		let mut tp = TermPositions::default();
+		// "a b c"
+		tp.add(1, 0, 0);
+		tp.add(2, 0, 1);
+		tp.add(3, 0, 2);
+		// Any of the terms sharing a position matches
+		assert!(tp.has_phrase(&[(0, vec![1]), (1, vec![5, 2])], 0));
+		assert!(tp.has_phrase(&[(0, vec![5, 2]), (1, vec![3])], 0));
+		assert!(tp.has_phrase(&[(0, vec![1]), (1, vec![3, 2])], 1));
+		assert!(!tp.has_phrase(&[(0, vec![1]), (1, vec![5, 6])], 9));
+		let docs = HashMap::from([
+			(1, RoaringTreemap::from_iter([1, 2])),
+			(2, RoaringTreemap::from_iter([2])),
+			(3, RoaringTreemap::from_iter([3])),
+		]);
+		let (hits, _) = Node::Phrase(vec![(0, vec![1]), (1, vec![2, 3])], 0).hits(&docs);
+		assert_eq!(hits.into_iter().collect::<Vec<_>>(), vec![2]);
+	}
+}
@@ -3,37 +3,51 @@ use crate::idx::docids::DocId;
 use crate::idx::ft::doclength::{DocLength, DocLengths};
 use crate::idx::ft::postings::{Postings, TermFrequency};
 use crate::idx::ft::termdocs::TermsDocs;
+use crate::idx::ft::terms::TermId;
 use crate::idx::ft::Bm25Params;
 use crate::kvs::Transaction;
+use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
 pub(super) type Score = f32;
 
+/// The weights applied to the scores of the terms, lowering the score of the fuzzy matches.
+/// A term without weight has a weight of `1.0`.
+pub(super) type TermWeights = HashMap<TermId, Score>;
+
 pub(crate) struct BM25Scorer {
 	postings: Arc<RwLock<Postings>>,
 	terms_docs: TermsDocs,
+	weights: TermWeights,
 	doc_lengths: Arc<RwLock<DocLengths>>,
 	average_doc_length: f32,
 	doc_count: f32,
+	/// The weight of an occurrence in the frequencies of a boosted index
+	frequency_unit: f32,
 	bm25: Bm25Params,
 }
 
 impl BM25Scorer {
+	#[expect(clippy::too_many_arguments)]
 	pub(super) fn new(
 		postings: Arc<RwLock<Postings>>,
 		terms_docs: TermsDocs,
+		weights: TermWeights,
 		doc_lengths: Arc<RwLock<DocLengths>>,
 		total_docs_length: u128,
 		doc_count: u64,
+		frequency_unit: f32,
 		bm25: Bm25Params,
 	) -> Self {
 		Self {
 			postings,
 			terms_docs,
+			weights,
 			doc_lengths,
 			average_doc_length: (total_docs_length as f32) / (doc_count as f32),
 			doc_count: doc_count as f32,
+			frequency_unit,
 			bm25,
 		}
 	}
@@ -48,7 +62,8 @@ impl BM25Scorer {
 		let dl = self.doc_lengths.read().await;
 		let doc_length = dl.get_doc_length(tx, doc_id).await?.unwrap_or(0);
 		drop(dl);
-		Ok(self.compute_bm25_score(term_frequency as f32, term_doc_count as f32, doc_length as f32))
+		let term_frequency = term_frequency as f32 / self.frequency_unit;
+		Ok(self.compute_bm25_score(term_frequency, term_doc_count as f32, doc_length as f32))
 	}
 
 	pub(crate) async fn score(
@@ -62,7 +77,8 @@ impl BM25Scorer {
 			if docs.contains(doc_id) {
 				let tf = p.get_term_frequency(tx, *term_id, doc_id).await?;
 				if let Some(term_freq) = tf {
-					sc += self.term_score(tx, doc_id, docs.len(), term_freq).await?;
+					let weight = self.weights.get(term_id).copied().unwrap_or(1.0);
+					sc += weight * self.term_score(tx, doc_id, docs.len(), term_freq).await?;
 				}
 			}
 		}
@@ -7,6 +7,7 @@ use crate::kvs::{Key, Transaction, TransactionType, Val};
 use revision::{revisioned, Revisioned};
 use roaring::RoaringTreemap;
 use serde::{Deserialize, Serialize};
+use strsim::osa_distance;
 
 pub(crate) type TermId = u64;
 pub(crate) type TermLen = u32;
@@ -95,6 +96,64 @@ impl Terms {
 		self.btree.search(tx, &self.store, &term.into()).await
 	}
 
+	/// Returns the terms starting with the given prefix, with their length
+	pub(super) async fn search_by_prefix(
+		&self,
+		tx: &Transaction,
+		prefix: &str,
+		limit: usize,
+	) -> Result<Vec<(TermId, TermLen)>, Error> {
+		let res = self.btree.search_by_prefix(tx, &self.store, &prefix.into(), limit).await?;
+		Ok(res
+			.into_iter()
+			.map(|(key, term_id)| {
+				(term_id, String::from_utf8_lossy(&key).chars().count() as TermLen)
+			})
+			.collect())
+	}
+
+	/// Returns the terms within the given edit distance of a term, with their length and distance.
+	/// The closest terms are kept first when there are more than `limit` of them.
+	/// The subtrees of the dictionary whose terms are all too distant are not visited.
+	pub(super) async fn search_fuzzy(
+		&self,
+		tx: &Transaction,
+		term: &str,
+		distance: u8,
+		limit: usize,
+	) -> Result<Vec<(TermId, TermLen, u8)>, Error> {
+		let chars: Vec<char> = term.chars().collect();
+		let len = chars.len();
+		let distance = distance as usize;
+		let res = self
+			.btree
+			.search_by_filter(
+				tx,
+				&self.store,
+				|prefix| prefix_distance(prefix, &chars, distance) <= distance,
+				|key| {
+					// A UTF-8 key has between a quarter of its length and its length in chars
+					key.len() + distance >= len && key.len() <= (len + distance) * 4
+				},
+			)
+			.await?;
+		let mut res: Vec<_> = res
+			.into_iter()
+			.filter_map(|(key, term_id)| {
+				let key = String::from_utf8_lossy(&key);
+				let key_len = key.chars().count();
+				if key_len.abs_diff(len) > distance {
+					return None;
+				}
+				let d = osa_distance(term, &key);
+				(d <= distance).then_some((term_id, key_len as TermLen, d as u8))
+			})
+			.collect();
+		res.sort_by_key(|(term_id, _, d)| (*d, *term_id));
+		res.truncate(limit);
+		Ok(res)
+	}
+
 	pub(super) async fn remove_term_id(
 		&mut self,
 		tx: &Transaction,
@@ -210,10 +269,43 @@ impl VersionedStore for State {
 	}
 }
 
+/// Returns the smallest edit distance between the prefix of a key and any prefix of the term,
+/// which is a lower bound of the distance between the term and every key starting with the prefix.
+/// A prefix too long to be within `max` edits of the term is rejected without computing the matrix.
+fn prefix_distance(prefix: &[u8], term: &[char], max: usize) -> usize {
+	// A prefix may end in the middle of a character
+	let prefix = match std::str::from_utf8(prefix) {
+		Ok(p) => p,
+		Err(e) => std::str::from_utf8(&prefix[..e.valid_up_to()]).unwrap_or_default(),
+	};
+	let prefix: Vec<char> = prefix.chars().collect();
+	// Deleting the extra characters of the prefix costs more than `max` edits
+	if prefix.len() > term.len() + max {
+		return max + 1;
+	}
+	// The optimal string alignment matrix, keeping the last three rows
+	let mut prev2: Vec<usize> = vec![0; term.len() + 1];
+	let mut prev: Vec<usize> = (0..=term.len()).collect();
+	let mut cur: Vec<usize> = vec![0; term.len() + 1];
+	for (i, pc) in prefix.iter().enumerate() {
+		cur[0] = i + 1;
+		for (j, tc) in term.iter().enumerate() {
+			let cost = usize::from(pc != tc);
+			cur[j + 1] = (prev[j] + cost).min(prev[j + 1] + 1).min(cur[j] + 1);
+			if i > 0 && j > 0 && *pc == term[j - 1] && prefix[i - 1] == *tc {
+				cur[j + 1] = cur[j + 1].min(prev2[j - 1] + 1);
+			}
+		}
+		std::mem::swap(&mut prev2, &mut prev);
+		std::mem::swap(&mut prev, &mut cur);
+	}
+	prev.into_iter().min().unwrap_or(0)
+}
+
 #[cfg(test)]
 mod tests {
 	use crate::idx::ft::postings::TermFrequency;
-	use crate::idx::ft::terms::{State, Terms};
+	use crate::idx::ft::terms::{prefix_distance, State, TermId, Terms};
 	use crate::idx::{IndexKeyBase, VersionedStore};
 	use crate::kvs::TransactionType::{Read, Write};
 	use crate::kvs::{Datastore, LockType::*, Transaction, TransactionType};
@@ -315,6 +407,74 @@ mod tests {
 		}
 	}
 
+	#[test(tokio::test)]
+	async fn test_search_fuzzy() {
+		let ds = Datastore::new("memory").await.unwrap();
+		{
+			let (tx, mut t) = new_operation(&ds, 7, Write).await;
+			for term in ["receive", "deceive", "recipe", "relieve", "believe"] {
+				t.resolve_term_id(&tx, term).await.unwrap();
+			}
+			finish(tx, t).await;
+		}
+		let (tx, t) = new_operation(&ds, 7, Read).await;
+		// A transposition counts as a single edit
+		assert_eq!(
+			t.search_fuzzy(&tx, "recieve", 1, 10).await.unwrap(),
+			vec![(0, 7, 1), (3, 7, 1)]
+		);
+		assert_eq!(
+			t.search_fuzzy(&tx, "recieve", 2, 10).await.unwrap(),
+			vec![(0, 7, 1), (3, 7, 1), (1, 7, 2), (2, 6, 2), (4, 7, 2)]
+		);
+		assert_eq!(t.search_fuzzy(&tx, "receive", 2, 2).await.unwrap(), vec![(0, 7, 0), (1, 7, 1)]);
+		assert!(t.search_fuzzy(&tx, "unknown", 2, 10).await.unwrap().is_empty());
+		tx.cancel().await.unwrap();
+	}
+
+	#[test(tokio::test)]
+	async fn test_search_fuzzy_pruned_walk() {
+		let ds = Datastore::new("memory").await.unwrap();
+		let terms: Vec<String> = (0..300).map(|i| format!("t{}", i * 7 % 1000)).collect();
+		{
+			let (tx, mut t) = new_operation(&ds, 3, Write).await;
+			for term in &terms {
+				t.resolve_term_id(&tx, term).await.unwrap();
+			}
+			finish(tx, t).await;
+		}
+		let (tx, t) = new_operation(&ds, 3, Read).await;
+		// The pruned walk of a deep tree finds the same terms as an exhaustive search
+		for (query, distance) in [("t42", 1), ("t7", 1), ("t999", 2), ("x1", 1)] {
+			let res = t.search_fuzzy(&tx, query, distance, usize::MAX).await.unwrap();
+			let mut expected: Vec<_> = terms
+				.iter()
+				.enumerate()
+				.filter(|(_, term)| strsim::osa_distance(query, term) <= distance as usize)
+				.map(|(i, _)| i as TermId)
+				.collect();
+			expected.sort();
+			let mut res: Vec<_> = res.into_iter().map(|(term_id, _, _)| term_id).collect();
+			res.sort();
+			assert_eq!(res, expected, "{query}");
+		}
+		tx.cancel().await.unwrap();
+	}
+
+	#[test]
+	fn test_prefix_distance() {
+		let term: Vec<char> = "recieve".chars().collect();
+		assert_eq!(prefix_distance(b"", &term, 1), 0);
+		assert_eq!(prefix_distance(b"rec", &term, 1), 0);
+		// A transposition counts as a single edit
+		assert_eq!(prefix_distance(b"recei", &term, 1), 1);
+		assert_eq!(prefix_distance(b"bel", &term, 1), 2);
+		// The bound is exceeded as soon as the prefix is too long
+		assert_eq!(prefix_distance(b"receiverless", &term, 1), 2);
+		// A prefix ending in the middle of a character
+		assert_eq!(prefix_distance(&"ré".as_bytes()[..2], &term, 1), 0);
+	}
+
 	#[test(tokio::test)]
 	async fn test_deletion() {
 		const BTREE_ORDER: u32 = 7;
@@ -2,6 +2,7 @@ pub mod docids;
 pub(crate) mod ft;
 pub(crate) mod index;
 pub mod planner;
+pub(crate) mod statistics;
 pub mod trees;
 
 use crate::err::Error;
@@ -27,6 +28,7 @@ use crate::key::index::hi::Hi;
 use crate::key::index::hl::Hl;
 use crate::key::index::hs::Hs;
 use crate::key::index::hv::Hv;
+use crate::key::index::is::Is;
 use crate::key::index::vm::Vm;
 use crate::kvs::{Key, KeyEncode as _, Val};
 use crate::sql::statements::DefineIndexStatement;
@@ -250,6 +252,16 @@ impl IndexKeyBase {
 		.encode()
 	}
 
+	fn new_is_key(&self) -> Result<Key, Error> {
+		Is::new(
+			self.inner.ns.as_str(),
+			self.inner.db.as_str(),
+			self.inner.tb.as_str(),
+			self.inner.ix.as_str(),
+		)
+		.encode()
+	}
+
 	fn new_vm_key(&self, node_id: Option<NodeId>) -> Result<Key, Error> {
 		Vm::new(
 			self.inner.ns.as_str(),
@@ -0,0 +1,297 @@
+//! Cost-based estimations, relying on the statistics persisted by `ANALYZE INDEX`.
+use crate::err::Error;
+use crate::idx::planner::executor::QueryExecutor;
+use crate::idx::planner::plan::{IndexOperator, IndexOption, RangeValue, UnionRangeQueryBuilder};
+use crate::idx::planner::tree::IndexReference;
+use crate::idx::planner::{ScanDirection, StatementContext};
+use crate::idx::statistics::{IdxStatistics, IndexStatistics};
+use crate::idx::IndexKeyBase;
+use crate::key::thing;
+use crate::sql::index::Index;
+use crate::sql::{Array, Object, Value};
+use futures::StreamExt;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+/// The cost of reading a record while scanning the table
+const TABLE_RECORD_COST: f64 = 1.0;
+/// The cost of positioning an index scan on a key
+const INDEX_SEEK_COST: f64 = 1.0;
+/// The cost of reading an entry while scanning an index
+const INDEX_ENTRY_COST: f64 = 0.2;
+/// The cost of fetching a record referenced by an index entry
+const RECORD_FETCH_COST: f64 = 2.0;
+/// The selectivity of an equality on an index without statistics
+const DEFAULT_EQUALITY_SELECTIVITY: f64 = 0.01;
+/// The selectivity of a range on an index without statistics
+const DEFAULT_RANGE_SELECTIVITY: f64 = 0.33;
+/// The statistics are stale once the table has grown or shrunk by this factor
+const STALE_STATISTICS_FACTOR: usize = 2;
+
+/// Estimates the number of records and the cost of the candidate plans.
+pub(super) struct CostEstimator {
+	/// The statistics of the standard and unique indexes, by index name
+	stats: HashMap<String, IdxStatistics>,
+	/// The estimated number of records in the table
+	table_rows: f64,
+	/// The table
+	tb: String,
+}
+
+impl CostEstimator {
+	/// Loads the statistics of the indexes of a table.
+	/// Returns `None` if no index of the table has been analyzed,
+	/// or if the size of the table is unknown.
+	pub(super) async fn new(ctx: &StatementContext<'_>, tb: &str) -> Result<Option<Self>, Error> {
+		let tx = ctx.ctx.tx();
+		let mut stats = HashMap::new();
+		let mut records = None;
+		let mut entries = None;
+		for ix in tx.all_tb_indexes(ctx.ns, ctx.db, tb).await?.iter() {
+			if !matches!(ix.index, Index::Idx | Index::Uniq) {
+				continue;
+			}
+			let ikb = IndexKeyBase::new(ctx.ns, ctx.db, ix)?;
+			if let Some(IndexStatistics::Idx(s)) = IndexStatistics::get(&tx, &ikb).await? {
+				if let Some(r) = s.records {
+					records = records.max(Some(r));
+				} else if matches!(ix.index, Index::Idx) {
+					// The statistics of a previous version don't count the records.
+					// Every record has an entry in a standard index, but not in a unique index,
+					// which has no entry for the NONE and NULL values.
+					entries = entries.max(Some(s.entries));
+				}
+				stats.insert(ix.name.to_raw(), s);
+			}
+		}
+		let Some(table_rows) = records.or(entries) else {
+			return Ok(None);
+		};
+		Ok(Some(Self {
+			stats,
+			table_rows: table_rows as f64,
+			tb: tb.to_owned(),
+		}))
+	}
+
+	/// Checks the size of the table is still close to the size it had when it was analyzed.
+	/// The keys are counted up to a bound proportional to the analyzed size,
+	/// so the check reads fewer keys than the table scan it validates.
+	pub(super) async fn is_current(&self, ctx: &StatementContext<'_>) -> Result<bool, Error> {
+		let rows = self.table_rows as usize;
+		let max = rows * STALE_STATISTICS_FACTOR;
+		let beg = thing::prefix(ctx.ns, ctx.db, &self.tb)?;
+		let end = thing::suffix(ctx.ns, ctx.db, &self.tb)?;
+		let tx = ctx.ctx.tx();
+		let mut stream = tx.stream_keys(beg..end, Some(max + 1), ScanDirection::Forward);
+		let mut count = 0;
+		while let Some(res) = stream.next().await {
+			res?;
+			count += 1;
+		}
+		Ok(count <= max && count * STALE_STATISTICS_FACTOR >= rows)
+	}
+
+	pub(super) fn table_scan(&self) -> Estimation {
+		Estimation {
+			rows: self.table_rows,
+			cost: self.table_rows * TABLE_RECORD_COST,
+		}
+	}
+
+	/// Estimates an equality or a union on a standard or unique index.
+	/// Returns `None` if the index option can't be estimated.
+	pub(super) fn index_scan(&self, io: &IndexOption) -> Option<Estimation> {
+		let (entries, seeks) = self.index_entries(io)?;
+		Some(Estimation {
+			rows: entries,
+			cost: seeks * INDEX_SEEK_COST + entries * (INDEX_ENTRY_COST + RECORD_FETCH_COST),
+		})
+	}
+
+	/// Estimates the intersection of several index scans,
+	/// assuming the conditions are independent.
+	pub(super) fn index_intersection(&self, ios: &[&IndexOption]) -> Option<Estimation> {
+		let mut selectivity = 1.0;
+		let mut cost = 0.0;
+		for io in ios {
+			let (entries, seeks) = self.index_entries(io)?;
+			selectivity *= self.selectivity(entries);
+			cost += seeks * INDEX_SEEK_COST + entries * INDEX_ENTRY_COST;
+		}
+		let rows = self.table_rows * selectivity;
+		Some(Estimation {
+			rows,
+			cost: cost + rows * RECORD_FETCH_COST,
+		})
+	}
+
+	/// Estimates a range scan on a standard or unique index.
+	pub(super) fn range_scan(
+		&self,
+		ixr: &IndexReference,
+		rq: &UnionRangeQueryBuilder,
+	) -> Option<Estimation> {
+		if !matches!(ixr.index, Index::Idx | Index::Uniq) {
+			return None;
+		}
+		let entries = match self.stats.get(&ixr.name.to_raw()) {
+			Some(s) => Self::estimate_range(s, &rq.from, &rq.to),
+			None => self.table_rows * DEFAULT_RANGE_SELECTIVITY,
+		};
+		Some(Estimation {
+			rows: entries,
+			cost: INDEX_SEEK_COST + entries * (INDEX_ENTRY_COST + RECORD_FETCH_COST),
+		})
+	}
+
+	/// Estimates the union of several index scans
+	pub(super) fn union(&self, estimations: &[Estimation]) -> Estimation {
+		let rows: f64 = estimations.iter().map(|e| e.rows).sum();
+		Estimation {
+			rows: rows.min(self.table_rows),
+			cost: estimations.iter().map(|e| e.cost).sum(),
+		}
+	}
+
+	fn selectivity(&self, rows: f64) -> f64 {
+		if self.table_rows > 0.0 {
+			(rows / self.table_rows).min(1.0)
+		} else {
+			0.0
+		}
+	}
+
+	/// Returns the estimated number of index entries, and the number of seeks
+	fn index_entries(&self, io: &IndexOption) -> Option<(f64, f64)> {
+		let ixr = io.ix_ref();
+		let unique = match ixr.index {
+			Index::Idx => false,
+			Index::Uniq => true,
+			_ => return None,
+		};
+		let variants = match io.op() {
+			IndexOperator::Equality(v) => QueryExecutor::get_equal_variants_from_value(v),
+			IndexOperator::Union(v) => QueryExecutor::get_equal_variants_from_values(v),
+			_ => return None,
+		};
+		let stats = self.stats.get(&ixr.name.to_raw());
+		let mut entries = 0.0;
+		for fd in &variants {
+			let e = match stats {
+				Some(s) => Self::estimate_equal(s, fd),
+				None => self.table_rows * DEFAULT_EQUALITY_SELECTIVITY,
+			};
+			// A complete key matches at most one record on a unique index
+			entries += if unique && fd.len() == ixr.cols.len() {
+				e.min(1.0)
+			} else {
+				e
+			};
+		}
+		Some((entries, variants.len() as f64))
+	}
+
+	/// Estimates the number of entries matching a key (or the prefix of a key),
+	/// assuming the values of a histogram bucket are uniformly distributed.
+	fn estimate_equal(stats: &IdxStatistics, fd: &Array) -> f64 {
+		let mut entries = 0.0;
+		for b in &stats.histogram {
+			if Self::compare(&b.min, fd) == Ordering::Greater {
+				break;
+			}
+			if Self::compare(&b.max, fd) != Ordering::Less {
+				entries += b.count as f64 / b.distinct.max(1) as f64;
+			}
+		}
+		entries
+	}
+
+	/// Estimates the number of entries whose first value is within a range.
+	/// Buckets partially covered by the range are counted by half.
+	fn estimate_range(stats: &IdxStatistics, from: &RangeValue, to: &RangeValue) -> f64 {
+		let from = Array::from(vec![from.value.clone()]);
+		let to = Array::from(vec![to.value.clone()]);
+		let mut entries = 0.0;
+		for b in &stats.histogram {
+			let after_from = from[0].is_none() || Self::compare(&b.min, &from) != Ordering::Less;
+			let before_to = to[0].is_none() || Self::compare(&b.max, &to) != Ordering::Greater;
+			if after_from && before_to {
+				entries += b.count as f64;
+				continue;
+			}
+			let overlaps = (from[0].is_none() || Self::compare(&b.max, &from) != Ordering::Less)
+				&& (to[0].is_none() || Self::compare(&b.min, &to) != Ordering::Greater);
+			if overlaps {
+				entries += b.count as f64 / 2.0;
+			}
+		}
+		entries
+	}
+
+	/// Compares two keys the same way they are ordered in the index,
+	/// considering only the columns both keys have.
+	fn compare(a: &Array, b: &Array) -> Ordering {
+		for (a, b) in a.iter().zip(b.iter()) {
+			let (Ok(a), Ok(b)) = (storekey::serialize(a), storekey::serialize(b)) else {
+				return Ordering::Equal;
+			};
+			match a.cmp(&b) {
+				Ordering::Equal => continue,
+				o => return o,
+			}
+		}
+		Ordering::Equal
+	}
+}
+
+#[derive(Clone, Copy, Debug)]
+pub(super) struct Estimation {
+	/// The estimated number of records
+	pub(super) rows: f64,
+	/// The estimated cost
+	pub(super) cost: f64,
+}
+
+/// The candidate plans which have been evaluated, reported by `EXPLAIN`
+#[derive(Default)]
+pub(super) struct PlanCosts {
+	candidates: Vec<(String, Estimation)>,
+	chosen: usize,
+}
+
+impl PlanCosts {
+	pub(super) fn add(&mut self, plan: String, e: Estimation) {
+		self.candidates.push((plan, e));
+	}
+
+	/// Chooses the cheapest candidate.
+	/// On equal costs, the first candidate is preferred.
+	pub(super) fn choose(&mut self) -> usize {
+		let mut chosen = 0;
+		for (i, (_, e)) in self.candidates.iter().enumerate() {
+			if e.cost < self.candidates[chosen].1.cost {
+				chosen = i;
+			}
+		}
+		self.chosen = chosen;
+		chosen
+	}
+
+	pub(super) fn explain(&self) -> Value {
+		let candidates: Vec<Value> = self
+			.candidates
+			.iter()
+			.enumerate()
+			.map(|(i, (plan, e))| {
+				Value::from(Object::from(HashMap::from([
+					("plan", Value::from(plan.as_str())),
+					("rows", Value::from(e.rows.round() as i64)),
+					("cost", Value::from((e.cost * 100.0).round() / 100.0)),
+					("chosen", Value::from(i == self.chosen)),
+				])))
+			})
+			.collect();
+		Value::from(candidates)
+	}
+}
@@ -3,17 +3,18 @@ use crate::dbs::Options;
 use crate::doc::CursorDoc;
 use crate::err::Error;
 use crate::idx::docids::DocIds;
-use crate::idx::ft::analyzer::{Analyzer, TermsList, TermsSet};
+use crate::idx::ft::analyzer::Analyzer;
 use crate::idx::ft::highlighter::HighlightParams;
+use crate::idx::ft::query::{FtQuery, TermPositions};
 use crate::idx::ft::scorer::BM25Scorer;
-use crate::idx::ft::termdocs::TermsDocs;
 use crate::idx::ft::terms::Terms;
 use crate::idx::ft::{FtIndex, MatchRef};
 use crate::idx::planner::checker::{HnswConditionChecker, MTreeConditionChecker};
+use crate::idx::planner::hybrid::{Fusion, HybridRanking};
 use crate::idx::planner::iterators::{
 	IndexEqualThingIterator, IndexJoinThingIterator, IndexRangeThingIterator,
-	IndexUnionThingIterator, IteratorRange, IteratorRecord, IteratorRef, KnnIterator,
-	KnnIteratorResult, MatchesThingIterator, MultipleIterators, ThingIterator,
+	IndexUnionThingIterator, IntersectionIterators, IteratorRange, IteratorRecord, IteratorRef,
+	KnnIterator, KnnIteratorResult, MatchesThingIterator, MultipleIterators, ThingIterator,
 	UniqueEqualThingIterator, UniqueJoinThingIterator, UniqueRangeThingIterator,
 	UniqueUnionThingIterator, ValueType,
 };
@@ -79,6 +80,7 @@ pub(super) struct InnerQueryExecutor {
 	mt_entries: HashMap<Arc<Expression>, MtEntry>,
 	hnsw_entries: HashMap<Arc<Expression>, HnswEntry>,
 	knn_bruteforce_entries: HashMap<Arc<Expression>, KnnBruteForceEntry>,
+	hybrid_rankings: RwLock<HashMap<MatchRef, Arc<HybridRanking>>>,
 }
 
 impl From<InnerQueryExecutor> for QueryExecutor {
@@ -90,6 +92,7 @@ impl From<InnerQueryExecutor> for QueryExecutor {
 pub(super) enum IteratorEntry {
 	Single(Option<Arc<Expression>>, IndexOption),
 	Range(HashSet<Arc<Expression>>, IndexReference, RangeValue, RangeValue),
+	Intersect(Vec<(Arc<Expression>, IndexOption)>),
 }
 
 impl IteratorEntry {
@@ -103,6 +106,15 @@ impl IteratorEntry {
 				e.insert("to", Value::from(to));
 				Value::from(Object::from(e))
 			}
+			Self::Intersect(ios) => {
+				let mut e = HashMap::default();
+				e.insert("operator", Value::from("intersection"));
+				e.insert(
+					"indexes",
+					Value::from(ios.iter().map(|(_, io)| io.explain()).collect::<Vec<_>>()),
+				);
+				Value::from(Object::from(e))
+			}
 		}
 	}
 }
@@ -155,7 +167,7 @@ impl InnerQueryExecutor {
 						}
 					};
 					if let Some(e) = ft_entry {
-						if let Matches(_, Some(mr)) = e.0.index_option.op() {
+						if let Matches(_, _, Some(mr)) = e.0.index_option.op() {
 							if mr_entries.insert(*mr, e.clone()).is_some() {
 								return Err(Error::DuplicatedMatchRef {
 									mr: *mr,
@@ -247,6 +259,7 @@ impl InnerQueryExecutor {
 			mt_entries,
 			hnsw_entries,
 			knn_bruteforce_entries,
+			hybrid_rankings: Default::default(),
 		})
 	}
 
@@ -267,6 +280,14 @@ impl QueryExecutor {
 		doc: Option<&CursorDoc>,
 		exp: &Expression,
 	) -> Result<Value, Error> {
+		// The records of an indexed KNN search may be iterated by another iterator,
+		// such as a table iterator when a phrase can't be resolved by its index
+		if let Some(e) = self.0.mt_entries.get(exp) {
+			return Ok(e.res.iter().any(|(t, _, _)| t.as_ref().eq(thg)).into());
+		}
+		if let Some(e) = self.0.hnsw_entries.get(exp) {
+			return Ok(e.res.iter().any(|(t, _, _)| t.as_ref().eq(thg)).into());
+		}
 		if let Some(IterationStage::Iterate(e)) = ctx.get_iteration_stage() {
 			if let Some(results) = e {
 				return Ok(results.contains(exp, thg).into());
@@ -305,8 +326,13 @@ impl QueryExecutor {
 	/// Returns `true` if the expression is matching the current iterator.
 	pub(crate) fn is_iterator_expression(&self, ir: IteratorRef, exp: &Expression) -> bool {
 		match self.0.it_entries.get(ir) {
-			Some(IteratorEntry::Single(Some(e), ..)) => exp.eq(e.as_ref()),
+			// Phrases are verified on the records
+			Some(IteratorEntry::Single(Some(e), ..)) => {
+				exp.eq(e.as_ref())
+					&& !self.0.exp_entries.get(exp).is_some_and(|e| e.0.query.has_phrases())
+			}
 			Some(IteratorEntry::Range(es, ..)) => es.contains(exp),
+			Some(IteratorEntry::Intersect(ios)) => ios.iter().any(|(e, _)| exp.eq(e.as_ref())),
 			_ => false,
 		}
 	}
@@ -338,6 +364,17 @@ impl QueryExecutor {
 				IteratorEntry::Range(_, ixr, from, to) => {
 					Ok(self.new_range_iterator(ir, opt, ixr, from, to)?)
 				}
+				IteratorEntry::Intersect(ios) => {
+					let mut iterators = VecDeque::with_capacity(ios.len());
+					for (_, io) in ios {
+						match self.new_single_iterator(opt, ir, io).await? {
+							Some(it) => iterators.push_back(it),
+							None => return Ok(None),
+						}
+					}
+					let intersection = Box::new(IntersectionIterators::new(iterators));
+					Ok(Some(ThingIterator::Intersection(intersection)))
+				}
 			}
 		} else {
 			Ok(None)
@@ -422,13 +459,13 @@ impl QueryExecutor {
 		})
 	}
 
-	fn get_equal_variants_from_value(value: &Value) -> Vec<Array> {
+	pub(super) fn get_equal_variants_from_value(value: &Value) -> Vec<Array> {
 		let mut variants = Vec::with_capacity(1);
 		Self::generate_variants_from_value(value, &mut variants);
 		variants
 	}
 
-	fn get_equal_variants_from_values(values: &Value) -> Vec<Array> {
+	pub(super) fn get_equal_variants_from_values(values: &Value) -> Vec<Array> {
 		if let Value::Array(a) = values {
 			let mut variants = Vec::with_capacity(a.len());
 			for v in &a.0 {
@@ -904,11 +941,10 @@ impl QueryExecutor {
 		io: IndexOption,
 	) -> Result<Option<ThingIterator>, Error> {
 		if let Some(IteratorEntry::Single(Some(exp), ..)) = self.0.it_entries.get(ir) {
-			if let Matches(_, _) = io.op() {
+			if let Matches(..) = io.op() {
 				if let Some(fti) = self.0.ft_map.get(io.ix_ref()) {
 					if let Some(fte) = self.0.exp_entries.get(exp) {
-						let it =
-							MatchesThingIterator::new(ir, fti, fte.0.terms_docs.clone()).await?;
+						let it = MatchesThingIterator::new(ir, fti, &fte.0.query);
 						return Ok(Some(ThingIterator::Matches(it)));
 					}
 				}
@@ -959,6 +995,7 @@ impl QueryExecutor {
 		ctx: &Context,
 		opt: &Options,
 		thg: &Thing,
+		doc: Option<&CursorDoc>,
 		exp: &Expression,
 		l: Value,
 		r: Value,
@@ -966,7 +1003,7 @@ impl QueryExecutor {
 		if let Some(ft) = self.0.exp_entries.get(exp) {
 			let ix = ft.0.index_option.ix_ref();
 			if self.0.table.eq(&ix.what.0) {
-				return self.matches_with_doc_id(ctx, thg, ft).await;
+				return self.matches_with_doc_id(stk, ctx, opt, thg, doc, ft, l, r).await;
 			}
 			return self.matches_with_value(stk, ctx, opt, ft, l, r).await;
 		}
@@ -977,35 +1014,45 @@ impl QueryExecutor {
 		})
 	}
 
+	#[expect(clippy::too_many_arguments)]
 	async fn matches_with_doc_id(
 		&self,
+		stk: &mut Stk,
 		ctx: &Context,
+		opt: &Options,
 		thg: &Thing,
+		doc: Option<&CursorDoc>,
 		ft: &FtEntry,
+		l: Value,
+		r: Value,
 	) -> Result<bool, Error> {
-		// TODO ask emmanual
 		let doc_key = revision::to_vec(thg)?;
 		let tx = ctx.tx();
 		let di = ft.0.doc_ids.read().await;
 		let doc_id = di.get_doc_id(&tx, doc_key).await?;
 		drop(di);
 		if let Some(doc_id) = doc_id {
-			let term_goals = ft.0.terms_docs.len();
-			// If there is no terms, it can't be a match
-			if term_goals == 0 {
-				return Ok(false);
+			if let Some(matches) = ft.0.query.matches_stored(&tx, doc_id).await? {
+				return Ok(matches);
 			}
-			for opt_td in ft.0.terms_docs.iter() {
-				if let Some((_, docs)) = opt_td {
-					if !docs.contains(doc_id) {
-						return Ok(false);
+			// The positions are not stored for the documents indexed by a previous version,
+			// the phrases are then verified on the positions of the terms in the record
+			let positions = if ft.0.query.has_phrases() {
+				let value = match doc {
+					// A multi-field index indexed the values of every field
+					Some(doc) if ft.0.index_option.ix_ref().cols.len() > 1 => {
+						Some(Self::indexed_values(stk, ctx, opt, ft, doc).await?)
 					}
-				} else {
-					// If one of the term is missing, it can't be a match
-					return Ok(false);
+					_ => Self::matched_value(ft, l, r),
+				};
+				match value {
+					Some(v) => Some(self.extract_term_positions(stk, ctx, opt, ft, v).await?),
+					None => return Ok(false),
 				}
-			}
-			return Ok(true);
+			} else {
+				None
+			};
+			return Ok(ft.0.query.matches_doc_id(doc_id, positions.as_ref()));
 		}
 		Ok(false)
 	}
@@ -1019,22 +1066,50 @@ impl QueryExecutor {
 		l: Value,
 		r: Value,
 	) -> Result<bool, Error> {
-		// If the query terms contains terms that are unknown in the index
-		// of if there are no terms in the query
-		// we are sure that it does not match any document
-		if !ft.0.query_terms_set.is_matchable() {
+		let Some(v) = Self::matched_value(ft, l, r) else {
 			return Ok(false);
-		}
-		let v = match ft.0.index_option.id_pos() {
-			IdiomPosition::Left => r,
-			IdiomPosition::Right => l,
-			IdiomPosition::None => return Ok(false),
 		};
+		let positions = self.extract_term_positions(stk, ctx, opt, ft, v).await?;
+		Ok(ft.0.query.matches_terms(&positions))
+	}
+
+	/// Returns the operand of the matches operator which is the indexed field
+	fn matched_value(ft: &FtEntry, l: Value, r: Value) -> Option<Value> {
+		match ft.0.index_option.id_pos() {
+			IdiomPosition::Left => Some(l),
+			IdiomPosition::Right => Some(r),
+			IdiomPosition::None => None,
+		}
+	}
+
+	/// Returns the values of every field of the index, as they are indexed
+	async fn indexed_values(
+		stk: &mut Stk,
+		ctx: &Context,
+		opt: &Options,
+		ft: &FtEntry,
+		doc: &CursorDoc,
+	) -> Result<Value, Error> {
+		let cols = &ft.0.index_option.ix_ref().cols;
+		let mut values = Vec::with_capacity(cols.len());
+		for col in cols.iter() {
+			values.push(col.compute(stk, ctx, opt, Some(doc)).await.catch_return()?);
+		}
+		Ok(Value::from(values))
+	}
+
+	async fn extract_term_positions(
+		&self,
+		stk: &mut Stk,
+		ctx: &Context,
+		opt: &Options,
+		ft: &FtEntry,
+		v: Value,
+	) -> Result<TermPositions, Error> {
 		let terms = ft.0.terms.read().await;
-		// Extract the terms set from the record
-		let t = ft.0.analyzer.extract_indexing_terms(stk, ctx, opt, &terms, v).await?;
+		let res = ft.0.analyzer.extract_term_positions(stk, ctx, opt, &terms, v).await;
 		drop(terms);
-		Ok(ft.0.query_terms_set.is_subset(&t))
+		res
 	}
 
 	fn get_ft_entry(&self, match_ref: &Value) -> Option<&FtEntry> {
@@ -1063,8 +1138,15 @@ impl QueryExecutor {
 	) -> Result<Value, Error> {
 		if let Some((e, ft)) = self.get_ft_entry_and_index(hlp.match_ref()) {
 			if let Some(id) = e.0.index_option.id_ref() {
+				// A multi-field index highlights every field
+				let cols = &e.0.index_option.ix_ref().cols;
+				let idioms = if cols.len() > 1 {
+					cols.as_slice()
+				} else {
+					std::slice::from_ref(id)
+				};
 				let tx = ctx.tx();
-				let res = ft.highlight(&tx, thg, &e.0.query_terms_list, hlp, id, doc).await;
+				let res = ft.highlight(&tx, thg, e.0.query.terms_list(), hlp, idioms, doc).await;
 				return res;
 			}
 		}
@@ -1080,7 +1162,7 @@ impl QueryExecutor {
 	) -> Result<Value, Error> {
 		if let Some((e, ft)) = self.get_ft_entry_and_index(&match_ref) {
 			let tx = ctx.tx();
-			let res = ft.extract_offsets(&tx, thg, &e.0.query_terms_list, partial).await;
+			let res = ft.extract_offsets(&tx, thg, e.0.query.terms_list(), partial).await;
 			return res;
 		}
 		Ok(Value::None)
@@ -1117,6 +1199,87 @@ impl QueryExecutor {
 		}
 		Ok(Value::None)
 	}
+
+	/// Returns the score of a record fusing the ranking of the full-text search of the match
+	/// reference with the rankings of the KNN searches of the query.
+	pub(crate) async fn hybrid_score(
+		&self,
+		ctx: &Context,
+		match_ref: &Value,
+		rid: &Thing,
+		fusion: Fusion,
+	) -> Result<Value, Error> {
+		let Some(mr) = Self::get_match_ref(match_ref) else {
+			return Ok(Value::None);
+		};
+		let cached = self.0.hybrid_rankings.read().await.get(&mr).cloned();
+		let ranking = if let Some(ranking) = cached {
+			ranking
+		} else {
+			// The rankings are built once, on the first record
+			let mut rankings = self.0.hybrid_rankings.write().await;
+			match rankings.entry(mr) {
+				Entry::Occupied(e) => e.get().clone(),
+				Entry::Vacant(e) => {
+					let Some(ranking) = self.build_hybrid_ranking(ctx, mr).await? else {
+						return Ok(Value::None);
+					};
+					e.insert(Arc::new(ranking)).clone()
+				}
+			}
+		};
+		Ok(ranking.fuse(rid, &fusion).into())
+	}
+
+	async fn build_hybrid_ranking(
+		&self,
+		ctx: &Context,
+		mr: MatchRef,
+	) -> Result<Option<HybridRanking>, Error> {
+		let Some(e) = self.0.mr_entries.get(&mr) else {
+			return Ok(None);
+		};
+		let Some(scorer) = &e.0.scorer else {
+			return Ok(None);
+		};
+		// Score every record matching the full-text search
+		let tx = ctx.tx();
+		let hits = e.0.query.hits();
+		let mut scores = Vec::with_capacity(hits.len() as usize);
+		let di = e.0.doc_ids.read().await;
+		for doc_id in hits {
+			// The candidates of the phrases are verified on the stored positions.
+			// The documents indexed without positions can only be verified by a rebuild.
+			if e.0.query.has_phrases()
+				&& e.0.query.matches_stored(&tx, doc_id).await? == Some(false)
+			{
+				continue;
+			}
+			if let Some(doc_key) = di.get_doc_key(&tx, doc_id).await? {
+				if let Some(score) = scorer.score(&tx, doc_id).await? {
+					scores.push((Arc::new(revision::from_slice(&doc_key)?), score as f64));
+				}
+			}
+		}
+		drop(di);
+		// The KNN searches are already executed
+		let distances = self
+			.knn_results()
+			.map(|res| res.iter().map(|(thg, dist, _)| (thg.clone(), *dist)).collect())
+			.collect();
+		Ok(Some(HybridRanking::new(scores, distances)))
+	}
+
+	/// Returns the distance of a record found by a KNN search backed by an index
+	pub(crate) fn knn_distance(&self, rid: &Thing) -> Option<f64> {
+		self.knn_results().flatten().find(|(thg, _, _)| thg.as_ref().eq(rid)).map(|(_, d, _)| *d)
+	}
+
+	fn knn_results(&self) -> impl Iterator<Item = &VecDeque<KnnIteratorResult>> {
+		let mt = self.0.mt_entries.values().map(|e| &e.res);
+		let hnsw = self.0.hnsw_entries.values().map(|e| &e.res);
+		mt.chain(hnsw)
+	}
 }
 
 #[derive(Clone)]
@@ -1126,10 +1289,8 @@ struct Inner {
 	index_option: IndexOption,
 	doc_ids: Arc<RwLock<DocIds>>,
 	analyzer: Analyzer,
-	query_terms_set: TermsSet,
-	query_terms_list: TermsList,
+	query: FtQuery,
 	terms: Arc<RwLock<Terms>>,
-	terms_docs: TermsDocs,
 	scorer: Option<BM25Scorer>,
 }
 
@@ -1141,21 +1302,15 @@ impl FtEntry {
 		ft: &FtIndex,
 		io: IndexOption,
 	) -> Result<Option<Self>, Error> {
-		if let Matches(qs, _) = io.op() {
-			let (terms_list, terms_set) =
-				ft.extract_querying_terms(stk, ctx, opt, qs.to_owned()).await?;
-			let tx = ctx.tx();
-			let terms_docs = Arc::new(ft.get_terms_docs(&tx, &terms_list).await?);
-			drop(tx);
+		if let Matches(_, q, _) = io.op() {
+			let query = ft.new_query(stk, ctx, opt, q).await?;
 			Ok(Some(Self(Arc::new(Inner {
 				index_option: io,
 				doc_ids: ft.doc_ids(),
 				analyzer: ft.analyzer(),
-				query_terms_set: terms_set,
-				query_terms_list: terms_list,
-				scorer: ft.new_scorer(terms_docs.clone())?,
+				scorer: ft.new_scorer(&query)?,
 				terms: ft.terms(),
-				terms_docs,
+				query,
 			}))))
 		} else {
 			Ok(None)
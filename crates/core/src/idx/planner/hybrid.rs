@@ -0,0 +1,145 @@
+use crate::sql::Thing;
+use ahash::{HashMap, HashMapExt};
+use std::cmp::Ordering;
+use std::sync::Arc;
+
+/// The default ranking constant of the reciprocal rank fusion
+pub(crate) const RRF_DEFAULT_K: f64 = 60.0;
+
+/// The default weight of the full-text score in a linear fusion
+pub(crate) const LINEAR_DEFAULT_WEIGHT: f64 = 0.5;
+
+/// How the rankings of a hybrid search are combined
+pub(crate) enum Fusion {
+	/// Reciprocal rank fusion: the sum of `1 / (k + rank)` over every ranking
+	Rrf(f64),
+	/// Weighted sum of the normalised scores, the weight being the one of the full-text score
+	Linear(f64),
+}
+
+/// A list of records ordered by relevance.
+struct Ranking(HashMap<Arc<Thing>, Ranked>);
+
+struct Ranked {
+	/// The position in the ranking, starting at 1
+	rank: usize,
+	/// The score min-max normalised between 0 (least relevant) and 1 (most relevant)
+	score: f64,
+}
+
+impl Ranking {
+	/// Ranks the records by score, the highest score being the most relevant
+	fn by_score(records: Vec<(Arc<Thing>, f64)>) -> Self {
+		Self::new(records, |a, b| b.total_cmp(a))
+	}
+
+	/// Ranks the records by distance, the smallest distance being the most relevant
+	fn by_distance(records: Vec<(Arc<Thing>, f64)>) -> Self {
+		Self::new(records, f64::total_cmp)
+	}
+
+	fn new(mut records: Vec<(Arc<Thing>, f64)>, cmp: fn(&f64, &f64) -> Ordering) -> Self {
+		// The ids break the ties, so the ranks are deterministic
+		records.sort_by(|(t1, v1), (t2, v2)| cmp(v1, v2).then_with(|| t1.cmp(t2)));
+		let (best, worst) = match (records.first(), records.last()) {
+			(Some((_, b)), Some((_, w))) => (*b, *w),
+			_ => (0.0, 0.0),
+		};
+		let mut ranking = HashMap::with_capacity(records.len());
+		for (i, (thg, v)) in records.into_iter().enumerate() {
+			let score = if best == worst {
+				1.0
+			} else {
+				(v - worst) / (best - worst)
+			};
+			ranking.insert(
+				thg,
+				Ranked {
+					rank: i + 1,
+					score,
+				},
+			);
+		}
+		Self(ranking)
+	}
+}
+
+/// The ranking of a full-text search and the rankings of the KNN searches of a query.
+pub(super) struct HybridRanking {
+	fulltext: Ranking,
+	vectors: Vec<Ranking>,
+}
+
+impl HybridRanking {
+	/// `scores` are the scores of the full-text matches,
+	/// `distances` are the results of each KNN search.
+	pub(super) fn new(
+		scores: Vec<(Arc<Thing>, f64)>,
+		distances: Vec<Vec<(Arc<Thing>, f64)>>,
+	) -> Self {
+		Self {
+			fulltext: Ranking::by_score(scores),
+			vectors: distances.into_iter().map(Ranking::by_distance).collect(),
+		}
+	}
+
+	/// Returns the fused score of a record.
+	/// A record missing from a ranking gets nothing from it.
+	pub(super) fn fuse(&self, thg: &Thing, fusion: &Fusion) -> f64 {
+		match fusion {
+			Fusion::Rrf(k) => self
+				.rankings()
+				.filter_map(|r| r.0.get(thg))
+				.fold(0.0, |s, r| s + 1.0 / (k + r.rank as f64)),
+			Fusion::Linear(weight) => {
+				let fulltext = self.fulltext.0.get(thg).map(|r| r.score).unwrap_or(0.0);
+				// Several KNN searches share the vector weight
+				let vectors = if self.vectors.is_empty() {
+					0.0
+				} else {
+					let sum: f64 =
+						self.vectors.iter().filter_map(|v| v.0.get(thg)).map(|r| r.score).sum();
+					sum / self.vectors.len() as f64
+				};
+				weight * fulltext + (1.0 - weight) * vectors
+			}
+		}
+	}
+
+	fn rankings(&self) -> impl Iterator<Item = &Ranking> {
+		std::iter::once(&self.fulltext).chain(self.vectors.iter())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::idx::planner::hybrid::{Fusion, HybridRanking};
+	use crate::sql::{Id, Thing};
+	use std::sync::Arc;
+
+	fn thg(id: i64) -> Arc<Thing> {
+		Arc::new(Thing::from(("t", Id::from(id))))
+	}
+
+	#[test]
+	fn test_hybrid_fusion() {
+		let h = HybridRanking::new(
+			vec![(thg(1), 3.0), (thg(2), 2.0), (thg(3), 1.0)],
+			vec![vec![(thg(3), 1.0), (thg(4), 2.0), (thg(1), 3.0)]],
+		);
+		// Reciprocal rank fusion
+		let rrf = Fusion::Rrf(60.0);
+		assert_eq!(h.fuse(&thg(1), &rrf), 1.0 / 61.0 + 1.0 / 63.0);
+		assert_eq!(h.fuse(&thg(2), &rrf), 1.0 / 62.0);
+		assert_eq!(h.fuse(&thg(3), &rrf), 1.0 / 63.0 + 1.0 / 61.0);
+		assert_eq!(h.fuse(&thg(4), &rrf), 1.0 / 62.0);
+		assert_eq!(h.fuse(&thg(5), &rrf), 0.0);
+		// Linear fusion of the normalised scores
+		let linear = Fusion::Linear(0.75);
+		assert_eq!(h.fuse(&thg(1), &linear), 0.75);
+		assert_eq!(h.fuse(&thg(2), &linear), 0.375);
+		assert_eq!(h.fuse(&thg(3), &linear), 0.25);
+		assert_eq!(h.fuse(&thg(4), &linear), 0.125);
+		assert_eq!(h.fuse(&thg(5), &linear), 0.0);
+	}
+}
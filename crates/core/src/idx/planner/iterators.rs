@@ -2,7 +2,7 @@ use crate::ctx::Context;
 use crate::dbs::Options;
 use crate::err::Error;
 use crate::idx::docids::DocId;
-use crate::idx::ft::termdocs::TermsDocs;
+use crate::idx::ft::query::FtQuery;
 use crate::idx::ft::{FtIndex, HitsIterator};
 use crate::idx::planner::plan::RangeValue;
 use crate::idx::planner::tree::IndexReference;
@@ -14,7 +14,7 @@ use crate::sql::{Array, Ident, Number, Thing, Value};
 use radix_trie::Trie;
 use rust_decimal::Decimal;
 use std::borrow::Cow;
-use std::collections::VecDeque;
+use std::collections::{HashSet, VecDeque};
 use std::ops::Range;
 use std::sync::Arc;
 
@@ -122,6 +122,7 @@ pub(crate) enum ThingIterator {
 	Matches(MatchesThingIterator),
 	Knn(KnnIterator),
 	Multiples(Box<MultipleIterators>),
+	Intersection(Box<IntersectionIterators>),
 }
 
 impl ThingIterator {
@@ -147,6 +148,7 @@ impl ThingIterator {
 			Self::IndexJoin(i) => Box::pin(i.next_batch(ctx, txn, size)).await,
 			Self::UniqueJoin(i) => Box::pin(i.next_batch(ctx, txn, size)).await,
 			Self::Multiples(i) => Box::pin(i.next_batch(ctx, txn, size)).await,
+			Self::Intersection(i) => Box::pin(i.next_batch(ctx, txn, size)).await,
 		}
 	}
 
@@ -172,6 +174,7 @@ impl ThingIterator {
 			Self::IndexJoin(i) => Box::pin(i.next_count(ctx, txn, size)).await,
 			Self::UniqueJoin(i) => Box::pin(i.next_count(ctx, txn, size)).await,
 			Self::Multiples(i) => Box::pin(i.next_count(ctx, txn, size)).await,
+			Self::Intersection(i) => Box::pin(i.next_count(ctx, txn, size)).await,
 		}
 	}
 }
@@ -1393,22 +1396,18 @@ pub(crate) struct MatchesThingIterator {
 }
 
 impl MatchesThingIterator {
-	pub(super) async fn new(
-		irf: IteratorRef,
-		fti: &FtIndex,
-		terms_docs: TermsDocs,
-	) -> Result<Self, Error> {
-		let hits = fti.new_hits_iterator(terms_docs)?;
+	pub(super) fn new(irf: IteratorRef, fti: &FtIndex, query: &FtQuery) -> Self {
+		let hits = fti.new_hits_iterator(query);
 		let hits_left = if let Some(h) = &hits {
 			h.len()
 		} else {
 			0
 		};
-		Ok(Self {
+		Self {
 			irf,
 			hits,
 			hits_left,
-		})
+		}
 	}
 
 	async fn next_batch<B: IteratorBatch>(
@@ -1588,3 +1587,95 @@ impl MultipleIterators {
 		}
 	}
 }
+
+/// Returns the records matched by every iterator.
+/// The records of all the iterators but the last are collected,
+/// then the last iterator is streamed and filtered.
+pub(crate) struct IntersectionIterators {
+	iterators: VecDeque<ThingIterator>,
+	matches: Option<HashSet<Arc<Thing>>>,
+}
+
+impl IntersectionIterators {
+	pub(super) fn new(iterators: VecDeque<ThingIterator>) -> Self {
+		Self {
+			iterators,
+			matches: None,
+		}
+	}
+
+	async fn collect(
+		&mut self,
+		ctx: &Context,
+		txn: &Transaction,
+		limit: u32,
+	) -> Result<HashSet<Arc<Thing>>, Error> {
+		let mut matches: Option<HashSet<Arc<Thing>>> = None;
+		while self.iterators.len() > 1 {
+			let Some(mut i) = self.iterators.pop_front() else {
+				break;
+			};
+			let mut things = HashSet::new();
+			loop {
+				let b: Vec<IndexItemRecord> = i.next_batch(ctx, txn, limit).await?;
+				if b.is_empty() {
+					break;
+				}
+				for r in b {
+					let (t, _, _) = r.consume();
+					if matches.as_ref().is_none_or(|m| m.contains(&t)) {
+						things.insert(t);
+					}
+				}
+			}
+			matches = Some(things);
+		}
+		Ok(matches.unwrap_or_default())
+	}
+
+	async fn next_batch<B: IteratorBatch>(
+		&mut self,
+		ctx: &Context,
+		txn: &Transaction,
+		limit: u32,
+	) -> Result<B, Error> {
+		if self.matches.is_none() {
+			self.matches = Some(self.collect(ctx, txn, limit).await?);
+		}
+		let Some(matches) = &mut self.matches else {
+			return Ok(B::empty());
+		};
+		let Some(last) = self.iterators.front_mut() else {
+			return Ok(B::empty());
+		};
+		loop {
+			if matches.is_empty() {
+				return Ok(B::empty());
+			}
+			let b: Vec<IndexItemRecord> = last.next_batch(ctx, txn, limit).await?;
+			if b.is_empty() {
+				return Ok(B::empty());
+			}
+			let mut res = B::with_capacity(b.len());
+			for r in b {
+				// Each record is returned once
+				if matches.remove(r.thing()) {
+					res.add(r);
+				}
+			}
+			if !res.is_empty() {
+				return Ok(res);
+			}
+		}
+	}
+
+	async fn next_count(
+		&mut self,
+		ctx: &Context,
+		txn: &Transaction,
+		limit: u32,
+	) -> Result<usize, Error> {
+		let b: Vec<IndexItemRecord> = self.next_batch(ctx, txn, limit).await?;
+		Ok(b.len())
+	}
+}
@@ -1,5 +1,7 @@
 pub mod checker;
+pub(in crate::idx) mod cost;
 pub(crate) mod executor;
+pub(crate) mod hybrid;
 pub(crate) mod iterators;
 pub(in crate::idx) mod knn;
 pub(crate) mod plan;
@@ -15,7 +17,7 @@ use crate::idx::planner::knn::KnnBruteForceResults;
 use crate::idx::planner::plan::{Plan, PlanBuilder, PlanBuilderParameters};
 use crate::idx::planner::tree::Tree;
 use crate::sql::with::With;
-use crate::sql::{order::Ordering, Cond, Fields, Groups, Table};
+use crate::sql::{order::Ordering, Cond, Fields, Groups, Table, Value};
 use reblessive::tree::Stk;
 use std::collections::HashMap;
 use std::fmt::{Display, Formatter};
@@ -224,6 +226,8 @@ pub(crate) struct QueryPlanner {
 	executors: HashMap<String, QueryExecutor>,
 	requires_distinct: bool,
 	fallbacks: Vec<String>,
+	/// The estimated costs of the candidate plans, by table
+	costs: Vec<(String, Value)>,
 	iteration_workflow: Vec<IterationStage>,
 	iteration_index: AtomicU8,
 	ordering_indexes: Vec<IteratorRef>,
@@ -237,6 +241,7 @@ impl QueryPlanner {
 			executors: HashMap::default(),
 			requires_distinct: false,
 			fallbacks: vec![],
+			costs: vec![],
 			iteration_workflow: Vec::default(),
 			iteration_index: AtomicU8::new(0),
 			ordering_indexes: vec![],
@@ -301,7 +306,11 @@ impl QueryPlanner {
 			all_and_groups: tree.all_and_groups,
 			reverse_scan: ctx.ctx.tx().reverse_scan(),
 		};
-		match PlanBuilder::build(ctx, p).await? {
+		let (plan, costs) = PlanBuilder::build(ctx, p).await?;
+		if let Some(costs) = costs {
+			self.costs.push((t.0.clone(), costs.explain()));
+		}
+		match plan {
 			Plan::SingleIndex(exp, io, rs) => {
 				if io.require_distinct() {
 					self.requires_distinct = true;
@@ -334,6 +343,10 @@ impl QueryPlanner {
 				}
 				self.add(t.clone(), Some(ir), exe, it, keys_only);
 			}
+			Plan::IntersectIndex(ios, rs) => {
+				let ir = exe.add_iterator(IteratorEntry::Intersect(ios));
+				self.add(t.clone(), Some(ir), exe, it, rs);
+			}
 			Plan::TableIterator(reason, rs, sc) => {
 				if let Some(reason) = reason {
 					self.fallbacks.push(reason);
@@ -380,6 +393,10 @@ impl QueryPlanner {
 		&self.fallbacks
 	}
 
+	pub(crate) fn costs(&self) -> &Vec<(String, Value)> {
+		&self.costs
+	}
+
 	pub(crate) fn is_order(&self, irf: &IteratorRef) -> bool {
 		self.ordering_indexes.contains(irf)
 	}
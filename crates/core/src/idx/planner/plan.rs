@@ -1,5 +1,7 @@
 use crate::err::Error;
+use crate::idx::ft::query::Query;
 use crate::idx::ft::MatchRef;
+use crate::idx::planner::cost::{CostEstimator, PlanCosts};
 use crate::idx::planner::tree::{
 	CompoundIndexes, GroupRef, IdiomCol, IdiomPosition, IndexReference, Node,
 };
@@ -40,7 +42,7 @@ impl PlanBuilder {
 	pub(super) async fn build(
 		ctx: &StatementContext<'_>,
 		p: PlanBuilderParameters,
-	) -> Result<Plan, Error> {
+	) -> Result<(Plan, Option<PlanCosts>), Error> {
 		let mut b = PlanBuilder {
 			has_indexes: false,
 			non_range_indexes: Default::default(),
@@ -49,13 +51,13 @@ impl PlanBuilder {
 		};
 
 		if let Some(With::NoIndex) = ctx.with {
-			return Self::table_iterator(ctx, Some("WITH NOINDEX"), p.gp).await;
+			return Ok((Self::table_iterator(ctx, Some("WITH NOINDEX"), p.gp).await?, None));
 		}
 
 		// Browse the AST and collect information
 		if let Some(root) = &p.root {
 			if let Err(e) = b.eval_node(root) {
-				return Self::table_iterator(ctx, Some(&e), p.gp).await;
+				return Ok((Self::table_iterator(ctx, Some(&e), p.gp).await?, None));
 			}
 		}
 
@@ -75,12 +77,63 @@ impl PlanBuilder {
 					}
 				}
 			}
+
+			// If the indexes have been analyzed, we choose the cheapest plan
+			let ixr = compound_index
+				.as_ref()
+				.map(|(_, io)| io.ix_ref())
+				.or_else(|| b.non_range_indexes.first().map(|(_, io)| io.ix_ref()))
+				.or_else(|| b.groups.values().next().and_then(|g| g.ranges.keys().next()));
+			if let Some(est) = b.cost_estimator(ctx, ixr).await? {
+				let evaluation = b.evaluate_and(&est, compound_index.as_ref().map(|(_, io)| io));
+				let evaluation = match evaluation {
+					// A table scan is only preferred to the indexes if the statistics are current,
+					// otherwise the plan is chosen by rules.
+					Some((Candidate::Table, _)) if !est.is_current(ctx).await? => None,
+					e => e,
+				};
+				if let Some((candidate, costs)) = evaluation {
+					// Evaluate the record strategy
+					let record_strategy =
+						ctx.check_record_strategy(p.all_expressions_with_index, p.gp)?;
+					let plan = match candidate {
+						Candidate::Table => Self::table_iterator(ctx, None, p.gp).await?,
+						Candidate::Compound => match compound_index {
+							Some((_, io)) => Plan::SingleIndex(None, io, record_strategy),
+							None => return Err(fail!("Missing compound index")),
+						},
+						Candidate::Range(ixr) => {
+							match b.groups.into_values().next().and_then(|g| g.take_range(&ixr)) {
+								Some(rq) => {
+									let is_order =
+										p.order_limit.as_ref().is_some_and(|io| io.ixr == ixr);
+									Plan::SingleIndexRange(ixr, rq, record_strategy, is_order)
+								}
+								None => return Err(fail!("Missing range index")),
+							}
+						}
+						Candidate::Single(i) => {
+							let (e, io) = b.non_range_indexes.swap_remove(i);
+							Plan::SingleIndex(Some(e), io, record_strategy)
+						}
+						Candidate::Intersection(i, j) => {
+							let ios = vec![
+								b.non_range_indexes[i].clone(),
+								b.non_range_indexes[j].clone(),
+							];
+							Plan::IntersectIndex(ios, record_strategy)
+						}
+					};
+					return Ok((plan, Some(costs)));
+				}
+			}
+
 			if let Some((_, io)) = compound_index {
 				// Evaluate if we can use keys only
 				let record_strategy =
 					ctx.check_record_strategy(p.all_expressions_with_index, p.gp)?;
 				// Return the plan
-				return Ok(Plan::SingleIndex(None, io, record_strategy));
+				return Ok((Plan::SingleIndex(None, io, record_strategy), None));
 			}
 
 			// We take the "first" range query if one is available
@@ -95,7 +148,7 @@ impl PlanBuilder {
 						false
 					};
 					// Return the plan
-					return Ok(Plan::SingleIndexRange(ir, rq, record_strategy, is_order));
+					return Ok((Plan::SingleIndexRange(ir, rq, record_strategy, is_order), None));
 				}
 			}
 
@@ -105,7 +158,7 @@ impl PlanBuilder {
 				let record_strategy =
 					ctx.check_record_strategy(p.all_expressions_with_index, p.gp)?;
 				// Return the plan
-				return Ok(Plan::SingleIndex(Some(e), i, record_strategy));
+				return Ok((Plan::SingleIndex(Some(e), i, record_strategy), None));
 			}
 			// If there is an order option
 			if let Some(o) = p.order_limit {
@@ -115,26 +168,141 @@ impl PlanBuilder {
 				// Check it is compatible with the reverse scan capability
 				if Self::check_order_scan(p.reverse_scan, o.op()) {
 					// Return the plan
-					return Ok(Plan::SingleIndex(None, o.clone(), record_strategy));
+					return Ok((Plan::SingleIndex(None, o.clone(), record_strategy), None));
 				}
 			}
 		}
 		// If every expression is backed by an index with can use the MultiIndex plan
 		else if p.all_expressions_with_index {
 			let mut ranges = Vec::with_capacity(b.groups.len());
-			for (gr, group) in b.groups {
+			for (gr, group) in std::mem::take(&mut b.groups) {
 				if p.all_and_groups.get(&gr) == Some(&true) {
 					group.take_union_ranges(&mut ranges);
 				} else {
 					group.take_intersect_ranges(&mut ranges);
 				}
 			}
+			// If the indexes have been analyzed, check a table scan is not cheaper
+			let mut union_costs = None;
+			let ixr = b
+				.non_range_indexes
+				.first()
+				.map(|(_, io)| io.ix_ref())
+				.or_else(|| ranges.first().map(|(ixr, _)| ixr));
+			if let Some(est) = b.cost_estimator(ctx, ixr).await? {
+				if let Some((use_indexes, costs)) =
+					Self::evaluate_union(&est, &b.non_range_indexes, &ranges)
+				{
+					if !use_indexes {
+						// The table scan is only preferred if the statistics are current
+						if est.is_current(ctx).await? {
+							let plan = Self::table_iterator(ctx, None, p.gp).await?;
+							return Ok((plan, Some(costs)));
+						}
+					} else {
+						union_costs = Some(costs);
+					}
+				}
+			}
 			// Evaluate the record strategy
 			let record_strategy = ctx.check_record_strategy(p.all_expressions_with_index, p.gp)?;
 			// Return the plan
-			return Ok(Plan::MultiIndex(b.non_range_indexes, ranges, record_strategy));
+			return Ok((
+				Plan::MultiIndex(b.non_range_indexes, ranges, record_strategy),
+				union_costs,
+			));
 		}
-		Self::table_iterator(ctx, None, p.gp).await
+		Ok((Self::table_iterator(ctx, None, p.gp).await?, None))
+	}
+
+	/// Loads the statistics of the table of a candidate index,
+	/// unless an explicit list of indexes has been given.
+	async fn cost_estimator(
+		&self,
+		ctx: &StatementContext<'_>,
+		ixr: Option<&IndexReference>,
+	) -> Result<Option<CostEstimator>, Error> {
+		match ixr {
+			Some(ixr) if self.with_indexes.is_none() => CostEstimator::new(ctx, &ixr.what).await,
+			_ => Ok(None),
+		}
+	}
+
+	/// Evaluates the cost of the candidate plans of a conjunction:
+	/// a compound index, a range, a single index, the intersection of two indexes, or a table scan.
+	/// Returns `None` if an index option can't be estimated, so the plan is chosen by rules.
+	fn evaluate_and(
+		&self,
+		est: &CostEstimator,
+		compound: Option<&IndexOption>,
+	) -> Option<(Candidate, PlanCosts)> {
+		let mut costs = PlanCosts::default();
+		let mut candidates = vec![];
+		if let Some(io) = compound {
+			costs.add(format!("index {}", io.ix_ref().name), est.index_scan(io)?);
+			candidates.push(Candidate::Compound);
+		}
+		if let Some(group) = self.groups.values().next() {
+			let mut ranges: Vec<_> = group.ranges.iter().collect();
+			ranges.sort_by(|(a, _), (b, _)| a.name.0.cmp(&b.name.0));
+			for (ixr, ri) in ranges {
+				let rq = UnionRangeQueryBuilder::new_aggregate(ri.clone())?;
+				costs.add(format!("range {}", ixr.name), est.range_scan(ixr, &rq)?);
+				candidates.push(Candidate::Range(ixr.clone()));
+			}
+		}
+		let mut singles = Vec::with_capacity(self.non_range_indexes.len());
+		for (i, (_, io)) in self.non_range_indexes.iter().enumerate() {
+			let e = est.index_scan(io)?;
+			costs.add(format!("index {}", io.ix_ref().name), e);
+			candidates.push(Candidate::Single(i));
+			singles.push((i, e));
+		}
+		if candidates.is_empty() {
+			return None;
+		}
+		// The intersection of the two most selective indexes
+		singles.sort_by(|(_, a), (_, b)| a.rows.total_cmp(&b.rows));
+		if let [(i, _), rest @ ..] = singles.as_slice() {
+			let io = &self.non_range_indexes[*i].1;
+			if let Some((j, _)) =
+				rest.iter().find(|(j, _)| self.non_range_indexes[*j].1.ixr != io.ixr)
+			{
+				let other = &self.non_range_indexes[*j].1;
+				if let Some(e) = est.index_intersection(&[io, other]) {
+					costs.add(
+						format!("intersection {}, {}", io.ix_ref().name, other.ix_ref().name),
+						e,
+					);
+					candidates.push(Candidate::Intersection(*i, *j));
+				}
+			}
+		}
+		costs.add("table".to_string(), est.table_scan());
+		candidates.push(Candidate::Table);
+		let chosen = costs.choose();
+		Some((candidates.swap_remove(chosen), costs))
+	}
+
+	/// Evaluates the cost of the union of index scans, compared to a table scan.
+	/// Returns true if the union is cheaper.
+	fn evaluate_union(
+		est: &CostEstimator,
+		non_range_indexes: &[(Arc<Expression>, IndexOption)],
+		ranges: &[(IndexReference, UnionRangeQueryBuilder)],
+	) -> Option<(bool, PlanCosts)> {
+		let mut estimations = Vec::with_capacity(non_range_indexes.len() + ranges.len());
+		for (_, io) in non_range_indexes {
+			estimations.push(est.index_scan(io)?);
+		}
+		for (ixr, rq) in ranges {
+			estimations.push(est.range_scan(ixr, rq)?);
+		}
+		let mut costs = PlanCosts::default();
+		costs.add("union".to_string(), est.union(&estimations));
+		costs.add("table".to_string(), est.table_scan());
+		let chosen = costs.choose();
+		Some((chosen == 0, costs))
 	}
 
 	async fn table_iterator(
@@ -284,6 +452,15 @@ impl PlanBuilder {
 	}
 }
 
+/// A candidate plan evaluated by the cost-based selection
+enum Candidate {
+	Table,
+	Compound,
+	Range(IndexReference),
+	Single(usize),
+	Intersection(usize, usize),
+}
+
 pub(super) enum Plan {
 	/// Table full scan
 	/// 1: An optional reason
@@ -308,6 +485,10 @@ pub(super) enum Plan {
 	/// 3. A record strategy
 	/// 4. True if it matches an order option
 	SingleIndexRange(IndexReference, UnionRangeQueryBuilder, RecordStrategy, bool),
+	/// Intersection of index scans
+	/// 1: A list of expression and index options
+	/// 2: A record strategy
+	IntersectIndex(Vec<(Arc<Expression>, IndexOption)>, RecordStrategy),
 }
 
 #[derive(Debug, Eq, PartialEq, Hash, Clone)]
@@ -328,7 +509,8 @@ pub(super) enum IndexOperator {
 	Union(Arc<Value>),
 	Join(Vec<IndexOption>),
 	RangePart(Operator, Arc<Value>),
-	Matches(String, Option<MatchRef>),
+	/// The query string, and its parsed query
+	Matches(String, Arc<Query>, Option<MatchRef>),
 	Knn(Arc<Vec<Number>>, u32),
 	Ann(Arc<Vec<Number>>, u32, u32),
 	/// false = ascending, true = descending
@@ -404,7 +586,7 @@ impl IndexOption {
 				let joins = Value::from(joins);
 				e.insert("joins", joins);
 			}
-			IndexOperator::Matches(qs, a) => {
+			IndexOperator::Matches(qs, _, a) => {
 				e.insert("operator", Value::from(Operator::Matches(*a).to_string()));
 				e.insert("value", Value::from(qs.to_owned()));
 			}
@@ -516,6 +698,10 @@ pub(super) struct Group {
 }
 
 impl Group {
+	fn take_range(mut self, ixr: &IndexReference) -> Option<UnionRangeQueryBuilder> {
+		self.ranges.remove(ixr).and_then(UnionRangeQueryBuilder::new_aggregate)
+	}
+
 	fn take_first_range(self) -> Option<(IndexReference, UnionRangeQueryBuilder)> {
 		if let Some((ir, ri)) = self.ranges.into_iter().take(1).next() {
 			UnionRangeQueryBuilder::new_aggregate(ri).map(|rb| (ir, rb))
@@ -1,5 +1,6 @@
 use crate::dbs::Options;
 use crate::err::Error;
+use crate::idx::ft::query::Query;
 use crate::idx::planner::executor::{
 	KnnBruteForceExpression, KnnBruteForceExpressions, KnnExpressions,
 };
@@ -178,6 +179,13 @@ impl<'a> TreeBuilder<'a> {
 		group: GroupRef,
 		v: &Value,
 	) -> Result<Node, Error> {
+		// Check if the value is the expression of an index, eg. `string::lowercase(email)`
+		if matches!(v, Value::Function(_) | Value::Expression(_) | Value::Subquery(_)) {
+			if let Some(n) = self.resolve_indexed_expression(v).await? {
+				self.leaf_nodes_count += 1;
+				return Ok(n);
+			}
+		}
 		match v {
 			Value::Expression(e) => self.eval_expression(stk, group, e).await,
 			Value::Idiom(i) => self.eval_idiom(stk, group, i).await,
@@ -273,6 +281,25 @@ impl<'a> TreeBuilder<'a> {
 		Ok(n)
 	}
 
+	async fn resolve_indexed_expression(&mut self, v: &Value) -> Result<Option<Node>, Error> {
+		let i = Idiom(vec![Part::Start(v.clone())]);
+		if let Some(node) = self.resolved_idioms.get(&i).cloned() {
+			return Ok(Some(node));
+		}
+		let tx = self.ctx.ctx.tx();
+		self.lazy_load_schema_resolver(&tx, self.table).await?;
+		if let Some(schema) = self.schemas.get(self.table).cloned() {
+			let irs = self.resolve_indexes(self.table, &i, &schema);
+			if !irs.is_empty() {
+				let i = Arc::new(i);
+				let n = Node::IndexedField(i.clone(), irs);
+				self.resolved_idioms.insert(i, n.clone());
+				return Ok(Some(n));
+			}
+		}
+		Ok(None)
+	}
+
 	fn resolve_indexes(&mut self, t: &Table, i: &Idiom, schema: &SchemaCache) -> LocalIndexRefs {
 		// Did we already resolve this idiom?
 		if let Some(m) = self.idioms_indexes.get(t) {
@@ -452,6 +479,12 @@ impl<'a> TreeBuilder<'a> {
 					return;
 				}
 			}
+			// Phrases are verified on the records, so the index does not fully resolve the expression
+			if let IndexOperator::Matches(_, q, _) = io.op() {
+				if q.has_phrases() {
+					return;
+				}
+			}
 			self.leaf_nodes_with_index_count += 2;
 		}
 	}
@@ -501,9 +534,10 @@ impl<'a> TreeBuilder<'a> {
 			let op = match &ixr.index {
 				Index::Idx => self.eval_index_operator(ixr, op, n, p, *col),
 				Index::Uniq => self.eval_index_operator(ixr, op, n, p, *col),
+				// Any field of a multi-field search index searches the whole index
 				Index::Search {
 					..
-				} if *col == 0 => Self::eval_matches_operator(op, n),
+				} => Self::eval_matches_operator(op, n),
 				Index::MTree(_) if *col == 0 => self.eval_mtree_knn(e, op, n)?,
 				Index::Hnsw(_) if *col == 0 => self.eval_hnsw_knn(e, op, n)?,
 				_ => None,
@@ -532,7 +566,9 @@ impl<'a> TreeBuilder<'a> {
 	fn eval_matches_operator(op: &Operator, n: &Node) -> Option<IndexOperator> {
 		if let Some(v) = n.is_computed() {
 			if let Operator::Matches(mr) = op {
-				return Some(IndexOperator::Matches(v.to_raw_string(), *mr));
+				let qs = v.to_raw_string();
+				let q = Arc::new(Query::new(&qs));
+				return Some(IndexOperator::Matches(qs, q, *mr));
 			}
 		}
 		None
@@ -0,0 +1,187 @@
+//! Statistics computed by `ANALYZE INDEX`, and persisted alongside the index
+use crate::cnf::INDEX_HISTOGRAM_BUCKETS;
+use crate::ctx::Context;
+use crate::err::Error;
+use crate::idx::planner::ScanDirection;
+use crate::idx::trees::hnsw::HnswStatistics;
+use crate::idx::{IndexKeyBase, VersionedStore};
+use crate::key::index::Index;
+use crate::key::thing;
+use crate::kvs::{KeyDecode, Transaction};
+use crate::sql::{Array, Object, Value};
+use futures::StreamExt;
+use revision::revisioned;
+use serde::{Deserialize, Serialize};
+
+#[revisioned(revision = 1)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub(crate) enum IndexStatistics {
+	/// Statistics of a standard or a unique index
+	Idx(IdxStatistics),
+	/// Statistics of an HNSW index
+	Hnsw(HnswStatistics),
+}
+
+impl VersionedStore for IndexStatistics {}
+
+impl IndexStatistics {
+	/// Retrieve the statistics persisted by the last `ANALYZE INDEX`, if any
+	pub(crate) async fn get(tx: &Transaction, ikb: &IndexKeyBase) -> Result<Option<Self>, Error> {
+		match tx.get(ikb.new_is_key()?, None).await? {
+			Some(val) => Ok(Some(VersionedStore::try_from(val)?)),
+			None => Ok(None),
+		}
+	}
+
+	/// Persist these statistics, replacing any previous ones
+	pub(crate) async fn set(&self, tx: &Transaction, ikb: &IndexKeyBase) -> Result<(), Error> {
+		tx.set(ikb.new_is_key()?, VersionedStore::try_into(self)?, None).await
+	}
+}
+
+impl From<IndexStatistics> for Value {
+	fn from(stats: IndexStatistics) -> Self {
+		match stats {
+			IndexStatistics::Idx(s) => s.into(),
+			IndexStatistics::Hnsw(s) => s.into(),
+		}
+	}
+}
+
+#[revisioned(revision = 2)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub(crate) struct IdxStatistics {
+	/// The number of entries in the index
+	pub(crate) entries: u64,
+	/// The number of distinct indexed values
+	pub(crate) distinct: u64,
+	/// The number of entries with at least one NULL or NONE value
+	pub(crate) nulls: u64,
+	/// An equi-depth histogram of the indexed values
+	pub(crate) histogram: Vec<HistogramBucket>,
+	/// The number of records in the table
+	#[revision(start = 2)]
+	pub(crate) records: Option<u64>,
+}
+
+#[revisioned(revision = 1)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub(crate) struct HistogramBucket {
+	/// The lowest indexed value of the bucket (inclusive)
+	pub(crate) min: Array,
+	/// The highest indexed value of the bucket (inclusive)
+	pub(crate) max: Array,
+	/// The number of entries in the bucket
+	pub(crate) count: u64,
+	/// The number of distinct values in the bucket
+	pub(crate) distinct: u64,
+}
+
+impl IdxStatistics {
+	/// Count the records of the table, and scan the entries of a standard or unique index.
+	/// A first pass counts the entries, which gives the depth of the histogram buckets.
+	/// The second pass builds the buckets, never splitting a value across two buckets.
+	pub(crate) async fn compute(
+		ctx: &Context,
+		ns: &str,
+		db: &str,
+		tb: &str,
+		ix: &str,
+	) -> Result<Self, Error> {
+		let beg = Index::prefix_beg(ns, db, tb, ix)?;
+		let end = Index::prefix_end(ns, db, tb, ix)?;
+		let txn = ctx.tx();
+		// A unique index has no entry for the NONE and NULL values,
+		// so the size of the table can't be derived from its entries.
+		let records = txn.count(thing::prefix(ns, db, tb)?..thing::suffix(ns, db, tb)?).await?;
+		// First pass: count the entries, the distinct values and the nulls
+		let mut stats = Self {
+			records: Some(records as u64),
+			..Self::default()
+		};
+		let mut prev: Option<Array> = None;
+		let mut stream = txn.stream_keys(beg.clone()..end.clone(), None, ScanDirection::Forward);
+		while let Some(res) = stream.next().await {
+			yield_now!();
+			let key = res?;
+			let key = Index::decode(&key)?;
+			stats.entries += 1;
+			if key.fd.iter().any(Value::is_none_or_null) {
+				stats.nulls += 1;
+			}
+			if prev.as_ref() != Some(key.fd.as_ref()) {
+				stats.distinct += 1;
+				prev = Some(key.fd.into_owned());
+			}
+		}
+		drop(stream);
+		if stats.entries == 0 {
+			return Ok(stats);
+		}
+		// Second pass: build the histogram
+		let buckets = (*INDEX_HISTOGRAM_BUCKETS).max(1) as u64;
+		let depth = stats.entries.div_ceil(buckets);
+		let mut current: Option<HistogramBucket> = None;
+		let mut stream = txn.stream_keys(beg..end, None, ScanDirection::Forward);
+		while let Some(res) = stream.next().await {
+			yield_now!();
+			let key = res?;
+			let key = Index::decode(&key)?;
+			match current.as_mut() {
+				// Same value as the previous entry
+				Some(b) if b.max == *key.fd => b.count += 1,
+				// A new value which still fits in the current bucket
+				Some(b) if b.count < depth => {
+					b.max = key.fd.into_owned();
+					b.count += 1;
+					b.distinct += 1;
+				}
+				// A new value, and either the bucket is full or there is no bucket yet
+				_ => {
+					let fd = key.fd.into_owned();
+					let b = HistogramBucket {
+						min: fd.clone(),
+						max: fd,
+						count: 1,
+						distinct: 1,
+					};
+					if let Some(full) = current.replace(b) {
+						stats.histogram.push(full);
+					}
+				}
+			}
+		}
+		if let Some(last) = current {
+			stats.histogram.push(last);
+		}
+		Ok(stats)
+	}
+}
+
+impl From<IdxStatistics> for Value {
+	fn from(stats: IdxStatistics) -> Self {
+		let mut res = Object::default();
+		res.insert("entries".to_owned(), Value::from(stats.entries));
+		res.insert("distinct".to_owned(), Value::from(stats.distinct));
+		res.insert("nulls".to_owned(), Value::from(stats.nulls));
+		if let Some(records) = stats.records {
+			res.insert("records".to_owned(), Value::from(records));
+		}
+		res.insert(
+			"histogram".to_owned(),
+			Value::from(stats.histogram.into_iter().map(Value::from).collect::<Vec<_>>()),
+		);
+		Value::from(res)
+	}
+}
+
+impl From<HistogramBucket> for Value {
+	fn from(bucket: HistogramBucket) -> Self {
+		let mut res = Object::default();
+		res.insert("min".to_owned(), Value::from(bucket.min));
+		res.insert("max".to_owned(), Value::from(bucket.max));
+		res.insert("count".to_owned(), Value::from(bucket.count));
+		res.insert("distinct".to_owned(), Value::from(bucket.distinct));
+		Value::from(res)
+	}
+}
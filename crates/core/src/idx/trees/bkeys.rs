@@ -102,8 +102,21 @@ impl BKeys for FstKeys {
 		}
 	}
 
-	fn collect_with_prefix(&self, _prefix_key: &Key) -> Result<VecDeque<(Key, Payload)>, Error> {
-		Err(fail!("BKeys/FSTKeys::collect_with_prefix"))
+	fn collect_with_prefix(&self, prefix_key: &Key) -> Result<VecDeque<(Key, Payload)>, Error> {
+		match &self.i {
+			Inner::Map(m) => {
+				let mut s = m.range().ge(prefix_key).into_stream();
+				let mut r = VecDeque::new();
+				while let Some((k, p)) = s.next() {
+					if !k.starts_with(prefix_key) {
+						break;
+					}
+					r.push_back((k.to_vec(), p));
+				}
+				Ok(r)
+			}
+			Inner::Trie(t) => t.collect_with_prefix(prefix_key),
+		}
 	}
 
 	fn insert(&mut self, key: Key, payload: Payload) -> Option<Payload> {
@@ -950,6 +950,96 @@ where
 		Ok((true, is_main_key, key_to_delete, left_id))
 	}
 
+	/// Collects the keys starting with the given prefix, with their payloads,
+	/// stopping once `limit` keys have been collected.
+	pub(in crate::idx) async fn search_by_prefix(
+		&self,
+		tx: &Transaction,
+		store: &BTreeStore<BK>,
+		prefix: &Key,
+		limit: usize,
+	) -> Result<Vec<(Key, Payload)>, Error> {
+		let mut res = Vec::new();
+		let mut node_queue = VecDeque::new();
+		if let Some(node_id) = self.state.root {
+			node_queue.push_back(node_id);
+		}
+		while let Some(node_id) = node_queue.pop_front() {
+			let stored = store.get_node(tx, node_id).await?;
+			for kp in stored.n.keys().collect_with_prefix(prefix)? {
+				if res.len() >= limit {
+					return Ok(res);
+				}
+				res.push(kp);
+			}
+			if let BTreeNode::Internal(keys, children) = &stored.n {
+				// The child at index `i` holds the keys between the keys `i - 1` and `i`
+				let mut lower: Option<Key> = None;
+				for (i, child_id) in children.iter().enumerate() {
+					if lower.as_ref().is_some_and(|k| k > prefix && !k.starts_with(prefix)) {
+						break;
+					}
+					let upper = keys.get_key(i);
+					if upper.as_ref().is_none_or(|k| k >= prefix) {
+						node_queue.push_back(*child_id);
+					}
+					lower = upper;
+				}
+			}
+		}
+		Ok(res)
+	}
+
+	/// Collects every key accepted by the filter, with its payload.
+	/// A child node is only visited if `descend` accepts the prefix shared by every key
+	/// the child can hold, which is the common prefix of the keys bounding it in its ancestors.
+	pub(in crate::idx) async fn search_by_filter<D, F>(
+		&self,
+		tx: &Transaction,
+		store: &BTreeStore<BK>,
+		descend: D,
+		filter: F,
+	) -> Result<Vec<(Key, Payload)>, Error>
+	where
+		D: Fn(&[u8]) -> bool,
+		F: Fn(&Key) -> bool,
+	{
+		let mut res = Vec::new();
+		let mut node_queue = VecDeque::new();
+		if let Some(node_id) = self.state.root {
+			node_queue.push_back((node_id, None, None));
+		}
+		while let Some((node_id, lower, upper)) = node_queue.pop_front() {
+			let stored = store.get_node(tx, node_id).await?;
+			res.extend(
+				stored
+					.n
+					.keys()
+					.collect_with_prefix(&vec![])?
+					.into_iter()
+					.filter(|(k, _)| filter(k)),
+			);
+			if let BTreeNode::Internal(keys, children) = &stored.n {
+				// The child at index `i` holds the keys between the keys `i - 1` and `i`
+				let mut child_lower: Option<Key> = lower;
+				for (i, child_id) in children.iter().enumerate() {
+					let child_upper = keys.get_key(i).or_else(|| upper.clone());
+					let prefix = match (&child_lower, &child_upper) {
+						(Some(l), Some(u)) => {
+							&l[..l.iter().zip(u.iter()).take_while(|(a, b)| a == b).count()]
+						}
+						_ => &[][..],
+					};
+					if descend(prefix) {
+						node_queue.push_back((*child_id, child_lower, child_upper.clone()));
+					}
+					child_lower = child_upper;
+				}
+			}
+		}
+		Ok(res)
+	}
+
 	pub(in crate::idx) async fn statistics(
 		&self,
 		tx: &Transaction,
@@ -1143,6 +1233,53 @@ mod tests {
 		}
 	}
 
+	#[test(tokio::test)]
+	async fn test_btree_search_by_prefix() {
+		let ds = Datastore::new("memory").await.unwrap();
+		let mut t = BTree::new(BState::new(3));
+		{
+			let (tx, st) = new_operation_fst(&ds, &t, TransactionType::Write, 20).await;
+			insertions_test::<_, FstKeys>(tx, st, &mut t, 100, get_key_value).await;
+		}
+		let (tx, st) = new_operation_fst(&ds, &t, TransactionType::Read, 20).await;
+		let mut res = t.search_by_prefix(&tx, &st, &"1".into(), 100).await.unwrap();
+		res.sort();
+		let expected: Vec<_> =
+			[1, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19].into_iter().map(get_key_value).collect();
+		assert_eq!(res, expected);
+		let res = t.search_by_prefix(&tx, &st, &"1".into(), 4).await.unwrap();
+		assert_eq!(res.len(), 4);
+		let res = t.search_by_prefix(&tx, &st, &"a".into(), 100).await.unwrap();
+		assert!(res.is_empty());
+		tx.cancel().await.unwrap();
+	}
+
+	#[test(tokio::test)]
+	async fn test_btree_search_by_filter() {
+		let ds = Datastore::new("memory").await.unwrap();
+		let mut t = BTree::new(BState::new(3));
+		{
+			let (tx, st) = new_operation_fst(&ds, &t, TransactionType::Write, 20).await;
+			insertions_test::<_, FstKeys>(tx, st, &mut t, 100, get_key_value).await;
+		}
+		let (tx, st) = new_operation_fst(&ds, &t, TransactionType::Read, 20).await;
+		let mut res = t.search_by_filter(&tx, &st, |_| true, |k| k.ends_with(b"7")).await.unwrap();
+		res.sort();
+		let expected: Vec<_> =
+			[17, 27, 37, 47, 57, 67, 7, 77, 87, 97].into_iter().map(get_key_value).collect();
+		assert_eq!(res, expected);
+		let res = t.search_by_filter(&tx, &st, |_| true, |_| true).await.unwrap();
+		assert_eq!(res.len(), 100);
+		// Pruning the subtrees which can't hold a key starting with "5" keeps every match
+		let descend = |p: &[u8]| p.is_empty() || p.starts_with(b"5") || b"5".starts_with(p);
+		let mut res = t.search_by_filter(&tx, &st, descend, |k| k.starts_with(b"5")).await.unwrap();
+		res.sort();
+		let expected: Vec<_> =
+			[5, 50, 51, 52, 53, 54, 55, 56, 57, 58, 59].into_iter().map(get_key_value).collect();
+		assert_eq!(res, expected);
+		tx.cancel().await.unwrap();
+	}
+
 	#[test(tokio::test)]
 	async fn test_btree_trie_small_order_sequential_insertions() {
 		let ds = Datastore::new("memory").await.unwrap();
@@ -78,6 +78,15 @@ where
 		}
 	}
 
+	pub(super) fn len(&self) -> usize {
+		self.nodes.len()
+	}
+
+	/// The total number of (directed) edges stored in the graph
+	pub(super) fn edges_len(&self) -> usize {
+		self.nodes.values().map(|s| s.len()).sum()
+	}
+
 	pub(super) fn to_val(&self) -> Result<BytesMut, Error> {
 		let mut buf = BytesMut::new();
 		buf.put_u32(self.nodes.len() as u32);
@@ -113,10 +122,6 @@ impl<S> UndirectedGraph<S>
 where
 	S: DynamicSet,
 {
-	pub(in crate::idx::trees) fn len(&self) -> usize {
-		self.nodes.len()
-	}
-
 	pub(in crate::idx::trees) fn nodes(&self) -> &HashMap<ElementId, S> {
 		&self.nodes
 	}
@@ -3,7 +3,7 @@ use crate::idx::planner::checker::HnswConditionChecker;
 use crate::idx::trees::dynamicset::{AHashSet, ArraySet};
 use crate::idx::trees::hnsw::docs::HnswDocs;
 use crate::idx::trees::hnsw::docs::VecDocs;
-use crate::idx::trees::hnsw::{ElementId, Hnsw, HnswSearch};
+use crate::idx::trees::hnsw::{ElementId, Hnsw, HnswSearch, HnswStatistics};
 use crate::idx::trees::vector::{SharedVector, Vector};
 use crate::idx::IndexKeyBase;
 use crate::kvs::Transaction;
@@ -215,6 +215,26 @@ impl HnswFlavor {
 			HnswFlavor::Hset(h) => h.get_vector(tx, e_id).await,
 		}
 	}
+
+	pub(super) fn statistics(&self) -> HnswStatistics {
+		match self {
+			HnswFlavor::H5_9(h) => h.statistics(),
+			HnswFlavor::H5_17(h) => h.statistics(),
+			HnswFlavor::H5_25(h) => h.statistics(),
+			HnswFlavor::H5set(h) => h.statistics(),
+			HnswFlavor::H9_17(h) => h.statistics(),
+			HnswFlavor::H9_25(h) => h.statistics(),
+			HnswFlavor::H9set(h) => h.statistics(),
+			HnswFlavor::H13_25(h) => h.statistics(),
+			HnswFlavor::H13set(h) => h.statistics(),
+			HnswFlavor::H17set(h) => h.statistics(),
+			HnswFlavor::H21set(h) => h.statistics(),
+			HnswFlavor::H25set(h) => h.statistics(),
+			HnswFlavor::H29set(h) => h.statistics(),
+			HnswFlavor::Hset(h) => h.statistics(),
+		}
+	}
+
 	#[cfg(test)]
 	pub(super) fn check_hnsw_properties(&self, expected_count: usize) {
 		match self {
@@ -4,7 +4,7 @@ use crate::idx::planner::iterators::KnnIteratorResult;
 use crate::idx::trees::hnsw::docs::{HnswDocs, VecDocs};
 use crate::idx::trees::hnsw::elements::HnswElements;
 use crate::idx::trees::hnsw::flavor::HnswFlavor;
-use crate::idx::trees::hnsw::{ElementId, HnswSearch};
+use crate::idx::trees::hnsw::{ElementId, HnswSearch, HnswStatistics};
 use crate::idx::trees::knn::{KnnResult, KnnResultBuilder};
 use crate::idx::trees::vector::{SharedVector, Vector};
 use crate::idx::IndexKeyBase;
@@ -196,6 +196,12 @@ impl HnswIndex {
 		))
 	}
 
+	pub(crate) async fn statistics(&mut self, tx: &Transaction) -> Result<HnswStatistics, Error> {
+		// Ensure the layers are up-to-date
+		self.hnsw.check_state(tx).await?;
+		Ok(self.hnsw.statistics())
+	}
+
 	#[cfg(test)]
 	pub(super) fn check_hnsw_properties(&self, expected_count: usize) {
 		self.hnsw.check_hnsw_properties(expected_count)
@@ -4,7 +4,7 @@ use crate::idx::trees::dynamicset::DynamicSet;
 use crate::idx::trees::graph::UndirectedGraph;
 use crate::idx::trees::hnsw::heuristic::Heuristic;
 use crate::idx::trees::hnsw::index::HnswCheckedSearchContext;
-use crate::idx::trees::hnsw::{ElementId, HnswElements};
+use crate::idx::trees::hnsw::{ElementId, HnswElements, HnswLayerStatistics};
 use crate::idx::trees::knn::DoublePriorityQueue;
 use crate::idx::trees::vector::SharedVector;
 use crate::idx::IndexKeyBase;
@@ -50,6 +50,14 @@ where
 		self.m_max
 	}
 
+	pub(super) fn statistics(&self) -> HnswLayerStatistics {
+		HnswLayerStatistics {
+			level: self.level,
+			nodes: self.graph.len() as u64,
+			edges: self.graph.edges_len() as u64,
+		}
+	}
+
 	pub(super) fn get_edges(&self, e_id: &ElementId) -> Option<&S> {
 		self.graph.get_edges(e_id)
 	}
@@ -20,6 +20,7 @@ use crate::idx::trees::vector::{SerializedVector, SharedVector, Vector};
 use crate::idx::{IndexKeyBase, VersionedStore};
 use crate::kvs::{Key, Transaction, Val};
 use crate::sql::index::HnswParams;
+use crate::sql::{Object, Value};
 use rand::prelude::SmallRng;
 use rand::{Rng, SeedableRng};
 use reblessive::tree::Stk;
@@ -73,6 +74,49 @@ where
 
 pub(crate) type ElementId = u64;
 
+#[revisioned(revision = 1)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub(crate) struct HnswStatistics {
+	/// The number of elements (nodes of the ground layer)
+	pub(crate) elements: u64,
+	pub(crate) enter_point: Option<ElementId>,
+	pub(crate) next_element_id: ElementId,
+	/// The statistics of each layer, starting from the ground layer
+	pub(crate) layers: Vec<HnswLayerStatistics>,
+}
+
+#[revisioned(revision = 1)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub(crate) struct HnswLayerStatistics {
+	pub(crate) level: u16,
+	pub(crate) nodes: u64,
+	pub(crate) edges: u64,
+}
+
+impl From<HnswStatistics> for Value {
+	fn from(stats: HnswStatistics) -> Self {
+		let mut res = Object::default();
+		res.insert("elements".to_owned(), Value::from(stats.elements));
+		res.insert("enter_point".to_owned(), Value::from(stats.enter_point));
+		res.insert("next_element_id".to_owned(), Value::from(stats.next_element_id));
+		res.insert(
+			"layers".to_owned(),
+			Value::from(stats.layers.into_iter().map(Value::from).collect::<Vec<_>>()),
+		);
+		Value::from(res)
+	}
+}
+
+impl From<HnswLayerStatistics> for Value {
+	fn from(stats: HnswLayerStatistics) -> Self {
+		let mut res = Object::default();
+		res.insert("level".to_owned(), Value::from(stats.level));
+		res.insert("nodes".to_owned(), Value::from(stats.nodes));
+		res.insert("edges".to_owned(), Value::from(stats.edges));
+		Value::from(res)
+	}
+}
+
 impl<L0, L> Hnsw<L0, L>
 where
 	L0: DynamicSet,
@@ -413,6 +457,19 @@ where
 	) -> Result<Option<SharedVector>, Error> {
 		self.elements.get_vector(tx, e_id).await
 	}
+
+	fn statistics(&self) -> HnswStatistics {
+		let mut layers = Vec::with_capacity(self.layers.len() + 1);
+		layers.push(self.layer0.statistics());
+		layers.extend(self.layers.iter().map(|l| l.statistics()));
+		HnswStatistics {
+			elements: self.layer0.statistics().nodes,
+			enter_point: self.state.enter_point,
+			next_element_id: self.state.next_element_id,
+			layers,
+		}
+	}
+
 	#[cfg(test)]
 	fn check_hnsw_properties(&self, expected_count: usize) {
 		check_hnsw_props(self, expected_count);
@@ -1,24 +1,41 @@
 use crate::err::Error;
 use crate::iam::file::is_path_allowed;
 use crate::idx::ft::analyzer::mapper::Mapper;
+use crate::idx::ft::analyzer::synonyms::Synonyms;
+use crate::sql::filter::Synonyms as SqlSynonyms;
 use crate::sql::statements::DefineAnalyzerStatement;
 use crate::sql::Filter;
 use ahash::HashSet;
 use dashmap::DashMap;
 use std::path::Path;
 
+/// The files used by the analyzers, loaded in memory.
 #[derive(Default)]
-pub(crate) struct Mappers(DashMap<String, Mapper>);
+pub(crate) struct Mappers {
+	mappers: DashMap<String, Mapper>,
+	synonyms: DashMap<String, Synonyms>,
+}
+
+enum MapperFile<'a> {
+	Mapper(&'a str),
+	Synonyms(&'a str),
+}
+
+impl MapperFile<'_> {
+	fn iter(az: &DefineAnalyzerStatement) -> impl Iterator<Item = MapperFile<'_>> {
+		az.filters.iter().flatten().filter_map(|f| match f {
+			Filter::Mapper(path) => Some(MapperFile::Mapper(path)),
+			Filter::Synonyms(SqlSynonyms::File(path), _) => Some(MapperFile::Synonyms(path)),
+			_ => None,
+		})
+	}
+}
 
 impl Mappers {
 	/// If any mapper is defined, it will be loaded in memory.
 	pub(crate) async fn load(&self, az: &DefineAnalyzerStatement) -> Result<(), Error> {
-		if let Some(filters) = &az.filters {
-			for f in filters {
-				if let Filter::Mapper(path) = f {
-					self.insert(path).await?;
-				}
-			}
+		for f in MapperFile::iter(az) {
+			self.insert(f).await?;
 		}
 		Ok(())
 	}
@@ -26,53 +43,76 @@ impl Mappers {
 	/// Ensure that if a mapper is defined, that it is also loaded in memory.
 	/// This method does not reload a mapper if it is already in memory.
 	pub(crate) async fn check(&self, az: &DefineAnalyzerStatement) -> Result<(), Error> {
-		if let Some(filters) = &az.filters {
-			for f in filters {
-				if let Filter::Mapper(path) = f {
-					if !self.0.contains_key(path) {
-						self.insert(path).await?;
-					}
-				}
+		for f in MapperFile::iter(az) {
+			let loaded = match f {
+				MapperFile::Mapper(path) => self.mappers.contains_key(path),
+				MapperFile::Synonyms(path) => self.synonyms.contains_key(path),
+			};
+			if !loaded {
+				self.insert(f).await?;
 			}
 		}
 		Ok(())
 	}
 
-	async fn insert(&self, path: &str) -> Result<(), Error> {
+	async fn insert(&self, f: MapperFile<'_>) -> Result<(), Error> {
+		let path = match f {
+			MapperFile::Mapper(path) | MapperFile::Synonyms(path) => path,
+		};
 		let p = Path::new(path);
 		// Check the path is allowed
 		is_path_allowed(p)?;
 		if !p.exists() || !p.is_file() {
 			return Err(Error::Internal(format!("Invalid mapper path: {p:?}")));
 		}
-		let mapper = Mapper::new(p).await?;
-		self.0.insert(path.to_string(), mapper);
+		match f {
+			MapperFile::Mapper(_) => {
+				let mapper = Mapper::new(p).await?;
+				self.mappers.insert(path.to_string(), mapper);
+			}
+			MapperFile::Synonyms(_) => {
+				let synonyms = Synonyms::from_file(p).await?;
+				self.synonyms.insert(path.to_string(), synonyms);
+			}
+		}
 		Ok(())
 	}
 
 	pub(in crate::idx) fn get(&self, path: &str) -> Result<Mapper, Error> {
-		match self.0.get(path) {
+		match self.mappers.get(path) {
 			None => Err(Error::Internal(format!("Mapper not found for {path}"))),
 			Some(e) => Ok(e.value().clone()),
 		}
 	}
 
+	pub(in crate::idx) fn get_synonyms(&self, path: &str) -> Result<Synonyms, Error> {
+		match self.synonyms.get(path) {
+			None => Err(Error::Internal(format!("Synonyms not found for {path}"))),
+			Some(e) => Ok(e.value().clone()),
+		}
+	}
+
 	pub(crate) fn cleanup(&self, azs: &[DefineAnalyzerStatement]) {
 		// Collect every existing mapper
-		let mut keys: HashSet<String> = self.0.iter().map(|e| e.key().to_string()).collect();
+		let mut mappers: HashSet<String> =
+			self.mappers.iter().map(|e| e.key().to_string()).collect();
+		let mut synonyms: HashSet<String> =
+			self.synonyms.iter().map(|e| e.key().to_string()).collect();
 		// Remove keys that still exist in the definitions
 		for az in azs {
-			if let Some(filters) = &az.filters {
-				for f in filters {
-					if let Filter::Mapper(path) = f {
-						keys.remove(path);
-					}
-				}
+			for f in MapperFile::iter(az) {
+				match f {
+					MapperFile::Mapper(path) => mappers.remove(path),
+					MapperFile::Synonyms(path) => synonyms.remove(path),
+				};
 			}
 		}
 		// Any left key can be removed
-		for key in keys {
-			self.0.remove(&key);
+		for key in mappers {
+			self.mappers.remove(&key);
+		}
+		for key in synonyms {
+			self.synonyms.remove(&key);
 		}
 	}
 }
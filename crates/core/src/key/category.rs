@@ -92,6 +92,16 @@ pub enum Category {
 	DatabaseConfig,
 	/// crate::key::database::sq             /*{ns}*{db}*sq{sq}
 	DatabaseSequence,
+	/// crate::key::database::cn             /*{ns}*{db}!cn{cn}
+	DatabaseConsumer,
+	/// crate::key::database::cu             /*{ns}*{db}!cu{cu}
+	DatabaseConsumerCursor,
+	/// crate::key::database::sk             /*{ns}*{db}!sk{sk}
+	DatabaseSink,
+	/// crate::key::database::sl             /*{ns}*{db}!sl{sl}
+	DatabaseSinkLease,
+	/// crate::key::database::sp             /*{ns}*{db}!sp{sp}
+	DatabaseSinkPosition,
 	///
 	/// ------------------------------
 	///
@@ -146,6 +156,8 @@ pub enum Category {
 	IndexAppendings,
 	/// crate::key::index::ip                /*{ns}*{db}*{tb}+{ix}!ip{id}
 	IndexPrimaryAppending,
+	/// crate::key::index::is                /*{ns}*{db}*{tb}+{ix}!is
+	IndexStatistics,
 	/// crate::key::index                    /*{ns}*{db}*{tb}+{ix}*{fd}{id}
 	Index,
 	///
@@ -215,6 +227,11 @@ impl Display for Category {
 			Self::DatabaseVersionstamp => "DatabaseVersionstamp",
 			Self::DatabaseSequence => "DatabaseSequence",
 			Self::DatabaseConfig => "DatabaseConfig",
+			Self::DatabaseConsumer => "DatabaseConsumer",
+			Self::DatabaseConsumerCursor => "DatabaseConsumerCursor",
+			Self::DatabaseSink => "DatabaseSink",
+			Self::DatabaseSinkLease => "DatabaseSinkLease",
+			Self::DatabaseSinkPosition => "DatabaseSinkPosition",
 			Self::TableRoot => "TableRoot",
 			Self::TableEvent => "TableEvent",
 			Self::TableField => "TableField",
@@ -239,6 +256,7 @@ impl Display for Category {
 			Self::IndexHnswVec => "IndexHnswVec",
 			Self::IndexAppendings => "IndexAppendings",
 			Self::IndexPrimaryAppending => "IndexPrimaryAppending",
+			Self::IndexStatistics => "IndexStatistics",
 			Self::Index => "Index",
 			Self::ChangeFeed => "ChangeFeed",
 			Self::Thing => "Thing",
@@ -0,0 +1,75 @@
+//! Stores a DEFINE CONSUMER config definition
+use crate::err::Error;
+use crate::key::category::Categorise;
+use crate::key::category::Category;
+use crate::kvs::{impl_key, KeyEncode};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct Cn<'a> {
+	__: u8,
+	_a: u8,
+	pub ns: &'a str,
+	_b: u8,
+	pub db: &'a str,
+	_c: u8,
+	_d: u8,
+	_e: u8,
+	pub cn: &'a str,
+}
+impl_key!(Cn<'a>);
+
+pub fn prefix(ns: &str, db: &str) -> Result<Vec<u8>, Error> {
+	let mut k = super::all::new(ns, db).encode()?;
+	k.extend_from_slice(b"!cn\x00");
+	Ok(k)
+}
+
+pub fn suffix(ns: &str, db: &str) -> Result<Vec<u8>, Error> {
+	let mut k = super::all::new(ns, db).encode()?;
+	k.extend_from_slice(b"!cn\xff");
+	Ok(k)
+}
+
+impl Categorise for Cn<'_> {
+	fn categorise(&self) -> Category {
+		Category::DatabaseConsumer
+	}
+}
+
+impl<'a> Cn<'a> {
+	pub fn new(ns: &'a str, db: &'a str, cn: &'a str) -> Self {
+		Self {
+			__: b'/',
+			_a: b'*',
+			ns,
+			_b: b'*',
+			db,
+			_c: b'!',
+			_d: b'c',
+			_e: b'n',
+			cn,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::kvs::KeyDecode;
+	#[test]
+	fn key() {
+		use super::*;
+		#[rustfmt::skip]
+		let val = Cn::new(
+			"testns",
+			"testdb",
+			"testcn",
+		);
+		let enc = Cn::encode(&val).unwrap();
+		assert_eq!(enc, b"/*testns\0*testdb\0!cntestcn\0");
+
+		let dec = Cn::decode(&enc).unwrap();
+		assert_eq!(val, dec);
+	}
+}
@@ -0,0 +1,66 @@
+//! Stores the acknowledged position of a change feed consumer
+use crate::key::category::Categorise;
+use crate::key::category::Category;
+use crate::kvs::impl_key;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct Cu<'a> {
+	__: u8,
+	_a: u8,
+	pub ns: &'a str,
+	_b: u8,
+	pub db: &'a str,
+	_c: u8,
+	_d: u8,
+	_e: u8,
+	pub cu: &'a str,
+}
+impl_key!(Cu<'a>);
+
+pub fn new<'a>(ns: &'a str, db: &'a str, cu: &'a str) -> Cu<'a> {
+	Cu::new(ns, db, cu)
+}
+
+impl Categorise for Cu<'_> {
+	fn categorise(&self) -> Category {
+		Category::DatabaseConsumerCursor
+	}
+}
+
+impl<'a> Cu<'a> {
+	pub fn new(ns: &'a str, db: &'a str, cu: &'a str) -> Self {
+		Self {
+			__: b'/',
+			_a: b'*',
+			ns,
+			_b: b'*',
+			db,
+			_c: b'!',
+			_d: b'c',
+			_e: b'u',
+			cu,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::kvs::{KeyDecode, KeyEncode};
+	#[test]
+	fn key() {
+		use super::*;
+		#[rustfmt::skip]
+		let val = Cu::new(
+			"testns",
+			"testdb",
+			"testcu",
+		);
+		let enc = Cu::encode(&val).unwrap();
+		assert_eq!(enc, b"/*testns\0*testdb\0!cutestcu\0");
+
+		let dec = Cu::decode(&enc).unwrap();
+		assert_eq!(val, dec);
+	}
+}
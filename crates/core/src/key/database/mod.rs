@@ -5,9 +5,14 @@ pub mod ap;
 pub mod az;
 pub mod bu;
 pub mod cg;
+pub mod cn;
+pub mod cu;
 pub mod fc;
 pub mod ml;
 pub mod pa;
+pub mod sk;
+pub mod sl;
+pub mod sp;
 pub mod sq;
 pub mod tb;
 pub mod ti;
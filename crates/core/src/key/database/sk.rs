@@ -0,0 +1,75 @@
+//! Stores a DEFINE SINK config definition
+use crate::err::Error;
+use crate::key::category::Categorise;
+use crate::key::category::Category;
+use crate::kvs::{impl_key, KeyEncode};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct Sk<'a> {
+	__: u8,
+	_a: u8,
+	pub ns: &'a str,
+	_b: u8,
+	pub db: &'a str,
+	_c: u8,
+	_d: u8,
+	_e: u8,
+	pub sk: &'a str,
+}
+impl_key!(Sk<'a>);
+
+pub fn prefix(ns: &str, db: &str) -> Result<Vec<u8>, Error> {
+	let mut k = super::all::new(ns, db).encode()?;
+	k.extend_from_slice(b"!sk\x00");
+	Ok(k)
+}
+
+pub fn suffix(ns: &str, db: &str) -> Result<Vec<u8>, Error> {
+	let mut k = super::all::new(ns, db).encode()?;
+	k.extend_from_slice(b"!sk\xff");
+	Ok(k)
+}
+
+impl Categorise for Sk<'_> {
+	fn categorise(&self) -> Category {
+		Category::DatabaseSink
+	}
+}
+
+impl<'a> Sk<'a> {
+	pub fn new(ns: &'a str, db: &'a str, sk: &'a str) -> Self {
+		Self {
+			__: b'/',
+			_a: b'*',
+			ns,
+			_b: b'*',
+			db,
+			_c: b'!',
+			_d: b's',
+			_e: b'k',
+			sk,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::kvs::KeyDecode;
+	#[test]
+	fn key() {
+		use super::*;
+		#[rustfmt::skip]
+		let val = Sk::new(
+			"testns",
+			"testdb",
+			"testsk",
+		);
+		let enc = Sk::encode(&val).unwrap();
+		assert_eq!(enc, b"/*testns\0*testdb\0!sktestsk\0");
+
+		let dec = Sk::decode(&enc).unwrap();
+		assert_eq!(val, dec);
+	}
+}
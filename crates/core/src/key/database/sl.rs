@@ -0,0 +1,66 @@
+//! Stores the lease of the node which delivers the changes of a change feed sink
+use crate::key::category::Categorise;
+use crate::key::category::Category;
+use crate::kvs::impl_key;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct Sl<'a> {
+	__: u8,
+	_a: u8,
+	pub ns: &'a str,
+	_b: u8,
+	pub db: &'a str,
+	_c: u8,
+	_d: u8,
+	_e: u8,
+	pub sl: &'a str,
+}
+impl_key!(Sl<'a>);
+
+pub fn new<'a>(ns: &'a str, db: &'a str, sl: &'a str) -> Sl<'a> {
+	Sl::new(ns, db, sl)
+}
+
+impl Categorise for Sl<'_> {
+	fn categorise(&self) -> Category {
+		Category::DatabaseSinkLease
+	}
+}
+
+impl<'a> Sl<'a> {
+	pub fn new(ns: &'a str, db: &'a str, sl: &'a str) -> Self {
+		Self {
+			__: b'/',
+			_a: b'*',
+			ns,
+			_b: b'*',
+			db,
+			_c: b'!',
+			_d: b's',
+			_e: b'l',
+			sl,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::kvs::{KeyDecode, KeyEncode};
+	#[test]
+	fn key() {
+		use super::*;
+		#[rustfmt::skip]
+		let val = Sl::new(
+			"testns",
+			"testdb",
+			"testsl",
+		);
+		let enc = Sl::encode(&val).unwrap();
+		assert_eq!(enc, b"/*testns\0*testdb\0!sltestsl\0");
+
+		let dec = Sl::decode(&enc).unwrap();
+		assert_eq!(val, dec);
+	}
+}
@@ -0,0 +1,66 @@
+//! Stores the delivered position of a change feed sink
+use crate::key::category::Categorise;
+use crate::key::category::Category;
+use crate::kvs::impl_key;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct Sp<'a> {
+	__: u8,
+	_a: u8,
+	pub ns: &'a str,
+	_b: u8,
+	pub db: &'a str,
+	_c: u8,
+	_d: u8,
+	_e: u8,
+	pub sp: &'a str,
+}
+impl_key!(Sp<'a>);
+
+pub fn new<'a>(ns: &'a str, db: &'a str, sp: &'a str) -> Sp<'a> {
+	Sp::new(ns, db, sp)
+}
+
+impl Categorise for Sp<'_> {
+	fn categorise(&self) -> Category {
+		Category::DatabaseSinkPosition
+	}
+}
+
+impl<'a> Sp<'a> {
+	pub fn new(ns: &'a str, db: &'a str, sp: &'a str) -> Self {
+		Self {
+			__: b'/',
+			_a: b'*',
+			ns,
+			_b: b'*',
+			db,
+			_c: b'!',
+			_d: b's',
+			_e: b'p',
+			sp,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::kvs::{KeyDecode, KeyEncode};
+	#[test]
+	fn key() {
+		use super::*;
+		#[rustfmt::skip]
+		let val = Sp::new(
+			"testns",
+			"testdb",
+			"testsp",
+		);
+		let enc = Sp::encode(&val).unwrap();
+		assert_eq!(enc, b"/*testns\0*testdb\0!sptestsp\0");
+
+		let dec = Sp::decode(&enc).unwrap();
+		assert_eq!(val, dec);
+	}
+}
@@ -0,0 +1,62 @@
+//! Stores the statistics computed by ANALYZE for an index
+use crate::kvs::impl_key;
+use serde::{Deserialize, Serialize};
+use std::fmt::Debug;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct Is<'a> {
+	__: u8,
+	_a: u8,
+	pub ns: &'a str,
+	_b: u8,
+	pub db: &'a str,
+	_c: u8,
+	pub tb: &'a str,
+	_d: u8,
+	pub ix: &'a str,
+	_e: u8,
+	_f: u8,
+	_g: u8,
+}
+impl_key!(Is<'a>);
+
+impl<'a> Is<'a> {
+	pub fn new(ns: &'a str, db: &'a str, tb: &'a str, ix: &'a str) -> Self {
+		Self {
+			__: b'/',
+			_a: b'*',
+			ns,
+			_b: b'*',
+			db,
+			_c: b'*',
+			tb,
+			_d: b'+',
+			ix,
+			_e: b'!',
+			_f: b'i',
+			_g: b's',
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::kvs::{KeyDecode, KeyEncode};
+
+	#[test]
+	fn key() {
+		use super::*;
+		let val = Is::new("testns", "testdb", "testtb", "testix");
+		let enc = Is::encode(&val).unwrap();
+		assert_eq!(
+			enc,
+			b"/*testns\0*testdb\0*testtb\0+testix\0!is",
+			"{}",
+			String::from_utf8_lossy(&enc)
+		);
+
+		let dec = Is::decode(&enc).unwrap();
+		assert_eq!(val, dec);
+	}
+}
@@ -19,6 +19,7 @@ pub mod hs;
 pub mod hv;
 pub mod ia;
 pub mod ip;
+pub mod is;
 pub mod vm;
 
 use crate::err::Error;
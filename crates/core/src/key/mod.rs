@@ -39,6 +39,11 @@
 /// crate::key::database::us             /*{ns}*{db}!us{us}
 /// crate::key::database::vs             /*{ns}*{db}!vs
 /// crate::key::database::cg             /*{ns}*{db}!cg{ty}
+/// crate::key::database::cn             /*{ns}*{db}!cn{cn}
+/// crate::key::database::cu             /*{ns}*{db}!cu{cu}
+/// crate::key::database::sk             /*{ns}*{db}!sk{sk}
+/// crate::key::database::sl             /*{ns}*{db}!sl{sl}
+/// crate::key::database::sp             /*{ns}*{db}!sp{sp}
 ///
 /// crate::key::database::access::all    /*{ns}*{db}&{ac}
 /// crate::key::database::access::gr     /*{ns}*{db}&{ac}!gr{gr}
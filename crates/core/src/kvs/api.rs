@@ -84,6 +84,15 @@ pub trait Transaction: requirements::TransactionRequirements {
 	/// will return an [`Error::TxReadonly`] error.
 	fn writeable(&self) -> bool;
 
+	/// Check if a version retention policy can be enforced.
+	///
+	/// If the storage engine does not keep the versions of keys,
+	/// or can remove the versions outside of a retention policy,
+	/// then this function will return [`true`].
+	fn retainable(&self) -> bool {
+		true
+	}
+
 	/// Cancel a transaction.
 	///
 	/// This reverses all changes made within the transaction.
@@ -366,6 +375,35 @@ pub trait Transaction: requirements::TransactionRequirements {
 		Err(Error::UnsupportedVersionedQueries)
 	}
 
+	/// Retrieve the versions of a range of keys from the datastore, without their values.
+	///
+	/// This function fetches the key-version pairs for the range, in a single request to the underlying datastore.
+	#[instrument(level = "trace", target = "surrealdb::core::kvs::api", skip(self), fields(rng = rng.sprint()))]
+	async fn scan_versions(
+		&mut self,
+		rng: Range<Key>,
+		limit: u32,
+	) -> Result<Vec<(Key, Version)>, Error> {
+		let res = self.scan_all_versions(rng, limit).await?;
+		Ok(res.into_iter().map(|(k, _, v, _)| (k, v)).collect())
+	}
+
+	/// Delete the versions of a range of keys which are outside of a retention policy.
+	///
+	/// For each key, the latest version at the timestamp, the latest `keep` versions, and any
+	/// newer versions are retained. Up to `limit` keys are processed in a single request to the
+	/// underlying datastore, returning the key from which to continue, if any keys remain.
+	#[instrument(level = "trace", target = "surrealdb::core::kvs::api", skip(self), fields(rng = _rng.sprint()))]
+	async fn prune_versions(
+		&mut self,
+		_rng: Range<Key>,
+		_ts: Option<u64>,
+		_keep: Option<u64>,
+		_limit: u32,
+	) -> Result<Option<Key>, Error> {
+		Err(Error::UnsupportedVersionedQueries)
+	}
+
 	/// Retrieve a batched scan over a specific range of keys in the datastore.
 	///
 	/// This function fetches keys, in batches, with multiple requests to the underlying datastore.
@@ -495,6 +533,48 @@ pub trait Transaction: requirements::TransactionRequirements {
 		}
 	}
 
+	/// Retrieve a batched scan over the versions of a specific range of keys in the datastore.
+	///
+	/// This function fetches key-version pairs, without their values, in batches, with multiple requests to the underlying datastore.
+	#[instrument(level = "trace", target = "surrealdb::core::kvs::api", skip(self), fields(rng = rng.sprint()))]
+	async fn batch_versions(
+		&mut self,
+		rng: Range<Key>,
+		batch: u32,
+	) -> Result<Batch<(Key, Version)>, Error> {
+		// Check to see if transaction is closed
+		if self.closed() {
+			return Err(Error::TxFinished);
+		}
+		// Continue with function logic
+		let beg: Key = rng.start.encode()?;
+		let end: Key = rng.end.encode()?;
+		// Scan for the next batch
+		let res = self.scan_versions(beg..end.clone(), batch).await?;
+		// Check if range is consumed
+		if res.len() < batch as usize && batch > 0 {
+			Ok(Batch::<(Key, Version)>::new(None, res))
+		} else {
+			match res.last() {
+				Some((k, _)) => {
+					let mut k = k.clone();
+					util::advance_key(&mut k);
+					Ok(Batch::<(Key, Version)>::new(
+						Some(Range {
+							start: k,
+							end,
+						}),
+						res,
+					))
+				}
+				// We have checked the length above, so
+				// there should be a last item in the
+				// vector, so we shouldn't arrive here
+				None => Ok(Batch::<(Key, Version)>::new(None, res)),
+			}
+		}
+	}
+
 	/// Obtain a new change timestamp for a key
 	/// which is replaced with the current timestamp when the transaction is committed.
 	/// NOTE: This should be called when composing the change feed entries for this transaction,
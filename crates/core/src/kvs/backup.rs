@@ -0,0 +1,767 @@
+//! Physical backups of a datastore.
+//!
+//! A backup is a binary stream which begins with a header, followed by
+//! batches of raw keys and values, and ends with a manifest. Every batch,
+//! and the manifest, is followed by a checksum of its contents, so that
+//! corruption can be detected before a backup is restored.
+//!
+//! A backup can instead contain only the change feeds of every database,
+//! along with the timestamps of their versionstamps. Archives of change
+//! feeds can be replayed on top of a full backup, to recover a datastore
+//! to any point in time after the full backup was taken.
+
+use super::version::Version;
+use super::{Datastore, KeyDecode, KeyEncode, LockType::*, Transaction, TransactionType::*};
+use crate::cnf::EXPORT_BATCH_SIZE;
+use crate::dbs::Session;
+use crate::env::VERSION;
+use crate::err::Error;
+use crate::key::change::Cf;
+use crate::key::database::ts::Ts;
+use crate::sql::statements::show::ShowSince;
+use crate::sql::Datetime;
+use crate::vs::VersionStamp;
+use async_channel::Sender;
+use bytes::{Bytes, BytesMut};
+use futures::{Stream, StreamExt};
+use revision::revisioned;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::pin::pin;
+
+/// The bytes which identify the start of a backup
+const MAGIC: &[u8; 8] = b"SURREAL\0";
+/// The version of the backup format
+const FORMAT: u16 = 1;
+/// A frame containing a batch of keys and values
+const BATCH: u8 = 1;
+/// A frame containing the backup manifest
+const MANIFEST: u8 = 2;
+
+/// A summary of a backup, which is written at the end of the backup stream
+#[revisioned(revision = 2)]
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct Manifest {
+	/// The version of SurrealDB which created the backup
+	pub version: String,
+	/// The storage engine which the backup was taken from
+	pub engine: String,
+	/// The storage version of the data in the backup
+	pub storage: u16,
+	/// When the backup snapshot was taken
+	pub created: Datetime,
+	/// The number of keys in the backup
+	pub keys: u64,
+	/// The total size of all keys and values in the backup
+	pub bytes: u64,
+	/// The checksum of all of the batches in the backup
+	pub checksum: String,
+	/// Whether the backup only contains change feeds
+	#[revision(start = 2)]
+	pub changes: bool,
+}
+
+/// Encodes a frame, with its type, length, and checksum
+fn frame(kind: u8, payload: &[u8]) -> Vec<u8> {
+	let mut out = Vec::with_capacity(1 + 8 + payload.len() + blake3::OUT_LEN);
+	out.push(kind);
+	out.extend_from_slice(&(payload.len() as u64).to_be_bytes());
+	out.extend_from_slice(payload);
+	out.extend_from_slice(blake3::hash(payload).as_bytes());
+	out
+}
+
+/// Appends a length-prefixed byte slice to a buffer
+fn put(buf: &mut Vec<u8>, val: &[u8]) {
+	buf.extend_from_slice(&(val.len() as u64).to_be_bytes());
+	buf.extend_from_slice(val);
+}
+
+/// Takes a length-prefixed byte slice from the start of a buffer
+fn take(buf: &mut Bytes) -> Result<Bytes, Error> {
+	if buf.len() < 8 {
+		return Err(Error::InvalidBackup("a batch of keys is malformed".to_string()));
+	}
+	let len = u64::from_be_bytes(buf.split_to(8)[..].try_into().unwrap()) as usize;
+	if buf.len() < len {
+		return Err(Error::InvalidBackup("a batch of keys is malformed".to_string()));
+	}
+	Ok(buf.split_to(len))
+}
+
+impl Transaction {
+	/// Writes the raw contents of the datastore as a binary backup.
+	///
+	/// The backup is a consistent snapshot of the datastore at the point
+	/// that this transaction was started, so other transactions can continue
+	/// to write to the datastore while the backup is being taken. If only
+	/// the change feeds are backed up, then the backup can be used as an
+	/// archive for point-in-time recovery.
+	pub async fn backup(
+		&self,
+		engine: String,
+		changes: bool,
+		chn: Sender<Vec<u8>>,
+	) -> Result<Manifest, Error> {
+		// Fetch the storage version of the data
+		let storage = match self.get(crate::key::version::new(), None).await? {
+			Some(v) => Version::try_from(v)?,
+			None => Version::latest(),
+		};
+		// Prepare the backup manifest
+		let mut manifest = Manifest {
+			version: VERSION.to_string(),
+			engine,
+			storage: storage.into(),
+			created: Datetime::default(),
+			keys: 0,
+			bytes: 0,
+			checksum: String::new(),
+			changes,
+		};
+		// Calculate the key ranges to back up
+		let mut ranges = Vec::new();
+		if changes {
+			for ns in self.all_ns().await?.iter() {
+				for db in self.all_db(&ns.name).await?.iter() {
+					let (ns, db) = (ns.name.as_str(), db.name.as_str());
+					let beg = crate::key::change::prefix_ts(ns, db, VersionStamp::ZERO)?;
+					ranges.push(beg..crate::key::change::suffix(ns, db)?);
+					let beg = crate::key::database::ts::prefix(ns, db)?;
+					ranges.push(beg..crate::key::database::ts::suffix(ns, db)?);
+				}
+			}
+		} else {
+			ranges.push(vec![0x00]..vec![0xff]);
+		}
+		// Output the backup header
+		let mut head = MAGIC.to_vec();
+		head.extend_from_slice(&FORMAT.to_be_bytes());
+		chn.send(head).await?;
+		// Output all of the keys in batches
+		let mut hasher = blake3::Hasher::new();
+		for rng in ranges {
+			let mut next = Some(rng);
+			while let Some(rng) = next {
+				let batch = self.batch_keys_vals(rng, *EXPORT_BATCH_SIZE, None).await?;
+				next = batch.next;
+				// If there are no values, return early.
+				if batch.result.is_empty() {
+					break;
+				}
+				let mut buf = Vec::new();
+				for (k, v) in batch.result {
+					manifest.keys += 1;
+					manifest.bytes += (k.len() + v.len()) as u64;
+					put(&mut buf, &k);
+					put(&mut buf, &v);
+				}
+				hasher.update(&buf);
+				chn.send(frame(BATCH, &buf)).await?;
+			}
+		}
+		// Output the backup manifest
+		manifest.checksum = hasher.finalize().to_hex().to_string();
+		chn.send(frame(MANIFEST, &revision::to_vec(&manifest)?)).await?;
+		// Everything ok
+		Ok(manifest)
+	}
+}
+
+/// Reads frames from a backup stream
+struct Reader<S> {
+	stream: S,
+	buf: BytesMut,
+}
+
+impl<S> Reader<S>
+where
+	S: Stream<Item = Result<Bytes, Error>> + Unpin,
+{
+	/// Reads the specified number of bytes from the stream
+	async fn read(&mut self, len: usize) -> Result<Bytes, Error> {
+		while self.buf.len() < len {
+			match self.stream.next().await {
+				Some(bytes) => self.buf.extend_from_slice(&bytes?),
+				None => {
+					return Err(Error::InvalidBackup("the backup ended unexpectedly".to_string()))
+				}
+			}
+		}
+		Ok(self.buf.split_to(len).freeze())
+	}
+
+	/// Reads the backup header, and checks the format version
+	async fn header(&mut self) -> Result<(), Error> {
+		let head = self.read(MAGIC.len() + 2).await?;
+		if head[..MAGIC.len()] != MAGIC[..] {
+			return Err(Error::InvalidBackup("the data is not a SurrealDB backup".to_string()));
+		}
+		let format = u16::from_be_bytes([head[MAGIC.len()], head[MAGIC.len() + 1]]);
+		if format != FORMAT {
+			return Err(Error::InvalidBackup(format!(
+				"the backup format version {format} is unsupported"
+			)));
+		}
+		Ok(())
+	}
+
+	/// Reads the next frame, and checks its checksum
+	async fn frame(&mut self) -> Result<(u8, Bytes), Error> {
+		let head = self.read(9).await?;
+		let len = u64::from_be_bytes(head[1..].try_into().unwrap()) as usize;
+		let payload = self.read(len).await?;
+		let checksum = self.read(blake3::OUT_LEN).await?;
+		if blake3::hash(&payload).as_bytes()[..] != checksum[..] {
+			return Err(Error::InvalidBackup("a checksum does not match".to_string()));
+		}
+		Ok((head[0], payload))
+	}
+}
+
+/// Checks that a binary backup is complete, without restoring it.
+///
+/// Every frame is read and checked against its checksum, and the keys
+/// and sizes of all batches are checked against the backup manifest,
+/// so that a truncated or corrupted backup is detected.
+pub async fn verify<S>(stream: S) -> Result<Manifest, Error>
+where
+	S: Stream<Item = Result<Bytes, Error>>,
+{
+	let mut reader = Reader {
+		stream: pin!(stream),
+		buf: BytesMut::new(),
+	};
+	// Check the backup header
+	reader.header().await?;
+	// Count the keys and bytes in each batch
+	let mut hasher = blake3::Hasher::new();
+	let (mut keys, mut bytes) = (0, 0);
+	loop {
+		match reader.frame().await? {
+			(BATCH, mut payload) => {
+				hasher.update(&payload);
+				while !payload.is_empty() {
+					let k = take(&mut payload)?;
+					let v = take(&mut payload)?;
+					keys += 1;
+					bytes += (k.len() + v.len()) as u64;
+				}
+			}
+			(MANIFEST, payload) => {
+				let manifest: Manifest = revision::from_slice(&payload)?;
+				if manifest.keys != keys
+					|| manifest.bytes != bytes
+					|| manifest.checksum != hasher.finalize().to_hex().to_string()
+				{
+					return Err(Error::InvalidBackup(
+						"the backup data does not match the backup manifest".to_string(),
+					));
+				}
+				// Nothing should follow the manifest
+				if !reader.buf.is_empty() || reader.stream.next().await.is_some() {
+					return Err(Error::InvalidBackup(
+						"the backup continues after its manifest".to_string(),
+					));
+				}
+				return Ok(manifest);
+			}
+			(kind, _) => {
+				return Err(Error::InvalidBackup(format!("the frame type {kind} is unknown")));
+			}
+		}
+	}
+}
+
+/// Restores a binary backup into an empty datastore.
+///
+/// A backup can only be checked once it has been read completely, so
+/// if the backup is found to be invalid, then every key which has been
+/// written is removed again, leaving the datastore empty.
+pub(super) async fn restore<S>(ds: &Datastore, stream: S) -> Result<Manifest, Error>
+where
+	S: Stream<Item = Result<Bytes, Error>>,
+{
+	// Ensure that no data will be overwritten
+	let existing = existing(ds).await?;
+	// Write all of the keys in the backup
+	let res = load(ds, stream, |_| Ok(())).await;
+	// Remove the keys if the backup was not restored
+	if res.is_err() {
+		rollback(ds, &existing).await?;
+	}
+	res
+}
+
+/// Restores a binary backup into an empty datastore, and then replays
+/// archived change feeds on top of it, up to the specified point in time.
+///
+/// The change sets which were recorded after the backup was taken, and
+/// before the point in time, are reapplied to each database. Afterwards,
+/// the change feeds of each database are truncated at the point in time,
+/// so that the datastore appears as it was at that point. If the backup,
+/// or any of the archives, is invalid, then every key which has been
+/// written is removed again, leaving the datastore empty.
+pub(super) async fn recover<S>(
+	ds: &Datastore,
+	stream: S,
+	archives: Vec<S>,
+	until: ShowSince,
+) -> Result<Manifest, Error>
+where
+	S: Stream<Item = Result<Bytes, Error>>,
+{
+	// Ensure that no data will be overwritten
+	let existing = existing(ds).await?;
+	// Restore the backup and replay the archives
+	let res = replay(ds, stream, archives, until).await;
+	// Remove the keys if the datastore was not recovered
+	if res.is_err() {
+		rollback(ds, &existing).await?;
+	}
+	res
+}
+
+/// Restores a binary backup, and replays archived change feeds on top of it
+async fn replay<S>(
+	ds: &Datastore,
+	stream: S,
+	archives: Vec<S>,
+	until: ShowSince,
+) -> Result<Manifest, Error>
+where
+	S: Stream<Item = Result<Bytes, Error>>,
+{
+	// Restore the full backup
+	let manifest = load(ds, stream, |_| Ok(())).await?;
+	if manifest.changes {
+		return Err(Error::InvalidBackup("the backup only contains change feeds".to_string()));
+	}
+	// Restore the archived change feeds, noting the last change in each database
+	let mut dbs = BTreeMap::<(String, String), u64>::new();
+	let mut archived = None;
+	for archive in archives {
+		let res = load(ds, archive, |k| {
+			if let Ok(cf) = Cf::decode(k) {
+				if Cf::new(cf.ns, cf.db, cf.vs, cf.tb).encode()? == k {
+					let last = dbs.entry((cf.ns.to_owned(), cf.db.to_owned())).or_default();
+					*last = cf.vs.into_u64_lossy().max(*last);
+					return Ok(());
+				}
+			}
+			if let Ok(ts) = Ts::decode(k) {
+				if Ts::new(ts.ns, ts.db, ts.ts).encode()? == k {
+					return Ok(());
+				}
+			}
+			Err(Error::InvalidBackup("the archive contains more than change feeds".to_string()))
+		})
+		.await?;
+		if !res.changes {
+			return Err(Error::InvalidBackup(
+				"the archive is not a change feed backup".to_string(),
+			));
+		}
+		archived = archived.max(Some(res.created));
+	}
+	// Replay the change sets for each database
+	for ((ns, db), last) in dbs {
+		let key = crate::key::database::vs::new(&ns, &db);
+		// Work out which changes are already in the full backup
+		let txn = ds.transaction(Write, Optimistic).await?;
+		let base = match txn.get(key.clone(), None).await? {
+			Some(v) => VersionStamp::from_slice(&v)?.into_u64_lossy(),
+			None => 0,
+		};
+		// Work out which changes were made before the point in time
+		let end = match &until {
+			ShowSince::Versionstamp(vs) => vs.saturating_add(1),
+			// All archived changes were made before the archives were taken
+			ShowSince::Timestamp(ts) if archived.as_ref().is_some_and(|v| ts >= v) => last + 1,
+			ShowSince::Timestamp(ts) => {
+				let ts = ts.0.timestamp() as u64;
+				let mut lock = txn.lock().await;
+				match lock.get_versionstamp_from_timestamp(ts, &ns, &db).await? {
+					Some(vs) => vs.into_u64_lossy(),
+					None => 1,
+				}
+			}
+		};
+		if end <= base {
+			txn.cancel().await?;
+			return Err(Error::RecoveryBeforeBackup(db));
+		}
+		// Ensure that replayed changes don't overwrite the archived changes
+		txn.set(key.clone(), VersionStamp::from_u64(base.max(last)).to_vec(), None).await?;
+		txn.commit().await?;
+		// Generate the SQL for the changes to replay, applying each change
+		// set as it is generated, so that the changes are never all buffered
+		let (snd, rcv) = async_channel::bounded(1);
+		let txn = ds.transaction(Read, Optimistic).await?;
+		let since = ShowSince::Versionstamp(base + 1);
+		let sync = txn.sync(&ns, &db, since, Some(VersionStamp::from_u64(end)), snd);
+		let apply = async {
+			let sess = Session::owner().with_ns(&ns).with_db(&db);
+			// The options which precede the first change set
+			let mut head = None;
+			let mut sql = Vec::new();
+			while let Ok(v) = rcv.recv().await {
+				if head.is_none() && v == b"BEGIN;\n" {
+					head = Some(sql.clone());
+				}
+				let commit = v == b"COMMIT;\n";
+				sql.extend(v);
+				// Apply each change set once it is complete
+				if commit {
+					let text =
+						std::str::from_utf8(&sql).map_err(|e| Error::Internal(e.to_string()))?;
+					for res in ds.execute(text, &sess, None).await? {
+						res.result?;
+					}
+					sql = head.clone().unwrap_or_default();
+				}
+			}
+			Ok(())
+		};
+		let res = futures::try_join!(sync, apply);
+		txn.cancel().await?;
+		res?;
+		// Remove any changes after the point in time, including replayed changes
+		let txn = ds.transaction(Write, Optimistic).await?;
+		let beg = crate::key::change::prefix_ts(&ns, &db, VersionStamp::from_u64(end))?;
+		txn.delr(beg..crate::key::change::suffix(&ns, &db)?).await?;
+		let beg = crate::key::database::ts::prefix(&ns, &db)?;
+		let rng = beg..crate::key::database::ts::suffix(&ns, &db)?;
+		for (k, v) in txn.getr(rng, None).await? {
+			if VersionStamp::from_slice(&v)?.into_u64_lossy() >= end {
+				txn.del(k).await?;
+			}
+		}
+		txn.set(key, VersionStamp::from_u64(end - 1).to_vec(), None).await?;
+		txn.commit().await?;
+	}
+	// Return the full backup manifest
+	Ok(manifest)
+}
+
+/// Fetches the keys in a datastore which a backup is about to be restored into.
+///
+/// The datastore must not contain any namespaces, but can contain keys
+/// which are used internally, such as the storage version and the nodes
+/// in the cluster, which are put back if the restore is rolled back.
+async fn existing(ds: &Datastore) -> Result<BTreeMap<Vec<u8>, Vec<u8>>, Error> {
+	let txn = ds.transaction(Read, Optimistic).await?;
+	let res = async {
+		if !txn.all_ns().await?.is_empty() {
+			return Err(Error::RestoreNotEmpty);
+		}
+		txn.getr(vec![0x00]..vec![0xff], None).await
+	}
+	.await;
+	txn.cancel().await?;
+	Ok(res?.into_iter().collect())
+}
+
+/// Removes every key written by a failed restore, and puts back the keys
+/// which were in the datastore before the restore was started.
+async fn rollback(ds: &Datastore, existing: &BTreeMap<Vec<u8>, Vec<u8>>) -> Result<(), Error> {
+	// Remove the keys in batches, each in a separate transaction
+	let mut next = Some(vec![0x00]..vec![0xff]);
+	while let Some(rng) = next {
+		let txn = ds.transaction(Write, Optimistic).await?;
+		let res = async {
+			let batch = txn.batch_keys(rng, *EXPORT_BATCH_SIZE, None).await?;
+			for k in batch.result {
+				if !existing.contains_key(&k) {
+					txn.del(k).await?;
+				}
+			}
+			Ok::<_, Error>(batch.next)
+		}
+		.await;
+		match res {
+			Ok(v) => {
+				txn.commit().await?;
+				next = v;
+			}
+			Err(e) => {
+				txn.cancel().await?;
+				return Err(e);
+			}
+		}
+	}
+	// Put back the keys which were overwritten
+	let txn = ds.transaction(Write, Optimistic).await?;
+	for (k, v) in existing {
+		if let Err(e) = txn.set(k.clone(), v.clone(), None).await {
+			txn.cancel().await?;
+			return Err(e);
+		}
+	}
+	txn.commit().await
+}
+
+/// Writes all of the keys in a binary backup, checking each key before it is written
+async fn load<S, F>(ds: &Datastore, stream: S, mut check: F) -> Result<Manifest, Error>
+where
+	S: Stream<Item = Result<Bytes, Error>>,
+	F: FnMut(&[u8]) -> Result<(), Error>,
+{
+	let mut reader = Reader {
+		stream: pin!(stream),
+		buf: BytesMut::new(),
+	};
+	// Check the backup header
+	reader.header().await?;
+	// Write each batch of keys in a separate transaction
+	let mut hasher = blake3::Hasher::new();
+	let mut keys = 0;
+	loop {
+		match reader.frame().await? {
+			(BATCH, mut payload) => {
+				hasher.update(&payload);
+				let txn = ds.transaction(Write, Optimistic).await?;
+				while !payload.is_empty() {
+					let res = match (take(&mut payload), take(&mut payload)) {
+						(Ok(k), Ok(v)) => match check(&k) {
+							Ok(()) => txn.set(k.to_vec(), v.to_vec(), None).await,
+							Err(e) => Err(e),
+						},
+						(Err(e), _) | (_, Err(e)) => Err(e),
+					};
+					if let Err(e) = res {
+						txn.cancel().await?;
+						return Err(e);
+					}
+					keys += 1;
+				}
+				txn.commit().await?;
+			}
+			(MANIFEST, payload) => {
+				let manifest: Manifest = revision::from_slice(&payload)?;
+				// Check that the full backup was restored
+				if manifest.keys != keys
+					|| manifest.checksum != hasher.finalize().to_hex().to_string()
+				{
+					return Err(Error::InvalidBackup(
+						"the restored data does not match the backup manifest".to_string(),
+					));
+				}
+				return Ok(manifest);
+			}
+			(kind, _) => {
+				return Err(Error::InvalidBackup(format!("the frame type {kind} is unknown")));
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::dbs::Session;
+	use crate::sql::Value;
+	use crate::syn::Parse;
+
+	async fn backup(ds: &Datastore) -> Vec<u8> {
+		let (snd, rcv) = async_channel::unbounded();
+		ds.backup(snd).await.unwrap().await.unwrap();
+		let mut out = Vec::new();
+		while let Ok(v) = rcv.try_recv() {
+			out.extend(v);
+		}
+		out
+	}
+
+	async fn backup_changes(ds: &Datastore) -> Vec<u8> {
+		let (snd, rcv) = async_channel::unbounded();
+		ds.backup_changes(snd).await.unwrap().await.unwrap();
+		let mut out = Vec::new();
+		while let Ok(v) = rcv.try_recv() {
+			out.extend(v);
+		}
+		out
+	}
+
+	async fn keys(ds: &Datastore) -> Vec<(Vec<u8>, Vec<u8>)> {
+		let txn = ds.transaction(Read, Optimistic).await.unwrap();
+		let res = txn.getr(vec![0x00]..vec![0xff], None).await.unwrap();
+		txn.cancel().await.unwrap();
+		res
+	}
+
+	fn stream(data: Vec<u8>) -> impl Stream<Item = Result<Bytes, Error>> {
+		// Split the backup into small chunks, to check frames are read across chunks
+		let chunks: Vec<_> = data.chunks(7).map(|c| Ok(Bytes::copy_from_slice(c))).collect();
+		futures::stream::iter(chunks)
+	}
+
+	#[tokio::test]
+	async fn backup_and_restore() {
+		let ses = Session::owner().with_ns("test").with_db("test");
+		let src = Datastore::new("memory").await.unwrap();
+		src.execute(
+			"DEFINE TABLE person; DEFINE INDEX name ON person FIELDS name; \
+			 CREATE person:tobie SET name = 'Tobie'; \
+			 CREATE person:jaime SET name = 'Jaime'; \
+			 RELATE person:tobie->knows->person:jaime",
+			&ses,
+			None,
+		)
+		.await
+		.unwrap();
+		let data = backup(&src).await;
+		// Restore the backup into a new datastore
+		let dst = Datastore::new("memory").await.unwrap();
+		let manifest = dst.restore(stream(data.clone())).await.unwrap();
+		assert_eq!(manifest.engine, "memory");
+		assert!(manifest.keys > 0);
+		// The restored datastore contains the same data
+		let sql =
+			"SELECT * FROM person WHERE name = 'Tobie'; SELECT ->knows->person FROM person:tobie";
+		let mut expected = src.execute(sql, &ses, None).await.unwrap();
+		let mut actual = dst.execute(sql, &ses, None).await.unwrap();
+		for _ in 0..2 {
+			let expected = expected.remove(0).result.unwrap();
+			assert_ne!(expected, Value::parse("[]"));
+			assert_eq!(actual.remove(0).result.unwrap(), expected);
+		}
+		// A backup can't be restored over existing data
+		let res = dst.restore(stream(data)).await;
+		assert!(matches!(res, Err(Error::RestoreNotEmpty)), "{res:?}");
+	}
+
+	#[tokio::test]
+	async fn restore_invalid() {
+		let ses = Session::owner().with_ns("test").with_db("test");
+		let src = Datastore::new("memory").await.unwrap();
+		src.execute("CREATE person:tobie", &ses, None).await.unwrap();
+		let data = backup(&src).await;
+		// Data which is not a backup is rejected
+		let dst = Datastore::new("memory").await.unwrap();
+		let res = dst.restore(stream(b"DEFINE TABLE person;".to_vec())).await;
+		assert!(matches!(res, Err(Error::InvalidBackup(_))), "{res:?}");
+		// A corrupted backup is rejected
+		let mut corrupt = data.clone();
+		corrupt[20] ^= 0xff;
+		let res = dst.restore(stream(corrupt)).await;
+		assert!(matches!(res, Err(Error::InvalidBackup(_))), "{res:?}");
+		// An incomplete backup is rejected
+		let res = dst.restore(stream(data[..data.len() - 10].to_vec())).await;
+		assert!(matches!(res, Err(Error::InvalidBackup(_))), "{res:?}");
+	}
+
+	#[tokio::test]
+	async fn restore_truncated() {
+		let ses = Session::owner().with_ns("test").with_db("test");
+		let src = Datastore::new("memory").await.unwrap();
+		src.execute("CREATE person:tobie; CREATE person:jaime", &ses, None).await.unwrap();
+		let data = backup(&src).await;
+		let dst = Datastore::new("memory").await.unwrap();
+		let empty = keys(&dst).await;
+		// A backup which ends within its manifest is only detected after every batch is read
+		let res = dst.restore(stream(data[..data.len() - 10].to_vec())).await;
+		assert!(matches!(res, Err(Error::InvalidBackup(_))), "{res:?}");
+		// None of the keys in the truncated backup remain
+		assert_eq!(keys(&dst).await, empty);
+		// The complete backup can still be restored
+		dst.restore(stream(data)).await.unwrap();
+		let mut res = dst.execute("SELECT VALUE id FROM person", &ses, None).await.unwrap();
+		assert_eq!(res.remove(0).result.unwrap(), Value::parse("[person:jaime, person:tobie]"));
+	}
+
+	#[tokio::test]
+	async fn verify_backup() {
+		let ses = Session::owner().with_ns("test").with_db("test");
+		let src = Datastore::new("memory").await.unwrap();
+		src.execute("CREATE person:tobie; CREATE person:jaime", &ses, None).await.unwrap();
+		let data = backup(&src).await;
+		// A complete backup matches its manifest
+		let manifest = verify(stream(data.clone())).await.unwrap();
+		assert!(manifest.keys > 0);
+		// A truncated backup is detected
+		let res = verify(stream(data[..data.len() - 10].to_vec())).await;
+		assert!(matches!(res, Err(Error::InvalidBackup(_))), "{res:?}");
+		// A backup which ends before its manifest is detected
+		let res = verify(stream(data[..20].to_vec())).await;
+		assert!(matches!(res, Err(Error::InvalidBackup(_))), "{res:?}");
+		// Data after the manifest is detected
+		let mut long = data.clone();
+		long.push(0);
+		let res = verify(stream(long)).await;
+		assert!(matches!(res, Err(Error::InvalidBackup(_))), "{res:?}");
+	}
+
+	#[tokio::test]
+	async fn recover_to_point_in_time() {
+		let ses = Session::owner().with_ns("test").with_db("test");
+		let src = Datastore::new("memory").await.unwrap();
+		src.execute(
+			"DEFINE TABLE person CHANGEFEED 1d; DEFINE INDEX name ON person FIELDS name; \
+			 CREATE person:tobie SET name = 'Tobie';",
+			&ses,
+			None,
+		)
+		.await
+		.unwrap();
+		src.changefeed_versionstamp(1000).await.unwrap();
+		let data = backup(&src).await;
+		// Make some changes after the backup was taken
+		src.execute(
+			"CREATE person:jaime SET name = 'Jaime'; UPDATE person:tobie SET name = 'Tobias';",
+			&ses,
+			None,
+		)
+		.await
+		.unwrap();
+		src.changefeed_versionstamp(2000).await.unwrap();
+		let sql = "SELECT * FROM person WHERE name = 'Tobias'; SELECT * FROM person";
+		let mut expected = src.execute(sql, &ses, None).await.unwrap();
+		// Accidentally delete all of the data
+		src.execute("DELETE person", &ses, None).await.unwrap();
+		src.changefeed_versionstamp(3000).await.unwrap();
+		let changes = backup_changes(&src).await;
+		// Recover to the point in time before the data was deleted
+		let until = ShowSince::Timestamp(Datetime::try_from("1970-01-01T00:40:00Z").unwrap());
+		let dst = Datastore::new("memory").await.unwrap();
+		dst.recover(stream(data.clone()), vec![stream(changes.clone())], until).await.unwrap();
+		let mut actual = dst.execute(sql, &ses, None).await.unwrap();
+		for _ in 0..2 {
+			let expected = expected.remove(0).result.unwrap();
+			assert_ne!(expected, Value::parse("[]"));
+			assert_eq!(actual.remove(0).result.unwrap(), expected);
+		}
+		// The deletion is no longer in the change feed, and new changes can be made
+		let mut res = dst
+			.execute("CREATE person:john; SHOW CHANGES FOR TABLE person SINCE 0", &ses, None)
+			.await
+			.unwrap();
+		res.remove(0).result.unwrap();
+		let changes = res.remove(0).result.unwrap().to_string();
+		assert!(!changes.contains("delete"), "{changes}");
+		assert!(changes.contains("person:john"), "{changes}");
+		// A datastore can't be recovered to before the backup was taken
+		let until = ShowSince::Timestamp(Datetime::try_from("1970-01-01T00:10:00Z").unwrap());
+		let dst = Datastore::new("memory").await.unwrap();
+		let empty = keys(&dst).await;
+		let res = dst
+			.recover(stream(data.clone()), vec![stream(backup_changes(&src).await)], until)
+			.await;
+		assert!(matches!(res, Err(Error::RecoveryBeforeBackup(_))), "{res:?}");
+		assert_eq!(keys(&dst).await, empty);
+		// A full backup is not an archive of change feeds
+		let until = ShowSince::Versionstamp(u64::MAX - 1);
+		let res =
+			dst.recover(stream(data.clone()), vec![stream(data.clone())], until.clone()).await;
+		assert!(matches!(res, Err(Error::InvalidBackup(_))), "{res:?}");
+		assert_eq!(keys(&dst).await, empty);
+		// An archive of change feeds is not a full backup
+		let changes = backup_changes(&src).await;
+		let res = dst.recover(stream(changes.clone()), vec![stream(changes)], until).await;
+		assert!(matches!(res, Err(Error::InvalidBackup(_))), "{res:?}");
+		assert_eq!(keys(&dst).await, empty);
+		// The datastore can still be restored into
+		dst.restore(stream(data)).await.unwrap();
+	}
+}
@@ -1,7 +1,10 @@
 use crate::dbs::node::Node;
 use crate::err::Error;
 use crate::sql::statements::define::DefineConfigStatement;
-use crate::sql::statements::define::{ApiDefinition, BucketDefinition, DefineSequenceStatement};
+use crate::sql::statements::define::{
+	ApiDefinition, BucketDefinition, DefineConsumerStatement, DefineSequenceStatement,
+	DefineSinkStatement,
+};
 use crate::sql::statements::AccessGrant;
 use crate::sql::statements::DefineAccessStatement;
 use crate::sql::statements::DefineAnalyzerStatement;
@@ -69,6 +72,10 @@ pub(crate) enum Entry {
 	Pas(Arc<[DefineParamStatement]>),
 	/// A slice of DefineSequenceStatement specified on a namespace.
 	Sqs(Arc<[DefineSequenceStatement]>),
+	/// A slice of DefineConsumerStatement specified on a database.
+	Cns(Arc<[DefineConsumerStatement]>),
+	/// A slice of DefineSinkStatement specified on a database.
+	Sks(Arc<[DefineSinkStatement]>),
 	/// A slice of DefineEventStatement specified on a table.
 	Evs(Arc<[DefineEventStatement]>),
 	/// A slice of DefineFieldStatement specified on a table.
@@ -220,6 +227,22 @@ impl Entry {
 			_ => Err(fail!("Unable to convert type into Entry::Sqs")),
 		}
 	}
+	/// Converts this cache entry into a slice of [`DefineConsumerStatement`].
+	/// This panics if called on a cache entry that is not an [`Entry::Cns`].
+	pub(crate) fn try_into_cns(self) -> Result<Arc<[DefineConsumerStatement]>, Error> {
+		match self {
+			Entry::Cns(v) => Ok(v),
+			_ => Err(fail!("Unable to convert type into Entry::Cns")),
+		}
+	}
+	/// Converts this cache entry into a slice of [`DefineSinkStatement`].
+	/// This panics if called on a cache entry that is not an [`Entry::Sks`].
+	pub(crate) fn try_into_sks(self) -> Result<Arc<[DefineSinkStatement]>, Error> {
+		match self {
+			Entry::Sks(v) => Ok(v),
+			_ => Err(fail!("Unable to convert type into Entry::Sks")),
+		}
+	}
 	/// Converts this cache entry into a slice of [`DefineFunctionStatement`].
 	/// This panics if called on a cache entry that is not an [`Entry::Fcs`].
 	pub(crate) fn try_into_fcs(self) -> Result<Arc<[DefineFunctionStatement]>, Error> {
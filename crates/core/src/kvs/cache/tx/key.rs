@@ -46,6 +46,10 @@ pub(crate) enum Key {
 	Tbs(String, String),
 	/// A cache key for sequences (on a database)
 	Seq(String, String),
+	/// A cache key for consumers (on a database)
+	Cns(String, String),
+	/// A cache key for sinks (on a database)
+	Sks(String, String),
 	/// A cache key for events (on a table)
 	Evs(String, String, String),
 	/// A cache key for fieds (on a table)
@@ -96,6 +100,10 @@ pub(crate) enum Key {
 	Pa(String, String, String),
 	/// A cache key for a sequence (on a database)
 	Sq(String, String, String),
+	/// A cache key for a consumer (on a database)
+	Cn(String, String, String),
+	/// A cache key for a sink (on a database)
+	Sk(String, String, String),
 	/// A cache key for a table
 	Tb(String, String, String),
 	/// A cache key for an event (on a table)
@@ -133,6 +141,8 @@ impl<'a> From<Lookup<'a>> for Key {
 			Lookup::Cgs(a, b) => Key::Cgs(a.to_string(), b.to_string()),
 			Lookup::Pas(a, b) => Key::Pas(a.to_string(), b.to_string()),
 			Lookup::Sqs(a, b) => Key::Seq(a.to_string(), b.to_string()),
+			Lookup::Cns(a, b) => Key::Cns(a.to_string(), b.to_string()),
+			Lookup::Sks(a, b) => Key::Sks(a.to_string(), b.to_string()),
 			Lookup::Tbs(a, b) => Key::Tbs(a.to_string(), b.to_string()),
 			Lookup::Evs(a, b, c) => Key::Evs(a.to_string(), b.to_string(), c.to_string()),
 			Lookup::Fds(a, b, c) => Key::Fds(a.to_string(), b.to_string(), c.to_string()),
@@ -160,6 +170,8 @@ impl<'a> From<Lookup<'a>> for Key {
 			Lookup::Cg(a, b, c) => Key::Cg(a.to_string(), b.to_string(), c.to_string()),
 			Lookup::Pa(a, b, c) => Key::Pa(a.to_string(), b.to_string(), c.to_string()),
 			Lookup::Sq(a, b,c) => Key::Sq(a.to_string(), b.to_string(), c.to_string()),
+			Lookup::Cn(a, b, c) => Key::Cn(a.to_string(), b.to_string(), c.to_string()),
+			Lookup::Sk(a, b, c) => Key::Sk(a.to_string(), b.to_string(), c.to_string()),
 			Lookup::Tb(a, b, c) => Key::Tb(a.to_string(), b.to_string(), c.to_string()),
 			Lookup::Ev(a, b, c, d) => Key::Ev(a.to_string(), b.to_string(), c.to_string(), d.to_string()),
 			Lookup::Fd(a, b, c, d) => Key::Fd(a.to_string(), b.to_string(), c.to_string(), d.to_string()),
@@ -45,6 +45,10 @@ pub(crate) enum Lookup<'a> {
 	Pas(&'a str, &'a str),
 	/// A cache key for sequences (on a database)
 	Sqs(&'a str, &'a str),
+	/// A cache key for consumers (on a database)
+	Cns(&'a str, &'a str),
+	/// A cache key for sinks (on a database)
+	Sks(&'a str, &'a str),
 	/// A cache key for tables
 	Tbs(&'a str, &'a str),
 	/// A cache key for events (on a table)
@@ -97,6 +101,10 @@ pub(crate) enum Lookup<'a> {
 	Pa(&'a str, &'a str, &'a str),
 	/// A cache key for a sequence (on a database)
 	Sq(&'a str, &'a str, &'a str),
+	/// A cache key for a consumer (on a database)
+	Cn(&'a str, &'a str, &'a str),
+	/// A cache key for a sink (on a database)
+	Sk(&'a str, &'a str, &'a str),
 	/// A cache key for a table
 	Tb(&'a str, &'a str, &'a str),
 	/// A cache key for an event (on a table)
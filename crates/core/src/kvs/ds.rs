@@ -1,10 +1,17 @@
+use super::backup;
 use super::export;
 use super::tr::Transactor;
 use super::tx::Transaction;
 use super::version::Version;
-use crate::buc::BucketConnections;
+use crate::api::limit::NodeRateLimiter;
+use crate::buc::store::{ObjectKey, ObjectStream, PutOptions};
+use crate::buc::{BucketConnections, BucketController, FileRange, FileStream};
 use crate::cf;
-use crate::ctx::MutableContext;
+use crate::cf::ChangeBatch;
+use crate::cnf::{
+	CHANGEFEED_STREAM_INTERVAL, EXPORT_BATCH_SIZE, INTERACTIVE_TRANSACTION_IDLE_TIMEOUT,
+};
+use crate::ctx::{Context, MutableContext};
 #[cfg(feature = "jwks")]
 use crate::dbs::capabilities::NetTarget;
 use crate::dbs::capabilities::{
@@ -12,12 +19,13 @@ use crate::dbs::capabilities::{
 };
 use crate::dbs::node::Timestamp;
 use crate::dbs::{
-	Attach, Capabilities, Executor, Notification, Options, Response, Session, Variables,
+	Attach, Capabilities, Executor, Notification, Options, QueryType, Response, Session, Variables,
 };
 use crate::err::Error;
+use crate::exe::try_join_all_buffered;
 #[cfg(feature = "jwks")]
 use crate::iam::jwks::JwksCache;
-use crate::iam::{Action, Auth, Error as IamError, Resource, Role};
+use crate::iam::{Action, Auth, Error as IamError, Resource, ResourceKind, Role};
 use crate::idx::trees::store::IndexStores;
 use crate::kvs::cache::ds::DatastoreCache;
 use crate::kvs::clock::SizedClock;
@@ -25,8 +33,10 @@ use crate::kvs::clock::SizedClock;
 use crate::kvs::clock::SystemClock;
 #[cfg(not(target_family = "wasm"))]
 use crate::kvs::index::IndexBuilder;
+use crate::kvs::interactive::{Interactive, Interactives};
 use crate::kvs::sequences::Sequences;
 use crate::kvs::{LockType, LockType::*, TransactionType, TransactionType::*};
+use crate::sql::statements::show::ShowSince;
 use crate::sql::FlowResultExt as _;
 use crate::sql::{statements::DefineUserStatement, Base, Query, Value};
 use crate::syn;
@@ -94,8 +104,12 @@ pub struct Datastore {
 	temporary_directory: Option<Arc<PathBuf>>,
 	// Map of bucket connections
 	buckets: Arc<BucketConnections>,
+	// The rate limiter for API endpoints on this node
+	rate_limiter: Arc<NodeRateLimiter>,
 	// The sequences
 	sequences: Sequences,
+	// The interactive transactions which are currently open
+	interactives: Interactives,
 }
 
 #[derive(Clone)]
@@ -285,7 +299,9 @@ impl Datastore {
 					warn!("file:// is deprecated, please use surrealkv:// or rocksdb://");
 					let s = s.trim_start_matches("file://");
 					let s = s.trim_start_matches("file:");
-					let v = super::rocksdb::Datastore::new(s).await.map(DatastoreFlavor::RocksDB);
+					let v = super::rocksdb::Datastore::new(s, false)
+						.await
+						.map(DatastoreFlavor::RocksDB);
 					let c = clock.unwrap_or_else(|| Arc::new(SizedClock::system()));
 					info!(target: TARGET, "Started kvs store at {}", path);
 					Ok((v, c))
@@ -294,18 +310,19 @@ impl Datastore {
                 return Err(Error::Ds("Cannot connect to the `rocksdb` storage engine as it is not enabled in this build of SurrealDB".to_owned()));
 			}
 			// Parse and initiate a RocksDB datastore
-			s if s.starts_with("rocksdb:") => {
+			s if s.starts_with("rocksdb") => {
 				#[cfg(feature = "kv-rocksdb")]
 				{
 					// Create a new blocking threadpool
 					super::threadpool::initialise();
 					// Initialise the storage engine
-					info!(target: TARGET, "Starting kvs store at {}", path);
-					let s = s.trim_start_matches("rocksdb://");
-					let s = s.trim_start_matches("rocksdb:");
-					let v = super::rocksdb::Datastore::new(s).await.map(DatastoreFlavor::RocksDB);
+					info!(target: TARGET, "Starting kvs store at {}", s);
+					let (path, enable_versions) = super::rocksdb::Datastore::parse_start_string(s)?;
+					let v = super::rocksdb::Datastore::new(path, enable_versions)
+						.await
+						.map(DatastoreFlavor::RocksDB);
 					let c = clock.unwrap_or_else(|| Arc::new(SizedClock::system()));
-					info!(target: TARGET, "Started kvs store at {}", path);
+					info!(target: TARGET, "Started kvs store at {} with versions {}", path, if enable_versions { "enabled" } else { "disabled" });
 					Ok((v, c))
 				}
 				#[cfg(not(feature = "kv-rocksdb"))]
@@ -406,7 +423,9 @@ impl Datastore {
 				temporary_directory: None,
 				cache: Arc::new(DatastoreCache::new()),
 				buckets: Arc::new(DashMap::new()),
+				rate_limiter: Arc::new(NodeRateLimiter::new()),
 				sequences: Sequences::new(tf),
+				interactives: Interactives::default(),
 			}
 		})
 	}
@@ -431,7 +450,9 @@ impl Datastore {
 			temporary_directory: self.temporary_directory,
 			cache: Arc::new(DatastoreCache::new()),
 			buckets: Arc::new(DashMap::new()),
+			rate_limiter: Arc::new(NodeRateLimiter::new()),
 			sequences: Sequences::new(self.transaction_factory.clone()),
+			interactives: Interactives::default(),
 			transaction_factory: self.transaction_factory,
 		}
 	}
@@ -717,6 +738,93 @@ impl Datastore {
 		Ok(())
 	}
 
+	/// Run the background task to deliver changes to all change data sinks
+	#[instrument(level = "trace", target = "surrealdb::core::kvs::ds", skip(self))]
+	pub async fn sink_process(&self) -> Result<(), Error> {
+		// Output function invocation details to logs
+		trace!(target: TARGET, "Delivering changes to sinks");
+		// Fetch all sinks in all databases
+		let txn = self.transaction(Read, Optimistic).await?;
+		let res = async {
+			let mut sinks = Vec::new();
+			for ns in txn.all_ns().await?.iter() {
+				for db in txn.all_db(&ns.name).await?.iter() {
+					for sk in txn.all_db_sinks(&ns.name, &db.name).await?.iter() {
+						sinks.push((ns.name.clone(), db.name.clone(), sk.name.to_raw()));
+					}
+				}
+			}
+			Ok::<_, Error>(sinks)
+		}
+		.await;
+		txn.cancel().await?;
+		// Deliver the changes for the sinks concurrently,
+		// so that one failing sink does not hold back the others
+		let futs = res?.into_iter().map(|(ns, db, sk)| async move {
+			if let Err(e) = crate::cf::sink::deliver(self, &ns, &db, &sk).await {
+				warn!(target: TARGET, "Failed to deliver changes to sink {ns}:{db}:{sk}: {e}");
+			}
+			Ok::<_, Error>(())
+		});
+		try_join_all_buffered(futs).await?;
+		// Everything ok
+		Ok(())
+	}
+
+	/// Run the background task to prune version history outside of table retention policies
+	#[instrument(level = "trace", target = "surrealdb::core::kvs::ds", skip(self))]
+	pub async fn versions_process(&self) -> Result<(), Error> {
+		// Output function invocation details to logs
+		trace!(target: TARGET, "Pruning table version history");
+		// Fetch all tables with a retention policy in all databases
+		let txn = self.transaction(Read, Optimistic).await?;
+		let res = async {
+			let mut tables = Vec::new();
+			for ns in txn.all_ns().await?.iter() {
+				for db in txn.all_db(&ns.name).await?.iter() {
+					for tb in txn.all_tb(&ns.name, &db.name, None).await?.iter() {
+						if let Some(v) = tb.versions {
+							tables.push((ns.name.clone(), db.name.clone(), tb.name.to_raw(), v));
+						}
+					}
+				}
+			}
+			Ok::<_, Error>(tables)
+		}
+		.await;
+		txn.cancel().await?;
+		// Prune the history of each table, so that
+		// one failing table does not hold back the others
+		for (ns, db, tb, retention) in res? {
+			match self.prune_history(&ns, &db, &tb, retention).await {
+				Ok(_) => {}
+				// The storage engine does not keep versions
+				Err(Error::UnsupportedVersionedQueries) => break,
+				Err(e) => {
+					warn!(target: TARGET, "Failed to prune version history of {ns}:{db}:{tb}: {e}")
+				}
+			}
+		}
+		// Everything ok
+		Ok(())
+	}
+
+	/// Run the background task to cancel expired interactive transactions
+	#[instrument(level = "trace", target = "surrealdb::core::kvs::ds", skip(self))]
+	pub async fn interactive_transaction_expire(&self) -> Result<(), Error> {
+		// Output function invocation details to logs
+		trace!(target: TARGET, "Cancelling expired interactive transactions");
+		// Remove any transactions which were abandoned
+		let idle = Duration::from_secs(*INTERACTIVE_TRANSACTION_IDLE_TIMEOUT);
+		let expired = self.interactives.expire(idle, self.transaction_timeout);
+		// Roll back the changes in the expired transactions
+		for txn in expired {
+			let _ = txn.txn().cancel().await;
+		}
+		// Everything ok
+		Ok(())
+	}
+
 	/// Run the datastore shutdown tasks, perfoming any necessary cleanup
 	#[instrument(level = "trace", target = "surrealdb::core::kvs::ds", skip(self))]
 	pub async fn shutdown(&self) -> Result<(), Error> {
@@ -946,6 +1054,162 @@ impl Datastore {
 		Executor::execute(self, ctx.freeze(), opt, ast).await
 	}
 
+	/// Start an interactive transaction, which is held open across
+	/// multiple queries until it is committed or cancelled
+	///
+	/// ```rust,no_run
+	/// use surrealdb_core::kvs::Datastore;
+	/// use surrealdb_core::err::Error;
+	/// use surrealdb_core::dbs::Session;
+	///
+	/// #[tokio::main]
+	/// async fn main() -> Result<(), Error> {
+	///     let ds = Datastore::new("memory").await?;
+	///     let ses = Session::owner().with_ns("test").with_db("test");
+	///     let txn = ds.begin(&ses).await?;
+	///     ds.execute_in_transaction(txn, "CREATE person", &ses, None).await?;
+	///     ds.commit(txn, &ses).await?;
+	///     Ok(())
+	/// }
+	/// ```
+	#[instrument(level = "debug", target = "surrealdb::core::kvs::ds", skip_all)]
+	pub async fn begin(&self, sess: &Session) -> Result<Uuid, Error> {
+		// Check if the session has expired
+		if sess.expired() {
+			return Err(Error::ExpiredSession);
+		}
+		// Check if anonymous actors can execute queries when auth is enabled
+		self.check_anon(sess).map_err(|_| IamError::NotAllowed {
+			actor: "anonymous".to_string(),
+			action: "process".to_string(),
+			resource: "query".to_string(),
+		})?;
+		// Start a new write transaction
+		let txn = self.transaction(Write, Optimistic).await?;
+		// Store the transaction until it is finished
+		Ok(self.interactives.insert(Interactive::new(sess.au.clone(), txn)))
+	}
+
+	/// Parse and execute an SQL query within an interactive transaction
+	#[instrument(level = "debug", target = "surrealdb::core::kvs::ds", skip_all)]
+	pub async fn execute_in_transaction(
+		&self,
+		id: Uuid,
+		txt: &str,
+		sess: &Session,
+		vars: Variables,
+	) -> Result<Vec<Response>, Error> {
+		// Parse the SQL query text
+		let ast = syn::parse_with_capabilities(txt, &self.capabilities)?;
+		// Process the AST
+		self.process_in_transaction(id, ast, sess, vars).await
+	}
+
+	/// Execute a pre-parsed SQL query within an interactive transaction
+	#[instrument(level = "debug", target = "surrealdb::core::kvs::ds", skip_all)]
+	pub async fn process_in_transaction(
+		&self,
+		id: Uuid,
+		ast: Query,
+		sess: &Session,
+		vars: Variables,
+	) -> Result<Vec<Response>, Error> {
+		// Check if the session has expired
+		if sess.expired() {
+			return Err(Error::ExpiredSession);
+		}
+		// Fetch the transaction
+		let interactive = self.interactives.get(&id, &sess.au)?;
+		// Wait for any other queries in the transaction to finish
+		let mut state = interactive.lock().await;
+		if state.aborted {
+			return Err(Error::TxAborted);
+		}
+		// Create a new query options
+		let mut opt = self.setup_options(sess);
+		// Create a default context
+		let mut ctx = self.setup_ctx()?;
+		// Notifications are only sent once the transaction commits
+		if ctx.has_notifications() {
+			opt.sender = Some(interactive.sender());
+		}
+		// Start an execution context
+		sess.context(&mut ctx);
+		// Store the query variables
+		vars.attach(&mut ctx)?;
+		// Process all statements
+		let res =
+			Executor::execute_in_transaction(ctx.freeze(), opt, ast, interactive.txn()).await?;
+		// Roll back all changes if any statement failed
+		if res.iter().any(|r| r.result.is_err()) {
+			state.aborted = true;
+			let _ = interactive.txn().cancel().await;
+		}
+		// Keep the live queries which are started or killed on commit
+		for r in res.iter() {
+			if let (QueryType::Live | QueryType::Kill, Ok(Value::Uuid(id))) =
+				(r.query_type, &r.result)
+			{
+				state.live.push(Response {
+					time: Duration::ZERO,
+					result: Ok(Value::Uuid(*id)),
+					query_type: r.query_type,
+				});
+			}
+		}
+		state.last = trice::Instant::now();
+		Ok(res)
+	}
+
+	/// Commit an interactive transaction
+	///
+	/// Returns the responses of the LIVE and KILL statements executed within
+	/// the transaction, which only take effect now that it has been committed.
+	#[instrument(level = "debug", target = "surrealdb::core::kvs::ds", skip_all)]
+	pub async fn commit(&self, id: Uuid, sess: &Session) -> Result<Vec<Response>, Error> {
+		// Remove the transaction, so it can't be used again
+		let interactive = self.interactives.remove(&id, &sess.au)?;
+		// Wait for any other queries in the transaction to finish
+		let mut state = interactive.lock().await;
+		if state.aborted {
+			return Err(Error::TxAborted);
+		}
+		// Commit the transaction
+		let txn = interactive.txn();
+		let mut lock = txn.lock().await;
+		if let Err(e) = lock.complete_changes(false).await {
+			let _ = lock.cancel().await;
+			return Err(e);
+		}
+		lock.commit().await?;
+		// Send the buffered notifications
+		if let Some((sink, _)) = &self.notification_channel {
+			let recv = interactive.receiver();
+			while let Ok(notification) = recv.try_recv() {
+				if sink.send(notification).await.is_err() {
+					break;
+				}
+			}
+		}
+		// Return the live queries to start or kill
+		Ok(std::mem::take(&mut state.live))
+	}
+
+	/// Cancel an interactive transaction, rolling back all of its changes
+	#[instrument(level = "debug", target = "surrealdb::core::kvs::ds", skip_all)]
+	pub async fn cancel(&self, id: Uuid, sess: &Session) -> Result<(), Error> {
+		// Remove the transaction, so it can't be used again
+		let interactive = self.interactives.remove(&id, &sess.au)?;
+		// Wait for any other queries in the transaction to finish
+		let state = interactive.lock().await;
+		// An aborted transaction has already been cancelled
+		if !state.aborted {
+			interactive.txn().cancel().await?;
+		}
+		// Everything ok
+		Ok(())
+	}
+
 	/// Ensure a SQL [`Value`] is fully computed
 	///
 	/// ```rust,no_run
@@ -1182,6 +1446,254 @@ impl Datastore {
 		})
 	}
 
+	/// Performs a physical backup of the entire datastore
+	#[instrument(level = "debug", target = "surrealdb::core::kvs::ds", skip_all)]
+	pub async fn backup(
+		&self,
+		chn: Sender<Vec<u8>>,
+	) -> Result<impl Future<Output = Result<(), Error>>, Error> {
+		self.backup_with_options(false, chn).await
+	}
+
+	/// Performs a physical backup of the change feeds of every database
+	#[instrument(level = "debug", target = "surrealdb::core::kvs::ds", skip_all)]
+	pub async fn backup_changes(
+		&self,
+		chn: Sender<Vec<u8>>,
+	) -> Result<impl Future<Output = Result<(), Error>>, Error> {
+		self.backup_with_options(true, chn).await
+	}
+
+	async fn backup_with_options(
+		&self,
+		changes: bool,
+		chn: Sender<Vec<u8>>,
+	) -> Result<impl Future<Output = Result<(), Error>>, Error> {
+		// Create a new readonly transaction
+		let txn = self.transaction(Read, Optimistic).await?;
+		// Specify the storage engine
+		let engine = self.to_string();
+		// Return an async backup job
+		Ok(async move {
+			// Process the backup
+			let manifest = txn.backup(engine, changes, chn).await?;
+			// Log the backup details
+			trace!(target: TARGET, "Backed up {} keys ({} bytes)", manifest.keys, manifest.bytes);
+			// Everything ok
+			Ok(())
+		})
+	}
+
+	/// Restores a physical backup into an empty datastore
+	#[instrument(level = "debug", target = "surrealdb::core::kvs::ds", skip_all)]
+	pub async fn restore<S>(&self, stream: S) -> Result<backup::Manifest, Error>
+	where
+		S: Stream<Item = Result<Bytes, Error>>,
+	{
+		// Restore the data from the backup
+		let res = backup::restore(self, stream).await;
+		// Clear any cached definitions
+		self.cache.clear();
+		// Return the backup manifest
+		res
+	}
+
+	/// Restores a physical backup into an empty datastore, and recovers it
+	/// to a point in time by replaying archived change feeds on top of it
+	#[instrument(level = "debug", target = "surrealdb::core::kvs::ds", skip_all)]
+	pub async fn recover<S>(
+		&self,
+		stream: S,
+		archives: Vec<S>,
+		until: ShowSince,
+	) -> Result<backup::Manifest, Error>
+	where
+		S: Stream<Item = Result<Bytes, Error>>,
+	{
+		// Restore the data from the backup and archives
+		let res = backup::recover(self, stream, archives, until).await;
+		// Clear any cached definitions
+		self.cache.clear();
+		// Return the backup manifest
+		res
+	}
+
+	/// Performs an export of the changes made to a database since a given point as SQL
+	#[instrument(level = "debug", target = "surrealdb::core::kvs::ds", skip_all)]
+	pub async fn sync(
+		&self,
+		sess: &Session,
+		since: ShowSince,
+		chn: Sender<Vec<u8>>,
+	) -> Result<impl Future<Output = Result<(), Error>>, Error> {
+		// Check if the session has expired
+		if sess.expired() {
+			return Err(Error::ExpiredSession);
+		}
+		// Retrieve the provided NS and DB
+		let (ns, db) = crate::iam::check::check_ns_db(sess)?;
+		// Create a new readonly transaction
+		let txn = self.transaction(Read, Optimistic).await?;
+		// Return an async sync job
+		Ok(async move {
+			// Process the changes
+			txn.sync(&ns, &db, since, None, chn).await?;
+			// Everything ok
+			Ok(())
+		})
+	}
+
+	/// Streams the changes made to a database, or a table, as they are committed.
+	///
+	/// Change sets are sent from the specified point onwards, and any new change
+	/// sets are sent once they have been committed, until the channel is closed.
+	/// The channel is bounded, so a slow receiver holds back further reads, and
+	/// each change set includes the versionstamp from which to resume the stream.
+	#[instrument(level = "debug", target = "surrealdb::core::kvs::ds", skip_all)]
+	pub async fn changes(
+		&self,
+		sess: &Session,
+		tb: Option<String>,
+		since: ShowSince,
+		chn: Sender<Value>,
+	) -> Result<(), Error> {
+		// Retrieve the provided NS and DB
+		let (ns, db) = crate::iam::check::check_ns_db(sess)?;
+		// Check the permissions level
+		self.check(sess, Action::View, ResourceKind::Table.on_db(&ns, &db))?;
+		// Stream the change sets in batches
+		let mut since = since;
+		loop {
+			// Read the next batch in its own transaction
+			let txn = self.transaction(Read, Optimistic).await?;
+			let res = txn.changes(&ns, &db, tb.as_deref(), since.clone(), *EXPORT_BATCH_SIZE).await;
+			txn.cancel().await?;
+			let ChangeBatch {
+				changes,
+				last,
+				more,
+			} = res?;
+			// Resume after the last versionstamp which was read, even if it
+			// only changed other tables, so the stream does not read it again
+			if let Some(vs) = last.and_then(|vs| vs.next()) {
+				since = ShowSince::versionstamp(&vs);
+			}
+			// Send the change sets, stopping if the receiver has gone
+			for change in changes {
+				// Output the versionstamp which the stream can be resumed from
+				let vs = change.0.into_u64_lossy();
+				let mut value = change.into_value();
+				if let Value::Object(ref mut o) = value {
+					o.insert("versionstamp".to_owned(), vs.into());
+				}
+				if chn.send(value).await.is_err() {
+					return Ok(());
+				}
+			}
+			// Wait for new changes once all changes have been read
+			if !more {
+				let dur = Duration::from_millis(*CHANGEFEED_STREAM_INTERVAL);
+				#[cfg(target_family = "wasm")]
+				wasmtimer::tokio::sleep(dur).await;
+				#[cfg(not(target_family = "wasm"))]
+				tokio::time::sleep(dur).await;
+				// Stop if the receiver has gone
+				if chn.is_closed() {
+					return Ok(());
+				}
+			}
+		}
+	}
+
+	/// Streams a file from a bucket, checking the bucket permissions for this session
+	#[instrument(level = "debug", target = "surrealdb::core::kvs::ds", skip_all)]
+	pub async fn get_file_stream(
+		&self,
+		sess: &Session,
+		bucket: &str,
+		key: &str,
+		range: Option<FileRange>,
+	) -> Result<Option<FileStream>, Error> {
+		// Setup the context for the bucket operation
+		let (ctx, opt, txn) = self.setup_file_context(sess).await?;
+		// Create a new memory stack
+		let mut stack = TreeStack::new();
+		// Stream the file
+		let key = ObjectKey::from(key);
+		let res = stack
+			.enter(|stk| async {
+				let mut controller = BucketController::new(stk, &ctx, &opt, None, bucket).await?;
+				controller.get_stream(&key, range).await
+			})
+			.finish()
+			.await;
+		// The bucket definition is only read, so cancel the transaction
+		txn.cancel().await?;
+		// Return result
+		res
+	}
+
+	/// Writes a file to a bucket from a stream, checking the bucket permissions for this session
+	#[instrument(level = "debug", target = "surrealdb::core::kvs::ds", skip_all)]
+	pub async fn put_file_stream(
+		&self,
+		sess: &Session,
+		bucket: &str,
+		key: &str,
+		content_type: Option<String>,
+		stream: ObjectStream,
+	) -> Result<(), Error> {
+		// Store the content type alongside the file
+		let mut opts = PutOptions::default();
+		opts.attributes.content_type = content_type;
+		// Setup the context for the bucket operation
+		let (ctx, opt, txn) = self.setup_file_context(sess).await?;
+		// Create a new memory stack
+		let mut stack = TreeStack::new();
+		// Write the file
+		let key = ObjectKey::from(key);
+		let res = stack
+			.enter(|stk| async {
+				let mut controller = BucketController::new(stk, &ctx, &opt, None, bucket).await?;
+				controller.put_stream(&key, stream, &opts).await
+			})
+			.finish()
+			.await;
+		// The bucket definition is only read, so cancel the transaction
+		txn.cancel().await?;
+		// Return result
+		res
+	}
+
+	/// Sets up the context and options for a bucket operation in this session
+	async fn setup_file_context(
+		&self,
+		sess: &Session,
+	) -> Result<(Context, Options, Arc<Transaction>), Error> {
+		// Check if the session has expired
+		if sess.expired() {
+			return Err(Error::ExpiredSession);
+		}
+		// Check if anonymous actors can access files when auth is enabled
+		self.check_anon(sess).map_err(|_| IamError::NotAllowed {
+			actor: "anonymous".to_string(),
+			action: "access".to_string(),
+			resource: "file".to_string(),
+		})?;
+		// Create a new query options
+		let opt = self.setup_options(sess);
+		// Create a default context
+		let mut ctx = self.setup_ctx()?;
+		// Start an execution context
+		sess.context(&mut ctx);
+		// Start a new readonly transaction
+		let txn = self.transaction(Read, Optimistic).await?.enclose();
+		// Store the transaction
+		ctx.set_transaction(txn.clone());
+		// Freeze the context
+		Ok((ctx.freeze(), opt, txn))
+	}
+
 	/// Checks the required permissions level for this session
 	#[instrument(level = "trace", target = "surrealdb::core::kvs::ds", skip(self, sess))]
 	pub fn check(&self, sess: &Session, action: Action, resource: Resource) -> Result<(), Error> {
@@ -1220,6 +1732,7 @@ impl Datastore {
 			#[cfg(storage)]
 			self.temporary_directory.clone(),
 			self.buckets.clone(),
+			self.rate_limiter.clone(),
 		)?;
 		// Setup the notification channel
 		if let Some(channel) = &self.notification_channel {
@@ -273,7 +273,7 @@ impl Transaction {
 		Ok(())
 	}
 
-	async fn export_section<T: ToString>(
+	pub(super) async fn export_section<T: ToString>(
 		&self,
 		title: &str,
 		items: Vec<T>,
@@ -0,0 +1,175 @@
+use super::{Datastore, LockType::*, Transaction, TransactionType::*};
+use crate::err::Error;
+use crate::key::thing;
+use crate::sql::{Datetime, Retention, Value};
+use chrono::{DateTime, Utc};
+
+/// The number of records whose versions are processed in each batch
+const HISTORY_BATCH_SIZE: u32 = 1000;
+
+impl Transaction {
+	/// Summarises the version history which is kept for the records in a table.
+	///
+	/// Returns `None` if the storage engine does not keep versions.
+	pub(crate) async fn history(
+		&self,
+		ns: &str,
+		db: &str,
+		tb: &str,
+	) -> Result<Option<Value>, Error> {
+		let beg = thing::prefix(ns, db, tb)?;
+		let end = thing::suffix(ns, db, tb)?;
+		let mut next = Some(beg..end);
+		let mut records = 0u64;
+		let mut versions = 0u64;
+		let mut oldest: Option<u64> = None;
+		while let Some(rng) = next {
+			let batch = match self.batch_versions(rng, HISTORY_BATCH_SIZE).await {
+				Ok(v) => v,
+				Err(Error::UnsupportedVersionedQueries) => return Ok(None),
+				Err(e) => return Err(e),
+			};
+			next = batch.next;
+			// The versions of each record are returned together
+			let mut last = None;
+			for (k, ts) in batch.result.iter() {
+				if last != Some(k) {
+					records += 1;
+					last = Some(k);
+				}
+				versions += 1;
+				oldest = Some(oldest.map_or(*ts, |v| v.min(*ts)));
+			}
+			yield_now!();
+		}
+		Ok(Some(Value::from(map! {
+			"records".to_string() => records.into(),
+			"versions".to_string() => versions.into(),
+			"oldest".to_string(), if let Some(v) = oldest => {
+				Datetime::from(DateTime::from_timestamp_nanos(v as i64)).into()
+			},
+		})))
+	}
+}
+
+impl Datastore {
+	/// Removes the versions of the records in a table which are outside of its retention policy.
+	pub(super) async fn prune_history(
+		&self,
+		ns: &str,
+		db: &str,
+		tb: &str,
+		retention: Retention,
+	) -> Result<(), Error> {
+		let (ts, keep) = match retention {
+			Retention::Duration(v) => {
+				let now = Utc::now().timestamp_nanos_opt().unwrap_or_default() as u64;
+				(Some(now.saturating_sub(v.as_nanos() as u64)), None)
+			}
+			Retention::Count(v) => (None, Some(v)),
+		};
+		let end = thing::suffix(ns, db, tb)?;
+		let mut next = Some(thing::prefix(ns, db, tb)?);
+		// Each batch of records is pruned in a separate transaction
+		while let Some(beg) = next {
+			let txn = self.transaction(Write, Optimistic).await?;
+			match txn.prune_versions(beg..end.clone(), ts, keep, HISTORY_BATCH_SIZE).await {
+				Ok(v) => {
+					txn.commit().await?;
+					next = v;
+				}
+				Err(e) => {
+					txn.cancel().await?;
+					return Err(e);
+				}
+			}
+			yield_now!();
+		}
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::dbs::Session;
+	use crate::kvs::Datastore;
+
+	#[tokio::test]
+	async fn retention_without_versions() {
+		let ses = Session::owner().with_ns("test").with_db("test");
+		// The memory engine only keeps the latest version, so any retention policy holds
+		let ds = Datastore::new("memory").await.unwrap();
+		let sql =
+			"DEFINE TABLE person VERSIONS RETAIN 2; CREATE person:tobie; UPDATE person:tobie;";
+		for res in ds.execute(sql, &ses, None).await.unwrap() {
+			res.result.unwrap();
+		}
+		ds.versions_process().await.unwrap();
+		let sql = "INFO FOR TABLE person";
+		let info = ds.execute(sql, &ses, None).await.unwrap().remove(0).result.unwrap();
+		assert!(info.to_string().contains("records: 1, versions: 1 }"), "{info}");
+	}
+
+	#[cfg(feature = "kv-surrealkv")]
+	#[tokio::test]
+	async fn retention_requires_pruning() {
+		use crate::err::Error;
+		use temp_dir::TempDir;
+
+		let ses = Session::owner().with_ns("test").with_db("test");
+		// SurrealKV keeps every version, but can not remove individual versions
+		let dir = TempDir::new().unwrap();
+		let path = format!("surrealkv+versioned:{}", dir.path().to_string_lossy());
+		let ds = Datastore::new(&path).await.unwrap();
+		let res = ds.execute("DEFINE TABLE person VERSIONS RETAIN 2", &ses, None).await.unwrap();
+		let res = res.into_iter().next().unwrap().result;
+		assert!(matches!(res, Err(Error::UnsupportedVersionRetention)), "{res:?}");
+	}
+
+	#[cfg(feature = "kv-rocksdb")]
+	#[tokio::test]
+	async fn prune_versions_outside_retention() {
+		use crate::sql::Value;
+		use crate::syn::Parse;
+		use temp_dir::TempDir;
+
+		let ses = Session::owner().with_ns("test").with_db("test");
+		let dir = TempDir::new().unwrap();
+		let path = format!("rocksdb+versioned:{}", dir.path().to_string_lossy());
+		let ds = Datastore::new(&path).await.unwrap();
+		// Keep the latest two versions of each person, and every version of each animal
+		ds.execute(
+			"DEFINE TABLE person VERSIONS RETAIN 2; \
+			 CREATE person:tobie SET name = 'v1'; \
+			 UPDATE person:tobie SET name = 'v2'; \
+			 UPDATE person:tobie SET name = 'v3'; \
+			 CREATE person:jaime SET name = 'v1'; \
+			 CREATE animal:cat SET name = 'v1'; \
+			 UPDATE animal:cat SET name = 'v2';",
+			&ses,
+			None,
+		)
+		.await
+		.unwrap();
+		let sql = "INFO FOR TABLE person";
+		let info = ds.execute(sql, &ses, None).await.unwrap().remove(0).result.unwrap();
+		assert!(info.to_string().contains("records: 2, versions: 4 }"), "{info}");
+		// Run the background task which prunes the version history
+		ds.versions_process().await.unwrap();
+		// The oldest version of person:tobie is gone
+		let info = ds.execute(sql, &ses, None).await.unwrap().remove(0).result.unwrap();
+		assert!(info.to_string().contains("records: 2, versions: 3 }"), "{info}");
+		// The current data is kept
+		let sql = "SELECT VALUE name FROM person ORDER BY id; SELECT VALUE name FROM animal";
+		let mut res = ds.execute(sql, &ses, None).await.unwrap();
+		assert_eq!(res.remove(0).result.unwrap(), Value::parse("['v1', 'v3']"));
+		assert_eq!(res.remove(0).result.unwrap(), Value::parse("['v2']"));
+		// Tables without a retention policy keep every version
+		let tx = ds.transaction(super::Read, super::Optimistic).await.unwrap();
+		let beg = crate::key::thing::prefix("test", "test", "animal").unwrap();
+		let end = crate::key::thing::suffix("test", "test", "animal").unwrap();
+		let all = tx.batch_keys_vals_versions(beg..end, 100).await.unwrap();
+		tx.cancel().await.unwrap();
+		assert_eq!(all.result.len(), 2);
+	}
+}
@@ -0,0 +1,170 @@
+use crate::dbs::{Notification, Response};
+use crate::err::Error;
+use crate::iam::Auth;
+use crate::kvs::Transaction;
+use async_channel::{Receiver, Sender};
+use dashmap::DashMap;
+use futures::lock::{Mutex, MutexGuard};
+use std::sync::Arc;
+use std::time::Duration;
+use trice::Instant;
+use uuid::Uuid;
+
+/// A write transaction which is held open across multiple requests,
+/// until it is explicitly committed or cancelled, or until it expires.
+pub(crate) struct Interactive {
+	/// The authentication of the session which started the transaction
+	au: Arc<Auth>,
+	/// The underlying datastore transaction
+	txn: Arc<Transaction>,
+	/// Live query notifications are buffered until the transaction commits
+	sender: Sender<Notification>,
+	receiver: Receiver<Notification>,
+	/// Ensures statements within the transaction are processed in order
+	state: Mutex<State>,
+	/// When the transaction was started
+	started: Instant,
+}
+
+pub(crate) struct State {
+	/// When the transaction was last used
+	pub(crate) last: Instant,
+	/// Whether an earlier statement failed, or the transaction expired,
+	/// and the transaction was cancelled
+	pub(crate) aborted: bool,
+	/// The LIVE and KILL statements which only take effect once the transaction commits
+	pub(crate) live: Vec<Response>,
+}
+
+impl Interactive {
+	pub(crate) fn new(au: Arc<Auth>, txn: Transaction) -> Self {
+		let (sender, receiver) = async_channel::unbounded();
+		Self {
+			au,
+			txn: Arc::new(txn),
+			sender,
+			receiver,
+			state: Mutex::new(State {
+				last: Instant::now(),
+				aborted: false,
+				live: vec![],
+			}),
+			started: Instant::now(),
+		}
+	}
+
+	pub(crate) fn txn(&self) -> Arc<Transaction> {
+		self.txn.clone()
+	}
+
+	pub(crate) fn sender(&self) -> Sender<Notification> {
+		self.sender.clone()
+	}
+
+	pub(crate) fn receiver(&self) -> Receiver<Notification> {
+		self.receiver.clone()
+	}
+
+	/// Waits until no other request is using the transaction
+	pub(crate) async fn lock(&self) -> MutexGuard<'_, State> {
+		self.state.lock().await
+	}
+
+	/// Checks whether the transaction has been idle, or open, for too long.
+	///
+	/// An expired transaction is marked as aborted while it is locked, so
+	/// that a request which has already fetched it can not use it later.
+	fn expire(&self, idle: Duration, max: Option<Duration>) -> bool {
+		let timeout = max.is_some_and(|max| self.started.elapsed() > max);
+		// A transaction which is currently in use is not idle
+		let Some(mut state) = self.state.try_lock() else {
+			return timeout;
+		};
+		if timeout || state.last.elapsed() > idle {
+			state.aborted = true;
+			return true;
+		}
+		false
+	}
+}
+
+/// The interactive transactions which are currently open on a datastore
+#[derive(Clone, Default)]
+pub(crate) struct Interactives {
+	txns: Arc<DashMap<Uuid, Arc<Interactive>>>,
+}
+
+impl Interactives {
+	pub(crate) fn insert(&self, txn: Interactive) -> Uuid {
+		let id = Uuid::new_v4();
+		self.txns.insert(id, Arc::new(txn));
+		id
+	}
+
+	/// Fetches a transaction, as long as it was started by the same user
+	pub(crate) fn get(&self, id: &Uuid, au: &Auth) -> Result<Arc<Interactive>, Error> {
+		match self.txns.get(id) {
+			Some(txn) if txn.au.as_ref() == au => Ok(txn.clone()),
+			_ => Err(Error::TxNotFound {
+				id: id.to_string(),
+			}),
+		}
+	}
+
+	/// Removes a transaction, as long as it was started by the same user
+	pub(crate) fn remove(&self, id: &Uuid, au: &Auth) -> Result<Arc<Interactive>, Error> {
+		match self.txns.remove_if(id, |_, txn| txn.au.as_ref() == au) {
+			Some((_, txn)) => Ok(txn),
+			None => Err(Error::TxNotFound {
+				id: id.to_string(),
+			}),
+		}
+	}
+
+	/// Removes and returns all of the transactions which have expired
+	pub(crate) fn expire(&self, idle: Duration, max: Option<Duration>) -> Vec<Arc<Interactive>> {
+		let ids: Vec<Uuid> = self.txns.iter().map(|e| *e.key()).collect();
+		// Each transaction is checked and removed while its entry is locked,
+		// so a transaction which comes into use is never removed as idle
+		ids.iter()
+			.filter_map(|id| self.txns.remove_if(id, |_, txn| txn.expire(idle, max)))
+			.map(|(_, txn)| txn)
+			.collect()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::iam::Role;
+	use crate::kvs::{Datastore, LockType::*, TransactionType::*};
+
+	#[tokio::test]
+	async fn expire_idle_transactions() {
+		let ds = Datastore::new("memory").await.unwrap();
+		let au = Arc::new(Auth::for_root(Role::Owner));
+		let txns = Interactives::default();
+		let txn = ds.transaction(Write, Optimistic).await.unwrap();
+		let id = txns.insert(Interactive::new(au.clone(), txn));
+		// A transaction which is in use is not idle
+		let interactive = txns.get(&id, &au).unwrap();
+		let state = interactive.lock().await;
+		tokio::time::sleep(Duration::from_millis(1)).await;
+		assert!(txns.expire(Duration::ZERO, None).is_empty());
+		// But is expired once it has been open for too long
+		let expired = txns.expire(Duration::ZERO, Some(Duration::ZERO));
+		assert_eq!(expired.len(), 1);
+		drop(state);
+		expired[0].txn().cancel().await.unwrap();
+		// A request which fetched an idle transaction can't use it once it has expired
+		let txn = ds.transaction(Write, Optimistic).await.unwrap();
+		let id = txns.insert(Interactive::new(au.clone(), txn));
+		let interactive = txns.get(&id, &au).unwrap();
+		tokio::time::sleep(Duration::from_millis(1)).await;
+		let expired = txns.expire(Duration::ZERO, None);
+		assert_eq!(expired.len(), 1);
+		assert!(interactive.lock().await.aborted);
+		expired[0].txn().cancel().await.unwrap();
+		assert!(matches!(txns.get(&id, &au), Err(Error::TxNotFound { .. })));
+	}
+}
@@ -13,6 +13,7 @@
 //! - `tikv`: [TiKV](https://github.com/tikv/tikv) a distributed, and transactional key-value database
 //! - `mem`: in-memory database
 
+pub mod backup;
 pub mod export;
 
 mod api;
@@ -20,11 +21,14 @@ mod batch;
 mod cf;
 mod clock;
 mod ds;
+mod history;
+mod interactive;
 mod key;
 mod live;
 mod node;
 mod scanner;
 mod stash;
+mod sync;
 mod threadpool;
 mod tr;
 mod tx;
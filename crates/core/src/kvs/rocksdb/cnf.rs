@@ -123,3 +123,15 @@ pub(super) static ROCKSDB_DELETION_FACTORY_DELETE_COUNT: LazyLock<usize> =
 /// The ratio of deletions to track in the window (default: 0.5)
 pub(super) static ROCKSDB_DELETION_FACTORY_RATIO: LazyLock<f64> =
 	lazy_env_parse!("SURREAL_ROCKSDB_DELETION_FACTORY_RATIO", f64, 0.5);
+
+/// The number of seconds for which versions are kept, or 0 to keep all versions (default: 0).
+///
+/// This window applies to every key in the datastore, including the records of tables
+/// which define their own `VERSIONS RETAIN` policy. A version is removed as soon as
+/// either policy no longer needs it, so a table can keep less history, but not more.
+pub(super) static ROCKSDB_VERSIONS_RETENTION: LazyLock<u64> =
+	lazy_env_parse!("SURREAL_ROCKSDB_VERSIONS_RETENTION", u64, 0);
+
+/// The interval in seconds between pruning versions outside the retention window (default: 600)
+pub(super) static ROCKSDB_VERSIONS_PRUNE_INTERVAL: LazyLock<u64> =
+	lazy_env_parse!("SURREAL_ROCKSDB_VERSIONS_PRUNE_INTERVAL", u64, 600);
@@ -1,19 +1,24 @@
 #![cfg(feature = "kv-rocksdb")]
 
 mod cnf;
+mod versions;
 
 use crate::err::Error;
 use crate::key::debug::Sprintable;
-use crate::kvs::{Check, Key, Val};
+use crate::kvs::{Check, Key, Val, Version};
 use rocksdb::{
-	BlockBasedOptions, Cache, DBCompactionStyle, DBCompressionType, FlushOptions, LogLevel,
-	OptimisticTransactionDB, OptimisticTransactionOptions, Options, ReadOptions, WriteOptions,
+	BlockBasedOptions, Cache, ColumnFamily, ColumnFamilyDescriptor, DBCompactionStyle,
+	DBCompressionType, FlushOptions, LogLevel, OptimisticTransactionDB,
+	OptimisticTransactionOptions, Options, ReadOptions, WriteOptions, DEFAULT_COLUMN_FAMILY_NAME,
 };
+use std::collections::HashMap;
 use std::ops::Range;
 use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use std::thread;
 use std::time::Duration;
+use versions::Change;
 
 use super::savepoint::SavePoints;
 
@@ -21,6 +26,10 @@ const TARGET: &str = "surrealdb::core::kvs::rocksdb";
 
 pub struct Datastore {
 	db: Pin<Arc<OptimisticTransactionDB>>,
+	/// Are versions stored for every write?
+	versioned: bool,
+	/// The timestamp of the latest committed version
+	clock: Arc<AtomicU64>,
 }
 
 pub struct Transaction {
@@ -34,6 +43,14 @@ pub struct Transaction {
 	inner: Option<rocksdb::Transaction<'static, OptimisticTransactionDB>>,
 	/// The read options containing the Snapshot
 	ro: ReadOptions,
+	/// Are versions stored for every write?
+	versioned: bool,
+	/// The timestamp of the latest committed version
+	clock: Arc<AtomicU64>,
+	/// The changes to store as versions when committed
+	changes: Vec<(Key, Change)>,
+	/// The number of changes at each save point
+	savepoints: Vec<usize>,
 	// The above, supposedly 'static transaction
 	// actually points here, so we need to ensure
 	// the memory is kept alive. This pointer must
@@ -61,7 +78,7 @@ impl Drop for Transaction {
 
 impl Datastore {
 	/// Open a new database
-	pub(crate) async fn new(path: &str) -> Result<Datastore, Error> {
+	pub(crate) async fn new(path: &str, enable_versions: bool) -> Result<Datastore, Error> {
 		// Configure custom options
 		let mut opts = Options::default();
 		// Ensure we use fdatasync
@@ -190,6 +207,12 @@ impl Datastore {
 				return Err(Error::Ds(format!("Invalid storage engine log level specified: {l}")));
 			}
 		});
+		// Versions are stored in a separate column family
+		let cfs = [
+			ColumnFamilyDescriptor::new(DEFAULT_COLUMN_FAMILY_NAME, opts.clone()),
+			ColumnFamilyDescriptor::new(versions::CF, opts.clone()),
+			ColumnFamilyDescriptor::new(versions::META_CF, opts.clone()),
+		];
 		// Configure background WAL flush behaviour
 		let db = match *cnf::ROCKSDB_BACKGROUND_FLUSH {
 			// Beckground flush is disabled which
@@ -201,7 +224,7 @@ impl Datastore {
 				// Enable manual WAL flush
 				opts.set_manual_wal_flush(false);
 				// Create the optimistic datastore
-				Arc::pin(OptimisticTransactionDB::open(&opts, path)?)
+				Arc::pin(OptimisticTransactionDB::open_cf_descriptors(&opts, path, cfs)?)
 			}
 			// Background flush is enabled so we
 			// spawn a background worker thread to
@@ -212,7 +235,7 @@ impl Datastore {
 				// Enable manual WAL flush
 				opts.set_manual_wal_flush(true);
 				// Create the optimistic datastore
-				let db = Arc::pin(OptimisticTransactionDB::open(&opts, path)?);
+				let db = Arc::pin(OptimisticTransactionDB::open_cf_descriptors(&opts, path, cfs)?);
 				// Clone the database reference
 				let dbc = db.clone();
 				// Create a new background thread
@@ -230,12 +253,57 @@ impl Datastore {
 				db
 			}
 		};
+		// Check the datastore is opened in the mode it was created in
+		versions::check_mode(&db, enable_versions)?;
+		// Configure background version pruning
+		if enable_versions && *cnf::ROCKSDB_VERSIONS_RETENTION > 0 {
+			// Dispay the configuration setting
+			info!(target: TARGET, "Version retention window: {}s, pruned every {}s", *cnf::ROCKSDB_VERSIONS_RETENTION, *cnf::ROCKSDB_VERSIONS_PRUNE_INTERVAL);
+			// Clone the database reference
+			let dbc = db.clone();
+			// Create a new background thread
+			thread::spawn(move || loop {
+				// Get the specified prune interval
+				let wait = *cnf::ROCKSDB_VERSIONS_PRUNE_INTERVAL;
+				// Wait for the specified interval
+				thread::sleep(Duration::from_secs(wait));
+				// Remove the versions outside of the retention window
+				let window = cnf::ROCKSDB_VERSIONS_RETENTION.saturating_mul(1_000_000_000);
+				match versions::prune(&dbc, versions::now().saturating_sub(window)) {
+					Ok(0) => {}
+					Ok(count) => {
+						trace!(target: TARGET, "Pruned {count} versions");
+						// Compact the versions to reclaim the space
+						if let Some(cf) = dbc.cf_handle(versions::CF) {
+							dbc.compact_range_cf(cf, None::<&[u8]>, None::<&[u8]>);
+						}
+					}
+					Err(err) => error!("Failed to prune versions: {err}"),
+				}
+			});
+		}
 		// Return the datastore
 		Ok(Datastore {
 			db,
+			versioned: enable_versions,
+			clock: Arc::new(AtomicU64::new(versions::now())),
 		})
 	}
 
+	pub(crate) fn parse_start_string(start: &str) -> Result<(&str, bool), Error> {
+		let (scheme, path) = start
+			// Support conventional paths like rocksdb:///absolute/path
+			.split_once("://")
+			// Or paths like rocksdb:/absolute/path
+			.or_else(|| start.split_once(':'))
+			.unwrap_or_default();
+		match scheme {
+			"rocksdb+versioned" => Ok((path, true)),
+			"rocksdb" => Ok((path, false)),
+			_ => Err(Error::Ds("Invalid start string".into())),
+		}
+	}
+
 	/// Shutdown the database
 	pub(crate) async fn shutdown(&self) -> Result<(), Error> {
 		// Create new flush options
@@ -297,6 +365,10 @@ impl Datastore {
 			check,
 			inner: Some(inner),
 			ro,
+			versioned: self.versioned,
+			clock: self.clock.clone(),
+			changes: Vec::new(),
+			savepoints: Vec::new(),
 			_db: self.db.clone(),
 		}))
 	}
@@ -350,6 +422,8 @@ impl super::api::Transaction for Transaction {
 		if !self.write {
 			return Err(Error::TxReadonly);
 		}
+		// Store the versions of the changed keys
+		self.commit_versions()?;
 		// Mark this transaction as done
 		self.done = true;
 		// Commit this transaction
@@ -361,8 +435,8 @@ impl super::api::Transaction for Transaction {
 	/// Check if a key exists
 	#[instrument(level = "trace", target = "surrealdb::core::kvs::api", skip(self), fields(key = key.sprint()))]
 	async fn exists(&mut self, key: Key, version: Option<u64>) -> Result<bool, Error> {
-		// RocksDB does not support versioned queries.
-		if version.is_some() {
+		// Check if versioned queries are enabled
+		if version.is_some() && !self.versioned {
 			return Err(Error::UnsupportedVersionedQueries);
 		}
 		// Check to see if transaction is closed
@@ -370,7 +444,11 @@ impl super::api::Transaction for Transaction {
 			return Err(Error::TxFinished);
 		}
 		// Get the key
-		let res = self.inner.as_ref().unwrap().get_pinned_opt(key, &self.ro)?.is_some();
+		let inner = self.inner.as_ref().unwrap();
+		let res = match version {
+			Some(ts) => versions::get(inner, self.versions()?, &key, ts)?.is_some(),
+			None => inner.get_pinned_opt(key, &self.ro)?.is_some(),
+		};
 		// Return result
 		Ok(res)
 	}
@@ -378,8 +456,8 @@ impl super::api::Transaction for Transaction {
 	/// Fetch a key from the database
 	#[instrument(level = "trace", target = "surrealdb::core::kvs::api", skip(self), fields(key = key.sprint()))]
 	async fn get(&mut self, key: Key, version: Option<u64>) -> Result<Option<Val>, Error> {
-		// RocksDB does not support versioned queries.
-		if version.is_some() {
+		// Check if versioned queries are enabled
+		if version.is_some() && !self.versioned {
 			return Err(Error::UnsupportedVersionedQueries);
 		}
 		// Check to see if transaction is closed
@@ -387,7 +465,11 @@ impl super::api::Transaction for Transaction {
 			return Err(Error::TxFinished);
 		}
 		// Get the key
-		let res = self.inner.as_ref().unwrap().get_opt(key, &self.ro)?;
+		let inner = self.inner.as_ref().unwrap();
+		let res = match version {
+			Some(ts) => versions::get(inner, self.versions()?, &key, ts)?,
+			None => inner.get_opt(key, &self.ro)?,
+		};
 		// Return result
 		Ok(res)
 	}
@@ -414,8 +496,8 @@ impl super::api::Transaction for Transaction {
 	/// Insert or update a key in the database
 	#[instrument(level = "trace", target = "surrealdb::core::kvs::api", skip(self), fields(key = key.sprint()))]
 	async fn set(&mut self, key: Key, val: Val, version: Option<u64>) -> Result<(), Error> {
-		// RocksDB does not support versioned queries.
-		if version.is_some() {
+		// Check if versioned queries are enabled
+		if version.is_some() && !self.versioned {
 			return Err(Error::UnsupportedVersionedQueries);
 		}
 		// Check to see if transaction is closed
@@ -426,8 +508,13 @@ impl super::api::Transaction for Transaction {
 		if !self.write {
 			return Err(Error::TxReadonly);
 		}
+		// Set the key at the specified version
+		if let Some(ts) = version {
+			return self.set_version(key, val, ts);
+		}
 		// Set the key
-		self.inner.as_ref().unwrap().put(key, val)?;
+		self.inner.as_ref().unwrap().put(&key, &val)?;
+		self.record(key, Some(val));
 		// Return result
 		Ok(())
 	}
@@ -435,8 +522,8 @@ impl super::api::Transaction for Transaction {
 	/// Insert a key if it doesn't exist in the database
 	#[instrument(level = "trace", target = "surrealdb::core::kvs::api", skip(self), fields(key = key.sprint()))]
 	async fn put(&mut self, key: Key, val: Val, version: Option<u64>) -> Result<(), Error> {
-		// RocksDB does not support versioned queries.
-		if version.is_some() {
+		// Check if versioned queries are enabled
+		if version.is_some() && !self.versioned {
 			return Err(Error::UnsupportedVersionedQueries);
 		}
 		// Check to see if transaction is closed
@@ -447,11 +534,16 @@ impl super::api::Transaction for Transaction {
 		if !self.write {
 			return Err(Error::TxReadonly);
 		}
+		// Set the key at the specified version
+		if let Some(ts) = version {
+			return self.set_version(key, val, ts);
+		}
 		// Set the key if empty
 		match self.inner.as_ref().unwrap().get_pinned_opt(&key, &self.ro)? {
-			None => self.inner.as_ref().unwrap().put(key, val)?,
+			None => self.inner.as_ref().unwrap().put(&key, &val)?,
 			_ => return Err(Error::TxKeyAlreadyExists),
 		};
+		self.record(key, Some(val));
 		// Return result
 		Ok(())
 	}
@@ -467,12 +559,13 @@ impl super::api::Transaction for Transaction {
 		if !self.write {
 			return Err(Error::TxReadonly);
 		}
-		// Set the key if empty
+		// Set the key if valid
 		match (self.inner.as_ref().unwrap().get_pinned_opt(&key, &self.ro)?, chk) {
-			(Some(v), Some(w)) if v.eq(&w) => self.inner.as_ref().unwrap().put(key, val)?,
-			(None, None) => self.inner.as_ref().unwrap().put(key, val)?,
+			(Some(v), Some(w)) if v.eq(&w) => self.inner.as_ref().unwrap().put(&key, &val)?,
+			(None, None) => self.inner.as_ref().unwrap().put(&key, &val)?,
 			_ => return Err(Error::TxConditionNotMet),
 		};
+		self.record(key, Some(val));
 		// Return result
 		Ok(())
 	}
@@ -489,7 +582,8 @@ impl super::api::Transaction for Transaction {
 			return Err(Error::TxReadonly);
 		}
 		// Remove the key
-		self.inner.as_ref().unwrap().delete(key)?;
+		self.inner.as_ref().unwrap().delete(&key)?;
+		self.record(key, None);
 		// Return result
 		Ok(())
 	}
@@ -507,10 +601,54 @@ impl super::api::Transaction for Transaction {
 		}
 		// Delete the key if valid
 		match (self.inner.as_ref().unwrap().get_pinned_opt(&key, &self.ro)?, chk) {
-			(Some(v), Some(w)) if v.eq(&w) => self.inner.as_ref().unwrap().delete(key)?,
-			(None, None) => self.inner.as_ref().unwrap().delete(key)?,
+			(Some(v), Some(w)) if v.eq(&w) => self.inner.as_ref().unwrap().delete(&key)?,
+			(None, None) => self.inner.as_ref().unwrap().delete(&key)?,
 			_ => return Err(Error::TxConditionNotMet),
 		};
+		self.record(key, None);
+		// Return result
+		Ok(())
+	}
+
+	/// Delete all versions of a key
+	#[instrument(level = "trace", target = "surrealdb::core::kvs::api", skip(self), fields(key = key.sprint()))]
+	async fn clr(&mut self, key: Key) -> Result<(), Error> {
+		// Check to see if transaction is closed
+		if self.done {
+			return Err(Error::TxFinished);
+		}
+		// Check to see if transaction is writable
+		if !self.write {
+			return Err(Error::TxReadonly);
+		}
+		// Remove the key and its versions
+		self.clear(key)?;
+		// Return result
+		Ok(())
+	}
+
+	/// Delete all versions of a key if the current value matches a condition
+	#[instrument(level = "trace", target = "surrealdb::core::kvs::api", skip(self), fields(key = key.sprint()))]
+	async fn clrc(&mut self, key: Key, chk: Option<Val>) -> Result<(), Error> {
+		// Check to see if transaction is closed
+		if self.done {
+			return Err(Error::TxFinished);
+		}
+		// Check to see if transaction is writable
+		if !self.write {
+			return Err(Error::TxReadonly);
+		}
+		// Check the current value
+		let valid = match (self.inner.as_ref().unwrap().get_pinned_opt(&key, &self.ro)?, chk) {
+			(Some(v), Some(w)) => v.eq(&w),
+			(None, None) => true,
+			_ => false,
+		};
+		// Remove the key and its versions if valid
+		if !valid {
+			return Err(Error::TxConditionNotMet);
+		}
+		self.clear(key)?;
 		// Return result
 		Ok(())
 	}
@@ -524,6 +662,16 @@ impl super::api::Transaction for Transaction {
 		version: Option<u64>,
 	) -> Result<Vec<Key>, Error> {
 		let rng = self.prepare_scan(rng, version).await?;
+		// Retrieve the keys at the specified version
+		if let Some(ts) = version {
+			let res = affinitypool::spawn_local(move || -> Result<Vec<Key>, Error> {
+				let inner = self.inner.as_ref().unwrap();
+				let res = versions::scan(inner, self.versions()?, &rng, ts, limit)?;
+				Ok(res.into_iter().map(|(k, _)| k).collect())
+			})
+			.await?;
+			return Ok(res);
+		}
 		// Execute on the blocking threadpool
 		let res = affinitypool::spawn_local(move || {
 			// Create result set
@@ -577,6 +725,11 @@ impl super::api::Transaction for Transaction {
 		let rng = self.prepare_scan(rng, version).await?;
 		// Get the transaction
 		let inner = self.inner.as_ref().unwrap();
+		// Retrieve the keys at the specified version
+		if let Some(ts) = version {
+			let res = versions::scanr(inner, self.versions()?, &rng, ts, limit)?;
+			return Ok(res.into_iter().map(|(k, _)| k).collect());
+		}
 		// Create result set
 		let mut res = vec![];
 		// Set the key range
@@ -620,6 +773,15 @@ impl super::api::Transaction for Transaction {
 		version: Option<u64>,
 	) -> Result<Vec<(Key, Val)>, Error> {
 		let rng = self.prepare_scan(rng, version).await?;
+		// Retrieve the keys and values at the specified version
+		if let Some(ts) = version {
+			let res = affinitypool::spawn_local(move || -> Result<_, Error> {
+				let inner = self.inner.as_ref().unwrap();
+				versions::scan(inner, self.versions()?, &rng, ts, limit)
+			})
+			.await?;
+			return Ok(res);
+		}
 		// Execute on the blocking threadpool
 		let res = affinitypool::spawn_local(move || {
 			// Create result set
@@ -672,6 +834,10 @@ impl super::api::Transaction for Transaction {
 		let rng = self.prepare_scan(rng, version).await?;
 		// Get the transaction
 		let inner = self.inner.as_ref().unwrap();
+		// Retrieve the keys and values at the specified version
+		if let Some(ts) = version {
+			return versions::scanr(inner, self.versions()?, &rng, ts, limit);
+		}
 		// Create result set
 		let mut res = vec![];
 		// Set the key range
@@ -706,6 +872,87 @@ impl super::api::Transaction for Transaction {
 		Ok(res)
 	}
 
+	/// Retrieve all the versions from a range of keys from the databases
+	#[instrument(level = "trace", target = "surrealdb::core::kvs::api", skip(self), fields(rng = rng.sprint()))]
+	async fn scan_all_versions(
+		&mut self,
+		rng: Range<Key>,
+		limit: u32,
+	) -> Result<Vec<(Key, Val, Version, bool)>, Error> {
+		// Check if versioned queries are enabled
+		if !self.versioned {
+			return Err(Error::UnsupportedVersionedQueries);
+		}
+		// Check to see if transaction is closed
+		if self.done {
+			return Err(Error::TxFinished);
+		}
+		// Execute on the blocking threadpool
+		let res = affinitypool::spawn_local(move || -> Result<_, Error> {
+			let inner = self.inner.as_ref().unwrap();
+			versions::scan_all(inner, self.versions()?, &rng, limit)
+		})
+		.await?;
+		// Return result
+		Ok(res)
+	}
+
+	/// Retrieve the versions from a range of keys from the databases, without their values
+	#[instrument(level = "trace", target = "surrealdb::core::kvs::api", skip(self), fields(rng = rng.sprint()))]
+	async fn scan_versions(
+		&mut self,
+		rng: Range<Key>,
+		limit: u32,
+	) -> Result<Vec<(Key, Version)>, Error> {
+		// Check if versioned queries are enabled
+		if !self.versioned {
+			return Err(Error::UnsupportedVersionedQueries);
+		}
+		// Check to see if transaction is closed
+		if self.done {
+			return Err(Error::TxFinished);
+		}
+		// Execute on the blocking threadpool
+		let res = affinitypool::spawn_local(move || -> Result<_, Error> {
+			let inner = self.inner.as_ref().unwrap();
+			versions::scan_keys(inner, self.versions()?, &rng, limit)
+		})
+		.await?;
+		// Return result
+		Ok(res)
+	}
+
+	/// Delete the versions of a range of keys which are outside of a retention policy
+	#[instrument(level = "trace", target = "surrealdb::core::kvs::api", skip(self), fields(rng = rng.sprint()))]
+	async fn prune_versions(
+		&mut self,
+		rng: Range<Key>,
+		ts: Option<u64>,
+		keep: Option<u64>,
+		limit: u32,
+	) -> Result<Option<Key>, Error> {
+		// Check if versioned queries are enabled
+		if !self.versioned {
+			return Err(Error::UnsupportedVersionedQueries);
+		}
+		// Check to see if transaction is closed
+		if self.done {
+			return Err(Error::TxFinished);
+		}
+		// Check to see if transaction is writable
+		if !self.write {
+			return Err(Error::TxReadonly);
+		}
+		// Execute on the blocking threadpool
+		let res = affinitypool::spawn_local(move || -> Result<_, Error> {
+			let inner = self.inner.as_ref().unwrap();
+			versions::expire(inner, self.versions()?, &rng, ts, keep, limit)
+		})
+		.await?;
+		// Return result
+		Ok(res)
+	}
+
 	fn get_save_points(&mut self) -> &mut SavePoints {
 		unimplemented!("Get save points not implemented for for the RocksDB backend");
 	}
@@ -715,6 +962,8 @@ impl super::api::Transaction for Transaction {
 		let inner = self.inner.as_ref().unwrap();
 		// Set the save point
 		inner.set_savepoint();
+		// Track the changes made before the save point
+		self.savepoints.push(self.changes.len());
 	}
 
 	async fn rollback_to_save_point(&mut self) -> Result<(), Error> {
@@ -722,6 +971,10 @@ impl super::api::Transaction for Transaction {
 		let inner = self.inner.as_ref().unwrap();
 		// Rollback
 		inner.rollback_to_savepoint()?;
+		// Discard the changes made since the save point
+		if let Some(len) = self.savepoints.pop() {
+			self.changes.truncate(len);
+		}
 		//
 		Ok(())
 	}
@@ -738,8 +991,8 @@ impl Transaction {
 		rng: Range<Key>,
 		version: Option<u64>,
 	) -> Result<Range<Key>, Error> {
-		// RocksDB does not support versioned queries.
-		if version.is_some() {
+		// Check if versioned queries are enabled
+		if version.is_some() && !self.versioned {
 			return Err(Error::UnsupportedVersionedQueries);
 		}
 		// Check to see if transaction is closed
@@ -748,4 +1001,76 @@ impl Transaction {
 		}
 		Ok(rng)
 	}
+
+	/// Returns the column family in which versions are stored
+	fn versions(&self) -> Result<&ColumnFamily, Error> {
+		self._db
+			.cf_handle(versions::CF)
+			.ok_or_else(|| Error::Ds("The RocksDB versions column family does not exist".into()))
+	}
+
+	/// Records a change to a key, so that it is stored as a version when committed
+	fn record(&mut self, key: Key, val: Option<Val>) {
+		if self.versioned {
+			let change = match val {
+				Some(v) => Change::Set(v),
+				None => Change::Del,
+			};
+			self.changes.push((key, change));
+		}
+	}
+
+	/// Inserts a value at a specific version
+	fn set_version(&self, key: Key, val: Val, ts: u64) -> Result<(), Error> {
+		let inner = self.inner.as_ref().unwrap();
+		let cf = self.versions()?;
+		// Only update the current value if this is the latest version
+		if versions::latest(inner, cf, &key)?.is_none_or(|v| v <= ts) {
+			inner.put(&key, &val)?;
+		}
+		inner.put_cf(cf, versions::encode(&key, ts), versions::value(Some(&val)))?;
+		Ok(())
+	}
+
+	/// Deletes a key, and all of its versions
+	fn clear(&mut self, key: Key) -> Result<(), Error> {
+		let inner = self.inner.as_ref().unwrap();
+		inner.delete(&key)?;
+		if self.versioned {
+			versions::clear(inner, self.versions()?, &key)?;
+			self.changes.push((key, Change::Clr));
+		}
+		Ok(())
+	}
+
+	/// Stores the changes made in this transaction as versions at the commit timestamp
+	fn commit_versions(&mut self) -> Result<(), Error> {
+		if self.changes.is_empty() {
+			return Ok(());
+		}
+		// Only the last change to each key is stored
+		let changes: HashMap<Key, Change> = std::mem::take(&mut self.changes).into_iter().collect();
+		// Ensure commit timestamps always increase
+		let now = versions::now();
+		let prev = self
+			.clock
+			.fetch_update(Ordering::SeqCst, Ordering::SeqCst, |v| Some(now.max(v + 1)))
+			.unwrap_or_else(|v| v);
+		let ts = now.max(prev + 1);
+		// Store the versions
+		let inner = self.inner.as_ref().unwrap();
+		let cf = self.versions()?;
+		for (key, change) in changes {
+			match change {
+				Change::Set(v) => {
+					inner.put_cf(cf, versions::encode(&key, ts), versions::value(Some(&v)))?
+				}
+				Change::Del => {
+					inner.put_cf(cf, versions::encode(&key, ts), versions::value(None))?
+				}
+				Change::Clr => {}
+			}
+		}
+		Ok(())
+	}
 }
@@ -0,0 +1,618 @@
+//! Versioned storage for the RocksDB storage engine.
+//!
+//! When versions are enabled, the latest value of each key is stored in the
+//! default column family as normal, and every write is also recorded in the
+//! `versions` column family. Each version is stored under the escaped key,
+//! suffixed with the big-endian timestamp of the write, so that all of the
+//! versions of a key sort together in timestamp order, and before the versions
+//! of any key which it is a prefix of. The value of each version is prefixed
+//! with a single byte, which specifies whether the key was set or deleted.
+//!
+//! Whether versions are stored is recorded in the `meta` column family when the
+//! datastore is first opened, as a datastore can only be read at a version if
+//! every write has been recorded. A datastore can not be opened in the other mode.
+
+use crate::err::Error;
+use crate::kvs::{Key, Val, Version};
+use rocksdb::{
+	ColumnFamily, DBAccess, DBRawIteratorWithThreadMode, OptimisticTransactionDB, ReadOptions,
+	WriteBatchWithTransaction, DEFAULT_COLUMN_FAMILY_NAME,
+};
+use std::ops::Range;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The name of the column family in which versions are stored
+pub(super) const CF: &str = "versions";
+
+/// The name of the column family in which the storage mode is recorded
+pub(super) const META_CF: &str = "meta";
+
+/// The key under which the storage mode is recorded
+const MODE_KEY: &[u8] = b"versioned";
+
+/// The number of deletes to group in a single batch when pruning versions
+const PRUNE_BATCH_SIZE: usize = 1000;
+
+/// Specifies that a version contains a value
+const VALUE: u8 = 0;
+
+/// Specifies that a version marks a deletion
+const TOMBSTONE: u8 = 1;
+
+type Tx = rocksdb::Transaction<'static, OptimisticTransactionDB>;
+
+/// A change to a key, which is recorded as a version when committed
+pub(super) enum Change {
+	/// The key was set to a value
+	Set(Val),
+	/// The key was deleted
+	Del,
+	/// All versions of the key were deleted
+	Clr,
+}
+
+/// Returns the current time in nanoseconds, which is the unit used for versions
+pub(super) fn now() -> u64 {
+	SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_nanos() as u64).unwrap_or_default()
+}
+
+/// Escapes a key, so that the escaped keys sort in the same order as the keys
+pub(super) fn escape(key: &[u8]) -> Vec<u8> {
+	let mut out = Vec::with_capacity(key.len() + 10);
+	for b in key {
+		match b {
+			0x00 => out.extend([0x00, 0xFF]),
+			b => out.push(*b),
+		}
+	}
+	out
+}
+
+/// Returns the prefix which all versions of a key are stored under
+pub(super) fn prefix(key: &[u8]) -> Vec<u8> {
+	let mut out = escape(key);
+	out.extend([0x00, 0x01]);
+	out
+}
+
+/// Returns the key which a version of a key is stored under
+pub(super) fn encode(key: &[u8], ts: u64) -> Vec<u8> {
+	let mut out = prefix(key);
+	out.extend(ts.to_be_bytes());
+	out
+}
+
+/// Returns the first stored key after all versions of a key
+fn after(key: &[u8]) -> Vec<u8> {
+	let mut out = encode(key, u64::MAX);
+	out.push(0x00);
+	out
+}
+
+/// Decodes the key and timestamp of a stored version
+pub(super) fn decode(raw: &[u8]) -> Result<(Key, u64), Error> {
+	let invalid = || Error::Unreachable("Invalid key in the RocksDB versions column family".into());
+	// Split the timestamp from the end of the key
+	let (key, ts) = raw.split_at_checked(raw.len().wrapping_sub(8)).ok_or_else(invalid)?;
+	let ts = u64::from_be_bytes(ts.try_into().map_err(|_| invalid())?);
+	// Unescape the key, up to the terminator
+	let mut out = Vec::with_capacity(key.len());
+	let mut i = 0;
+	while i < key.len() {
+		match (key[i], key.get(i + 1).copied()) {
+			(0x00, Some(0xFF)) => out.push(0x00),
+			(0x00, Some(0x01)) if i + 2 == key.len() => return Ok((out, ts)),
+			(0x00, _) => return Err(invalid()),
+			(b, _) => {
+				out.push(b);
+				i += 1;
+				continue;
+			}
+		}
+		i += 2;
+	}
+	Err(invalid())
+}
+
+/// Encodes the value of a stored version
+pub(super) fn value(val: Option<&[u8]>) -> Vec<u8> {
+	match val {
+		Some(v) => {
+			let mut out = Vec::with_capacity(v.len() + 1);
+			out.push(VALUE);
+			out.extend_from_slice(v);
+			out
+		}
+		None => vec![TOMBSTONE],
+	}
+}
+
+/// Decodes the value of a stored version, returning `None` for a deletion
+fn decode_value(val: &[u8]) -> Option<Val> {
+	match val.split_first() {
+		Some((&VALUE, v)) => Some(v.to_vec()),
+		_ => None,
+	}
+}
+
+/// Creates an iterator over the versions column family, using the transaction snapshot
+fn iter<'a>(
+	inner: &'a Tx,
+	cf: &ColumnFamily,
+	beg: Vec<u8>,
+	end: Vec<u8>,
+) -> DBRawIteratorWithThreadMode<'a, Tx> {
+	let mut ro = ReadOptions::default();
+	ro.set_snapshot(&inner.snapshot());
+	ro.set_iterate_lower_bound(beg);
+	ro.set_iterate_upper_bound(end);
+	ro.set_async_io(true);
+	ro.fill_cache(true);
+	inner.raw_iterator_cf_opt(cf, ro)
+}
+
+/// Checks that the datastore is opened in the mode in which it was created,
+/// recording the mode if the datastore is opened for the first time.
+pub(super) fn check_mode(db: &OptimisticTransactionDB, versioned: bool) -> Result<(), Error> {
+	let meta = db
+		.cf_handle(META_CF)
+		.ok_or_else(|| Error::Ds("The RocksDB meta column family does not exist".into()))?;
+	let recorded = match db.get_cf(meta, MODE_KEY)? {
+		Some(v) => Some(v.first() == Some(&1)),
+		// Datastores created before the mode was recorded are versioned if any version is stored
+		None => {
+			let is_empty = |cf: &ColumnFamily| {
+				let mut iter = db.raw_iterator_cf(cf);
+				iter.seek_to_first();
+				let empty = !iter.valid();
+				iter.status().map(|_| empty)
+			};
+			let versions = db.cf_handle(CF).ok_or_else(|| {
+				Error::Ds("The RocksDB versions column family does not exist".into())
+			})?;
+			if !is_empty(versions)? {
+				Some(true)
+			} else if !is_empty(db.cf_handle(DEFAULT_COLUMN_FAMILY_NAME).ok_or_else(|| {
+				Error::Ds("The RocksDB default column family does not exist".into())
+			})?)? {
+				Some(false)
+			} else {
+				None
+			}
+		}
+	};
+	match recorded {
+		Some(true) if !versioned => Err(Error::Ds(
+			"The RocksDB datastore stores versions, and must be opened with `rocksdb+versioned:`"
+				.into(),
+		)),
+		Some(false) if versioned => Err(Error::Ds(
+			"The RocksDB datastore was created without versions, and can not be opened with `rocksdb+versioned:`"
+				.into(),
+		)),
+		_ => {
+			db.put_cf(meta, MODE_KEY, [versioned as u8])?;
+			Ok(())
+		}
+	}
+}
+
+/// Fetches the value of a key at a specific timestamp
+pub(super) fn get(
+	inner: &Tx,
+	cf: &ColumnFamily,
+	key: &[u8],
+	ts: u64,
+) -> Result<Option<Val>, Error> {
+	let beg = prefix(key);
+	let mut end = prefix(key);
+	end.push(0xFF);
+	let mut iter = iter(inner, cf, beg, end);
+	// Find the latest version at or before the timestamp
+	iter.seek_for_prev(encode(key, ts));
+	let res = iter.value().and_then(decode_value);
+	iter.status()?;
+	Ok(res)
+}
+
+/// Fetches the timestamp of the latest version of a key
+pub(super) fn latest(inner: &Tx, cf: &ColumnFamily, key: &[u8]) -> Result<Option<u64>, Error> {
+	let beg = prefix(key);
+	let mut end = prefix(key);
+	end.push(0xFF);
+	let mut iter = iter(inner, cf, beg, end);
+	iter.seek_to_last();
+	let res = match iter.key() {
+		Some(k) => Some(decode(k)?.1),
+		None => None,
+	};
+	iter.status()?;
+	Ok(res)
+}
+
+/// Deletes all versions of a key
+pub(super) fn clear(inner: &Tx, cf: &ColumnFamily, key: &[u8]) -> Result<(), Error> {
+	let beg = prefix(key);
+	let mut end = prefix(key);
+	end.push(0xFF);
+	let mut keys = vec![];
+	let mut iter = iter(inner, cf, beg, end);
+	iter.seek_to_first();
+	while let Some(k) = iter.key() {
+		keys.push(k.to_vec());
+		iter.next();
+	}
+	iter.status()?;
+	drop(iter);
+	for k in keys {
+		inner.delete_cf(cf, k)?;
+	}
+	Ok(())
+}
+
+/// Retrieves a range of keys and values at a specific timestamp
+pub(super) fn scan(
+	inner: &Tx,
+	cf: &ColumnFamily,
+	rng: &Range<Key>,
+	ts: u64,
+	limit: u32,
+) -> Result<Vec<(Key, Val)>, Error> {
+	let mut res = vec![];
+	let mut iter = iter(inner, cf, escape(&rng.start), escape(&rng.end));
+	iter.seek_to_first();
+	while res.len() < limit as usize {
+		let Some(k) = iter.key() else {
+			break;
+		};
+		let (key, _) = decode(k)?;
+		if let Some(v) = at(&mut iter, &key, ts)? {
+			res.push((key.clone(), v));
+		}
+		// Skip the remaining versions of the key
+		iter.seek(after(&key));
+	}
+	iter.status()?;
+	Ok(res)
+}
+
+/// Retrieves a range of keys and values at a specific timestamp, in reverse order
+pub(super) fn scanr(
+	inner: &Tx,
+	cf: &ColumnFamily,
+	rng: &Range<Key>,
+	ts: u64,
+	limit: u32,
+) -> Result<Vec<(Key, Val)>, Error> {
+	let mut res = vec![];
+	let mut iter = iter(inner, cf, escape(&rng.start), escape(&rng.end));
+	iter.seek_to_last();
+	while res.len() < limit as usize {
+		let Some(k) = iter.key() else {
+			break;
+		};
+		let (key, _) = decode(k)?;
+		if let Some(v) = at(&mut iter, &key, ts)? {
+			res.push((key.clone(), v));
+		}
+		// Skip the remaining versions of the key
+		iter.seek_for_prev(prefix(&key));
+	}
+	iter.status()?;
+	Ok(res)
+}
+
+/// Seeks to the latest version of a key at a timestamp, returning its value, if any
+fn at<D: DBAccess>(
+	iter: &mut DBRawIteratorWithThreadMode<'_, D>,
+	key: &[u8],
+	ts: u64,
+) -> Result<Option<Val>, Error> {
+	iter.seek_for_prev(encode(key, ts));
+	match iter.item() {
+		Some((k, v)) if decode(k)?.0 == key => Ok(decode_value(v)),
+		_ => Ok(None),
+	}
+}
+
+/// Retrieves all versions of a range of keys, for up to `limit` keys
+pub(super) fn scan_all(
+	inner: &Tx,
+	cf: &ColumnFamily,
+	rng: &Range<Key>,
+	limit: u32,
+) -> Result<Vec<(Key, Val, Version, bool)>, Error> {
+	let mut res: Vec<(Key, Val, Version, bool)> = vec![];
+	let mut keys = 0;
+	let mut iter = iter(inner, cf, escape(&rng.start), escape(&rng.end));
+	iter.seek_to_first();
+	while let Some((k, v)) = iter.item() {
+		let (key, at) = decode(k)?;
+		// Never split the versions of a key across batches
+		if res.last().is_none_or(|(l, ..)| *l != key) {
+			if keys >= limit {
+				break;
+			}
+			keys += 1;
+		}
+		match decode_value(v) {
+			Some(v) => res.push((key, v, at, false)),
+			None => res.push((key, vec![], at, true)),
+		}
+		iter.next();
+	}
+	iter.status()?;
+	Ok(res)
+}
+
+/// Retrieves the timestamps of all versions of a range of keys, for up to `limit` keys
+pub(super) fn scan_keys(
+	inner: &Tx,
+	cf: &ColumnFamily,
+	rng: &Range<Key>,
+	limit: u32,
+) -> Result<Vec<(Key, Version)>, Error> {
+	let mut res: Vec<(Key, Version)> = vec![];
+	let mut keys = 0;
+	let mut iter = iter(inner, cf, escape(&rng.start), escape(&rng.end));
+	iter.seek_to_first();
+	while let Some(k) = iter.key() {
+		let (key, at) = decode(k)?;
+		// Never split the versions of a key across batches
+		if res.last().is_none_or(|(l, _)| *l != key) {
+			if keys >= limit {
+				break;
+			}
+			keys += 1;
+		}
+		res.push((key, at));
+		iter.next();
+	}
+	iter.status()?;
+	Ok(res)
+}
+
+/// Deletes the versions of a range of keys which are outside of a retention policy,
+/// for up to `limit` keys, returning the key from which to continue, if any remain.
+pub(super) fn expire(
+	inner: &Tx,
+	cf: &ColumnFamily,
+	rng: &Range<Key>,
+	ts: Option<u64>,
+	keep: Option<u64>,
+	limit: u32,
+) -> Result<Option<Key>, Error> {
+	let mut keys = vec![];
+	let mut iter = iter(inner, cf, escape(&rng.start), escape(&rng.end));
+	iter.seek_to_first();
+	let next = retain(&mut iter, ts, keep, limit as usize, |k| {
+		keys.push(k);
+		Ok(())
+	})?;
+	drop(iter);
+	for k in keys {
+		inner.delete_cf(cf, k)?;
+	}
+	Ok(next.1)
+}
+
+/// Deletes all versions which are older than the timestamp, returning the number removed.
+///
+/// The latest version of each key at the timestamp is kept, so that the value
+/// of every key can still be read at any point after the timestamp, unless
+/// that version is a deletion, in which case it is no longer needed.
+pub(super) fn prune(db: &OptimisticTransactionDB, ts: u64) -> Result<usize, Error> {
+	let cf = db
+		.cf_handle(CF)
+		.ok_or_else(|| Error::Ds("The RocksDB versions column family does not exist".into()))?;
+	let mut batch = WriteBatchWithTransaction::<true>::default();
+	let mut iter = db.raw_iterator_cf_opt(cf, ReadOptions::default());
+	iter.seek_to_first();
+	let (count, _) = retain(&mut iter, Some(ts), None, usize::MAX, |k| {
+		batch.delete_cf(cf, k);
+		// Write the deletes in batches
+		if batch.len() >= PRUNE_BATCH_SIZE {
+			db.write(std::mem::take(&mut batch))?;
+		}
+		Ok(())
+	})?;
+	db.write(batch)?;
+	Ok(count)
+}
+
+/// Passes the versions which are outside of a retention policy to `delete`, for up to
+/// `limit` keys, returning the number of versions removed, and the next key, if any remain.
+fn retain<D: DBAccess>(
+	iter: &mut DBRawIteratorWithThreadMode<'_, D>,
+	ts: Option<u64>,
+	keep: Option<u64>,
+	limit: usize,
+	mut delete: impl FnMut(Vec<u8>) -> Result<(), Error>,
+) -> Result<(usize, Option<Key>), Error> {
+	let mut count = 0;
+	let mut keys = 0;
+	// The key being processed, and each of its stored versions
+	let mut cur: Option<Key> = None;
+	let mut versions: Vec<(Vec<u8>, u64, bool)> = vec![];
+	while let Some((k, v)) = iter.item() {
+		let (key, at) = decode(k)?;
+		// Process the previous key once all of its versions have been seen
+		if cur.as_ref() != Some(&key) {
+			if cur.take().is_some() {
+				count += expired(&mut versions, ts, keep, &mut delete)?;
+				keys += 1;
+			}
+			if keys >= limit {
+				return Ok((count, Some(key)));
+			}
+			cur = Some(key);
+		}
+		versions.push((k.to_vec(), at, decode_value(v).is_none()));
+		iter.next();
+	}
+	iter.status()?;
+	if cur.is_some() {
+		count += expired(&mut versions, ts, keep, &mut delete)?;
+	}
+	Ok((count, None))
+}
+
+/// Deletes the versions of a single key which are outside of a retention policy
+fn expired(
+	versions: &mut Vec<(Vec<u8>, u64, bool)>,
+	ts: Option<u64>,
+	keep: Option<u64>,
+	delete: &mut impl FnMut(Vec<u8>) -> Result<(), Error>,
+) -> Result<usize, Error> {
+	// Keep the latest version at the timestamp, and any newer versions
+	let by_ts = ts.map(|ts| versions.iter().rposition(|(_, at, _)| *at <= ts).unwrap_or_default());
+	// Keep the latest number of versions
+	let by_count = keep.map(|keep| match usize::try_from(keep) {
+		Ok(keep) => versions.len().saturating_sub(keep),
+		Err(_) => 0,
+	});
+	// A version is only removed if no policy keeps it
+	let mut first = match (by_ts, by_count) {
+		(Some(a), Some(b)) => a.min(b),
+		(Some(a), None) | (None, Some(a)) => a,
+		(None, None) => 0,
+	};
+	// Deletions are not needed once older versions are removed
+	while versions.get(first).is_some_and(|(_, _, deleted)| *deleted) {
+		first += 1;
+	}
+	for (k, _, _) in versions.drain(..).take(first) {
+		delete(k)?;
+	}
+	Ok(first)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn encode_decode() {
+		for key in [&b""[..], b"a", b"a\x00", b"\x00\x01", b"a\xff\x00b"] {
+			let enc = encode(key, 42);
+			let (dec, ts) = decode(&enc).unwrap();
+			assert_eq!(dec, key);
+			assert_eq!(ts, 42);
+		}
+	}
+
+	#[test]
+	fn expired_versions() {
+		let run = |versions: &[(u64, bool)], ts, keep| {
+			let mut versions: Vec<_> =
+				versions.iter().map(|(at, del)| (encode(b"a", *at), *at, *del)).collect();
+			let mut removed = vec![];
+			expired(&mut versions, ts, keep, &mut |k| {
+				removed.push(decode(&k)?.1);
+				Ok(())
+			})
+			.unwrap();
+			removed
+		};
+		let versions = [(1, false), (2, false), (3, true), (4, false), (5, false)];
+		// The latest version at the timestamp is kept
+		assert_eq!(run(&versions, Some(2), None), vec![1]);
+		assert_eq!(run(&versions, Some(0), None), Vec::<u64>::new());
+		// Deletions are removed once older versions are removed
+		assert_eq!(run(&versions, Some(3), None), vec![1, 2, 3]);
+		// The latest number of versions are kept
+		assert_eq!(run(&versions, None, Some(2)), vec![1, 2, 3]);
+		assert_eq!(run(&versions, None, Some(10)), Vec::<u64>::new());
+		// Versions are kept if either policy keeps them
+		assert_eq!(run(&versions, Some(5), Some(4)), vec![1]);
+		assert_eq!(run(&versions, Some(2), Some(1)), vec![1]);
+		// Keys which were deleted are removed entirely
+		assert_eq!(run(&[(1, false), (2, true)], Some(2), None), vec![1, 2]);
+	}
+
+	#[test]
+	fn datastore_and_table_retention() {
+		let dir = temp_dir::TempDir::new().unwrap();
+		let mut opts = rocksdb::Options::default();
+		opts.create_if_missing(true);
+		opts.create_missing_column_families(true);
+		let cfs = [DEFAULT_COLUMN_FAMILY_NAME, CF, META_CF];
+		let db = OptimisticTransactionDB::open_cf(&opts, dir.path(), cfs).unwrap();
+		let db: &'static OptimisticTransactionDB = Box::leak(Box::new(db));
+		let cf = db.cf_handle(CF).unwrap();
+		let rng = b"a".to_vec()..b"b".to_vec();
+		let versions = |key: &[u8]| {
+			let tx = db.transaction();
+			let res = scan_keys(&tx, cf, &rng, 10).unwrap();
+			res.into_iter().filter(|(k, _)| k == key).map(|(_, at)| at).collect::<Vec<_>>()
+		};
+		for at in 1..=5 {
+			db.put_cf(cf, encode(b"a", at), value(Some(&b"val"[..]))).unwrap();
+			db.put_cf(cf, encode(b"aa", at), value(Some(&b"val"[..]))).unwrap();
+		}
+		// A table policy which keeps four versions
+		let tx = db.transaction();
+		expire(&tx, cf, &rng, None, Some(4), 10).unwrap();
+		tx.commit().unwrap();
+		assert_eq!(versions(b"a"), vec![2, 3, 4, 5]);
+		// The datastore window still removes the versions which the table policy keeps
+		assert_eq!(prune(db, 4).unwrap(), 4);
+		assert_eq!(versions(b"a"), vec![4, 5]);
+		assert_eq!(versions(b"aa"), vec![4, 5]);
+		// A table policy which keeps fewer versions than the datastore window applies
+		let tx = db.transaction();
+		expire(&tx, cf, &rng, None, Some(1), 10).unwrap();
+		tx.commit().unwrap();
+		assert_eq!(versions(b"a"), vec![5]);
+	}
+
+	#[test]
+	fn storage_mode() {
+		let open = |dir: &temp_dir::TempDir| {
+			let mut opts = rocksdb::Options::default();
+			opts.create_if_missing(true);
+			opts.create_missing_column_families(true);
+			let cfs = [DEFAULT_COLUMN_FAMILY_NAME, CF, META_CF];
+			OptimisticTransactionDB::open_cf(&opts, dir.path(), cfs).unwrap()
+		};
+		// A new datastore records the mode it is opened in
+		let dir = temp_dir::TempDir::new().unwrap();
+		let db = open(&dir);
+		check_mode(&db, true).unwrap();
+		assert!(check_mode(&db, false).is_err());
+		check_mode(&db, true).unwrap();
+		// A datastore created before the mode was recorded, with data but no versions
+		let dir = temp_dir::TempDir::new().unwrap();
+		let db = open(&dir);
+		db.put(b"key", b"val").unwrap();
+		assert!(check_mode(&db, true).is_err());
+		check_mode(&db, false).unwrap();
+		// A datastore created before the mode was recorded, with versions
+		let dir = temp_dir::TempDir::new().unwrap();
+		let db = open(&dir);
+		db.put(b"key", b"val").unwrap();
+		db.put_cf(db.cf_handle(CF).unwrap(), encode(b"key", 1), value(Some(&b"val"[..]))).unwrap();
+		assert!(check_mode(&db, false).is_err());
+		check_mode(&db, true).unwrap();
+	}
+
+	#[test]
+	fn encoding_order() {
+		// Keys sort in the same order, with all versions of a key kept together
+		let keys: [&[u8]; 5] = [b"a", b"a\x00", b"a\x00\x00", b"a\x01", b"b"];
+		let enc: Vec<_> =
+			keys.iter().flat_map(|k| [encode(k, 0), encode(k, 1), encode(k, u64::MAX)]).collect();
+		let mut sorted = enc.clone();
+		sorted.sort();
+		assert_eq!(enc, sorted);
+		// Range bounds include the start key and exclude the end key
+		assert!(escape(b"a\x00") <= encode(b"a\x00", 0));
+		assert!(escape(b"a\x00") > encode(b"a", u64::MAX));
+		assert!(escape(b"b") > encode(b"a\x01", u64::MAX));
+		// The versions of a key are skipped by seeking to the next key
+		assert!(after(b"a") > encode(b"a", u64::MAX));
+		assert!(after(b"a") <= encode(b"a\x00", 0));
+		assert!(prefix(b"a\x00") < encode(b"a\x00", 0));
+		assert!(prefix(b"a\x00") > encode(b"a", u64::MAX));
+	}
+}
@@ -18,6 +18,8 @@ const TARGET: &str = "surrealdb::core::kvs::surrealkv";
 
 pub struct Datastore {
 	db: Store,
+	/// Does the datastore keep the versions of keys?
+	versioned: bool,
 }
 
 pub struct Transaction {
@@ -27,6 +29,8 @@ pub struct Transaction {
 	write: bool,
 	/// Should we check unhandled transactions?
 	check: Check,
+	/// Does the datastore keep the versions of keys?
+	versioned: bool,
 	/// The underlying datastore transaction
 	inner: Option<Tx>,
 }
@@ -75,6 +79,7 @@ impl Datastore {
 		match Store::new(opts) {
 			Ok(db) => Ok(Datastore {
 				db,
+				versioned: enable_versions,
 			}),
 			Err(e) => Err(Error::Ds(e.to_string())),
 		}
@@ -129,6 +134,7 @@ impl Datastore {
 			done: false,
 			check,
 			write,
+			versioned: self.versioned,
 			inner: Some(txn),
 		}))
 	}
@@ -156,6 +162,13 @@ impl super::api::Transaction for Transaction {
 		self.write
 	}
 
+	/// Check if a version retention policy can be enforced
+	fn retainable(&self) -> bool {
+		// SurrealKV can not remove individual versions of a key,
+		// so the versions it keeps can never be pruned
+		!self.versioned
+	}
+
 	/// Cancels the transaction.
 	#[instrument(level = "trace", target = "surrealdb::core::kvs::api", skip(self))]
 	async fn cancel(&mut self) -> Result<(), Error> {
@@ -0,0 +1,116 @@
+use super::Transaction;
+use crate::cf::{self, ChangeBatch, ChangeSet, TableMutation};
+use crate::cnf::EXPORT_BATCH_SIZE;
+use crate::err::Error;
+use crate::sql::paths::EDGE;
+use crate::sql::paths::IN;
+use crate::sql::paths::OUT;
+use crate::sql::statements::show::ShowSince;
+use crate::sql::{Thing, Value};
+use crate::vs::VersionStamp;
+use async_channel::Sender;
+
+impl Transaction {
+	/// Writes the changes made to a database since a given point as SurrealQL text.
+	///
+	/// If a versionstamp is specified to read changes until, then only the
+	/// change sets before that versionstamp are written.
+	///
+	/// Each change set is written as a separate transaction, preceded by
+	/// a comment containing its versionstamp, so that a consumer which
+	/// applies the output can resume from the last change it has seen.
+	/// Only tables and databases with a change feed are included.
+	pub async fn sync(
+		&self,
+		ns: &str,
+		db: &str,
+		since: ShowSince,
+		until: Option<VersionStamp>,
+		chn: Sender<Vec<u8>>,
+	) -> Result<(), Error> {
+		// Output OPTIONS
+		self.export_section("OPTION", vec!["OPTION IMPORT"], &chn).await?;
+		// Output the change sets in batches
+		let mut since = since;
+		loop {
+			let batch = self.changes(ns, db, None, since, *EXPORT_BATCH_SIZE).await?;
+			// Output the change sets in this batch
+			for change in batch.changes {
+				// Stop once the specified versionstamp is reached
+				if until.is_some_and(|vs| change.0.into_u64_lossy() >= vs.into_u64_lossy()) {
+					return Ok(());
+				}
+				Self::sync_changeset(change, &chn).await?;
+			}
+			// Continue after the last change set which was read
+			match (batch.more, batch.last.and_then(|vs| vs.next())) {
+				(true, Some(vs)) => since = ShowSince::versionstamp(&vs),
+				_ => break,
+			}
+		}
+		Ok(())
+	}
+
+	/// Reads the next batch of change sets to a database, or a table, since a given point.
+	///
+	/// The batch only contains complete change sets. The next batch can be read from the
+	/// versionstamp following the last one which was read, which may be later than the last
+	/// change set in the batch, when the changes to other tables were skipped.
+	pub(crate) async fn changes(
+		&self,
+		ns: &str,
+		db: &str,
+		tb: Option<&str>,
+		since: ShowSince,
+		limit: u32,
+	) -> Result<ChangeBatch, Error> {
+		cf::read_batch(self, ns, db, tb, since, Some(limit)).await
+	}
+
+	async fn sync_changeset(change: ChangeSet, chn: &Sender<Vec<u8>>) -> Result<(), Error> {
+		let ChangeSet(vs, mutations) = change;
+		chn.send(bytes!("-- ------------------------------")).await?;
+		chn.send(bytes!(format!("-- CHANGE: {}", vs.into_u64_lossy()))).await?;
+		chn.send(bytes!("-- ------------------------------")).await?;
+		chn.send(bytes!("")).await?;
+		chn.send(bytes!("BEGIN;")).await?;
+		for tb in mutations.0 {
+			for mutation in tb.1 {
+				chn.send(bytes!(Self::sync_mutation(mutation))).await?;
+			}
+		}
+		chn.send(bytes!("COMMIT;")).await?;
+		chn.send(bytes!("")).await?;
+		Ok(())
+	}
+
+	/// Generates the SQL statement which applies a single table mutation
+	fn sync_mutation(mutation: TableMutation) -> String {
+		match mutation {
+			TableMutation::Set(id, v) | TableMutation::SetWithDiff(id, v, _) => {
+				Self::sync_record(id, v)
+			}
+			TableMutation::Del(id) | TableMutation::DelWithOriginal(id, _) => {
+				format!("DELETE {id};")
+			}
+			TableMutation::Def(mut tb) => {
+				// Ensure the definition replaces any existing table
+				tb.if_not_exists = false;
+				tb.overwrite = true;
+				format!("{tb};")
+			}
+		}
+	}
+
+	/// Generates the SQL statement which stores a record with its current value
+	fn sync_record(id: Thing, v: Value) -> String {
+		match (v.pick(&*EDGE), v.pick(&*IN), v.pick(&*OUT)) {
+			// Graph edges need to be stored with their graph pointers
+			(Value::Bool(true), Value::Thing(i), Value::Thing(o)) => {
+				format!("RELATE {i}->{id}->{o} CONTENT {v};")
+			}
+			// Any other records can be stored directly
+			_ => format!("UPSERT {id} CONTENT {v};"),
+		}
+	}
+}
@@ -108,6 +108,16 @@ impl Transactor {
 		self.inner.closed()
 	}
 
+	/// Check if a version retention policy can be enforced.
+	///
+	/// If the storage engine does not keep the versions of keys,
+	/// or can remove the versions outside of a retention policy,
+	/// then this function will return [`true`].
+	#[instrument(level = "trace", target = TARGET, skip_all)]
+	pub(crate) fn retainable(&self) -> bool {
+		self.inner.retainable()
+	}
+
 	/// Cancel a transaction.
 	///
 	/// This reverses all changes made within the transaction.
@@ -505,6 +515,46 @@ impl Transactor {
 		self.inner.batch_keys_vals_versions(rng, batch).await
 	}
 
+	/// Retrieve a batched scan over the versions of a specific range of keys in the datastore.
+	///
+	/// This function fetches key-version pairs, without their values, in batches, with multiple requests to the underlying datastore.
+	#[instrument(level = "trace", target = TARGET, skip_all)]
+	pub async fn batch_versions<K>(
+		&mut self,
+		rng: Range<K>,
+		batch: u32,
+	) -> Result<Batch<(Key, Version)>, Error>
+	where
+		K: KeyEncode + Debug,
+	{
+		let beg: Key = rng.start.encode_owned()?;
+		let end: Key = rng.end.encode_owned()?;
+		let rng = beg..end;
+		trace!(target: TARGET, rng = rng.sprint(), "BatchVersionKeys");
+		self.inner.batch_versions(rng, batch).await
+	}
+
+	/// Delete the versions of a range of keys which are outside of a retention policy.
+	///
+	/// This function processes up to `limit` keys, returning the key from which to continue.
+	#[instrument(level = "trace", target = TARGET, skip_all)]
+	pub async fn prune_versions<K>(
+		&mut self,
+		rng: Range<K>,
+		ts: Option<u64>,
+		keep: Option<u64>,
+		limit: u32,
+	) -> Result<Option<Key>, Error>
+	where
+		K: KeyEncode + Debug,
+	{
+		let beg: Key = rng.start.encode_owned()?;
+		let end: Key = rng.end.encode_owned()?;
+		let rng = beg..end;
+		trace!(target: TARGET, rng = rng.sprint(), ts = ts, keep = keep, "PruneVersions");
+		self.inner.prune_versions(rng, ts, keep, limit).await
+	}
+
 	/// Obtain a new change timestamp for a key
 	/// which is replaced with the current timestamp when the transaction is committed.
 	/// NOTE: This should be called when composing the change feed entries for this transaction,
@@ -10,6 +10,8 @@ use crate::dbs::node::Node;
 use crate::err::Error;
 use crate::idx::planner::ScanDirection;
 use crate::idx::trees::store::cache::IndexTreeCaches;
+use crate::key::database::cn::Cn;
+use crate::key::database::sk::Sk;
 use crate::key::database::sq::Sq;
 use crate::kvs::cache;
 use crate::kvs::cache::tx::TransactionCache;
@@ -17,7 +19,9 @@ use crate::kvs::scanner::Scanner;
 use crate::kvs::Transactor;
 use crate::sql::statements::define::BucketDefinition;
 use crate::sql::statements::define::DefineConfigStatement;
-use crate::sql::statements::define::{ApiDefinition, DefineSequenceStatement};
+use crate::sql::statements::define::{
+	ApiDefinition, DefineConsumerStatement, DefineSequenceStatement, DefineSinkStatement,
+};
 use crate::sql::statements::AccessGrant;
 use crate::sql::statements::DefineAccessStatement;
 use crate::sql::statements::DefineAnalyzerStatement;
@@ -104,6 +108,15 @@ impl Transaction {
 		self.lock().await.closed()
 	}
 
+	/// Check if a version retention policy can be enforced.
+	///
+	/// If the storage engine does not keep the versions of keys,
+	/// or can remove the versions outside of a retention policy,
+	/// then this function will return [`true`].
+	pub async fn retainable(&self) -> bool {
+		self.lock().await.retainable()
+	}
+
 	/// Cancel a transaction.
 	///
 	/// This reverses all changes made within the transaction.
@@ -414,6 +427,38 @@ impl Transaction {
 		self.lock().await.batch_keys_vals_versions(rng, batch).await
 	}
 
+	/// Retrieve a batched scan over the versions of a specific range of keys in the datastore.
+	///
+	/// This function fetches the key-version pairs, without their values, in batches, with multiple requests to the underlying datastore.
+	#[instrument(level = "trace", target = "surrealdb::core::kvs::tx", skip_all)]
+	pub async fn batch_versions<K>(
+		&self,
+		rng: Range<K>,
+		batch: u32,
+	) -> Result<Batch<(Key, Version)>, Error>
+	where
+		K: KeyEncode + Debug,
+	{
+		self.lock().await.batch_versions(rng, batch).await
+	}
+
+	/// Delete the versions of a range of keys which are outside of a retention policy.
+	///
+	/// This function processes up to `limit` keys, returning the key from which to continue.
+	#[instrument(level = "trace", target = "surrealdb::core::kvs::tx", skip_all)]
+	pub async fn prune_versions<K>(
+		&self,
+		rng: Range<K>,
+		ts: Option<u64>,
+		keep: Option<u64>,
+		limit: u32,
+	) -> Result<Option<Key>, Error>
+	where
+		K: KeyEncode + Debug,
+	{
+		self.lock().await.prune_versions(rng, ts, keep, limit).await
+	}
+
 	/// Retrieve a stream over a specific range of keys in the datastore.
 	///
 	/// This function fetches the key-value pairs in batches, with multiple requests to the underlying datastore.
@@ -782,6 +827,50 @@ impl Transaction {
 		}
 	}
 
+	/// Retrieve all consumer definitions for a specific database.
+	#[instrument(level = "trace", target = "surrealdb::core::kvs::tx", skip(self))]
+	pub async fn all_db_consumers(
+		&self,
+		ns: &str,
+		db: &str,
+	) -> Result<Arc<[DefineConsumerStatement]>, Error> {
+		let qey = cache::tx::Lookup::Cns(ns, db);
+		match self.cache.get(&qey) {
+			Some(val) => val.try_into_cns(),
+			None => {
+				let beg = crate::key::database::cn::prefix(ns, db)?;
+				let end = crate::key::database::cn::suffix(ns, db)?;
+				let val = self.getr(beg..end, None).await?;
+				let val = util::deserialize_cache(val.iter().map(|x| x.1.as_slice()))?;
+				let entry = cache::tx::Entry::Cns(val.clone());
+				self.cache.insert(qey, entry);
+				Ok(val)
+			}
+		}
+	}
+
+	/// Retrieve all sink definitions for a specific database.
+	#[instrument(level = "trace", target = "surrealdb::core::kvs::tx", skip(self))]
+	pub async fn all_db_sinks(
+		&self,
+		ns: &str,
+		db: &str,
+	) -> Result<Arc<[DefineSinkStatement]>, Error> {
+		let qey = cache::tx::Lookup::Sks(ns, db);
+		match self.cache.get(&qey) {
+			Some(val) => val.try_into_sks(),
+			None => {
+				let beg = crate::key::database::sk::prefix(ns, db)?;
+				let end = crate::key::database::sk::suffix(ns, db)?;
+				let val = self.getr(beg..end, None).await?;
+				let val = util::deserialize_cache(val.iter().map(|x| x.1.as_slice()))?;
+				let entry = cache::tx::Entry::Sks(val.clone());
+				self.cache.insert(qey, entry);
+				Ok(val)
+			}
+		}
+	}
+
 	/// Retrieve all function definitions for a specific database.
 	#[instrument(level = "trace", target = "surrealdb::core::kvs::tx", skip(self))]
 	pub async fn all_db_functions(
@@ -1418,6 +1507,56 @@ impl Transaction {
 		}
 	}
 
+	/// Retrieve a specific consumer definition from a database.
+	#[instrument(level = "trace", target = "surrealdb::core::kvs::tx", skip(self))]
+	pub async fn get_db_consumer(
+		&self,
+		ns: &str,
+		db: &str,
+		cn: &str,
+	) -> Result<Arc<DefineConsumerStatement>, Error> {
+		let qey = cache::tx::Lookup::Cn(ns, db, cn);
+		match self.cache.get(&qey) {
+			Some(val) => val.try_into_type(),
+			None => {
+				let key = Cn::new(ns, db, cn).encode()?;
+				let val = self.get(key, None).await?.ok_or_else(|| Error::CnNotFound {
+					name: cn.to_owned(),
+				})?;
+				let val: DefineConsumerStatement = revision::from_slice(&val)?;
+				let val = Arc::new(val);
+				let entr = cache::tx::Entry::Any(val.clone());
+				self.cache.insert(qey, entr);
+				Ok(val)
+			}
+		}
+	}
+
+	/// Retrieve a specific sink definition from a database.
+	#[instrument(level = "trace", target = "surrealdb::core::kvs::tx", skip(self))]
+	pub async fn get_db_sink(
+		&self,
+		ns: &str,
+		db: &str,
+		sk: &str,
+	) -> Result<Arc<DefineSinkStatement>, Error> {
+		let qey = cache::tx::Lookup::Sk(ns, db, sk);
+		match self.cache.get(&qey) {
+			Some(val) => val.try_into_type(),
+			None => {
+				let key = Sk::new(ns, db, sk).encode()?;
+				let val = self.get(key, None).await?.ok_or_else(|| Error::SkNotFound {
+					name: sk.to_owned(),
+				})?;
+				let val: DefineSinkStatement = revision::from_slice(&val)?;
+				let val = Arc::new(val);
+				let entr = cache::tx::Entry::Any(val.clone());
+				self.cache.insert(qey, entr);
+				Ok(val)
+			}
+		}
+	}
+
 	/// Retrieve a specific function definition from a database.
 	#[instrument(level = "trace", target = "surrealdb::core::kvs::tx", skip(self))]
 	pub async fn get_db_function(
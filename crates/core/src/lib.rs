@@ -21,7 +21,6 @@ extern crate tracing;
 #[macro_use]
 mod mac;
 
-mod buc;
 mod cf;
 mod doc;
 mod exe;
@@ -31,6 +30,7 @@ mod key;
 mod sys;
 
 pub mod api;
+pub mod buc;
 pub mod cnf;
 pub mod ctx;
 pub mod dbs;
@@ -10,6 +10,9 @@ pub struct EngineOptions {
 	pub node_membership_check_interval: Duration,
 	pub node_membership_cleanup_interval: Duration,
 	pub changefeed_gc_interval: Duration,
+	pub transaction_expiry_interval: Duration,
+	pub sink_delivery_interval: Duration,
+	pub versions_gc_interval: Duration,
 }
 
 impl Default for EngineOptions {
@@ -19,6 +22,9 @@ impl Default for EngineOptions {
 			node_membership_check_interval: Duration::from_secs(15),
 			node_membership_cleanup_interval: Duration::from_secs(300),
 			changefeed_gc_interval: Duration::from_secs(10),
+			transaction_expiry_interval: Duration::from_secs(1),
+			sink_delivery_interval: Duration::from_secs(1),
+			versions_gc_interval: Duration::from_secs(60),
 		}
 	}
 }
@@ -40,4 +46,16 @@ impl EngineOptions {
 		self.changefeed_gc_interval = interval;
 		self
 	}
+	pub fn with_transaction_expiry_interval(mut self, interval: Duration) -> Self {
+		self.transaction_expiry_interval = interval;
+		self
+	}
+	pub fn with_sink_delivery_interval(mut self, interval: Duration) -> Self {
+		self.sink_delivery_interval = interval;
+		self
+	}
+	pub fn with_versions_gc_interval(mut self, interval: Duration) -> Self {
+		self.versions_gc_interval = interval;
+		self
+	}
 }
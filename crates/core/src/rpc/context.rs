@@ -11,6 +11,7 @@ use super::RpcProtocolV1;
 use super::RpcProtocolV2;
 use crate::dbs::Session;
 use crate::kvs::Datastore;
+use crate::sql::statements::show::ShowSince;
 use crate::sql::Array;
 
 #[expect(async_fn_in_trait)]
@@ -46,6 +47,27 @@ pub trait RpcContext {
 		async { unimplemented!("cleanup_lqs function must be implemented if LQ_SUPPORT = true") }
 	}
 
+	// ------------------------------
+	// Change feeds
+	// ------------------------------
+
+	/// Change feed streams are disabled by default
+	const CF_SUPPORT: bool = false;
+
+	/// Handles the start of a change feed stream
+	fn handle_changes(
+		&self,
+		_id: Uuid,
+		_tb: Option<String>,
+		_since: ShowSince,
+	) -> impl std::future::Future<Output = ()> + Send {
+		async { unimplemented!("handle_changes function must be implemented if CF_SUPPORT = true") }
+	}
+	/// Handles the cancellation of a change feed stream, returning whether it existed
+	fn kill_changes(&self, _id: &Uuid) -> impl std::future::Future<Output = bool> + Send {
+		async { unimplemented!("kill_changes function must be implemented if CF_SUPPORT = true") }
+	}
+
 	// ------------------------------
 	// GraphQL
 	// ------------------------------
@@ -68,6 +90,7 @@ pub trait RpcContext {
 	async fn execute(
 		&self,
 		version: Option<u8>,
+		txn: Option<Uuid>,
 		method: Method,
 		params: Array,
 	) -> Result<Data, RpcError>
@@ -76,9 +99,9 @@ pub trait RpcContext {
 		Self: RpcProtocolV2,
 	{
 		match version {
-			Some(1) => RpcProtocolV1::execute(self, method, params).await,
-			Some(2) => RpcProtocolV2::execute(self, method, params).await,
-			_ => RpcProtocolV1::execute(self, method, params).await,
+			Some(1) => RpcProtocolV1::execute(self, txn, method, params).await,
+			Some(2) => RpcProtocolV2::execute(self, txn, method, params).await,
+			_ => RpcProtocolV1::execute(self, txn, method, params).await,
 		}
 	}
 }
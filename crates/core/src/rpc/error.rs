@@ -23,6 +23,10 @@ pub enum RpcError {
 	BadLQConfig,
 	#[error("A GraphQL request was made, but GraphQL is not supported by the context")]
 	BadGQLConfig,
+	#[error("A change feed stream was requested, but streaming is not supported by the context")]
+	BadCFConfig,
+	#[error("Method can not be run within a transaction")]
+	MethodNotTransactional,
 	#[error("Error: {0}")]
 	Thrown(String),
 }
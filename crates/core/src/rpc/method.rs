@@ -28,6 +28,10 @@ pub enum Method {
 	Run,
 	GraphQL,
 	InsertRelation,
+	Begin,
+	Commit,
+	Cancel,
+	Changes,
 }
 
 impl Method {
@@ -79,6 +83,10 @@ impl Method {
 			"run" => Self::Run,
 			"graphql" => Self::GraphQL,
 			"insert_relation" => Self::InsertRelation,
+			"begin" => Self::Begin,
+			"commit" => Self::Commit,
+			"cancel" => Self::Cancel,
+			"changes" => Self::Changes,
 			_ => Self::Unknown,
 		}
 	}
@@ -114,6 +122,10 @@ impl Method {
 			Self::Run => "run",
 			Self::GraphQL => "graphql",
 			Self::InsertRelation => "insert_relation",
+			Self::Begin => "begin",
+			Self::Commit => "commit",
+			Self::Cancel => "cancel",
+			Self::Changes => "changes",
 		}
 	}
 }
@@ -129,4 +141,26 @@ impl Method {
 	pub fn is_valid(&self) -> bool {
 		!matches!(self, Self::Unknown)
 	}
+
+	/// Checks if the method can be run within an interactive transaction
+	pub fn is_transactional(&self) -> bool {
+		// Methods which query or modify records
+		matches!(
+			self,
+			Self::Select
+				| Self::Insert
+				| Self::InsertRelation
+				| Self::Create
+				| Self::Upsert
+				| Self::Update
+				| Self::Merge
+				| Self::Patch
+				| Self::Delete
+				| Self::Relate
+				| Self::Query
+				| Self::Run
+		)
+		// Methods which finish the transaction
+		|| matches!(self, Self::Commit | Self::Cancel)
+	}
 }
@@ -2,14 +2,18 @@
 use async_graphql::BatchRequest;
 use std::collections::BTreeMap;
 use std::sync::Arc;
+use uuid::Uuid;
 
 #[cfg(not(target_family = "wasm"))]
 use crate::dbs::capabilities::ExperimentalTarget;
 use crate::err::Error;
+use crate::iam::check::check_ns_db;
+use crate::iam::{Action, ResourceKind};
 use crate::rpc::Data;
 use crate::rpc::Method;
 use crate::rpc::RpcContext;
 use crate::rpc::RpcError;
+use crate::sql::statements::show::ShowSince;
 use crate::{
 	dbs::{capabilities::MethodTarget, QueryType, Response},
 	rpc::args::Take,
@@ -18,7 +22,7 @@ use crate::{
 			CreateStatement, DeleteStatement, InsertStatement, KillStatement, LiveStatement,
 			RelateStatement, SelectStatement, UpdateStatement, UpsertStatement,
 		},
-		Array, Fields, Function, Model, Output, Query, Strand, Value,
+		Array, Fields, Function, Model, Number, Output, Query, Strand, Value,
 	},
 };
 
@@ -29,7 +33,12 @@ pub trait RpcProtocolV1: RpcContext {
 	// ------------------------------
 
 	/// Executes a method on this RPC implementation
-	async fn execute(&self, method: Method, params: Array) -> Result<Data, RpcError> {
+	async fn execute(
+		&self,
+		txn: Option<Uuid>,
+		method: Method,
+		params: Array,
+	) -> Result<Data, RpcError> {
 		// Check if capabilities allow executing the requested RPC method
 		if !self.kvs().allows_rpc_method(&MethodTarget {
 			method,
@@ -37,6 +46,10 @@ pub trait RpcProtocolV1: RpcContext {
 			warn!("Capabilities denied RPC method call attempt, target: '{method}'");
 			return Err(RpcError::MethodNotAllowed);
 		}
+		// Check if the method can be run within a transaction
+		if txn.is_some() && !method.is_transactional() {
+			return Err(RpcError::MethodNotTransactional);
+		}
 		// Execute the desired method
 		match method {
 			Method::Ping => Ok(Value::None.into()),
@@ -51,20 +64,24 @@ pub trait RpcProtocolV1: RpcContext {
 			Method::Live => self.live(params).await,
 			Method::Set => self.set(params).await,
 			Method::Unset => self.unset(params).await,
-			Method::Select => self.select(params).await,
-			Method::Insert => self.insert(params).await,
-			Method::Create => self.create(params).await,
-			Method::Upsert => self.upsert(params).await,
-			Method::Update => self.update(params).await,
-			Method::Merge => self.merge(params).await,
-			Method::Patch => self.patch(params).await,
-			Method::Delete => self.delete(params).await,
+			Method::Select => self.select(txn, params).await,
+			Method::Insert => self.insert(txn, params).await,
+			Method::Create => self.create(txn, params).await,
+			Method::Upsert => self.upsert(txn, params).await,
+			Method::Update => self.update(txn, params).await,
+			Method::Merge => self.merge(txn, params).await,
+			Method::Patch => self.patch(txn, params).await,
+			Method::Delete => self.delete(txn, params).await,
 			Method::Version => self.version(params).await,
-			Method::Query => self.query(params).await,
-			Method::Relate => self.relate(params).await,
-			Method::Run => self.run(params).await,
+			Method::Query => self.query(txn, params).await,
+			Method::Relate => self.relate(txn, params).await,
+			Method::Run => self.run(txn, params).await,
 			Method::GraphQL => self.graphql(params).await,
-			Method::InsertRelation => self.insert_relation(params).await,
+			Method::InsertRelation => self.insert_relation(txn, params).await,
+			Method::Begin => self.begin().await,
+			Method::Commit => self.commit(txn).await,
+			Method::Cancel => self.cancel(txn).await,
+			Method::Changes => self.changes(params).await,
 			_ => Err(RpcError::MethodNotFound),
 		}
 	}
@@ -336,6 +353,17 @@ pub trait RpcProtocolV1: RpcContext {
 		}
 		// Process the method arguments
 		let id = params.needs_one()?;
+		// Stop the change feed stream with this id, if there is one
+		let uuid = match id {
+			Value::Uuid(ref v) => Some(v.0),
+			Value::Strand(ref v) => Uuid::try_parse(v).ok(),
+			_ => None,
+		};
+		if let Some(uuid) = uuid {
+			if Self::CF_SUPPORT && self.kill_changes(&uuid).await {
+				return Ok(Value::None.into());
+			}
+		}
 		// Specify the SQL query string
 		let sql = KillStatement {
 			id,
@@ -344,7 +372,7 @@ pub trait RpcProtocolV1: RpcContext {
 		// Specify the query parameters
 		let var = Some(self.session().parameters.clone());
 		// Execute the query on the database
-		let mut res = self.query_inner(Value::Query(sql), var).await?;
+		let mut res = self.query_inner(None, Value::Query(sql), var).await?;
 		// Extract the first query result
 		Ok(res.remove(0).result?.into())
 	}
@@ -368,16 +396,56 @@ pub trait RpcProtocolV1: RpcContext {
 		// Specify the query parameters
 		let var = Some(self.session().parameters.clone());
 		// Execute the query on the database
-		let mut res = self.query_inner(Value::Query(sql), var).await?;
+		let mut res = self.query_inner(None, Value::Query(sql), var).await?;
 		// Extract the first query result
 		Ok(res.remove(0).result?.into())
 	}
 
+	// ------------------------------
+	// Methods for change feeds
+	// ------------------------------
+
+	async fn changes(&self, params: Array) -> Result<Data, RpcError> {
+		// Check if the user is allowed to query
+		if !self.kvs().allows_query_by_subject(self.session().au.as_ref()) {
+			return Err(RpcError::MethodNotAllowed);
+		}
+		// Check if change feeds can be streamed
+		if !Self::CF_SUPPORT {
+			return Err(RpcError::BadCFConfig);
+		}
+		// Process the method arguments
+		let (what, since) = params.needs_one_or_two()?;
+		// Check which table to stream the changes for
+		let tb = match what {
+			Value::None | Value::Null => None,
+			Value::Table(v) => Some(v.0),
+			Value::Strand(v) => Some(v.0),
+			_ => return Err(RpcError::InvalidParams),
+		};
+		// Check where the changes should be streamed from
+		let since = match since {
+			Value::None | Value::Null => ShowSince::Versionstamp(0),
+			Value::Number(Number::Int(v)) if v >= 0 => ShowSince::Versionstamp(v as u64),
+			Value::Datetime(v) => ShowSince::Timestamp(v),
+			_ => return Err(RpcError::InvalidParams),
+		};
+		// Check the permissions level
+		let sess = self.session();
+		let (ns, db) = check_ns_db(&sess)?;
+		self.kvs().check(&sess, Action::View, ResourceKind::Table.on_db(&ns, &db))?;
+		// Start streaming the changes
+		let id = Uuid::new_v4();
+		self.handle_changes(id, tb, since).await;
+		// Return the id of the stream
+		Ok(Value::Uuid(id.into()).into())
+	}
+
 	// ------------------------------
 	// Methods for selecting
 	// ------------------------------
 
-	async fn select(&self, params: Array) -> Result<Data, RpcError> {
+	async fn select(&self, txn: Option<Uuid>, params: Array) -> Result<Data, RpcError> {
 		// Check if the user is allowed to query
 		if !self.kvs().allows_query_by_subject(self.session().au.as_ref()) {
 			return Err(RpcError::MethodNotAllowed);
@@ -397,7 +465,7 @@ pub trait RpcProtocolV1: RpcContext {
 		// Specify the query parameters
 		let var = Some(self.session().parameters.clone());
 		// Execute the query on the database
-		let mut res = self.kvs().process(sql, &self.session(), var).await?;
+		let mut res = self.process_inner(txn, sql, var).await?;
 		// Extract the first query result
 		Ok(res
 			.remove(0)
@@ -413,7 +481,7 @@ pub trait RpcProtocolV1: RpcContext {
 	// Methods for inserting
 	// ------------------------------
 
-	async fn insert(&self, params: Array) -> Result<Data, RpcError> {
+	async fn insert(&self, txn: Option<Uuid>, params: Array) -> Result<Data, RpcError> {
 		// Check if the user is allowed to query
 		if !self.kvs().allows_query_by_subject(self.session().au.as_ref()) {
 			return Err(RpcError::MethodNotAllowed);
@@ -436,7 +504,7 @@ pub trait RpcProtocolV1: RpcContext {
 		// Specify the query parameters
 		let var = Some(self.session().parameters.clone());
 		// Execute the query on the database
-		let mut res = self.kvs().process(sql, &self.session(), var).await?;
+		let mut res = self.process_inner(txn, sql, var).await?;
 		// Extract the first query result
 		Ok(res
 			.remove(0)
@@ -448,7 +516,7 @@ pub trait RpcProtocolV1: RpcContext {
 			.into())
 	}
 
-	async fn insert_relation(&self, params: Array) -> Result<Data, RpcError> {
+	async fn insert_relation(&self, txn: Option<Uuid>, params: Array) -> Result<Data, RpcError> {
 		// Check if the user is allowed to query
 		if !self.kvs().allows_query_by_subject(self.session().au.as_ref()) {
 			return Err(RpcError::MethodNotAllowed);
@@ -472,7 +540,7 @@ pub trait RpcProtocolV1: RpcContext {
 		// Specify the query parameters
 		let var = Some(self.session().parameters.clone());
 		// Execute the query on the database
-		let mut res = self.kvs().process(sql, &self.session(), var).await?;
+		let mut res = self.process_inner(txn, sql, var).await?;
 		// Extract the first query result
 		Ok(res
 			.remove(0)
@@ -488,7 +556,7 @@ pub trait RpcProtocolV1: RpcContext {
 	// Methods for creating
 	// ------------------------------
 
-	async fn create(&self, params: Array) -> Result<Data, RpcError> {
+	async fn create(&self, txn: Option<Uuid>, params: Array) -> Result<Data, RpcError> {
 		// Check if the user is allowed to query
 		if !self.kvs().allows_query_by_subject(self.session().au.as_ref()) {
 			return Err(RpcError::MethodNotAllowed);
@@ -511,7 +579,7 @@ pub trait RpcProtocolV1: RpcContext {
 		}
 		.into();
 		// Execute the query on the database
-		let mut res = self.kvs().process(sql, &self.session(), None).await?;
+		let mut res = self.process_inner(txn, sql, None).await?;
 		// Extract the first query result
 		Ok(res
 			.remove(0)
@@ -527,7 +595,7 @@ pub trait RpcProtocolV1: RpcContext {
 	// Methods for upserting
 	// ------------------------------
 
-	async fn upsert(&self, params: Array) -> Result<Data, RpcError> {
+	async fn upsert(&self, txn: Option<Uuid>, params: Array) -> Result<Data, RpcError> {
 		// Check if the user is allowed to query
 		if !self.kvs().allows_query_by_subject(self.session().au.as_ref()) {
 			return Err(RpcError::MethodNotAllowed);
@@ -551,7 +619,7 @@ pub trait RpcProtocolV1: RpcContext {
 		// Specify the query parameters
 		let var = Some(self.session().parameters.clone());
 		// Execute the query on the database
-		let mut res = self.kvs().process(sql, &self.session(), var).await?;
+		let mut res = self.process_inner(txn, sql, var).await?;
 		// Extract the first query result
 		Ok(res
 			.remove(0)
@@ -567,7 +635,7 @@ pub trait RpcProtocolV1: RpcContext {
 	// Methods for updating
 	// ------------------------------
 
-	async fn update(&self, params: Array) -> Result<Data, RpcError> {
+	async fn update(&self, txn: Option<Uuid>, params: Array) -> Result<Data, RpcError> {
 		// Check if the user is allowed to query
 		if !self.kvs().allows_query_by_subject(self.session().au.as_ref()) {
 			return Err(RpcError::MethodNotAllowed);
@@ -591,7 +659,7 @@ pub trait RpcProtocolV1: RpcContext {
 		// Specify the query parameters
 		let var = Some(self.session().parameters.clone());
 		// Execute the query on the database
-		let mut res = self.kvs().process(sql, &self.session(), var).await?;
+		let mut res = self.process_inner(txn, sql, var).await?;
 		// Extract the first query result
 		Ok(res
 			.remove(0)
@@ -607,7 +675,7 @@ pub trait RpcProtocolV1: RpcContext {
 	// Methods for merging
 	// ------------------------------
 
-	async fn merge(&self, params: Array) -> Result<Data, RpcError> {
+	async fn merge(&self, txn: Option<Uuid>, params: Array) -> Result<Data, RpcError> {
 		// Check if the user is allowed to query
 		if !self.kvs().allows_query_by_subject(self.session().au.as_ref()) {
 			return Err(RpcError::MethodNotAllowed);
@@ -631,7 +699,7 @@ pub trait RpcProtocolV1: RpcContext {
 		// Specify the query parameters
 		let var = Some(self.session().parameters.clone());
 		// Execute the query on the database
-		let mut res = self.kvs().process(sql, &self.session(), var).await?;
+		let mut res = self.process_inner(txn, sql, var).await?;
 		// Extract the first query result
 		Ok(res
 			.remove(0)
@@ -647,7 +715,7 @@ pub trait RpcProtocolV1: RpcContext {
 	// Methods for patching
 	// ------------------------------
 
-	async fn patch(&self, params: Array) -> Result<Data, RpcError> {
+	async fn patch(&self, txn: Option<Uuid>, params: Array) -> Result<Data, RpcError> {
 		// Check if the user is allowed to query
 		if !self.kvs().allows_query_by_subject(self.session().au.as_ref()) {
 			return Err(RpcError::MethodNotAllowed);
@@ -671,7 +739,7 @@ pub trait RpcProtocolV1: RpcContext {
 		// Specify the query parameters
 		let var = Some(self.session().parameters.clone());
 		// Execute the query on the database
-		let mut res = self.kvs().process(sql, &self.session(), var).await?;
+		let mut res = self.process_inner(txn, sql, var).await?;
 		// Extract the first query result
 		Ok(res
 			.remove(0)
@@ -687,7 +755,7 @@ pub trait RpcProtocolV1: RpcContext {
 	// Methods for relating
 	// ------------------------------
 
-	async fn relate(&self, params: Array) -> Result<Data, RpcError> {
+	async fn relate(&self, txn: Option<Uuid>, params: Array) -> Result<Data, RpcError> {
 		// Check if the user is allowed to query
 		if !self.kvs().allows_query_by_subject(self.session().au.as_ref()) {
 			return Err(RpcError::MethodNotAllowed);
@@ -713,7 +781,7 @@ pub trait RpcProtocolV1: RpcContext {
 		// Specify the query parameters
 		let var = Some(self.session().parameters.clone());
 		// Execute the query on the database
-		let mut res = self.kvs().process(sql, &self.session(), var).await?;
+		let mut res = self.process_inner(txn, sql, var).await?;
 		// Extract the first query result
 		Ok(res
 			.remove(0)
@@ -729,7 +797,7 @@ pub trait RpcProtocolV1: RpcContext {
 	// Methods for deleting
 	// ------------------------------
 
-	async fn delete(&self, params: Array) -> Result<Data, RpcError> {
+	async fn delete(&self, txn: Option<Uuid>, params: Array) -> Result<Data, RpcError> {
 		// Check if the user is allowed to query
 		if !self.kvs().allows_query_by_subject(self.session().au.as_ref()) {
 			return Err(RpcError::MethodNotAllowed);
@@ -749,7 +817,7 @@ pub trait RpcProtocolV1: RpcContext {
 		// Specify the query parameters
 		let var = Some(self.session().parameters.clone());
 		// Execute the query on the database
-		let mut res = self.kvs().process(sql, &self.session(), var).await?;
+		let mut res = self.process_inner(txn, sql, var).await?;
 		// Extract the first query result
 		Ok(res
 			.remove(0)
@@ -761,6 +829,47 @@ pub trait RpcProtocolV1: RpcContext {
 			.into())
 	}
 
+	// ------------------------------
+	// Methods for transactions
+	// ------------------------------
+
+	async fn begin(&self) -> Result<Data, RpcError> {
+		// Check if the user is allowed to query
+		if !self.kvs().allows_query_by_subject(self.session().au.as_ref()) {
+			return Err(RpcError::MethodNotAllowed);
+		}
+		// Start a new interactive transaction
+		let txn = self.kvs().begin(&self.session()).await?;
+		// Return the transaction id
+		Ok(Value::Uuid(txn.into()).into())
+	}
+
+	async fn commit(&self, txn: Option<Uuid>) -> Result<Data, RpcError> {
+		// Process the transaction id
+		let Some(txn) = txn else {
+			return Err(RpcError::InvalidParams);
+		};
+		// Commit the interactive transaction
+		let res = self.kvs().commit(txn, &self.session()).await?;
+		// Post-process hooks for web layer
+		for response in &res {
+			self.handle_live_query_results(response).await;
+		}
+		// Return nothing on success
+		Ok(Value::None.into())
+	}
+
+	async fn cancel(&self, txn: Option<Uuid>) -> Result<Data, RpcError> {
+		// Process the transaction id
+		let Some(txn) = txn else {
+			return Err(RpcError::InvalidParams);
+		};
+		// Cancel the interactive transaction
+		self.kvs().cancel(txn, &self.session()).await?;
+		// Return nothing on success
+		Ok(Value::None.into())
+	}
+
 	// ------------------------------
 	// Methods for getting info
 	// ------------------------------
@@ -776,7 +885,7 @@ pub trait RpcProtocolV1: RpcContext {
 	// Methods for querying
 	// ------------------------------
 
-	async fn query(&self, params: Array) -> Result<Data, RpcError> {
+	async fn query(&self, txn: Option<Uuid>, params: Array) -> Result<Data, RpcError> {
 		// Check if the user is allowed to query
 		if !self.kvs().allows_query_by_subject(self.session().au.as_ref()) {
 			return Err(RpcError::MethodNotAllowed);
@@ -796,14 +905,14 @@ pub trait RpcProtocolV1: RpcContext {
 			_ => return Err(RpcError::InvalidParams),
 		};
 		// Execute the specified query
-		self.query_inner(query, vars).await.map(Into::into)
+		self.query_inner(txn, query, vars).await.map(Into::into)
 	}
 
 	// ------------------------------
 	// Methods for running functions
 	// ------------------------------
 
-	async fn run(&self, params: Array) -> Result<Data, RpcError> {
+	async fn run(&self, txn: Option<Uuid>, params: Array) -> Result<Data, RpcError> {
 		// Check if the user is allowed to query
 		if !self.kvs().allows_query_by_subject(self.session().au.as_ref()) {
 			return Err(RpcError::MethodNotAllowed);
@@ -843,7 +952,7 @@ pub trait RpcProtocolV1: RpcContext {
 		// Specify the query parameters
 		let var = Some(self.session().parameters.clone());
 		// Execute the function on the database
-		let mut res = self.kvs().process(func, &self.session(), var).await?;
+		let mut res = self.process_inner(txn, func, var).await?;
 		// Extract the first query result
 		Ok(res.remove(0).result?.into())
 	}
@@ -976,6 +1085,7 @@ pub trait RpcProtocolV1: RpcContext {
 
 	async fn query_inner(
 		&self,
+		txn: Option<Uuid>,
 		query: Value,
 		vars: Option<BTreeMap<String, Value>>,
 	) -> Result<Vec<Response>, RpcError> {
@@ -984,21 +1094,45 @@ pub trait RpcProtocolV1: RpcContext {
 			return Err(RpcError::BadLQConfig);
 		}
 		// Execute the query on the database
-		let res = match query {
-			Value::Query(sql) => self.kvs().process(sql, &self.session(), vars).await?,
-			Value::Strand(sql) => self.kvs().execute(&sql, &self.session(), vars).await?,
-			_ => return Err(fail!("Unexpected query type: {query:?}").into()),
+		let res = match (txn, query) {
+			(None, Value::Query(sql)) => self.kvs().process(sql, &self.session(), vars).await?,
+			(None, Value::Strand(sql)) => self.kvs().execute(&sql, &self.session(), vars).await?,
+			(Some(txn), Value::Query(sql)) => {
+				self.kvs().process_in_transaction(txn, sql, &self.session(), vars).await?
+			}
+			(Some(txn), Value::Strand(sql)) => {
+				self.kvs().execute_in_transaction(txn, &sql, &self.session(), vars).await?
+			}
+			(_, query) => return Err(fail!("Unexpected query type: {query:?}").into()),
 		};
 
-		// Post-process hooks for web layer
-		for response in &res {
-			// This error should be unreachable because we shouldn't proceed if there's no handler
-			self.handle_live_query_results(response).await;
+		// Post-process hooks for web layer, which are
+		// deferred until an interactive transaction commits
+		if txn.is_none() {
+			for response in &res {
+				// This error should be unreachable because we shouldn't proceed if there's no handler
+				self.handle_live_query_results(response).await;
+			}
 		}
 		// Return the result to the client
 		Ok(res)
 	}
 
+	async fn process_inner(
+		&self,
+		txn: Option<Uuid>,
+		query: Query,
+		vars: Option<BTreeMap<String, Value>>,
+	) -> Result<Vec<Response>, RpcError> {
+		// Execute the query on the database, within the transaction if specified
+		match txn {
+			None => Ok(self.kvs().process(query, &self.session(), vars).await?),
+			Some(txn) => {
+				Ok(self.kvs().process_in_transaction(txn, query, &self.session(), vars).await?)
+			}
+		}
+	}
+
 	async fn handle_live_query_results(&self, res: &Response) {
 		match &res.query_type {
 			QueryType::Live => {
@@ -2,16 +2,19 @@
 use async_graphql::BatchRequest;
 use std::collections::BTreeMap;
 use std::sync::Arc;
+use uuid::Uuid;
 
 #[cfg(not(target_family = "wasm"))]
 use crate::dbs::capabilities::ExperimentalTarget;
 use crate::err::Error;
+use crate::iam::check::check_ns_db;
+use crate::iam::{Action, ResourceKind};
 use crate::rpc::statement_options::StatementOptions;
 use crate::rpc::Data;
 use crate::rpc::Method;
 use crate::rpc::RpcContext;
 use crate::rpc::RpcError;
-use crate::sql::Uuid;
+use crate::sql::statements::show::ShowSince;
 use crate::{
 	dbs::{capabilities::MethodTarget, QueryType, Response},
 	rpc::args::Take,
@@ -20,7 +23,7 @@ use crate::{
 			CreateStatement, DeleteStatement, InsertStatement, KillStatement, LiveStatement,
 			RelateStatement, SelectStatement, UpdateStatement, UpsertStatement,
 		},
-		Array, Fields, Function, Model, Output, Query, Strand, Value,
+		Array, Fields, Function, Model, Number, Output, Query, Strand, Value,
 	},
 };
 
@@ -31,7 +34,12 @@ pub trait RpcProtocolV2: RpcContext {
 	// ------------------------------
 
 	/// Executes a method on this RPC implementation
-	async fn execute(&self, method: Method, params: Array) -> Result<Data, RpcError> {
+	async fn execute(
+		&self,
+		txn: Option<Uuid>,
+		method: Method,
+		params: Array,
+	) -> Result<Data, RpcError> {
 		// Check if capabilities allow executing the requested RPC method
 		if !self.kvs().allows_rpc_method(&MethodTarget {
 			method,
@@ -39,6 +47,10 @@ pub trait RpcProtocolV2: RpcContext {
 			warn!("Capabilities denied RPC method call attempt, target: '{method}'");
 			return Err(RpcError::MethodNotAllowed);
 		}
+		// Check if the method can be run within a transaction
+		if txn.is_some() && !method.is_transactional() {
+			return Err(RpcError::MethodNotTransactional);
+		}
 		// Execute the desired method
 		match method {
 			Method::Ping => Ok(Value::None.into()),
@@ -53,17 +65,21 @@ pub trait RpcProtocolV2: RpcContext {
 			Method::Live => self.live(params).await,
 			Method::Set => self.set(params).await,
 			Method::Unset => self.unset(params).await,
-			Method::Select => self.select(params).await,
-			Method::Insert => self.insert(params).await,
-			Method::Create => self.create(params).await,
-			Method::Upsert => self.upsert(params).await,
-			Method::Update => self.update(params).await,
-			Method::Delete => self.delete(params).await,
+			Method::Select => self.select(txn, params).await,
+			Method::Insert => self.insert(txn, params).await,
+			Method::Create => self.create(txn, params).await,
+			Method::Upsert => self.upsert(txn, params).await,
+			Method::Update => self.update(txn, params).await,
+			Method::Delete => self.delete(txn, params).await,
 			Method::Version => self.version(params).await,
-			Method::Query => self.query(params).await,
-			Method::Relate => self.relate(params).await,
-			Method::Run => self.run(params).await,
+			Method::Query => self.query(txn, params).await,
+			Method::Relate => self.relate(txn, params).await,
+			Method::Run => self.run(txn, params).await,
 			Method::GraphQL => self.graphql(params).await,
+			Method::Begin => self.begin().await,
+			Method::Commit => self.commit(txn).await,
+			Method::Cancel => self.cancel(txn).await,
+			Method::Changes => self.changes(params).await,
 			_ => Err(RpcError::MethodNotFound),
 		}
 	}
@@ -329,6 +345,17 @@ pub trait RpcProtocolV2: RpcContext {
 		}
 		// Process the method arguments
 		let id = params.needs_one()?;
+		// Stop the change feed stream with this id, if there is one
+		let uuid = match id {
+			Value::Uuid(ref v) => Some(v.0),
+			Value::Strand(ref v) => Uuid::try_parse(v).ok(),
+			_ => None,
+		};
+		if let Some(uuid) = uuid {
+			if Self::CF_SUPPORT && self.kill_changes(&uuid).await {
+				return Ok(Value::None.into());
+			}
+		}
 		// Specify the SQL query string
 		let sql = KillStatement {
 			id,
@@ -337,7 +364,7 @@ pub trait RpcProtocolV2: RpcContext {
 		// Specify the query parameters
 		let var = Some(self.session().parameters.clone());
 		// Execute the query on the database
-		let mut res = self.query_inner(Value::Query(sql), var).await?;
+		let mut res = self.query_inner(None, Value::Query(sql), var).await?;
 		// Extract the first query result
 		Ok(res.remove(0).result?.into())
 	}
@@ -359,8 +386,8 @@ pub trait RpcProtocolV2: RpcContext {
 		let var = Some(opts.merge_vars(&self.session().parameters));
 		// Specify the SQL query string
 		let sql = LiveStatement {
-			id: Uuid::new_v4(),
-			node: Uuid::new_v4(),
+			id: Uuid::new_v4().into(),
+			node: Uuid::new_v4().into(),
 			what: what.could_be_table(),
 			expr: if opts.diff {
 				Fields::default()
@@ -373,16 +400,56 @@ pub trait RpcProtocolV2: RpcContext {
 		}
 		.into();
 		// Execute the query on the database
-		let mut res = self.query_inner(Value::Query(sql), var).await?;
+		let mut res = self.query_inner(None, Value::Query(sql), var).await?;
 		// Extract the first query result
 		Ok(res.remove(0).result?.into())
 	}
 
+	// ------------------------------
+	// Methods for change feeds
+	// ------------------------------
+
+	async fn changes(&self, params: Array) -> Result<Data, RpcError> {
+		// Check if the user is allowed to query
+		if !self.kvs().allows_query_by_subject(self.session().au.as_ref()) {
+			return Err(RpcError::MethodNotAllowed);
+		}
+		// Check if change feeds can be streamed
+		if !Self::CF_SUPPORT {
+			return Err(RpcError::BadCFConfig);
+		}
+		// Process the method arguments
+		let (what, since) = params.needs_one_or_two()?;
+		// Check which table to stream the changes for
+		let tb = match what {
+			Value::None | Value::Null => None,
+			Value::Table(v) => Some(v.0),
+			Value::Strand(v) => Some(v.0),
+			_ => return Err(RpcError::InvalidParams),
+		};
+		// Check where the changes should be streamed from
+		let since = match since {
+			Value::None | Value::Null => ShowSince::Versionstamp(0),
+			Value::Number(Number::Int(v)) if v >= 0 => ShowSince::Versionstamp(v as u64),
+			Value::Datetime(v) => ShowSince::Timestamp(v),
+			_ => return Err(RpcError::InvalidParams),
+		};
+		// Check the permissions level
+		let sess = self.session();
+		let (ns, db) = check_ns_db(&sess)?;
+		self.kvs().check(&sess, Action::View, ResourceKind::Table.on_db(&ns, &db))?;
+		// Start streaming the changes
+		let id = Uuid::new_v4();
+		self.handle_changes(id, tb, since).await;
+		// Return the id of the stream
+		Ok(Value::Uuid(id.into()).into())
+	}
+
 	// ------------------------------
 	// Methods for selecting
 	// ------------------------------
 
-	async fn select(&self, params: Array) -> Result<Data, RpcError> {
+	async fn select(&self, txn: Option<Uuid>, params: Array) -> Result<Data, RpcError> {
 		// Check if the user is allowed to query
 		if !self.kvs().allows_query_by_subject(self.session().au.as_ref()) {
 			return Err(RpcError::MethodNotAllowed);
@@ -414,7 +481,7 @@ pub trait RpcProtocolV2: RpcContext {
 		}
 		.into();
 		// Execute the query on the database
-		let mut res = self.kvs().process(sql, &self.session(), var).await?;
+		let mut res = self.process_inner(txn, sql, var).await?;
 		// Extract the first query result
 		Ok(res
 			.remove(0)
@@ -430,7 +497,7 @@ pub trait RpcProtocolV2: RpcContext {
 	// Methods for inserting
 	// ------------------------------
 
-	async fn insert(&self, params: Array) -> Result<Data, RpcError> {
+	async fn insert(&self, txn: Option<Uuid>, params: Array) -> Result<Data, RpcError> {
 		// Check if the user is allowed to query
 		if !self.kvs().allows_query_by_subject(self.session().au.as_ref()) {
 			return Err(RpcError::MethodNotAllowed);
@@ -470,7 +537,7 @@ pub trait RpcProtocolV2: RpcContext {
 		}
 		.into();
 		// Execute the query on the database
-		let mut res = self.kvs().process(sql, &self.session(), var).await?;
+		let mut res = self.process_inner(txn, sql, var).await?;
 		// Extract the first query result
 		Ok(res
 			.remove(0)
@@ -486,7 +553,7 @@ pub trait RpcProtocolV2: RpcContext {
 	// Methods for creating
 	// ------------------------------
 
-	async fn create(&self, params: Array) -> Result<Data, RpcError> {
+	async fn create(&self, txn: Option<Uuid>, params: Array) -> Result<Data, RpcError> {
 		// Check if the user is allowed to query
 		if !self.kvs().allows_query_by_subject(self.session().au.as_ref()) {
 			return Err(RpcError::MethodNotAllowed);
@@ -522,7 +589,7 @@ pub trait RpcProtocolV2: RpcContext {
 		}
 		.into();
 		// Execute the query on the database
-		let mut res = self.kvs().process(sql, &self.session(), var).await?;
+		let mut res = self.process_inner(txn, sql, var).await?;
 		// Extract the first query result
 		Ok(res
 			.remove(0)
@@ -538,7 +605,7 @@ pub trait RpcProtocolV2: RpcContext {
 	// Methods for upserting
 	// ------------------------------
 
-	async fn upsert(&self, params: Array) -> Result<Data, RpcError> {
+	async fn upsert(&self, txn: Option<Uuid>, params: Array) -> Result<Data, RpcError> {
 		// Check if the user is allowed to query
 		if !self.kvs().allows_query_by_subject(self.session().au.as_ref()) {
 			return Err(RpcError::MethodNotAllowed);
@@ -573,7 +640,7 @@ pub trait RpcProtocolV2: RpcContext {
 		}
 		.into();
 		// Execute the query on the database
-		let mut res = self.kvs().process(sql, &self.session(), var).await?;
+		let mut res = self.process_inner(txn, sql, var).await?;
 		// Extract the first query result
 		Ok(res
 			.remove(0)
@@ -589,7 +656,7 @@ pub trait RpcProtocolV2: RpcContext {
 	// Methods for updating
 	// ------------------------------
 
-	async fn update(&self, params: Array) -> Result<Data, RpcError> {
+	async fn update(&self, txn: Option<Uuid>, params: Array) -> Result<Data, RpcError> {
 		// Check if the user is allowed to query
 		if !self.kvs().allows_query_by_subject(self.session().au.as_ref()) {
 			return Err(RpcError::MethodNotAllowed);
@@ -624,7 +691,7 @@ pub trait RpcProtocolV2: RpcContext {
 		}
 		.into();
 		// Execute the query on the database
-		let mut res = self.kvs().process(sql, &self.session(), var).await?;
+		let mut res = self.process_inner(txn, sql, var).await?;
 		// Extract the first query result
 		Ok(res
 			.remove(0)
@@ -640,7 +707,7 @@ pub trait RpcProtocolV2: RpcContext {
 	// Methods for relating
 	// ------------------------------
 
-	async fn relate(&self, params: Array) -> Result<Data, RpcError> {
+	async fn relate(&self, txn: Option<Uuid>, params: Array) -> Result<Data, RpcError> {
 		// Check if the user is allowed to query
 		if !self.kvs().allows_query_by_subject(self.session().au.as_ref()) {
 			return Err(RpcError::MethodNotAllowed);
@@ -677,7 +744,7 @@ pub trait RpcProtocolV2: RpcContext {
 		}
 		.into();
 		// Execute the query on the database
-		let mut res = self.kvs().process(sql, &self.session(), var).await?;
+		let mut res = self.process_inner(txn, sql, var).await?;
 		// Extract the first query result
 		Ok(res
 			.remove(0)
@@ -693,7 +760,7 @@ pub trait RpcProtocolV2: RpcContext {
 	// Methods for deleting
 	// ------------------------------
 
-	async fn delete(&self, params: Array) -> Result<Data, RpcError> {
+	async fn delete(&self, txn: Option<Uuid>, params: Array) -> Result<Data, RpcError> {
 		// Check if the user is allowed to query
 		if !self.kvs().allows_query_by_subject(self.session().au.as_ref()) {
 			return Err(RpcError::MethodNotAllowed);
@@ -723,7 +790,7 @@ pub trait RpcProtocolV2: RpcContext {
 		}
 		.into();
 		// Execute the query on the database
-		let mut res = self.kvs().process(sql, &self.session(), var).await?;
+		let mut res = self.process_inner(txn, sql, var).await?;
 		// Extract the first query result
 		Ok(res
 			.remove(0)
@@ -735,6 +802,47 @@ pub trait RpcProtocolV2: RpcContext {
 			.into())
 	}
 
+	// ------------------------------
+	// Methods for transactions
+	// ------------------------------
+
+	async fn begin(&self) -> Result<Data, RpcError> {
+		// Check if the user is allowed to query
+		if !self.kvs().allows_query_by_subject(self.session().au.as_ref()) {
+			return Err(RpcError::MethodNotAllowed);
+		}
+		// Start a new interactive transaction
+		let txn = self.kvs().begin(&self.session()).await?;
+		// Return the transaction id
+		Ok(Value::Uuid(txn.into()).into())
+	}
+
+	async fn commit(&self, txn: Option<Uuid>) -> Result<Data, RpcError> {
+		// Process the transaction id
+		let Some(txn) = txn else {
+			return Err(RpcError::InvalidParams);
+		};
+		// Commit the interactive transaction
+		let res = self.kvs().commit(txn, &self.session()).await?;
+		// Post-process hooks for web layer
+		for response in &res {
+			self.handle_live_query_results(response).await;
+		}
+		// Return nothing on success
+		Ok(Value::None.into())
+	}
+
+	async fn cancel(&self, txn: Option<Uuid>) -> Result<Data, RpcError> {
+		// Process the transaction id
+		let Some(txn) = txn else {
+			return Err(RpcError::InvalidParams);
+		};
+		// Cancel the interactive transaction
+		self.kvs().cancel(txn, &self.session()).await?;
+		// Return nothing on success
+		Ok(Value::None.into())
+	}
+
 	// ------------------------------
 	// Methods for getting info
 	// ------------------------------
@@ -750,7 +858,7 @@ pub trait RpcProtocolV2: RpcContext {
 	// Methods for querying
 	// ------------------------------
 
-	async fn query(&self, params: Array) -> Result<Data, RpcError> {
+	async fn query(&self, txn: Option<Uuid>, params: Array) -> Result<Data, RpcError> {
 		// Check if the user is allowed to query
 		if !self.kvs().allows_query_by_subject(self.session().au.as_ref()) {
 			return Err(RpcError::MethodNotAllowed);
@@ -770,14 +878,14 @@ pub trait RpcProtocolV2: RpcContext {
 			_ => return Err(RpcError::InvalidParams),
 		};
 		// Execute the specified query
-		self.query_inner(query, vars).await.map(Into::into)
+		self.query_inner(txn, query, vars).await.map(Into::into)
 	}
 
 	// ------------------------------
 	// Methods for running functions
 	// ------------------------------
 
-	async fn run(&self, params: Array) -> Result<Data, RpcError> {
+	async fn run(&self, txn: Option<Uuid>, params: Array) -> Result<Data, RpcError> {
 		// Check if the user is allowed to query
 		if !self.kvs().allows_query_by_subject(self.session().au.as_ref()) {
 			return Err(RpcError::MethodNotAllowed);
@@ -817,7 +925,7 @@ pub trait RpcProtocolV2: RpcContext {
 		// Specify the query parameters
 		let var = Some(self.session().parameters.clone());
 		// Execute the function on the database
-		let mut res = self.kvs().process(func, &self.session(), var).await?;
+		let mut res = self.process_inner(txn, func, var).await?;
 		// Extract the first query result
 		Ok(res.remove(0).result?.into())
 	}
@@ -950,6 +1058,7 @@ pub trait RpcProtocolV2: RpcContext {
 
 	async fn query_inner(
 		&self,
+		txn: Option<Uuid>,
 		query: Value,
 		vars: Option<BTreeMap<String, Value>>,
 	) -> Result<Vec<Response>, RpcError> {
@@ -958,21 +1067,45 @@ pub trait RpcProtocolV2: RpcContext {
 			return Err(RpcError::BadLQConfig);
 		}
 		// Execute the query on the database
-		let res = match query {
-			Value::Query(sql) => self.kvs().process(sql, &self.session(), vars).await?,
-			Value::Strand(sql) => self.kvs().execute(&sql, &self.session(), vars).await?,
-			_ => return Err(fail!("Unexpected query type: {query:?}").into()),
+		let res = match (txn, query) {
+			(None, Value::Query(sql)) => self.kvs().process(sql, &self.session(), vars).await?,
+			(None, Value::Strand(sql)) => self.kvs().execute(&sql, &self.session(), vars).await?,
+			(Some(txn), Value::Query(sql)) => {
+				self.kvs().process_in_transaction(txn, sql, &self.session(), vars).await?
+			}
+			(Some(txn), Value::Strand(sql)) => {
+				self.kvs().execute_in_transaction(txn, &sql, &self.session(), vars).await?
+			}
+			(_, query) => return Err(fail!("Unexpected query type: {query:?}").into()),
 		};
 
-		// Post-process hooks for web layer
-		for response in &res {
-			// This error should be unreachable because we shouldn't proceed if there's no handler
-			self.handle_live_query_results(response).await;
+		// Post-process hooks for web layer, which are
+		// deferred until an interactive transaction commits
+		if txn.is_none() {
+			for response in &res {
+				// This error should be unreachable because we shouldn't proceed if there's no handler
+				self.handle_live_query_results(response).await;
+			}
 		}
 		// Return the result to the client
 		Ok(res)
 	}
 
+	async fn process_inner(
+		&self,
+		txn: Option<Uuid>,
+		query: Query,
+		vars: Option<BTreeMap<String, Value>>,
+	) -> Result<Vec<Response>, RpcError> {
+		// Execute the query on the database, within the transaction if specified
+		match txn {
+			None => Ok(self.kvs().process(query, &self.session(), vars).await?),
+			Some(txn) => {
+				Ok(self.kvs().process_in_transaction(txn, query, &self.session(), vars).await?)
+			}
+		}
+	}
+
 	async fn handle_live_query_results(&self, res: &Response) {
 		match &res.query_type {
 			QueryType::Live => {
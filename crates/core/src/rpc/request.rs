@@ -6,16 +6,19 @@ use crate::sql::Number;
 use crate::sql::Part;
 use crate::sql::Value;
 use std::sync::LazyLock;
+use uuid::Uuid;
 
 pub static ID: LazyLock<[Part; 1]> = LazyLock::new(|| [Part::from("id")]);
 pub static METHOD: LazyLock<[Part; 1]> = LazyLock::new(|| [Part::from("method")]);
 pub static PARAMS: LazyLock<[Part; 1]> = LazyLock::new(|| [Part::from("params")]);
 pub static VERSION: LazyLock<[Part; 1]> = LazyLock::new(|| [Part::from("version")]);
+pub static TXN: LazyLock<[Part; 1]> = LazyLock::new(|| [Part::from("txn")]);
 
 #[derive(Debug)]
 pub struct Request {
 	pub id: Option<Value>,
 	pub version: Option<u8>,
+	pub txn: Option<Uuid>,
 	pub method: Method,
 	pub params: Array,
 }
@@ -51,6 +54,17 @@ impl TryFrom<Value> for Request {
 			},
 			_ => return Err(RpcError::InvalidRequest),
 		};
+		// Fetch the 'txn' argument
+		let txn = match val.pick(&*TXN) {
+			v if v.is_none() => None,
+			v if v.is_null() => None,
+			Value::Uuid(v) => Some(v.0),
+			Value::Strand(v) => match Uuid::try_parse(&v) {
+				Ok(v) => Some(v),
+				_ => return Err(RpcError::InvalidRequest),
+			},
+			_ => return Err(RpcError::InvalidRequest),
+		};
 		// Fetch the 'method' argument
 		let method = match val.pick(&*METHOD) {
 			Value::Strand(v) => v.to_raw(),
@@ -69,6 +83,7 @@ impl TryFrom<Value> for Request {
 			method,
 			params,
 			version,
+			txn,
 		})
 	}
 }
@@ -1,10 +1,12 @@
+use crate::sql::escape::QuoteStr;
+use crate::sql::fmt::Fmt;
 use crate::sql::language::Language;
 use revision::revisioned;
 use serde::{Deserialize, Serialize};
 use std::fmt;
 use std::fmt::Display;
 
-#[revisioned(revision = 1)]
+#[revisioned(revision = 2)]
 #[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Hash)]
 #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[non_exhaustive]
@@ -16,6 +18,44 @@ pub enum Filter {
 	Snowball(Language),
 	Uppercase,
 	Mapper(String),
+	#[revision(start = 2)]
+	Stopwords(Stopwords),
+	#[revision(start = 2)]
+	Synonyms(Synonyms, Option<FilterStage>),
+}
+
+/// The terms removed by the stop-words filter
+#[revisioned(revision = 1)]
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Hash)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[non_exhaustive]
+pub enum Stopwords {
+	/// The built-in stop-words of a language
+	Language(Language),
+	List(Vec<String>),
+}
+
+/// The groups of equivalent terms of the synonyms filter
+#[revisioned(revision = 1)]
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Hash)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[non_exhaustive]
+pub enum Synonyms {
+	List(Vec<Vec<String>>),
+	/// The path of a file with one group of comma separated terms per line
+	File(String),
+}
+
+/// The only stage at which a filter applies, when it doesn't apply at both stages
+#[revisioned(revision = 1)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Hash)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[non_exhaustive]
+pub enum FilterStage {
+	/// When the content of a record is indexed
+	Index,
+	/// When the terms of a query are analyzed
+	Query,
 }
 
 impl Display for Filter {
@@ -28,6 +68,47 @@ impl Display for Filter {
 			Self::Snowball(lang) => write!(f, "SNOWBALL({lang})"),
 			Self::Uppercase => f.write_str("UPPERCASE"),
 			Self::Mapper(path) => write!(f, "MAPPER({path})"),
+			Self::Stopwords(s) => write!(f, "STOPWORDS({s})"),
+			Self::Synonyms(s, None) => write!(f, "SYNONYMS({s})"),
+			Self::Synonyms(s, Some(stage)) => write!(f, "SYNONYMS({s}, {stage})"),
+		}
+	}
+}
+
+impl Display for Stopwords {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::Language(lang) => write!(f, "{lang}"),
+			Self::List(terms) => {
+				write!(f, "[{}]", Fmt::comma_separated(terms.iter().map(|t| QuoteStr(t))))
+			}
+		}
+	}
+}
+
+impl Display for FilterStage {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::Index => f.write_str("INDEX"),
+			Self::Query => f.write_str("QUERY"),
+		}
+	}
+}
+
+impl Display for Synonyms {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::List(groups) => {
+				write!(
+					f,
+					"[{}]",
+					Fmt::comma_separated(groups.iter().map(|g| format!(
+						"[{}]",
+						Fmt::comma_separated(g.iter().map(|t| QuoteStr(t)))
+					)))
+				)
+			}
+			Self::File(path) => write!(f, "{}", QuoteStr(path)),
 		}
 	}
 }
@@ -3,6 +3,7 @@ use crate::fnc::util::math::vector::{
 	ChebyshevDistance, CosineDistance, EuclideanDistance, HammingDistance, JaccardSimilarity,
 	ManhattanDistance, MinkowskiDistance, PearsonSimilarity,
 };
+use crate::sql::fmt::Fmt;
 use crate::sql::ident::Ident;
 use crate::sql::scoring::Scoring;
 use crate::sql::statements::info::InfoStructure;
@@ -31,7 +32,10 @@ pub enum Index {
 	Hnsw(HnswParams),
 }
 
-#[revisioned(revision = 2)]
+/// The maximum edit distance supported by the fuzzy matching of full-text queries
+pub(crate) const MAX_FUZZY_DISTANCE: u8 = 2;
+
+#[revisioned(revision = 4)]
 #[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Hash)]
 #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[non_exhaustive]
@@ -51,6 +55,12 @@ pub struct SearchParams {
 	pub postings_cache: u32,
 	#[revision(start = 2)]
 	pub terms_cache: u32,
+	/// The maximum edit distance of the fuzzy matching of the query terms
+	#[revision(start = 3)]
+	pub fuzzy: u8,
+	/// The weight of each indexed field, in the order of the fields (empty when not boosted)
+	#[revision(start = 4)]
+	pub boosts: Vec<Number>,
 }
 
 #[revisioned(revision = 2)]
@@ -259,6 +269,12 @@ impl Display for Index {
 				if p.hl {
 					f.write_str(" HIGHLIGHTS")?
 				}
+				if p.fuzzy > 0 {
+					write!(f, " FUZZY {}", p.fuzzy)?
+				}
+				if !p.boosts.is_empty() {
+					write!(f, " BOOSTS({})", Fmt::comma_separated(&p.boosts))?
+				}
 				Ok(())
 			}
 			Self::MTree(p) => {
@@ -56,6 +56,7 @@ pub(crate) mod query;
 pub(crate) mod range;
 pub(crate) mod reference;
 pub(crate) mod regex;
+pub(crate) mod retention;
 pub(crate) mod scoring;
 pub(crate) mod script;
 pub(crate) mod split;
@@ -141,6 +142,7 @@ pub use self::permission::Permissions;
 pub use self::query::Query;
 pub use self::range::Range;
 pub use self::regex::Regex;
+pub use self::retention::Retention;
 pub use self::scoring::Scoring;
 pub use self::script::Script;
 pub use self::split::Split;
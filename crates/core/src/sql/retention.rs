@@ -0,0 +1,41 @@
+use crate::sql::duration::Duration;
+use crate::sql::statements::info::InfoStructure;
+use crate::sql::Value;
+use revision::revisioned;
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Display, Formatter};
+use std::time;
+
+/// How much version history is kept for each record in a table
+#[revisioned(revision = 1)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Hash)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[non_exhaustive]
+pub enum Retention {
+	/// Versions are kept until they have been replaced for longer than the duration
+	Duration(time::Duration),
+	/// Only the latest number of versions are kept
+	Count(u64),
+}
+
+impl Display for Retention {
+	fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+		match self {
+			Self::Duration(v) => write!(f, "VERSIONS RETAIN {}", Duration(*v)),
+			Self::Count(v) => write!(f, "VERSIONS RETAIN {v}"),
+		}
+	}
+}
+
+impl InfoStructure for Retention {
+	fn structure(self) -> Value {
+		match self {
+			Self::Duration(v) => Value::from(map! {
+				"duration".to_string() => Duration(v).structure(),
+			}),
+			Self::Count(v) => Value::from(map! {
+				"count".to_string() => v.into(),
+			}),
+		}
+	}
+}
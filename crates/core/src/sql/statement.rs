@@ -106,7 +106,7 @@ impl Statement {
 			Self::Value(v) => v.writeable(),
 			Self::Access(_) => true,
 			Self::Alter(_) => true,
-			Self::Analyze(_) => false,
+			Self::Analyze(_) => true,
 			Self::Break(_) => false,
 			Self::Continue(_) => false,
 			Self::Create(v) => v.writeable(),
@@ -125,7 +125,7 @@ impl Statement {
 			Self::Remove(_) => true,
 			Self::Select(v) => v.writeable(),
 			Self::Set(v) => v.writeable(),
-			Self::Show(_) => false,
+			Self::Show(v) => v.writeable(),
 			Self::Sleep(_) => false,
 			Self::Throw(_) => false,
 			Self::Upsert(v) => v.writeable(),
@@ -4,6 +4,7 @@ use crate::doc::CursorDoc;
 use crate::err::Error;
 use crate::iam::{Action, ResourceKind};
 use crate::idx::ft::FtIndex;
+use crate::idx::statistics::{IdxStatistics, IndexStatistics};
 use crate::idx::trees::mtree::MTreeIndex;
 use crate::idx::IndexKeyBase;
 use crate::kvs::TransactionType;
@@ -54,10 +55,19 @@ impl AnalyzeStatement {
 						let mt = MTreeIndex::new(&tx, ikb, p, TransactionType::Read).await?;
 						mt.statistics(&tx).await?.into()
 					}
-					_ => {
-						return Err(Error::FeatureNotYetImplemented {
-							feature: "Statistics on unique and non-unique indexes.".to_string(),
-						})
+					Index::Idx | Index::Uniq => {
+						let stats = IdxStatistics::compute(ctx, ns, db, tb, idx).await?;
+						let stats = IndexStatistics::Idx(stats);
+						stats.set(&ctx.tx(), &ikb).await?;
+						stats.into()
+					}
+					Index::Hnsw(p) => {
+						let tx = ctx.tx();
+						let hnsw = ctx.get_index_stores().get_index_hnsw(ctx, opt, &ix, p).await?;
+						let stats = hnsw.write().await.statistics(&tx).await?;
+						let stats = IndexStatistics::Hnsw(stats);
+						stats.set(&tx, &ikb).await?;
+						stats.into()
 					}
 				};
 				// Return the result object
@@ -3,6 +3,7 @@ mod analyzer;
 mod api;
 mod bucket;
 pub mod config;
+mod consumer;
 mod database;
 mod deprecated;
 mod event;
@@ -13,6 +14,7 @@ mod model;
 mod namespace;
 mod param;
 mod sequence;
+mod sink;
 mod table;
 mod user;
 
@@ -21,6 +23,7 @@ pub use analyzer::DefineAnalyzerStatement;
 pub use api::DefineApiStatement;
 pub use bucket::DefineBucketStatement;
 pub use config::DefineConfigStatement;
+pub use consumer::DefineConsumerStatement;
 pub use database::DefineDatabaseStatement;
 pub use event::DefineEventStatement;
 pub use field::DefineFieldStatement;
@@ -30,6 +33,7 @@ pub use model::DefineModelStatement;
 pub use namespace::DefineNamespaceStatement;
 pub use param::DefineParamStatement;
 pub use sequence::DefineSequenceStatement;
+pub use sink::DefineSinkStatement;
 pub use table::DefineTableStatement;
 pub use user::DefineUserStatement;
 
@@ -53,7 +57,7 @@ use revision::revisioned;
 use serde::{Deserialize, Serialize};
 use std::fmt::{self, Display};
 
-#[revisioned(revision = 5)]
+#[revisioned(revision = 7)]
 #[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Hash)]
 #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[non_exhaustive]
@@ -90,6 +94,10 @@ pub enum DefineStatement {
 	Bucket(DefineBucketStatement),
 	#[revision(start = 5)]
 	Sequence(DefineSequenceStatement),
+	#[revision(start = 6)]
+	Consumer(DefineConsumerStatement),
+	#[revision(start = 7)]
+	Sink(DefineSinkStatement),
 }
 
 // Revision implementations
@@ -139,6 +147,8 @@ impl DefineStatement {
 			Self::Api(ref v) => v.compute(stk, ctx, opt, doc).await,
 			Self::Bucket(ref v) => v.compute(stk, ctx, opt, doc).await,
 			Self::Sequence(ref v) => v.compute(ctx, opt).await,
+			Self::Consumer(ref v) => v.compute(ctx, opt).await,
+			Self::Sink(ref v) => v.compute(ctx, opt).await,
 		}
 	}
 }
@@ -162,6 +172,8 @@ impl Display for DefineStatement {
 			Self::Api(v) => Display::fmt(v, f),
 			Self::Bucket(v) => Display::fmt(v, f),
 			Self::Sequence(v) => Display::fmt(v, f),
+			Self::Consumer(v) => Display::fmt(v, f),
+			Self::Sink(v) => Display::fmt(v, f),
 		}
 	}
 }
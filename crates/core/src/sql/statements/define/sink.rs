@@ -0,0 +1,116 @@
+use crate::ctx::Context;
+use crate::dbs::Options;
+use crate::err::Error;
+use crate::iam::{Action, ResourceKind};
+use crate::key::database::sk::Sk;
+use crate::sql::statements::info::InfoStructure;
+use crate::sql::{Base, Duration, Ident, Strand, Value};
+
+use revision::revisioned;
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Display};
+use url::Url;
+
+#[revisioned(revision = 1)]
+#[derive(Clone, Debug, Default, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Hash)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[non_exhaustive]
+pub struct DefineSinkStatement {
+	pub name: Ident,
+	pub what: Ident,
+	pub url: Strand,
+	pub batch: Option<u32>,
+	pub retry: Option<u32>,
+	pub timeout: Option<Duration>,
+	pub comment: Option<Strand>,
+	pub if_not_exists: bool,
+	pub overwrite: bool,
+}
+
+impl DefineSinkStatement {
+	pub(crate) async fn compute(&self, ctx: &Context, opt: &Options) -> Result<Value, Error> {
+		// Allowed to run?
+		opt.is_allowed(Action::Edit, ResourceKind::Table, &Base::Db)?;
+		// Get the NS and DB
+		let (ns, db) = opt.ns_db()?;
+		// Fetch the transaction
+		let txn = ctx.tx();
+		// Check if the definition exists
+		if txn.get_db_sink(ns, db, &self.name).await.is_ok() {
+			if self.if_not_exists {
+				return Ok(Value::None);
+			} else if !self.overwrite {
+				return Err(Error::SkAlreadyExists {
+					name: self.name.to_string(),
+				});
+			}
+		}
+		// Check the URL is valid, and that it can be connected to
+		#[cfg_attr(not(feature = "http"), expect(unused_variables))]
+		let url = Url::parse(&self.url).map_err(|_| Error::InvalidUrl(self.url.to_string()))?;
+		#[cfg(feature = "http")]
+		ctx.check_allowed_net(&url)?;
+		// Process the statement
+		let key = Sk::new(ns, db, &self.name);
+		txn.get_or_add_ns(ns, opt.strict).await?;
+		txn.get_or_add_db(ns, db, opt.strict).await?;
+		txn.get_or_add_tb(ns, db, &self.what, opt.strict).await?;
+		txn.set(
+			key,
+			revision::to_vec(&DefineSinkStatement {
+				// Don't persist the `IF NOT EXISTS` clause to schema
+				if_not_exists: false,
+				overwrite: false,
+				..self.clone()
+			})?,
+			None,
+		)
+		.await?;
+		// Start delivering from the latest change
+		crate::cf::sink::reset(&txn, ns, db, &self.name).await?;
+		// Clear the cache
+		txn.clear();
+		// Ok all good
+		Ok(Value::None)
+	}
+}
+
+impl Display for DefineSinkStatement {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "DEFINE SINK")?;
+		if self.if_not_exists {
+			write!(f, " IF NOT EXISTS")?
+		}
+		if self.overwrite {
+			write!(f, " OVERWRITE")?
+		}
+		write!(f, " {} ON {} URL {}", self.name, self.what, self.url)?;
+		if let Some(v) = self.batch {
+			write!(f, " BATCH {v}")?
+		}
+		if let Some(v) = self.retry {
+			write!(f, " RETRY {v}")?
+		}
+		if let Some(ref v) = self.timeout {
+			write!(f, " TIMEOUT {v}")?
+		}
+		if let Some(ref v) = self.comment {
+			write!(f, " COMMENT {v}")?
+		}
+		Ok(())
+	}
+}
+
+impl InfoStructure for DefineSinkStatement {
+	fn structure(self) -> Value {
+		Value::from(map! {
+			"name".to_string() => self.name.structure(),
+			"what".to_string() => self.what.structure(),
+			"url".to_string() => self.url.into(),
+			"batch".to_string(), if let Some(v) = self.batch => v.into(),
+			"retry".to_string(), if let Some(v) = self.retry => v.into(),
+			"timeout".to_string(), if let Some(v) = self.timeout => v.into(),
+			"comment".to_string(), if let Some(v) = self.comment => v.into(),
+		})
+	}
+}
@@ -9,8 +9,8 @@ use crate::sql::fmt::{is_pretty, pretty_indent};
 use crate::sql::paths::{IN, OUT};
 use crate::sql::statements::info::InfoStructure;
 use crate::sql::{
-	changefeed::ChangeFeed, statements::UpdateStatement, Base, Ident, Output, Permissions, Strand,
-	Value, Values, View,
+	changefeed::ChangeFeed, statements::UpdateStatement, Base, Ident, Output, Permissions,
+	Retention, Strand, Value, Values, View,
 };
 use crate::sql::{Idiom, Kind, TableType};
 
@@ -22,7 +22,7 @@ use std::fmt::{self, Display, Write};
 use std::sync::Arc;
 use uuid::Uuid;
 
-#[revisioned(revision = 6)]
+#[revisioned(revision = 7)]
 #[derive(Clone, Debug, Default, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Hash)]
 #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[non_exhaustive]
@@ -57,6 +57,9 @@ pub struct DefineTableStatement {
 	/// The last time that a LIVE query was added to this table
 	#[revision(start = 5, end = 6, convert_fn = "convert_cache_ts")]
 	pub cache_lives_ts: Uuid,
+	/// How much version history is kept for each record, within any datastore retention window
+	#[revision(start = 7)]
+	pub versions: Option<Retention>,
 }
 
 impl DefineTableStatement {
@@ -83,6 +86,10 @@ impl DefineTableStatement {
 				});
 			}
 		}
+		// A retention policy can only be enforced if old versions can be removed
+		if self.versions.is_some() && !txn.retainable().await {
+			return Err(Error::UnsupportedVersionRetention);
+		}
 		// Process the statement
 		let key = crate::key::database::tb::new(ns, db, &self.name);
 		let nsv = txn.get_or_add_ns(ns, opt.strict).await?;
@@ -290,6 +297,9 @@ impl Display for DefineTableStatement {
 		if let Some(ref v) = self.changefeed {
 			write!(f, " {v}")?;
 		}
+		if let Some(ref v) = self.versions {
+			write!(f, " {v}")?;
+		}
 		let _indent = if is_pretty() {
 			Some(pretty_indent())
 		} else {
@@ -310,6 +320,7 @@ impl InfoStructure for DefineTableStatement {
 			"kind".to_string() => self.kind.structure(),
 			"view".to_string(), if let Some(v) = self.view => v.structure(),
 			"changefeed".to_string(), if let Some(v) = self.changefeed => v.structure(),
+			"versions".to_string(), if let Some(v) = self.versions => v.structure(),
 			"permissions".to_string() => self.permissions.structure(),
 			"comment".to_string(), if let Some(v) = self.comment => v.into(),
 		})
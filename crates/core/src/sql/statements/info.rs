@@ -4,6 +4,8 @@ use crate::doc::CursorDoc;
 use crate::err::Error;
 use crate::iam::Action;
 use crate::iam::ResourceKind;
+use crate::idx::statistics::IndexStatistics;
+use crate::idx::IndexKeyBase;
 use crate::sql::{Base, Ident, Object, Value, Version};
 use crate::sys::INFORMATION;
 
@@ -165,7 +167,9 @@ impl InfoStatement {
 						"tables".to_string() => process(txn.all_tb(ns, db, version).await?),
 						"users".to_string() => process(txn.all_db_users(ns, db).await?),
 						"configs".to_string() => process(txn.all_db_configs(ns, db).await?),
+						"consumers".to_string() => process(txn.all_db_consumers(ns, db).await?),
 						"sequences".to_string() => process(txn.all_db_sequences(ns, db).await?),
+						"sinks".to_string() => process(txn.all_db_sinks(ns, db).await?),
 					}),
 					false => Value::from(map! {
 						"accesses".to_string() => {
@@ -238,6 +242,13 @@ impl InfoStatement {
 							}
 							out.into()
 						},
+						"consumers".to_string() => {
+							let mut out = Object::default();
+							for v in txn.all_db_consumers(ns, db).await?.iter() {
+								out.insert(v.name.to_raw(), v.to_string().into());
+							}
+							out.into()
+						},
 						"sequences".to_string() => {
 							let mut out = Object::default();
 							for v in txn.all_db_sequences( ns, db).await?.iter() {
@@ -245,6 +256,13 @@ impl InfoStatement {
 							}
 							out.into()
 						},
+						"sinks".to_string() => {
+							let mut out = Object::default();
+							for v in txn.all_db_sinks(ns, db).await?.iter() {
+								out.insert(v.name.to_raw(), v.to_string().into());
+							}
+							out.into()
+						},
 					}),
 				})
 			}
@@ -260,11 +278,21 @@ impl InfoStatement {
 				};
 				// Get the transaction
 				let txn = ctx.tx();
+				// Get the version history, if the table has a retention policy
+				let history = match txn.get_tb(ns, db, tb).await {
+					Ok(v) if v.versions.is_some() => txn.history(ns, db, tb).await?,
+					Ok(_)
+					| Err(Error::TbNotFound {
+						..
+					}) => None,
+					Err(e) => return Err(e),
+				};
 				// Create the result set
 				Ok(match structured {
 					true => Value::from(map! {
 						"events".to_string() => process(txn.all_tb_events(ns, db, tb).await?),
 						"fields".to_string() => process(txn.all_tb_fields(ns, db, tb, version).await?),
+						"history".to_string(), if let Some(v) = history => v,
 						"indexes".to_string() => process(txn.all_tb_indexes(ns, db, tb).await?),
 						"lives".to_string() => process(txn.all_tb_lives(ns, db, tb).await?),
 						"tables".to_string() => process(txn.all_tb_views(ns, db, tb).await?),
@@ -284,6 +312,7 @@ impl InfoStatement {
 							}
 							out.into()
 						},
+						"history".to_string(), if let Some(v) = history => v,
 						"indexes".to_string() => {
 							let mut out = Object::default();
 							for v in txn.all_tb_indexes(ns, db, tb).await?.iter() {
@@ -336,23 +365,24 @@ impl InfoStatement {
 				// Allowed to run?
 				opt.is_allowed(Action::View, ResourceKind::Actor, &Base::Db)?;
 
+				// Get the transaction
+				let txn = ctx.tx();
+				// Obtain the index
+				let (ns, db) = opt.ns_db()?;
+				let res = txn.get_tb_index(ns, db, table, index).await?;
 				// Output
+				let mut out = Object::default();
 				#[cfg(not(target_family = "wasm"))]
-				{
-					// Get the transaction
-					let txn = ctx.tx();
-
-					if let Some(ib) = ctx.get_index_builder() {
-						// Obtain the index
-						let (ns, db) = opt.ns_db()?;
-						let res = txn.get_tb_index(ns, db, table, index).await?;
-						let status = ib.get_status(ns, db, &res).await;
-						let mut out = Object::default();
-						out.insert("building".to_string(), status.into());
-						return Ok(out.into());
-					}
+				if let Some(ib) = ctx.get_index_builder() {
+					let status = ib.get_status(ns, db, &res).await;
+					out.insert("building".to_string(), status.into());
+				}
+				// Statistics persisted by the last ANALYZE
+				let ikb = IndexKeyBase::new(ns, db, &res)?;
+				if let Some(stats) = IndexStatistics::get(&txn, &ikb).await? {
+					out.insert("statistics".to_string(), stats.into());
 				}
-				Ok(Object::default().into())
+				Ok(out.into())
 			}
 		}
 	}
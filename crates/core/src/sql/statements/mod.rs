@@ -50,7 +50,7 @@ pub use self::r#use::UseStatement;
 pub use self::relate::RelateStatement;
 pub use self::select::SelectStatement;
 pub use self::set::SetStatement;
-pub use self::show::ShowStatement;
+pub use self::show::{ShowSince, ShowStatement};
 pub use self::sleep::SleepStatement;
 pub use self::throw::ThrowStatement;
 pub use self::update::UpdateStatement;
@@ -1,6 +1,7 @@
 mod access;
 mod analyzer;
 mod bucket;
+mod consumer;
 mod database;
 mod event;
 mod field;
@@ -10,12 +11,14 @@ mod model;
 mod namespace;
 mod param;
 mod sequence;
+mod sink;
 mod table;
 mod user;
 
 pub use access::RemoveAccessStatement;
 pub use analyzer::RemoveAnalyzerStatement;
 pub use bucket::RemoveBucketStatement;
+pub use consumer::RemoveConsumerStatement;
 pub use database::RemoveDatabaseStatement;
 pub use event::RemoveEventStatement;
 pub use field::RemoveFieldStatement;
@@ -25,6 +28,7 @@ pub use model::RemoveModelStatement;
 pub use namespace::RemoveNamespaceStatement;
 pub use param::RemoveParamStatement;
 pub use sequence::RemoveSequenceStatement;
+pub use sink::RemoveSinkStatement;
 pub use table::RemoveTableStatement;
 pub use user::RemoveUserStatement;
 
@@ -57,6 +61,8 @@ pub enum RemoveStatement {
 	Model(RemoveModelStatement),
 	Bucket(RemoveBucketStatement),
 	Sequence(RemoveSequenceStatement),
+	Consumer(RemoveConsumerStatement),
+	Sink(RemoveSinkStatement),
 }
 
 impl RemoveStatement {
@@ -86,6 +92,8 @@ impl RemoveStatement {
 			Self::Model(ref v) => v.compute(ctx, opt).await,
 			Self::Bucket(ref v) => v.compute(ctx, opt).await,
 			Self::Sequence(ref v) => v.compute(ctx, opt).await,
+			Self::Consumer(ref v) => v.compute(ctx, opt).await,
+			Self::Sink(ref v) => v.compute(ctx, opt).await,
 		}
 	}
 }
@@ -107,6 +115,8 @@ impl Display for RemoveStatement {
 			Self::Model(v) => Display::fmt(v, f),
 			Self::Bucket(v) => Display::fmt(v, f),
 			Self::Sequence(v) => Display::fmt(v, f),
+			Self::Consumer(v) => Display::fmt(v, f),
+			Self::Sink(v) => Display::fmt(v, f),
 		}
 	}
 }
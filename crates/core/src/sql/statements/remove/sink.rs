@@ -0,0 +1,64 @@
+use crate::ctx::Context;
+use crate::dbs::Options;
+use crate::err::Error;
+use crate::iam::{Action, ResourceKind};
+use crate::key::database::sk::Sk;
+use crate::key::database::sl::Sl;
+use crate::key::database::sp::Sp;
+use crate::sql::{Base, Ident, Value};
+
+use revision::revisioned;
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Display, Formatter};
+
+#[revisioned(revision = 1)]
+#[derive(Clone, Debug, Default, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Hash)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[non_exhaustive]
+pub struct RemoveSinkStatement {
+	pub name: Ident,
+	pub if_exists: bool,
+}
+
+impl RemoveSinkStatement {
+	pub(crate) async fn compute(&self, ctx: &Context, opt: &Options) -> Result<Value, Error> {
+		let future = async {
+			// Allowed to run?
+			opt.is_allowed(Action::Edit, ResourceKind::Table, &Base::Db)?;
+			// Get the NS and DB
+			let (ns, db) = opt.ns_db()?;
+			// Get the transaction
+			let txn = ctx.tx();
+			// Get the definition
+			let sk = txn.get_db_sink(ns, db, &self.name).await?;
+			// Delete the delivered position
+			txn.del(Sp::new(ns, db, &sk.name)).await?;
+			// Delete the delivery lease
+			txn.del(Sl::new(ns, db, &sk.name)).await?;
+			// Delete the definition
+			txn.del(Sk::new(ns, db, &sk.name)).await?;
+			// Clear the cache
+			txn.clear();
+			// Ok all good
+			Ok(Value::None)
+		}
+		.await;
+		match future {
+			Err(Error::SkNotFound {
+				..
+			}) if self.if_exists => Ok(Value::None),
+			v => v,
+		}
+	}
+}
+
+impl Display for RemoveSinkStatement {
+	fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+		write!(f, "REMOVE SINK")?;
+		if self.if_exists {
+			write!(f, " IF EXISTS")?
+		}
+		write!(f, " {}", self.name)?;
+		Ok(())
+	}
+}
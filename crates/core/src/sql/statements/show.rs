@@ -3,7 +3,7 @@ use crate::dbs::Options;
 use crate::doc::CursorDoc;
 use crate::err::Error;
 use crate::iam::{Action, ResourceKind};
-use crate::sql::{Base, Datetime, Table, Value};
+use crate::sql::{Base, Datetime, Ident, Table, Value};
 use crate::vs::VersionStamp;
 
 use revision::revisioned;
@@ -32,8 +32,9 @@ impl ShowSince {
 	}
 }
 
-/// A SHOW CHANGES statement for displaying changes made to a table or database.
-#[revisioned(revision = 1)]
+/// A SHOW CHANGES statement for displaying changes made to a table or database,
+/// or for reading the changes which a named consumer has not yet acknowledged.
+#[revisioned(revision = 2)]
 #[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Hash)]
 #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[non_exhaustive]
@@ -41,9 +42,16 @@ pub struct ShowStatement {
 	pub table: Option<Table>,
 	pub since: ShowSince,
 	pub limit: Option<u32>,
+	#[revision(start = 2)]
+	pub consumer: Option<Ident>,
 }
 
 impl ShowStatement {
+	/// Check if we require a writeable transaction
+	pub(crate) fn writeable(&self) -> bool {
+		// Reading changes for a consumer records its position
+		self.consumer.is_some()
+	}
 	/// Process this type returning a computed simple Value
 	pub(crate) async fn compute(
 		&self,
@@ -57,15 +65,28 @@ impl ShowStatement {
 		let txn = ctx.tx();
 		// Process the show query
 		let (ns, db) = opt.ns_db()?;
-		let r = crate::cf::read(
-			&txn,
-			ns,
-			db,
-			self.table.as_deref().map(String::as_str),
-			self.since.clone(),
-			self.limit,
-		)
-		.await?;
+		let r = match self.consumer {
+			// Resume from the acknowledged position of the consumer,
+			// acknowledging the changes before the given versionstamp
+			Some(ref cn) => {
+				let since = match self.since {
+					ShowSince::Versionstamp(v) => v,
+					ShowSince::Timestamp(_) => 0,
+				};
+				crate::cf::consumer::read(&txn, ns, db, cn, since, self.limit).await?
+			}
+			None => {
+				crate::cf::read(
+					&txn,
+					ns,
+					db,
+					self.table.as_deref().map(String::as_str),
+					self.since.clone(),
+					self.limit,
+				)
+				.await?
+			}
+		};
 		// Return the changes
 		let a: Vec<Value> = r.iter().cloned().map(|x| x.into_value()).collect();
 		Ok(a.into())
@@ -75,11 +96,14 @@ impl ShowStatement {
 impl fmt::Display for ShowStatement {
 	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
 		write!(f, "SHOW CHANGES FOR")?;
-		match self.table {
-			Some(ref v) => write!(f, " TABLE {}", v)?,
-			None => write!(f, " DATABASE")?,
+		match (&self.consumer, &self.table) {
+			(Some(v), _) => write!(f, " CONSUMER {}", v)?,
+			(None, Some(v)) => write!(f, " TABLE {}", v)?,
+			(None, None) => write!(f, " DATABASE")?,
 		}
 		match self.since {
+			// A consumer only acknowledges changes with a SINCE clause
+			ShowSince::Versionstamp(0) if self.consumer.is_some() => {}
 			ShowSince::Timestamp(ref v) => write!(f, " SINCE {}", v)?,
 			ShowSince::Versionstamp(ref v) => write!(f, " SINCE {}", v)?,
 		}
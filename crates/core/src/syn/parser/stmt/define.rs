@@ -1,14 +1,17 @@
 use reblessive::Stk;
+use std::ops::Bound;
 
 use crate::api::method::Method;
 use crate::api::middleware::RequestMiddleware;
 use crate::sql::access_type::JwtAccessVerify;
-use crate::sql::index::HnswParams;
+use crate::sql::filter::{FilterStage, Stopwords, Synonyms};
+use crate::sql::index::{HnswParams, MAX_FUZZY_DISTANCE};
 use crate::sql::statements::define::config::api::ApiConfig;
 use crate::sql::statements::define::config::graphql::{GraphQLConfig, TableConfig};
 use crate::sql::statements::define::config::ConfigInner;
 use crate::sql::statements::define::{
-	ApiAction, DefineBucketStatement, DefineConfigStatement, DefineSequenceStatement,
+	ApiAction, DefineBucketStatement, DefineConfigStatement, DefineConsumerStatement,
+	DefineSequenceStatement, DefineSinkStatement,
 };
 use crate::sql::statements::DefineApiStatement;
 use crate::sql::Value;
@@ -28,8 +31,8 @@ use crate::{
 		},
 		table_type,
 		tokenizer::Tokenizer,
-		user, AccessType, Ident, Idioms, Index, Kind, Param, Permissions, Scoring, Strand,
-		TableType, Values,
+		user, AccessType, Expression, Function, Id, Ident, Idiom, Idioms, Index, Kind, Number,
+		Operator, Param, Part, Permissions, Scoring, Strand, TableType, Values,
 	},
 	syn::{
 		parser::{
@@ -70,6 +73,8 @@ impl Parser<'_> {
 			t!("CONFIG") => self.parse_define_config(ctx).await.map(DefineStatement::Config),
 			t!("BUCKET") => self.parse_define_bucket(ctx, next).await.map(DefineStatement::Bucket),
 			t!("SEQUENCE") => self.parse_define_sequence().map(DefineStatement::Sequence),
+			t!("CONSUMER") => self.parse_define_consumer().map(DefineStatement::Consumer),
+			t!("SINK") => self.parse_define_sink().map(DefineStatement::Sink),
 			_ => unexpected!(self, next, "a define statement keyword"),
 		}
 	}
@@ -778,6 +783,10 @@ impl Parser<'_> {
 					self.pop_peek();
 					res.changefeed = Some(self.parse_changefeed()?);
 				}
+				t!("VERSIONS") => {
+					self.pop_peek();
+					res.versions = Some(self.parse_retention()?);
+				}
 				t!("AS") => {
 					self.pop_peek();
 					let peek = self.peek();
@@ -1046,15 +1055,16 @@ impl Parser<'_> {
 			overwrite,
 			..Default::default()
 		};
+		let mut boosts_span = None;
 
 		loop {
 			match self.peek_kind() {
 				// COLUMNS and FIELDS are the same tokenkind
 				t!("FIELDS") => {
 					self.pop_peek();
-					res.cols = Idioms(vec![self.parse_local_idiom(ctx).await?]);
+					res.cols = Idioms(vec![self.parse_index_column(ctx).await?]);
 					while self.eat(t!(",")) {
-						res.cols.0.push(self.parse_local_idiom(ctx).await?);
+						res.cols.0.push(self.parse_index_column(ctx).await?);
 					}
 				}
 				t!("UNIQUE") => {
@@ -1074,9 +1084,12 @@ impl Parser<'_> {
 					let mut postings_cache = 100;
 					let mut terms_cache = 100;
 					let mut hl = false;
+					let mut fuzzy = 0;
+					let mut boosts = Vec::new();
 
 					loop {
-						match self.peek_kind() {
+						let peek = self.peek();
+						match peek.kind {
 							t!("ANALYZER") => {
 								self.pop_peek();
 								analyzer = Some(self.next_token_value()).transpose()?;
@@ -1137,6 +1150,46 @@ impl Parser<'_> {
 								self.pop_peek();
 								hl = true;
 							}
+							// Every keyword token is taken, so `FUZZY` is matched as an identifier
+							TokenKind::Identifier
+								if self.lexer.span_str(peek.span).eq_ignore_ascii_case("FUZZY") =>
+							{
+								self.pop_peek();
+								fuzzy = self.next_token_value()?;
+								if fuzzy > MAX_FUZZY_DISTANCE {
+									bail!(
+										"Invalid fuzzy distance {fuzzy}, expected a distance of at most {MAX_FUZZY_DISTANCE}",
+										@self.last_span()
+									);
+								}
+							}
+							// `BOOSTS` is matched as an identifier for the same reason
+							TokenKind::Identifier
+								if self
+									.lexer
+									.span_str(peek.span)
+									.eq_ignore_ascii_case("BOOSTS") =>
+							{
+								self.pop_peek();
+								let open = expected!(self, t!("(")).span;
+								boosts.clear();
+								loop {
+									let boost: Number = self.next_token_value()?;
+									let value = boost.to_float();
+									if !value.is_finite() || value <= 0.0 {
+										bail!(
+											"Invalid boost {boost}, expected a positive number",
+											@self.last_span()
+										);
+									}
+									boosts.push(boost);
+									if !self.eat(t!(",")) {
+										break;
+									}
+								}
+								self.expect_closing_delimiter(t!(")"), open)?;
+								boosts_span = Some(peek.span.covers(self.last_span()));
+							}
 							_ => break,
 						}
 					}
@@ -1153,6 +1206,8 @@ impl Parser<'_> {
 						doc_lengths_cache,
 						postings_cache,
 						terms_cache,
+						fuzzy,
+						boosts,
 					});
 				}
 				t!("MTREE") => {
@@ -1283,9 +1338,47 @@ impl Parser<'_> {
 			}
 		}
 
+		// The boosts are given in the order of the fields, which may be defined after them
+		if let (Some(span), Index::Search(p)) = (boosts_span, &res.index) {
+			if p.boosts.len() != res.cols.len() {
+				bail!(
+					"Invalid number of boosts, expected {} boosts but found {}", res.cols.len(), p.boosts.len(),
+					@span => "there should be one boost per field of the index"
+				);
+			}
+		}
+
 		Ok(res)
 	}
 
+	/// Parses a column of an index: either a field, or an expression such as
+	/// `string::lowercase(email)` which is stored as an idiom starting with the expression.
+	///
+	/// An expression is computed again whenever a record is written or removed, so it
+	/// may not contain anything which could give a different result for the same record.
+	async fn parse_index_column(&mut self, ctx: &mut Stk) -> ParseResult<Idiom> {
+		let is_expression = match self.peek_kind() {
+			t!("(") | t!("fn") => true,
+			x if Self::kind_is_identifier(x) => matches!(self.peek1().kind, t!("::") | t!("(")),
+			_ => false,
+		};
+		if !is_expression {
+			return self.parse_local_idiom(ctx).await;
+		}
+		let start = self.peek().span;
+		let value = ctx.run(|ctx| self.parse_value_field(ctx)).await?;
+		if let Some(found) = non_deterministic(&value) {
+			bail!(
+				"Invalid index expression, found {found}",
+				@start.covers(self.last_span()) => "index expressions must always give the same result for a record"
+			);
+		}
+		match value {
+			Value::Idiom(i) => Ok(i),
+			v => Ok(Idiom(vec![Part::Start(v)])),
+		}
+	}
+
 	pub fn parse_define_analyzer(&mut self) -> ParseResult<DefineAnalyzerStatement> {
 		let (if_not_exists, overwrite) = if self.eat(t!("IF")) {
 			expected!(self, t!("NOT"));
@@ -1353,6 +1446,64 @@ impl Parser<'_> {
 								self.expect_closing_delimiter(t!(")"), open_span)?;
 								filters.push(Filter::Mapper(path.into()))
 							}
+							// Every keyword token is taken, so these filters are matched as identifiers
+							TokenKind::Identifier
+								if self
+									.lexer
+									.span_str(next.span)
+									.eq_ignore_ascii_case("STOPWORDS") =>
+							{
+								let open_span = expected!(self, t!("(")).span;
+								let stopwords = if self.peek_kind() == t!("[") {
+									Stopwords::List(self.parse_filter_terms()?)
+								} else {
+									Stopwords::Language(self.next_token_value()?)
+								};
+								self.expect_closing_delimiter(t!(")"), open_span)?;
+								filters.push(Filter::Stopwords(stopwords))
+							}
+							TokenKind::Identifier
+								if self
+									.lexer
+									.span_str(next.span)
+									.eq_ignore_ascii_case("SYNONYMS") =>
+							{
+								let open_span = expected!(self, t!("(")).span;
+								let synonyms = if self.peek_kind() == t!("[") {
+									let list_span = self.pop_peek().span;
+									let mut groups = Vec::new();
+									while !self.eat(t!("]")) {
+										groups.push(self.parse_filter_terms()?);
+										if !self.eat(t!(",")) {
+											self.expect_closing_delimiter(t!("]"), list_span)?;
+											break;
+										}
+									}
+									Synonyms::List(groups)
+								} else {
+									let path: Strand = self.next_token_value()?;
+									Synonyms::File(path.into())
+								};
+								let stage = if self.eat(t!(",")) {
+									let next = self.next();
+									match next.kind {
+										t!("INDEX") => Some(FilterStage::Index),
+										TokenKind::Identifier
+											if self
+												.lexer
+												.span_str(next.span)
+												.eq_ignore_ascii_case("QUERY") =>
+										{
+											Some(FilterStage::Query)
+										}
+										_ => unexpected!(self, next, "INDEX or QUERY"),
+									}
+								} else {
+									None
+								};
+								self.expect_closing_delimiter(t!(")"), open_span)?;
+								filters.push(Filter::Synonyms(synonyms, stage))
+							}
 							_ => unexpected!(self, next, "a filter"),
 						}
 						if !self.eat(t!(",")) {
@@ -1404,6 +1555,21 @@ impl Parser<'_> {
 		Ok(res)
 	}
 
+	/// Parses a list of terms of an analyzer filter, such as `['a', 'the']`
+	fn parse_filter_terms(&mut self) -> ParseResult<Vec<String>> {
+		let open_span = expected!(self, t!("[")).span;
+		let mut terms = Vec::new();
+		while !self.eat(t!("]")) {
+			let term: Strand = self.next_token_value()?;
+			terms.push(term.into());
+			if !self.eat(t!(",")) {
+				self.expect_closing_delimiter(t!("]"), open_span)?;
+				break;
+			}
+		}
+		Ok(terms)
+	}
+
 	pub async fn parse_define_bucket(
 		&mut self,
 		stk: &mut Stk,
@@ -1491,6 +1657,86 @@ impl Parser<'_> {
 		})
 	}
 
+	pub fn parse_define_consumer(&mut self) -> ParseResult<DefineConsumerStatement> {
+		let (if_not_exists, overwrite) = if self.eat(t!("IF")) {
+			expected!(self, t!("NOT"));
+			expected!(self, t!("EXISTS"));
+			(true, false)
+		} else if self.eat(t!("OVERWRITE")) {
+			(false, true)
+		} else {
+			(false, false)
+		};
+		let name = self.next_token_value()?;
+		expected!(self, t!("ON"));
+		self.eat(t!("TABLE"));
+		let what = self.next_token_value()?;
+		let mut res = DefineConsumerStatement {
+			name,
+			what,
+			if_not_exists,
+			overwrite,
+			..Default::default()
+		};
+
+		while let t!("COMMENT") = self.peek_kind() {
+			self.pop_peek();
+			res.comment = Some(self.next_token_value()?);
+		}
+
+		Ok(res)
+	}
+
+	pub fn parse_define_sink(&mut self) -> ParseResult<DefineSinkStatement> {
+		let (if_not_exists, overwrite) = if self.eat(t!("IF")) {
+			expected!(self, t!("NOT"));
+			expected!(self, t!("EXISTS"));
+			(true, false)
+		} else if self.eat(t!("OVERWRITE")) {
+			(false, true)
+		} else {
+			(false, false)
+		};
+		let name = self.next_token_value()?;
+		expected!(self, t!("ON"));
+		self.eat(t!("TABLE"));
+		let what = self.next_token_value()?;
+		expected!(self, t!("URL"));
+		let url = self.next_token_value()?;
+		let mut res = DefineSinkStatement {
+			name,
+			what,
+			url,
+			if_not_exists,
+			overwrite,
+			..Default::default()
+		};
+
+		loop {
+			match self.peek_kind() {
+				t!("BATCH") => {
+					self.pop_peek();
+					res.batch = Some(self.next_token_value()?);
+				}
+				t!("RETRY") => {
+					self.pop_peek();
+					res.retry = Some(self.next_token_value()?);
+				}
+				t!("TIMEOUT") => {
+					self.pop_peek();
+					res.timeout = Some(self.next_token_value()?);
+				}
+				t!("COMMENT") => {
+					self.pop_peek();
+					res.comment = Some(self.next_token_value()?);
+				}
+				_ => break,
+			}
+		}
+
+		Ok(res)
+	}
+
 	pub async fn parse_define_config(
 		&mut self,
 		stk: &mut Stk,
@@ -1531,36 +1777,35 @@ impl Parser<'_> {
 					self.pop_peek();
 
 					let mut middleware: Vec<(String, Vec<Value>)> = Vec::new();
-					// let mut parsed_custom = false;
 
 					loop {
-						let mut name = match self.peek_kind() {
+						let name = match self.peek_kind() {
 							t!("API") => {
-								// if parsed_custom {
-								// 	bail!("Cannot specify builtin middlewares after custom middlewares");
-								// }
-
 								self.pop_peek();
 								expected!(self, t!("::"));
-								"api::".to_string()
+								let mut name = "api::".to_string();
+
+								let part = self.next_token_value::<Ident>()?;
+								name.push_str(part.0.to_lowercase().as_str());
+
+								while self.eat(t!("::")) {
+									let part = self.next_token_value::<Ident>()?;
+									name.push_str("::");
+									name.push_str(part.0.to_lowercase().as_str());
+								}
+
+								name
 							}
 							t!("fn") => {
-								bail!("Custom middlewares are not yet supported")
+								// Custom function names are case-sensitive
+								let name = self.parse_custom_function_name()?;
+								format!("fn::{}", name.0)
 							}
 							_ => {
 								break;
 							}
 						};
 
-						let part = self.next_token_value::<Ident>()?;
-						name.push_str(part.0.to_lowercase().as_str());
-
-						while self.eat(t!("::")) {
-							let part = self.next_token_value::<Ident>()?;
-							name.push_str("::");
-							name.push_str(part.0.to_lowercase().as_str());
-						}
-
 						expected!(self, t!("("));
 						let args = self.parse_function_args(stk).await?;
 
@@ -1801,3 +2046,98 @@ impl Parser<'_> {
 		Ok(res)
 	}
 }
+
+/// The builtin functions, or modules of functions, which can give a different
+/// result when called with the same arguments, or which read other data.
+const NON_DETERMINISTIC_FUNCTIONS: &[&str] = &[
+	"api",
+	"array::shuffle",
+	"crypto::argon2::generate",
+	"crypto::bcrypt::generate",
+	"crypto::pbkdf2::generate",
+	"crypto::scrypt::generate",
+	"file",
+	"http",
+	"rand",
+	"record::exists",
+	"record::refs",
+	"search::analyze",
+	"search::highlight",
+	"search::offsets",
+	"search::score",
+	"sequence",
+	"session",
+	"sleep",
+	"time::now",
+];
+
+/// Finds the first part of an index expression which could give a different
+/// result each time it is computed for the same record, describing it if found.
+fn non_deterministic(v: &Value) -> Option<String> {
+	let values = |v: &[Value]| v.iter().find_map(non_deterministic);
+	match v {
+		Value::Param(p) => Some(format!("the parameter {p}")),
+		Value::Subquery(_) | Value::Block(_) | Value::Query(_) => Some("a subquery".to_owned()),
+		Value::Future(_) => Some("a future".to_owned()),
+		Value::Closure(_) => Some("a closure".to_owned()),
+		Value::Model(_) => Some("a machine learning model".to_owned()),
+		Value::Mock(_) | Value::Edges(_) | Value::Refs(_) | Value::File(_) => {
+			Some(format!("the value {v}"))
+		}
+		Value::Thing(t) => match &t.id {
+			Id::Generate(_) => Some(format!("the generated record id {t}")),
+			Id::Array(a) => values(a),
+			Id::Object(o) => o.values().find_map(non_deterministic),
+			_ => None,
+		},
+		Value::Function(f) => match f.as_ref() {
+			Function::Normal(name, args) => {
+				let matches = |x: &&str| {
+					name.as_str() == *x
+						|| name.strip_prefix(*x).is_some_and(|n| n.starts_with("::"))
+				};
+				match NON_DETERMINISTIC_FUNCTIONS.iter().any(matches) {
+					true => Some(format!("the function {name}()")),
+					false => values(args),
+				}
+			}
+			Function::Custom(name, _) => Some(format!("the function fn::{name}()")),
+			_ => Some("a scripting function".to_owned()),
+		},
+		Value::Expression(e) => match e.as_ref() {
+			Expression::Unary {
+				v,
+				..
+			} => non_deterministic(v),
+			Expression::Binary {
+				o: Operator::Matches(_) | Operator::Knn(..) | Operator::Ann(..),
+				..
+			} => Some("a search operator".to_owned()),
+			Expression::Binary {
+				l,
+				r,
+				..
+			} => non_deterministic(l).or_else(|| non_deterministic(r)),
+		},
+		Value::Cast(c) => non_deterministic(&c.1),
+		Value::Array(a) => values(a),
+		Value::Object(o) => o.values().find_map(non_deterministic),
+		Value::Range(r) => [&r.beg, &r.end].into_iter().find_map(|b| match b {
+			Bound::Included(v) | Bound::Excluded(v) => non_deterministic(v),
+			Bound::Unbounded => None,
+		}),
+		Value::Idiom(i) => i.iter().find_map(|p| match p {
+			Part::Where(v) | Part::Value(v) | Part::Start(v) => non_deterministic(v),
+			Part::Method(name, args) => {
+				let matches = |x: &&str| x.ends_with(&format!("::{name}"));
+				match NON_DETERMINISTIC_FUNCTIONS.iter().any(matches) {
+					true => Some(format!("the method {name}()")),
+					false => values(args),
+				}
+			}
+			Part::Graph(_) | Part::Recurse(..) => Some("a graph traversal".to_owned()),
+			_ => None,
+		}),
+		_ => None,
+	}
+}
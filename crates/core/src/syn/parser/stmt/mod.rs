@@ -770,7 +770,32 @@ impl Parser<'_> {
 				Some(table)
 			}
 			t!("DATABASE") => None,
-			_ => unexpected!(self, next, "`TABLE` or `DATABASE`"),
+			t!("CONSUMER") => {
+				// A consumer resumes from its own position,
+				// and SINCE acknowledges the changes before a versionstamp
+				let consumer = Some(self.next_token_value()?);
+				let since = if self.eat(t!("SINCE")) {
+					let next = self.peek();
+					match next.kind {
+						TokenKind::Digits => {
+							self.pop_peek();
+							let int = self.lexer.lex_compound(next, compound::integer)?.value;
+							ShowSince::Versionstamp(int)
+						}
+						_ => unexpected!(self, next, "a version stamp"),
+					}
+				} else {
+					ShowSince::Versionstamp(0)
+				};
+				let limit = self.eat(t!("LIMIT")).then(|| self.next_token_value()).transpose()?;
+				return Ok(ShowStatement {
+					table: None,
+					since,
+					limit,
+					consumer,
+				});
+			}
+			_ => unexpected!(self, next, "`TABLE`, `DATABASE` or `CONSUMER`"),
 		};
 
 		expected!(self, t!("SINCE"));
@@ -797,6 +822,7 @@ impl Parser<'_> {
 			table,
 			since,
 			limit,
+			consumer: None,
 		})
 	}
 
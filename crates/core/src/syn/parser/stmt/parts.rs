@@ -9,10 +9,11 @@ use crate::{
 	sql::{
 		changefeed::ChangeFeed,
 		index::{Distance, VectorType},
-		Base, Cond, Data, Duration, Fetchs, Field, Fields, Group, Groups, Ident, Idiom, Output,
-		Permission, Permissions, Tables, Timeout, Value, View,
+		Base, Cond, Data, Duration, Fetchs, Field, Fields, Group, Groups, Ident, Idiom, Number,
+		Output, Permission, Permissions, Retention, Tables, Timeout, Value, View,
 	},
 	syn::{
+		lexer::compound,
 		parser::{
 			mac::{expected, unexpected},
 			ParseResult, Parser,
@@ -430,6 +431,31 @@ impl Parser<'_> {
 		})
 	}
 
+	/// Parses a version retention production
+	///
+	/// # Parser State
+	/// Expects the parser to have already eating the `VERSIONS` keyword
+	pub fn parse_retention(&mut self) -> ParseResult<Retention> {
+		expected!(self, t!("RETAIN"));
+		let peek = self.peek();
+		match peek.kind {
+			TokenKind::Digits => {
+				self.pop_peek();
+				let compound = self.lexer.lex_compound(peek, compound::numeric)?;
+				match compound.value {
+					compound::Numeric::Duration(x) => Ok(Retention::Duration(x)),
+					compound::Numeric::Number(Number::Int(x)) if x > 0 => {
+						Ok(Retention::Count(x as u64))
+					}
+					compound::Numeric::Number(_) => {
+						bail!("Unexpected number", @compound.span => "Expected a duration, or a positive integer number of versions")
+					}
+				}
+			}
+			_ => unexpected!(self, peek, "a duration, or a number of versions"),
+		}
+	}
+
 	/// Parses a reference
 	///
 	/// # Parser State
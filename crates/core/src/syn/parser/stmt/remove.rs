@@ -1,6 +1,8 @@
 use reblessive::Stk;
 
-use crate::sql::statements::remove::RemoveSequenceStatement;
+use crate::sql::statements::remove::{
+	RemoveConsumerStatement, RemoveSequenceStatement, RemoveSinkStatement,
+};
 use crate::{
 	sql::{
 		statements::{
@@ -223,6 +225,32 @@ impl Parser<'_> {
 					if_exists,
 				})
 			}
+			t!("CONSUMER") => {
+				let if_exists = if self.eat(t!("IF")) {
+					expected!(self, t!("EXISTS"));
+					true
+				} else {
+					false
+				};
+				let name = self.next_token_value()?;
+				RemoveStatement::Consumer(RemoveConsumerStatement {
+					name,
+					if_exists,
+				})
+			}
+			t!("SINK") => {
+				let if_exists = if self.eat(t!("IF")) {
+					expected!(self, t!("EXISTS"));
+					true
+				} else {
+					false
+				};
+				let name = self.next_token_value()?;
+				RemoveStatement::Sink(RemoveSinkStatement {
+					name,
+					if_exists,
+				})
+			}
 			t!("USER") => {
 				let if_exists = if self.eat(t!("IF")) {
 					expected!(self, t!("EXISTS"));
@@ -7,17 +7,20 @@ use crate::{
 		},
 		block::Entry,
 		changefeed::ChangeFeed,
-		filter::Filter,
+		filter::{Filter, FilterStage, Stopwords, Synonyms},
 		graph::{GraphSubject, GraphSubjects},
 		index::{Distance, HnswParams, MTreeParams, SearchParams, VectorType},
 		language::Language,
 		order::{OrderList, Ordering},
+		retention::Retention,
 		statements::{
 			access::{
 				self, AccessStatementGrant, AccessStatementPurge, AccessStatementRevoke,
 				AccessStatementShow,
 			},
 			analyze::AnalyzeStatement,
+			define::{DefineConsumerStatement, DefineSinkStatement},
+			remove::{RemoveConsumerStatement, RemoveSinkStatement},
 			show::{ShowSince, ShowStatement},
 			sleep::SleepStatement,
 			AccessStatement, BeginStatement, BreakStatement, CancelStatement, CommitStatement,
@@ -36,10 +39,10 @@ use crate::{
 		tokenizer::Tokenizer,
 		user::UserDuration,
 		Algorithm, Array, Base, Block, Cond, Data, Datetime, Dir, Duration, Edges, Explain,
-		Expression, Fetch, Fetchs, Field, Fields, Future, Graph, Group, Groups, Id, Ident, Idiom,
-		Idioms, Index, Kind, Limit, Number, Object, Operator, Order, Output, Param, Part,
-		Permission, Permissions, Scoring, Split, Splits, Start, Statement, Strand, Subquery, Table,
-		TableType, Tables, Thing, Timeout, Uuid, Value, Values, Version, With,
+		Expression, Fetch, Fetchs, Field, Fields, Function, Future, Graph, Group, Groups, Id,
+		Ident, Idiom, Idioms, Index, Kind, Limit, Number, Object, Operator, Order, Output, Param,
+		Part, Permission, Permissions, Scoring, Split, Splits, Start, Statement, Strand, Subquery,
+		Table, TableType, Tables, Thing, Timeout, Uuid, Value, Values, Version, With,
 	},
 	syn::parser::{
 		mac::{test_parse, test_parse_with_settings},
@@ -1863,10 +1866,29 @@ fn parse_define_table() {
 			cache_events_ts: uuid::Uuid::default(),
 			cache_tables_ts: uuid::Uuid::default(),
 			cache_indexes_ts: uuid::Uuid::default(),
+			versions: None,
 		}))
 	);
 }
 
+#[test]
+fn parse_define_table_versions() {
+	let res = test_parse!(parse_stmt, r#"DEFINE TABLE name VERSIONS RETAIN 30d"#).unwrap();
+	let Statement::Define(DefineStatement::Table(tb)) = res else {
+		panic!("expected a DEFINE TABLE statement");
+	};
+	assert_eq!(tb.versions, Some(Retention::Duration(std::time::Duration::from_secs(30 * 86400))));
+
+	let res = test_parse!(parse_stmt, r#"DEFINE TABLE name VERSIONS RETAIN 10"#).unwrap();
+	let Statement::Define(DefineStatement::Table(tb)) = res else {
+		panic!("expected a DEFINE TABLE statement");
+	};
+	assert_eq!(tb.versions, Some(Retention::Count(10)));
+
+	let res = test_parse!(parse_stmt, r#"DEFINE TABLE name VERSIONS RETAIN 0"#);
+	assert!(res.is_err());
+}
+
 #[test]
 fn parse_define_event() {
 	let res =
@@ -1887,6 +1909,46 @@ fn parse_define_event() {
 	)
 }
 
+#[test]
+fn parse_define_consumer() {
+	let res = test_parse!(parse_stmt, r#"DEFINE CONSUMER foo ON TABLE bar COMMENT "baz""#).unwrap();
+
+	assert_eq!(
+		res,
+		Statement::Define(DefineStatement::Consumer(DefineConsumerStatement {
+			name: Ident("foo".to_owned()),
+			what: Ident("bar".to_owned()),
+			comment: Some(Strand("baz".to_owned())),
+			if_not_exists: false,
+			overwrite: false,
+		}))
+	)
+}
+
+#[test]
+fn parse_define_sink() {
+	let res = test_parse!(
+		parse_stmt,
+		r#"DEFINE SINK foo ON TABLE bar URL "https://example.com/hook" BATCH 50 RETRY 5 TIMEOUT 5s COMMENT "baz""#
+	)
+	.unwrap();
+
+	assert_eq!(
+		res,
+		Statement::Define(DefineStatement::Sink(DefineSinkStatement {
+			name: Ident("foo".to_owned()),
+			what: Ident("bar".to_owned()),
+			url: Strand("https://example.com/hook".to_owned()),
+			batch: Some(50),
+			retry: Some(5),
+			timeout: Some(Duration(std::time::Duration::from_secs(5))),
+			comment: Some(Strand("baz".to_owned())),
+			if_not_exists: false,
+			overwrite: false,
+		}))
+	)
+}
+
 #[test]
 fn parse_define_field() {
 	// General
@@ -1978,7 +2040,9 @@ fn parse_define_index() {
 			DOC_LENGTHS_CACHE 6
 			POSTINGS_CACHE 7
 			TERMS_CACHE 8
-			HIGHLIGHTS"#
+			HIGHLIGHTS
+			FUZZY 1
+			BOOSTS(3, 0.5)"#
 	)
 	.unwrap();
 
@@ -2006,6 +2070,8 @@ fn parse_define_index() {
 				doc_lengths_cache: 6,
 				postings_cache: 7,
 				terms_cache: 8,
+				fuzzy: 1,
+				boosts: vec![Number::Int(3), Number::Float(0.5)],
 			}),
 			comment: None,
 			if_not_exists: false,
@@ -2014,6 +2080,25 @@ fn parse_define_index() {
 		}))
 	);
 
+	// Fuzzy matching supports an edit distance of at most 2
+	test_parse!(
+		parse_stmt,
+		r#"DEFINE INDEX index ON TABLE table FIELDS a SEARCH ANALYZER ana FUZZY 3"#
+	)
+	.unwrap_err();
+
+	// There must be one positive boost per field
+	test_parse!(
+		parse_stmt,
+		r#"DEFINE INDEX index ON TABLE table FIELDS a, b SEARCH ANALYZER ana BOOSTS(2)"#
+	)
+	.unwrap_err();
+	test_parse!(
+		parse_stmt,
+		r#"DEFINE INDEX index ON TABLE table FIELDS a, b SEARCH ANALYZER ana BOOSTS(2, 0)"#
+	)
+	.unwrap_err();
+
 	let res =
 		test_parse!(parse_stmt, r#"DEFINE INDEX index ON TABLE table FIELDS a UNIQUE"#).unwrap();
 
@@ -2031,6 +2116,32 @@ fn parse_define_index() {
 		}))
 	);
 
+	let res = test_parse!(
+		parse_stmt,
+		r#"DEFINE INDEX index ON TABLE table FIELDS string::lowercase(email), a UNIQUE"#
+	)
+	.unwrap();
+
+	assert_eq!(
+		res,
+		Statement::Define(DefineStatement::Index(DefineIndexStatement {
+			name: Ident("index".to_owned()),
+			what: Ident("table".to_owned()),
+			cols: Idioms(vec![
+				Idiom(vec![Part::Start(Value::Function(Box::new(Function::Normal(
+					"string::lowercase".to_owned(),
+					vec![Value::Idiom(Idiom(vec![Part::Field(Ident("email".to_owned()))]))]
+				))))]),
+				Idiom(vec![Part::Field(Ident("a".to_owned()))]),
+			]),
+			index: Index::Uniq,
+			comment: None,
+			if_not_exists: false,
+			overwrite: false,
+			concurrently: false
+		}))
+	);
+
 	let res =
 		test_parse!(parse_stmt, r#"DEFINE INDEX index ON TABLE table FIELDS a MTREE DIMENSION 4 DISTANCE MINKOWSKI 5 CAPACITY 6 TYPE I16 DOC_IDS_ORDER 7 DOC_IDS_CACHE 8 MTREE_CACHE 9"#).unwrap();
 
@@ -2084,6 +2195,32 @@ fn parse_define_index() {
 	);
 }
 
+#[test]
+fn parse_define_index_expression_is_deterministic() {
+	// Expressions which always give the same result for a record are allowed
+	for expr in ["string::concat(first, ' ', last)", "time::floor(created, 1d)", "(a + b) * 2"] {
+		let sql = format!("DEFINE INDEX index ON TABLE table FIELDS {expr}");
+		let res = test_parse!(parse_stmt, &sql);
+		assert!(res.is_ok(), "Unexpected error parsing index on {expr}: {res:?}");
+	}
+	// Expressions which could change, or which read other data, are rejected
+	for expr in [
+		"rand()",
+		"rand::uuid::v4()",
+		"time::now()",
+		"array::shuffle(tags)",
+		"string::concat(email, $suffix)",
+		"(SELECT VALUE email FROM user)",
+		"string::lowercase((SELECT VALUE email FROM user))",
+		"fn::normalise(email)",
+		"record::exists(author)",
+	] {
+		let sql = format!("DEFINE INDEX index ON TABLE table FIELDS {expr}");
+		let res = test_parse!(parse_stmt, &sql);
+		assert!(res.is_err(), "Unexpected successful parsing of index on {expr}: {res:?}");
+	}
+}
+
 #[test]
 fn parse_define_analyzer() {
 	let res = test_parse!(
@@ -2117,6 +2254,46 @@ fn parse_define_analyzer() {
 	)
 }
 
+#[test]
+fn parse_define_analyzer_stopwords_synonyms() {
+	let res = test_parse!(
+		parse_stmt,
+		r#"DEFINE ANALYZER ana FILTERS STOPWORDS(english), STOPWORDS(['a', 'the',]), SYNONYMS([['tv', 'television'], []]), SYNONYMS('synonyms.txt'), SYNONYMS([['car', 'auto']], QUERY), SYNONYMS('synonyms.txt', index)"#
+	).unwrap();
+
+	assert_eq!(
+		res,
+		Statement::Define(DefineStatement::Analyzer(DefineAnalyzerStatement {
+			name: Ident("ana".to_owned()),
+			tokenizers: None,
+			filters: Some(vec![
+				Filter::Stopwords(Stopwords::Language(Language::English)),
+				Filter::Stopwords(Stopwords::List(vec!["a".to_owned(), "the".to_owned()])),
+				Filter::Synonyms(
+					Synonyms::List(vec![vec!["tv".to_owned(), "television".to_owned()], vec![]]),
+					None
+				),
+				Filter::Synonyms(Synonyms::File("synonyms.txt".to_owned()), None),
+				Filter::Synonyms(
+					Synonyms::List(vec![vec!["car".to_owned(), "auto".to_owned()]]),
+					Some(FilterStage::Query)
+				),
+				Filter::Synonyms(
+					Synonyms::File("synonyms.txt".to_owned()),
+					Some(FilterStage::Index)
+				),
+			]),
+			comment: None,
+			function: None,
+			if_not_exists: false,
+			overwrite: false,
+		})),
+	);
+	test_parse!(parse_stmt, r#"DEFINE ANALYZER ana FILTERS STOPWORDS('the')"#).unwrap_err();
+	test_parse!(parse_stmt, r#"DEFINE ANALYZER ana FILTERS SYNONYMS('synonyms.txt', BOTH)"#)
+		.unwrap_err();
+}
+
 #[test]
 fn parse_delete() {
 	let res = test_parse!(
@@ -2397,7 +2574,8 @@ fn parse_show() {
 		Statement::Show(ShowStatement {
 			table: Some(Table("foo".to_owned())),
 			since: ShowSince::Versionstamp(1),
-			limit: Some(10)
+			limit: Some(10),
+			consumer: None,
 		})
 	);
 
@@ -2423,9 +2601,34 @@ fn parse_show() {
 		Statement::Show(ShowStatement {
 			table: None,
 			since: ShowSince::Timestamp(Datetime(expected_datetime)),
-			limit: None
+			limit: None,
+			consumer: None,
 		})
-	)
+	);
+
+	let res = test_parse!(parse_stmt, r#"SHOW CHANGES FOR CONSUMER foo LIMIT 10"#).unwrap();
+	assert_eq!(
+		res,
+		Statement::Show(ShowStatement {
+			table: None,
+			since: ShowSince::Versionstamp(0),
+			limit: Some(10),
+			consumer: Some(Ident("foo".to_owned())),
+		})
+	);
+
+	let res = test_parse!(parse_stmt, r#"SHOW CHANGES FOR CONSUMER foo SINCE 5"#).unwrap();
+	assert_eq!(
+		res,
+		Statement::Show(ShowStatement {
+			table: None,
+			since: ShowSince::Versionstamp(5),
+			limit: None,
+			consumer: Some(Ident("foo".to_owned())),
+		})
+	);
+	test_parse!(parse_stmt, r#"SHOW CHANGES FOR CONSUMER foo SINCE d'2012-04-23T18:25:43Z'"#)
+		.unwrap_err();
 }
 
 #[test]
@@ -2778,6 +2981,24 @@ fn parse_remove() {
 		}))
 	);
 
+	let res = test_parse!(parse_stmt, r#"REMOVE CONSUMER IF EXISTS foo"#).unwrap();
+	assert_eq!(
+		res,
+		Statement::Remove(RemoveStatement::Consumer(RemoveConsumerStatement {
+			name: Ident("foo".to_owned()),
+			if_exists: true,
+		}))
+	);
+
+	let res = test_parse!(parse_stmt, r#"REMOVE SINK IF EXISTS foo"#).unwrap();
+	assert_eq!(
+		res,
+		Statement::Remove(RemoveStatement::Sink(RemoveSinkStatement {
+			name: Ident("foo".to_owned()),
+			if_exists: true,
+		}))
+	);
+
 	let res = test_parse!(parse_stmt, r#"REMOVE ACCESS foo ON DATABASE"#).unwrap();
 	assert_eq!(
 		res,
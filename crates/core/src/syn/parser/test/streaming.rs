@@ -297,6 +297,7 @@ fn statements() -> Vec<Statement> {
 			cache_events_ts: uuid::Uuid::default(),
 			cache_tables_ts: uuid::Uuid::default(),
 			cache_indexes_ts: uuid::Uuid::default(),
+			versions: None,
 		})),
 		Statement::Define(DefineStatement::Event(DefineEventStatement {
 			name: Ident("event".to_owned()),
@@ -358,6 +359,8 @@ fn statements() -> Vec<Statement> {
 				doc_lengths_cache: 6,
 				postings_cache: 7,
 				terms_cache: 8,
+				fuzzy: 0,
+				boosts: Vec::new(),
 			}),
 			comment: None,
 			if_not_exists: false,
@@ -551,11 +554,13 @@ fn statements() -> Vec<Statement> {
 			table: Some(Table("foo".to_owned())),
 			since: ShowSince::Versionstamp(1),
 			limit: Some(10),
+			consumer: None,
 		}),
 		Statement::Show(ShowStatement {
 			table: None,
 			since: ShowSince::Timestamp(Datetime(expected_datetime)),
 			limit: None,
+			consumer: None,
 		}),
 		Statement::Sleep(SleepStatement {
 			duration: Duration(std::time::Duration::from_secs(1)),
@@ -62,6 +62,7 @@ keyword! {
 	Commit => "COMMIT",
 	Concurrently => "CONCURRENTLY",
 	Config => "CONFIG",
+	Consumer => "CONSUMER",
 	Content => "CONTENT",
 	Continue => "CONTINUE",
 	Create => "CREATE",
@@ -169,6 +170,8 @@ keyword! {
 	Relation => "RELATION",
 	Remove => "REMOVE",
 	Replace => "REPLACE",
+	Retain => "RETAIN",
+	Retry => "RETRY",
 	Return => "RETURN",
 	Revoke => "REVOKE",
 	Revoked => "REVOKED",
@@ -186,6 +189,7 @@ keyword! {
 	Signin => "SIGNIN",
 	Signup => "SIGNUP",
 	Since => "SINCE",
+	Sink => "SINK",
 	Sleep => "SLEEP",
 	Snowball => "SNOWBALL",
 	Split => "SPLIT",
@@ -216,6 +220,7 @@ keyword! {
 	Value => "VALUE",
 	Values => "VALUES",
 	Version => "VERSION",
+	Versions => "VERSIONS",
 	Vs => "VS",
 	When => "WHEN",
 	Where => "WHERE",
@@ -119,6 +119,9 @@ pub(crate) enum Command {
 		version: Option<String>,
 		args: CoreArray,
 	},
+	Begin,
+	Commit,
+	Cancel,
 }
 
 impl Command {
@@ -136,6 +139,7 @@ impl Command {
 				database,
 			} => RouterRequest {
 				id,
+				txn: None,
 				method: "use",
 				params: Some(vec![CoreValue::from(namespace), CoreValue::from(database)].into()),
 			},
@@ -143,6 +147,7 @@ impl Command {
 				credentials,
 			} => RouterRequest {
 				id,
+				txn: None,
 				method: "signup",
 				params: Some(vec![CoreValue::from(credentials)].into()),
 			},
@@ -150,6 +155,7 @@ impl Command {
 				credentials,
 			} => RouterRequest {
 				id,
+				txn: None,
 				method: "signin",
 				params: Some(vec![CoreValue::from(credentials)].into()),
 			},
@@ -157,11 +163,13 @@ impl Command {
 				token,
 			} => RouterRequest {
 				id,
+				txn: None,
 				method: "authenticate",
 				params: Some(vec![CoreValue::from(token)].into()),
 			},
 			Command::Invalidate => RouterRequest {
 				id,
+				txn: None,
 				method: "invalidate",
 				params: None,
 			},
@@ -176,6 +184,7 @@ impl Command {
 
 				RouterRequest {
 					id,
+					txn: None,
 					method: "create",
 					params: Some(params.into()),
 				}
@@ -192,6 +201,7 @@ impl Command {
 
 				RouterRequest {
 					id,
+					txn: None,
 					method: "upsert",
 					params: Some(params.into()),
 				}
@@ -209,6 +219,7 @@ impl Command {
 
 				RouterRequest {
 					id,
+					txn: None,
 					method: "update",
 					params: Some(params.into()),
 				}
@@ -230,6 +241,7 @@ impl Command {
 
 				RouterRequest {
 					id,
+					txn: None,
 					method: "insert",
 					params: Some(params.into()),
 				}
@@ -250,6 +262,7 @@ impl Command {
 
 				RouterRequest {
 					id,
+					txn: None,
 					method: "insert_relation",
 					params: Some(params.into()),
 				}
@@ -279,6 +292,7 @@ impl Command {
 
 				RouterRequest {
 					id,
+					txn: None,
 					method: "query",
 					params: Some(params.into()),
 				}
@@ -308,6 +322,7 @@ impl Command {
 
 				RouterRequest {
 					id,
+					txn: None,
 					method: "query",
 					params: Some(params.into()),
 				}
@@ -317,6 +332,7 @@ impl Command {
 				..
 			} => RouterRequest {
 				id,
+				txn: None,
 				method: "select",
 				params: Some(CoreValue::Array(vec![what.into_core_value()].into())),
 			},
@@ -325,6 +341,7 @@ impl Command {
 				..
 			} => RouterRequest {
 				id,
+				txn: None,
 				method: "delete",
 				params: Some(CoreValue::Array(vec![what.into_core_value()].into())),
 			},
@@ -335,6 +352,7 @@ impl Command {
 				let params: Vec<CoreValue> = vec![query.into(), variables.into()];
 				RouterRequest {
 					id,
+					txn: None,
 					method: "query",
 					params: Some(params.into()),
 				}
@@ -346,6 +364,7 @@ impl Command {
 				let params: Vec<CoreValue> = vec![query.into_owned().into(), variables.into()];
 				RouterRequest {
 					id,
+					txn: None,
 					method: "query",
 					params: Some(params.into()),
 				}
@@ -370,11 +389,13 @@ impl Command {
 			} => return None,
 			Command::Health => RouterRequest {
 				id,
+				txn: None,
 				method: "ping",
 				params: None,
 			},
 			Command::Version => RouterRequest {
 				id,
+				txn: None,
 				method: "version",
 				params: None,
 			},
@@ -383,6 +404,7 @@ impl Command {
 				value,
 			} => RouterRequest {
 				id,
+				txn: None,
 				method: "let",
 				params: Some(CoreValue::from(vec![CoreValue::from(key), value])),
 			},
@@ -390,6 +412,7 @@ impl Command {
 				key,
 			} => RouterRequest {
 				id,
+				txn: None,
 				method: "unset",
 				params: Some(CoreValue::from(vec![CoreValue::from(key)])),
 			},
@@ -400,6 +423,7 @@ impl Command {
 				uuid,
 			} => RouterRequest {
 				id,
+				txn: None,
 				method: "kill",
 				params: Some(CoreValue::from(vec![CoreValue::from(uuid)])),
 			},
@@ -409,12 +433,31 @@ impl Command {
 				args,
 			} => RouterRequest {
 				id,
+				txn: None,
 				method: "run",
 				params: Some(
 					vec![CoreValue::from(name), CoreValue::from(version), CoreValue::Array(args)]
 						.into(),
 				),
 			},
+			Command::Begin => RouterRequest {
+				id,
+				txn: None,
+				method: "begin",
+				params: None,
+			},
+			Command::Commit => RouterRequest {
+				id,
+				txn: None,
+				method: "commit",
+				params: None,
+			},
+			Command::Cancel => RouterRequest {
+				id,
+				txn: None,
+				method: "cancel",
+				params: None,
+			},
 		};
 		Some(res)
 	}
@@ -459,6 +502,7 @@ impl Command {
 #[derive(Debug)]
 pub(crate) struct RouterRequest {
 	id: Option<i64>,
+	txn: Option<Uuid>,
 	method: &'static str,
 	params: Option<CoreValue>,
 }
@@ -475,6 +519,14 @@ fn stringify_queries(value: CoreValue) -> CoreValue {
 }
 
 impl RouterRequest {
+	/// Runs the request within an interactive transaction
+	pub(crate) fn with_txn(self, txn: Option<Uuid>) -> Self {
+		Self {
+			txn,
+			..self
+		}
+	}
+
 	#[cfg(feature = "protocol-ws")]
 	pub(crate) fn stringify_queries(self) -> Self {
 		Self {
@@ -537,11 +589,17 @@ impl Serialize for RouterRequest {
 			where
 				S: serde::Serializer,
 			{
-				let size = 1 + self.0.id.is_some() as usize + self.0.params.is_some() as usize;
+				let size = 1
+					+ self.0.id.is_some() as usize
+					+ self.0.txn.is_some() as usize
+					+ self.0.params.is_some() as usize;
 				let mut map = serializer.serialize_map(Some(size))?;
 				if let Some(id) = self.0.id.as_ref() {
 					map.serialize_entry("id", &InnerNumberVariant(*id))?;
 				}
+				if let Some(txn) = self.0.txn {
+					map.serialize_entry("txn", &CoreValue::from(txn))?;
+				}
 				map.serialize_entry("method", &InnerMethod(self.0.method))?;
 				if let Some(params) = self.0.params.as_ref() {
 					map.serialize_entry("params", params)?;
@@ -584,7 +642,10 @@ impl Revisioned for RouterRequest {
 		// object wrapper version
 		Revisioned::serialize_revisioned(&1u32, w)?;
 
-		let size = 1 + self.id.is_some() as usize + self.params.is_some() as usize;
+		let size = 1
+			+ self.id.is_some() as usize
+			+ self.txn.is_some() as usize
+			+ self.params.is_some() as usize;
 		size.serialize_revisioned(w)?;
 
 		let serializer = bincode::options()
@@ -613,6 +674,14 @@ impl Revisioned for RouterRequest {
 			x.serialize_revisioned(w)?;
 		}
 
+		if let Some(x) = self.txn {
+			serializer
+				.serialize_into(&mut *w, "txn")
+				.map_err(|err| revision::Error::Serialize(err.to_string()))?;
+
+			CoreValue::from(x).serialize_revisioned(w)?;
+		}
+
 		serializer
 			.serialize_into(&mut *w, "method")
 			.map_err(|err| revision::Error::Serialize(err.to_string()))?;
@@ -655,6 +724,7 @@ mod test {
 
 	use revision::Revisioned;
 	use surrealdb_core::sql::{Number, Value};
+	use uuid::Uuid;
 
 	use super::RouterRequest;
 
@@ -681,6 +751,15 @@ mod test {
 		};
 		assert_eq!(x.0, req.method);
 
+		assert_eq!(
+			obj.get("txn").cloned().and_then(|x| if let Value::Uuid(x) = x {
+				Some(x.0)
+			} else {
+				None
+			}),
+			req.txn
+		);
+
 		assert_eq!(obj.get("params").cloned(), req.params);
 	}
 
@@ -688,6 +767,7 @@ mod test {
 	fn router_request_value_conversion() {
 		let request = RouterRequest {
 			id: Some(1234),
+			txn: Some(Uuid::new_v4()),
 			method: "request",
 			params: Some(vec![Value::from(1234i64), Value::from("request")].into()),
 		};
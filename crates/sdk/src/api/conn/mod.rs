@@ -13,7 +13,9 @@ use serde::de::DeserializeOwned;
 use std::collections::HashSet;
 use std::sync::atomic::AtomicI64;
 use std::sync::atomic::Ordering;
+use std::sync::Arc;
 use surrealdb_core::sql::{from_value as from_core_value, Value as CoreValue};
+use uuid::Uuid;
 
 mod cmd;
 pub(crate) use cmd::Command;
@@ -27,6 +29,8 @@ use super::opt::Config;
 pub struct RequestData {
 	pub(crate) id: i64,
 	pub(crate) command: Command,
+	/// The interactive transaction the request is run within
+	pub(crate) txn: Option<Uuid>,
 }
 
 #[derive(Debug)]
@@ -43,8 +47,9 @@ pub(crate) struct Route {
 pub struct Router {
 	pub(crate) sender: Sender<Route>,
 	pub(crate) config: Config,
-	pub(crate) last_id: AtomicI64,
+	pub(crate) last_id: Arc<AtomicI64>,
 	pub(crate) features: HashSet<ExtraFeatures>,
+	pub(crate) txn: Option<Uuid>,
 }
 
 impl Router {
@@ -52,6 +57,17 @@ impl Router {
 		self.last_id.fetch_add(1, Ordering::SeqCst)
 	}
 
+	/// Creates a router which runs every request within an interactive transaction
+	pub(crate) fn with_transaction(&self, txn: Uuid) -> Self {
+		Self {
+			sender: self.sender.clone(),
+			config: self.config.clone(),
+			last_id: self.last_id.clone(),
+			features: self.features.clone(),
+			txn: Some(txn),
+		}
+	}
+
 	pub(crate) fn send(
 		&self,
 		command: Command,
@@ -63,6 +79,7 @@ impl Router {
 				request: RequestData {
 					id,
 					command,
+					txn: self.txn,
 				},
 				response: sender,
 			};
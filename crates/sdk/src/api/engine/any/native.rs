@@ -22,6 +22,7 @@ use crate::opt::WaitFor;
 use reqwest::ClientBuilder;
 use std::collections::HashSet;
 use std::sync::atomic::AtomicI64;
+use std::sync::Arc;
 use tokio::sync::watch;
 #[cfg(feature = "protocol-ws")]
 use tokio_tungstenite::tungstenite::protocol::WebSocketConfig;
@@ -79,7 +80,7 @@ impl conn::Sealed for Any {
 					);
 				}
 
-				EndpointKind::File | EndpointKind::RocksDb => {
+				EndpointKind::File | EndpointKind::RocksDb | EndpointKind::RocksDbVersioned => {
 					#[cfg(feature = "kv-rocksdb")]
 					{
 						features.insert(ExtraFeatures::Backup);
@@ -207,7 +208,8 @@ impl conn::Sealed for Any {
 				features,
 				config,
 				sender: route_tx,
-				last_id: AtomicI64::new(0),
+				last_id: Arc::new(AtomicI64::new(0)),
+				txn: None,
 			};
 
 			Ok((router, waiter).into())
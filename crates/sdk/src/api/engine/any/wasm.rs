@@ -13,6 +13,7 @@ use crate::error::Db as DbError;
 use crate::opt::WaitFor;
 use std::collections::HashSet;
 use std::sync::atomic::AtomicI64;
+use std::sync::Arc;
 use tokio::sync::watch;
 use wasm_bindgen_futures::spawn_local;
 
@@ -78,7 +79,7 @@ impl conn::Sealed for Any {
 					);
 				}
 
-				EndpointKind::File | EndpointKind::RocksDb => {
+				EndpointKind::File | EndpointKind::RocksDb | EndpointKind::RocksDbVersioned => {
 					#[cfg(feature = "kv-rocksdb")]
 					{
 						features.insert(ExtraFeatures::LiveQueries);
@@ -164,7 +165,8 @@ impl conn::Sealed for Any {
 				features,
 				config,
 				sender: route_tx,
-				last_id: AtomicI64::new(0),
+				last_id: Arc::new(AtomicI64::new(0)),
+				txn: None,
 			};
 
 			Ok((router, waiter).into())
@@ -476,9 +476,38 @@ async fn kill_live_query(
 	take(true, response).await
 }
 
+/// Processes a query, within an interactive transaction if one is specified
+async fn process_in(
+	kvs: &Datastore,
+	txn: Option<Uuid>,
+	query: Query,
+	session: &Session,
+	vars: Option<BTreeMap<String, CoreValue>>,
+) -> std::result::Result<Vec<Response>, surrealdb_core::err::Error> {
+	match txn {
+		Some(txn) => kvs.process_in_transaction(txn, query, session, vars).await,
+		None => kvs.process(query, session, vars).await,
+	}
+}
+
+/// Executes a query, within an interactive transaction if one is specified
+async fn execute_in(
+	kvs: &Datastore,
+	txn: Option<Uuid>,
+	query: &str,
+	session: &Session,
+	vars: Option<BTreeMap<String, CoreValue>>,
+) -> std::result::Result<Vec<Response>, surrealdb_core::err::Error> {
+	match txn {
+		Some(txn) => kvs.execute_in_transaction(txn, query, session, vars).await,
+		None => kvs.execute(query, session, vars).await,
+	}
+}
+
 async fn router(
 	RequestData {
 		command,
+		txn,
 		..
 	}: RequestData,
 	kvs: &Arc<Datastore>,
@@ -536,8 +565,14 @@ async fn router(
 				stmt
 			};
 			query.0 .0 = vec![Statement::Create(statement)];
-			let response =
-				kvs.process(query, &*session.read().await, Some(vars.read().await.clone())).await?;
+			let response = process_in(
+				kvs,
+				txn,
+				query,
+				&*session.read().await,
+				Some(vars.read().await.clone()),
+			)
+			.await?;
 			let value = take(true, response).await?;
 			Ok(DbResponse::Other(value))
 		}
@@ -556,7 +591,7 @@ async fn router(
 			};
 			query.0 .0 = vec![Statement::Upsert(statement)];
 			let vars = vars.read().await.iter().map(|(k, v)| (k.clone(), v.clone())).collect();
-			let response = kvs.process(query, &*session.read().await, Some(vars)).await?;
+			let response = process_in(kvs, txn, query, &*session.read().await, Some(vars)).await?;
 			let value = take(one, response).await?;
 			Ok(DbResponse::Other(value))
 		}
@@ -575,7 +610,7 @@ async fn router(
 			};
 			query.0 .0 = vec![Statement::Update(statement)];
 			let vars = vars.read().await.iter().map(|(k, v)| (k.clone(), v.clone())).collect();
-			let response = kvs.process(query, &*session.read().await, Some(vars)).await?;
+			let response = process_in(kvs, txn, query, &*session.read().await, Some(vars)).await?;
 			let value = take(one, response).await?;
 			Ok(DbResponse::Other(value))
 		}
@@ -594,7 +629,7 @@ async fn router(
 			};
 			query.0 .0 = vec![Statement::Insert(statement)];
 			let vars = vars.read().await.iter().map(|(k, v)| (k.clone(), v.clone())).collect();
-			let response = kvs.process(query, &*session.read().await, Some(vars)).await?;
+			let response = process_in(kvs, txn, query, &*session.read().await, Some(vars)).await?;
 			let value = take(one, response).await?;
 			Ok(DbResponse::Other(value))
 		}
@@ -613,8 +648,14 @@ async fn router(
 				stmt
 			};
 			query.0 .0 = vec![Statement::Insert(statement)];
-			let response =
-				kvs.process(query, &*session.read().await, Some(vars.read().await.clone())).await?;
+			let response = process_in(
+				kvs,
+				txn,
+				query,
+				&*session.read().await,
+				Some(vars.read().await.clone()),
+			)
+			.await?;
 			let value = take(one, response).await?;
 			Ok(DbResponse::Other(value))
 		}
@@ -639,7 +680,7 @@ async fn router(
 			};
 			query.0 .0 = vec![statement];
 			let vars = vars.read().await.iter().map(|(k, v)| (k.clone(), v.clone())).collect();
-			let response = kvs.process(query, &*session.read().await, Some(vars)).await?;
+			let response = process_in(kvs, txn, query, &*session.read().await, Some(vars)).await?;
 			let response = process(response);
 			Ok(DbResponse::Query(response))
 		}
@@ -664,7 +705,7 @@ async fn router(
 			};
 			query.0 .0 = vec![statement];
 			let vars = vars.read().await.iter().map(|(k, v)| (k.clone(), v.clone())).collect();
-			let response = kvs.process(query, &*session.read().await, Some(vars)).await?;
+			let response = process_in(kvs, txn, query, &*session.read().await, Some(vars)).await?;
 			let response = process(response);
 			Ok(DbResponse::Query(response))
 		}
@@ -681,7 +722,7 @@ async fn router(
 			};
 			query.0 .0 = vec![Statement::Select(statement)];
 			let vars = vars.read().await.iter().map(|(k, v)| (k.clone(), v.clone())).collect();
-			let response = kvs.process(query, &*session.read().await, Some(vars)).await?;
+			let response = process_in(kvs, txn, query, &*session.read().await, Some(vars)).await?;
 			let value = take(one, response).await?;
 			Ok(DbResponse::Other(value))
 		}
@@ -698,7 +739,7 @@ async fn router(
 			};
 			query.0 .0 = vec![Statement::Delete(statement)];
 			let vars = vars.read().await.iter().map(|(k, v)| (k.clone(), v.clone())).collect();
-			let response = kvs.process(query, &*session.read().await, Some(vars)).await?;
+			let response = process_in(kvs, txn, query, &*session.read().await, Some(vars)).await?;
 			let value = take(one, response).await?;
 			Ok(DbResponse::Other(value))
 		}
@@ -708,7 +749,7 @@ async fn router(
 		} => {
 			let mut vars = vars.read().await.clone();
 			vars.append(&mut variables.0);
-			let response = kvs.process(query, &*session.read().await, Some(vars)).await?;
+			let response = process_in(kvs, txn, query, &*session.read().await, Some(vars)).await?;
 			let response = process(response);
 			Ok(DbResponse::Query(response))
 		}
@@ -718,7 +759,8 @@ async fn router(
 		} => {
 			let mut vars = vars.read().await.clone();
 			vars.append(&mut variables.0);
-			let response = kvs.execute(query.as_ref(), &*session.read().await, Some(vars)).await?;
+			let response =
+				execute_in(kvs, txn, query.as_ref(), &*session.read().await, Some(vars)).await?;
 			let response = process(response);
 			Ok(DbResponse::Query(response))
 		}
@@ -1004,8 +1046,14 @@ async fn router(
 			model.comment = Some(file.header.description.to_string().into());
 			model.hash = hash;
 			let query = DefineStatement::Model(model).into();
-			let responses =
-				kvs.process(query, &*session.read().await, Some(vars.read().await.clone())).await?;
+			let responses = process_in(
+				kvs,
+				txn,
+				query,
+				&*session.read().await,
+				Some(vars.read().await.clone()),
+			)
+			.await?;
 
 			for response in responses {
 				response.result?;
@@ -1083,12 +1131,31 @@ async fn router(
 
 			let stmt = Statement::Value(func);
 
-			let response = kvs
-				.process(stmt.into(), &*session.read().await, Some(vars.read().await.clone()))
-				.await?;
+			let response = process_in(
+				kvs,
+				txn,
+				stmt.into(),
+				&*session.read().await,
+				Some(vars.read().await.clone()),
+			)
+			.await?;
 			let value = take(true, response).await?;
 
 			Ok(DbResponse::Other(value))
 		}
+		Command::Begin => {
+			let txn = kvs.begin(&*session.read().await).await?;
+			Ok(DbResponse::Other(CoreValue::from(txn)))
+		}
+		Command::Commit => {
+			let txn = txn.ok_or(Error::InternalError("no transaction to commit".to_string()))?;
+			kvs.commit(txn, &*session.read().await).await?;
+			Ok(DbResponse::Other(CoreValue::None))
+		}
+		Command::Cancel => {
+			let txn = txn.ok_or(Error::InternalError("no transaction to cancel".to_string()))?;
+			kvs.cancel(txn, &*session.read().await).await?;
+			Ok(DbResponse::Other(CoreValue::None))
+		}
 	}
 }
@@ -54,7 +54,8 @@ impl conn::Sealed for Db {
 				features,
 				config,
 				sender: route_tx,
-				last_id: AtomicI64::new(0),
+				last_id: Arc::new(AtomicI64::new(0)),
+				txn: None,
 			};
 
 			Ok((router, waiter).into())
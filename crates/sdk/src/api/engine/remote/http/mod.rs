@@ -299,6 +299,7 @@ async fn router(
 	vars: &mut IndexMap<String, CoreValue>,
 	auth: &mut Option<Auth>,
 ) -> Result<DbResponse> {
+	let txn = req.txn;
 	match req.command {
 		Command::Query {
 			query,
@@ -310,7 +311,8 @@ async fn router(
 				variables,
 			}
 			.into_router_request(None)
-			.expect("query should be valid request");
+			.expect("query should be valid request")
+			.with_txn(txn);
 			send_request(req, base_url, client, headers, auth).await
 		}
 		Command::Use {
@@ -542,7 +544,7 @@ async fn router(
 		} => Err(Error::LiveQueriesNotSupported.into()),
 		cmd => {
 			let needs_flatten = cmd.needs_flatten();
-			let req = cmd.into_router_request(None).unwrap();
+			let req = cmd.into_router_request(None).unwrap().with_txn(txn);
 			let mut res = send_request(req, base_url, client, headers, auth).await?;
 			if needs_flatten {
 				res = flatten_dbresponse_array(res);
@@ -16,6 +16,7 @@ use reqwest::header::HeaderMap;
 use reqwest::ClientBuilder;
 use std::collections::HashSet;
 use std::sync::atomic::AtomicI64;
+use std::sync::Arc;
 use tokio::sync::watch;
 use url::Url;
 
@@ -60,7 +61,8 @@ impl conn::Sealed for Client {
 				features,
 				config,
 				sender: route_tx,
-				last_id: AtomicI64::new(0),
+				last_id: Arc::new(AtomicI64::new(0)),
+				txn: None,
 			};
 
 			Ok((router, waiter).into())
@@ -13,6 +13,7 @@ use reqwest::header::HeaderMap;
 use reqwest::ClientBuilder;
 use std::collections::HashSet;
 use std::sync::atomic::AtomicI64;
+use std::sync::Arc;
 use tokio::sync::watch;
 use url::Url;
 use wasm_bindgen_futures::spawn_local;
@@ -38,7 +39,8 @@ impl conn::Sealed for Client {
 				features: HashSet::new(),
 				config,
 				sender: route_tx,
-				last_id: AtomicI64::new(0),
+				last_id: Arc::new(AtomicI64::new(0)),
+				txn: None,
 			};
 
 			Ok((router, waiter).into())
@@ -28,6 +28,7 @@ use serde::Deserialize;
 use std::collections::hash_map::Entry;
 use std::collections::HashSet;
 use std::sync::atomic::AtomicI64;
+use std::sync::Arc;
 use surrealdb_core::sql::Value as CoreValue;
 use tokio::net::TcpStream;
 use tokio::sync::watch;
@@ -138,7 +139,8 @@ impl conn::Sealed for Client {
 				features,
 				config,
 				sender: route_tx,
-				last_id: AtomicI64::new(0),
+				last_id: Arc::new(AtomicI64::new(0)),
+				txn: None,
 			};
 
 			Ok((router, waiter).into())
@@ -157,6 +159,7 @@ async fn router_handle_route(
 	let RequestData {
 		id,
 		command,
+		txn,
 	} = request;
 
 	// We probably shouldn't be sending duplicate id requests.
@@ -236,7 +239,7 @@ async fn router_handle_route(
 	}
 
 	let message = {
-		let Some(request) = command.into_router_request(Some(id)) else {
+		let Some(request) = command.into_router_request(Some(id)).map(|r| r.with_txn(txn)) else {
 			let _ = response.send(Err(Error::BackupsNotSupported.into())).await;
 			return HandleResult::Ok;
 		};
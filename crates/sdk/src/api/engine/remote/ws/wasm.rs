@@ -32,6 +32,7 @@ use std::collections::hash_map::Entry;
 use std::collections::BTreeMap;
 use std::collections::HashSet;
 use std::sync::atomic::AtomicI64;
+use std::sync::Arc;
 use std::time::Duration;
 use surrealdb_core::sql::Value as CoreValue;
 use tokio::sync::watch;
@@ -76,7 +77,8 @@ impl conn::Sealed for Client {
 				features,
 				config,
 				sender: route_tx,
-				last_id: AtomicI64::new(0),
+				last_id: Arc::new(AtomicI64::new(0)),
+				txn: None,
 			};
 
 			Ok((router, waiter).into())
@@ -95,6 +97,7 @@ async fn router_handle_request(
 	let RequestData {
 		id,
 		command,
+		txn,
 	} = request;
 
 	let entry = state.pending_requests.entry(id);
@@ -176,7 +179,7 @@ async fn router_handle_request(
 	}
 
 	let message = {
-		let Some(req) = command.into_router_request(Some(id)) else {
+		let Some(req) = command.into_router_request(Some(id)).map(|r| r.with_txn(txn)) else {
 			let _ = response.send(Err(Error::BackupsNotSupported.into())).await;
 			return HandleResult::Ok;
 		};
@@ -1,12 +1,18 @@
+use crate::api::conn::Command;
+use crate::api::err::Error;
 use crate::api::method::BoxFuture;
 use crate::api::method::Cancel;
 use crate::api::method::Commit;
+use crate::api::method::OnceLockExt;
 use crate::api::Connection;
 use crate::api::Result;
 use crate::api::Surreal;
+use crate::opt::WaitFor;
 use std::future::IntoFuture;
 use std::ops::Deref;
-use surrealdb_core::sql::statements::BeginStatement;
+use surrealdb_core::sql::Value as CoreValue;
+use tokio::sync::watch;
+use uuid::Uuid;
 
 /// A beginning of a transaction
 #[derive(Debug)]
@@ -24,8 +30,21 @@ where
 
 	fn into_future(self) -> Self::IntoFuture {
 		Box::pin(async move {
-			self.client.query(BeginStatement::default()).await?;
+			let router = self.client.inner.router.extract()?;
+			let id = match router.execute_value(Command::Begin).await?.0 {
+				CoreValue::Uuid(id) => id.0,
+				value => {
+					return Err(Error::InternalError(format!(
+						"expected a transaction id, but the server returned `{value}`"
+					))
+					.into())
+				}
+			};
+			// The connection is already established, so there is nothing to wait for
+			let waiter = watch::channel(Some(WaitFor::Database));
 			Ok(Transaction {
+				id,
+				txn: Surreal::from((router.with_transaction(id), waiter)),
 				client: self.client,
 			})
 		})
@@ -33,9 +52,14 @@ where
 }
 
 /// An ongoing transaction
+///
+/// Requests made through a transaction, which dereferences to a client,
+/// are all run within the same transaction on the server.
 #[derive(Debug)]
 #[must_use = "transactions must be committed or cancelled to complete them"]
 pub struct Transaction<C: Connection> {
+	id: Uuid,
+	txn: Surreal<C>,
 	client: Surreal<C>,
 }
 
@@ -43,10 +67,16 @@ impl<C> Transaction<C>
 where
 	C: Connection,
 {
+	/// The id of the transaction on the server
+	pub fn id(&self) -> Uuid {
+		self.id
+	}
+
 	/// Creates a commit future
 	pub fn commit(self) -> Commit<C> {
 		Commit {
 			client: self.client,
+			txn: self.txn,
 		}
 	}
 
@@ -54,6 +84,7 @@ where
 	pub fn cancel(self) -> Cancel<C> {
 		Cancel {
 			client: self.client,
+			txn: self.txn,
 		}
 	}
 }
@@ -65,6 +96,6 @@ where
 	type Target = Surreal<C>;
 
 	fn deref(&self) -> &Self::Target {
-		&self.client
+		&self.txn
 	}
 }
@@ -1,15 +1,17 @@
+use crate::api::conn::Command;
 use crate::api::method::BoxFuture;
+use crate::api::method::OnceLockExt;
 use crate::api::Connection;
 use crate::api::Result;
 use crate::api::Surreal;
 use std::future::IntoFuture;
-use surrealdb_core::sql::statements::CommitStatement;
 
 /// A transaction commit future
 #[derive(Debug)]
 #[must_use = "futures do nothing unless you `.await` or poll them"]
 pub struct Commit<C: Connection> {
 	pub(crate) client: Surreal<C>,
+	pub(crate) txn: Surreal<C>,
 }
 
 impl<C> IntoFuture for Commit<C>
@@ -21,7 +23,8 @@ where
 
 	fn into_future(self) -> Self::IntoFuture {
 		Box::pin(async move {
-			self.client.query(CommitStatement::default()).await?;
+			let router = self.txn.inner.router.extract()?;
+			router.execute_unit(Command::Commit).await?;
 			Ok(self.client)
 		})
 	}
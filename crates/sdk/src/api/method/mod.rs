@@ -1314,6 +1314,32 @@ where
 		}
 	}
 
+	/// Starts an interactive transaction
+	///
+	/// Every request made through the returned [`Transaction`] runs within the
+	/// same server-side transaction, until it is committed or cancelled. A
+	/// transaction which is left idle is cancelled by the server.
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// # #[tokio::main]
+	/// # async fn main() -> surrealdb::Result<()> {
+	/// # let db = surrealdb::engine::any::connect("mem://").await?;
+	/// db.use_ns("namespace").use_db("database").await?;
+	/// let txn = db.transaction().await?;
+	/// txn.query("CREATE account:one SET balance = 100").await?;
+	/// txn.query("CREATE account:two SET balance = 0").await?;
+	/// txn.commit().await?;
+	/// # Ok(())
+	/// # }
+	/// ```
+	pub fn transaction(&self) -> Begin<C> {
+		Begin {
+			client: self.clone(),
+		}
+	}
+
 	/// Checks whether the server is healthy or not
 	///
 	/// # Examples
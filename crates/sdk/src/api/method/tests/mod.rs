@@ -170,6 +170,13 @@ async fn api() {
 
 	// run
 	let _: Option<User> = DB.run("foo").await.unwrap();
+
+	// transaction
+	let txn = DB.transaction().await.unwrap();
+	let _: Vec<User> = txn.select(USER).await.unwrap();
+	let _: Surreal<Client> = txn.commit().await.unwrap();
+	let txn = DB.transaction().await.unwrap();
+	let _: Surreal<Client> = txn.cancel().await.unwrap();
 }
 
 fn assert_send_sync(_: impl Send + Sync) {}
@@ -13,6 +13,7 @@ use crate::api::Surreal;
 use std::collections::HashSet;
 use std::marker::PhantomData;
 use std::sync::atomic::AtomicI64;
+use std::sync::Arc;
 use std::sync::OnceLock;
 use tokio::sync::watch;
 use url::Url;
@@ -58,7 +59,8 @@ impl conn::Sealed for Client {
 				features,
 				sender: route_tx,
 				config: address.config,
-				last_id: AtomicI64::new(0),
+				last_id: Arc::new(AtomicI64::new(0)),
+				txn: None,
 			};
 			server::mock(route_rx);
 			Ok((OnceLock::with_value(router), watch::channel(None)).into())
@@ -29,6 +29,8 @@ pub(super) fn mock(route_rx: Receiver<Route>) {
 					..
 				} => Ok(DbResponse::Other("c6c0e36c-e2cf-42cb-b2d5-75415249b261".to_owned().into())),
 				Command::Version => Ok(DbResponse::Other("1.0.0".into())),
+				Command::Begin => Ok(DbResponse::Other(CoreValue::Uuid(Default::default()))),
+				Command::Commit | Command::Cancel => Ok(DbResponse::Other(CoreValue::None)),
 				Command::Use {
 					..
 				} => Ok(DbResponse::Other(CoreValue::None)),
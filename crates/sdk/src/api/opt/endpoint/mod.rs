@@ -114,6 +114,66 @@ mod tests {
 	}
 }
 
+#[cfg(any(feature = "kv-rocksdb", feature = "kv-surrealkv"))]
+impl<R> crate::Connect<crate::api::engine::local::Db, R> {
+	/// RocksDB or SurrealKV database with versions enabled
+	///
+	/// # Examples
+	///
+	/// Instantiating a SurrealKV-backed instance with versions
+	///
+	/// ```no_run
+	/// # #[tokio::main]
+	/// # async fn main() -> surrealdb::Result<()> {
+	/// use surrealdb::Surreal;
+	/// use surrealdb::engine::local::SurrealKv;
+	///
+	/// let db = Surreal::new::<SurrealKv>("path/to/database-folder").versioned().await?;
+	/// # Ok(())
+	/// # }
+	/// ```
+	///
+	/// Instantiating a RocksDB-backed strict instance with versions
+	///
+	/// ```no_run
+	/// # #[tokio::main]
+	/// # async fn main() -> surrealdb::Result<()> {
+	/// use surrealdb::opt::Config;
+	/// use surrealdb::Surreal;
+	/// use surrealdb::engine::local::RocksDb;
+	///
+	/// let config = Config::default().strict();
+	/// let db = Surreal::new::<RocksDb>(("path/to/database-folder", config)).versioned().await?;
+	/// # Ok(())
+	/// # }
+	/// ```
+	pub fn versioned(mut self) -> Self {
+		let replace_scheme = |mut endpoint: Endpoint| -> Result<Endpoint> {
+			let scheme = match endpoint.url.scheme() {
+				// If the engine is unversioned, we want to switch it to a versioned one
+				"surrealkv" => "surrealkv+versioned",
+				"rocksdb" => "rocksdb+versioned",
+				// The engine is already versioned, nothing to do here
+				"surrealkv+versioned" | "rocksdb+versioned" => return Ok(endpoint),
+				// This engine doesn't support versions
+				scheme => return Err(Error::VersionsNotSupported(scheme.to_owned()).into()),
+			};
+			// Replace the scheme in the URL
+			endpoint
+				.url
+				.set_scheme(scheme)
+				.unwrap_or_else(|_| unreachable!("`{scheme}` should be static and valid"));
+			// and in the path
+			if let Some((_, rest)) = endpoint.path.split_once(':') {
+				endpoint.path = format!("{scheme}:{rest}");
+			}
+			Ok(endpoint)
+		};
+		self.address = self.address.and_then(replace_scheme);
+		self
+	}
+}
+
 #[derive(Debug)]
 #[doc(hidden)]
 pub enum EndpointKind {
@@ -126,6 +186,7 @@ pub enum EndpointKind {
 	IndxDb,
 	Memory,
 	RocksDb,
+	RocksDbVersioned,
 	File,
 	TiKv,
 	Unsupported(String),
@@ -146,6 +207,7 @@ impl From<&str> for EndpointKind {
 			"mem" => Self::Memory,
 			"file" => Self::File,
 			"rocksdb" => Self::RocksDb,
+			"rocksdb+versioned" => Self::RocksDbVersioned,
 			"tikv" => Self::TiKv,
 			"surrealkv" => Self::SurrealKv,
 			"surrealkv+versioned" => Self::SurrealKvVersioned,
@@ -1604,6 +1604,42 @@ pub async fn return_bool(new_db: impl CreateDb) {
 	assert_eq!(value.into_inner(), CoreValue::Bool(false));
 }
 
+pub async fn transaction_commit(new_db: impl CreateDb) {
+	let (permit, db) = new_db.create_db().await;
+	db.use_ns(NS).use_db(Ulid::new().to_string()).await.unwrap();
+	drop(permit);
+	let txn = db.transaction().await.unwrap();
+	let _: Option<ApiRecordId> = txn.create(("user", "john")).await.unwrap();
+	txn.query("CREATE user:jane").await.unwrap().check().unwrap();
+	// The changes are not visible outside of the transaction
+	let users: Vec<ApiRecordId> = db.select("user").await.unwrap();
+	assert!(users.is_empty());
+	let users: Vec<ApiRecordId> = txn.select("user").await.unwrap();
+	assert_eq!(users.len(), 2);
+	let db = txn.commit().await.unwrap();
+	let users: Vec<ApiRecordId> = db.select("user").await.unwrap();
+	assert_eq!(users.len(), 2);
+}
+
+pub async fn transaction_cancel(new_db: impl CreateDb) {
+	let (permit, db) = new_db.create_db().await;
+	db.use_ns(NS).use_db(Ulid::new().to_string()).await.unwrap();
+	drop(permit);
+	let txn = db.transaction().await.unwrap();
+	txn.query("CREATE user:john").await.unwrap().check().unwrap();
+	let db = txn.cancel().await.unwrap();
+	let users: Vec<ApiRecordId> = db.select("user").await.unwrap();
+	assert!(users.is_empty());
+	// A failed statement rolls back the whole transaction
+	let txn = db.transaction().await.unwrap();
+	txn.query("CREATE user:john").await.unwrap().check().unwrap();
+	txn.query("THROW 'failed'").await.unwrap().check().unwrap_err();
+	txn.query("CREATE user:jane").await.unwrap_err();
+	txn.commit().await.unwrap_err();
+	let users: Vec<ApiRecordId> = db.select("user").await.unwrap();
+	assert!(users.is_empty());
+}
+
 pub async fn run(new_db: impl CreateDb) {
 	let (permit, db) = new_db.create_db().await;
 	db.use_ns(NS).use_db(Ulid::new().to_string()).await.unwrap();
@@ -1786,6 +1822,10 @@ define_include_tests!(basic => {
 	#[test_log::test(tokio::test)]
 	return_bool,
 	#[test_log::test(tokio::test)]
+	transaction_commit,
+	#[test_log::test(tokio::test)]
+	transaction_cancel,
+	#[test_log::test(tokio::test)]
 	run,
 	#[test_log::test(tokio::test)]
 	multi_take,
@@ -399,6 +399,58 @@ mod rocksdb {
 	include_tests!(new_db => basic, serialisation, live, backup);
 }
 
+#[cfg(feature = "kv-rocksdb")]
+mod rocksdb_versioned {
+	use surrealdb::engine::local::Db;
+	use surrealdb::engine::local::RocksDb;
+	use surrealdb::opt::capabilities::Capabilities;
+
+	use surrealdb::opt::auth::Root;
+	use surrealdb::opt::Config;
+	use surrealdb::Surreal;
+	use tokio::sync::Semaphore;
+	use tokio::sync::SemaphorePermit;
+	use ulid::Ulid;
+
+	use super::{ROOT_PASS, ROOT_USER, TEMP_DIR};
+
+	static PERMITS: Semaphore = Semaphore::const_new(1);
+
+	async fn new_db() -> (SemaphorePermit<'static>, Surreal<Db>) {
+		let permit = PERMITS.acquire().await.unwrap();
+		let path = TEMP_DIR.join(Ulid::new().to_string());
+		let root = Root {
+			username: ROOT_USER,
+			password: ROOT_PASS,
+		};
+		let config = Config::new().user(root).capabilities(Capabilities::all());
+		let db = Surreal::new::<RocksDb>((path, config)).versioned().await.unwrap();
+		db.signin(root).await.unwrap();
+		(permit, db)
+	}
+
+	#[test_log::test(tokio::test)]
+	async fn any_engine_can_connect() {
+		let db_dir = Ulid::new().to_string();
+		// Create a database directory using an absolute path
+		surrealdb::engine::any::connect(format!(
+			"rocksdb+versioned://{}",
+			TEMP_DIR.join("absolute").join(&db_dir).display()
+		))
+		.await
+		.unwrap();
+		// Switch to the temporary directory, if possible, to test relative paths
+		if std::env::set_current_dir(&*TEMP_DIR).is_ok() {
+			// Create a database directory using a relative path
+			surrealdb::engine::any::connect(format!("rocksdb+versioned://relative/{db_dir}"))
+				.await
+				.unwrap();
+		}
+	}
+
+	include_tests!(new_db => basic, serialisation, version, live, backup, backup_version);
+}
+
 #[cfg(feature = "kv-tikv")]
 mod tikv {
 	use surrealdb::engine::local::Db;
@@ -572,6 +624,15 @@ mod surrealkv_versioned {
 		}
 	}
 
+	#[test_log::test(tokio::test)]
+	async fn version_retention_is_rejected() {
+		let (_permit, db) = new_db().await;
+		db.use_ns(super::NS).use_db(Ulid::new().to_string()).await.unwrap();
+		// SurrealKV can not remove old versions, so a retention policy could never be enforced
+		let res = db.query("DEFINE TABLE person VERSIONS RETAIN 2").await.unwrap().check();
+		assert!(res.is_err(), "{res:?}");
+	}
+
 	include_tests!(new_db => basic, serialisation, version, live, backup, backup_version);
 }
 
@@ -1,6 +1,6 @@
-#![cfg(feature = "kv-surrealkv")]
+#![cfg(any(feature = "kv-rocksdb", feature = "kv-surrealkv"))]
 
-use surrealdb::Value;
+use surrealdb::{error::Db as DbError, Error, Value};
 use ulid::Ulid;
 
 use crate::api_integration::NS;
@@ -212,6 +212,34 @@ pub async fn info_for_table_with_versioned_fields(new_db: impl CreateDb) {
 	));
 }
 
+pub async fn info_for_table_with_version_history(new_db: impl CreateDb) {
+	let (permit, db) = new_db.create_db().await;
+	db.use_ns(NS).use_db(Ulid::new().to_string()).await.unwrap();
+	drop(permit);
+
+	// Create a table which keeps the latest two versions of each record.
+	// Engines which keep every version, but can not remove them, reject it.
+	match db.query("DEFINE TABLE person VERSIONS RETAIN 2").await.unwrap().check() {
+		Ok(_) => {}
+		Err(Error::Db(DbError::UnsupportedVersionRetention)) => return,
+		Err(e) => panic!("{e}"),
+	}
+	let _ = db.query("CREATE person:john SET name = 'John v1'").await.unwrap().check().unwrap();
+	let _ = db.query("UPDATE person:john SET name = 'John v2'").await.unwrap().check().unwrap();
+	let _ = db.query("CREATE person:jane SET name = 'Jane v1'").await.unwrap().check().unwrap();
+
+	// The retention policy is shown in the table definition.
+	let mut response = db.query("INFO FOR DB").await.unwrap().check().unwrap();
+	let info = response.take::<Value>(0).unwrap().to_string();
+	assert!(info.contains("DEFINE TABLE person TYPE ANY SCHEMALESS VERSIONS RETAIN 2"));
+
+	// The version history of the records is shown for the table.
+	let mut response = db.query("INFO FOR TABLE person").await.unwrap().check().unwrap();
+	let info = response.take::<Value>(0).unwrap().to_string();
+	assert!(info.contains("history: { oldest: d'"));
+	assert!(info.contains("records: 2, versions: 3 }"));
+}
+
 define_include_tests!(version => {
 	#[test_log::test(tokio::test)]
 	select_with_version,
@@ -223,4 +251,6 @@ define_include_tests!(version => {
 	info_for_db_with_versioned_tables,
 	#[test_log::test(tokio::test)]
 	info_for_table_with_versioned_fields,
+	#[test_log::test(tokio::test)]
+	info_for_table_with_version_history,
 });
@@ -9,7 +9,7 @@ use surrealdb::err::Error;
 use surrealdb::kvs::Datastore;
 use surrealdb::kvs::LockType::Optimistic;
 use surrealdb::kvs::TransactionType::Write;
-use surrealdb::sql::Value;
+use surrealdb::sql::{Part, Value};
 use surrealdb_core::vs::VersionStamp;
 
 mod helpers;
@@ -575,3 +575,279 @@ async fn changefeed_with_ts() -> Result<(), Error> {
 	assert_eq!(array.len(), 0);
 	Ok(())
 }
+
+#[tokio::test]
+async fn changefeed_consumers() -> Result<(), Error> {
+	let db = new_ds().await?;
+	let ses = Session::owner().with_ns("test-cf-consumer").with_db("test-cf-consumer");
+	let now = chrono::Utc::now().timestamp() as u64;
+	// Enable change feeds, and define a consumer after some changes
+	let sql = "
+		DEFINE TABLE user CHANGEFEED 1h;
+		DEFINE TABLE other CHANGEFEED 1h;
+		CREATE user:before;
+		DEFINE CONSUMER reader ON TABLE user;
+		CREATE user:amos;
+		CREATE other:one;
+		CREATE user:jane;
+	";
+	for res in db.execute(sql, &ses, None).await? {
+		res.result?;
+	}
+	// The consumer only sees changes to its table after it was defined
+	let sql = "SHOW CHANGES FOR CONSUMER reader LIMIT 10";
+	let value = db.execute(sql, &ses, None).await?.remove(0).result?;
+	let changes = value.pick(&[Part::from("changes")]).to_string();
+	assert_eq!(changes, "[[{ update: { id: user:amos } }], [{ update: { id: user:jane } }]]");
+	// The changes are read again until the consumer acknowledges them
+	let value = db.execute(sql, &ses, None).await?.remove(0).result?;
+	let changes = value.pick(&[Part::from("changes")]).to_string();
+	assert_eq!(changes, "[[{ update: { id: user:amos } }], [{ update: { id: user:jane } }]]");
+	// Reading since the change after the last processed one acknowledges the earlier changes
+	let Value::Number(vs) = value.pick(&[Part::Index(1.into()), Part::from("versionstamp")]) else {
+		panic!("Expected a versionstamp: {value}");
+	};
+	let next = vs.as_int() + 1;
+	let sql = format!("SHOW CHANGES FOR CONSUMER reader SINCE {next} LIMIT 10");
+	let value = db.execute(&sql, &ses, None).await?.remove(0).result?;
+	assert_eq!(value, Value::parse("[]"));
+	let sql = "UPDATE user:amos SET name = 'Amos'; CREATE user:tobie;";
+	for res in db.execute(sql, &ses, None).await? {
+		res.result?;
+	}
+	let sql = "SHOW CHANGES FOR CONSUMER reader LIMIT 1";
+	let value = db.execute(sql, &ses, None).await?.remove(0).result?;
+	let changes = value.pick(&[Part::from("changes")]).to_string();
+	assert_eq!(changes, "[[{ update: { id: user:amos, name: 'Amos' } }]]");
+	let Value::Number(vs) = value.pick(&[Part::Index(0.into()), Part::from("versionstamp")]) else {
+		panic!("Expected a versionstamp: {value}");
+	};
+	let sql = format!("SHOW CHANGES FOR CONSUMER reader SINCE {} LIMIT 1", vs.as_int() + 1);
+	db.execute(&sql, &ses, None).await?.remove(0).result?;
+	// Changes which the consumer has not acknowledged are not garbage collected
+	db.changefeed_process_at(now - 3600).await?;
+	db.changefeed_process_at(now).await?;
+	let sql = "SHOW CHANGES FOR TABLE user SINCE 0";
+	let value = db.execute(sql, &ses, None).await?.remove(0).result?;
+	let changes = value.pick(&[Part::from("changes")]).to_string();
+	assert_eq!(changes, "[[{ update: { id: user:tobie } }]]");
+	let sql = "SHOW CHANGES FOR CONSUMER reader";
+	let value = db.execute(sql, &ses, None).await?.remove(0).result?;
+	let changes = value.pick(&[Part::from("changes")]).to_string();
+	assert_eq!(changes, "[[{ update: { id: user:tobie } }]]");
+	// Once the consumer is removed, its changes are no longer retained
+	let sql = "CREATE user:jaime; REMOVE CONSUMER reader;";
+	for res in db.execute(sql, &ses, None).await? {
+		res.result?;
+	}
+	db.changefeed_process_at(now + 1).await?;
+	let sql = "SHOW CHANGES FOR TABLE user SINCE 0";
+	let value = db.execute(sql, &ses, None).await?.remove(0).result?;
+	let changes = value.pick(&[Part::from("changes")]).to_string();
+	assert_eq!(changes, "[[{ update: { id: user:jaime } }]]");
+	let sql = "SHOW CHANGES FOR CONSUMER reader";
+	let res = db.execute(sql, &ses, None).await?.remove(0).result;
+	assert!(matches!(res, Err(Error::CnNotFound { .. })), "{res:?}");
+	Ok(())
+}
+
+#[tokio::test]
+async fn changefeed_consumers_skip_other_tables() -> Result<(), Error> {
+	let db = new_ds().await?;
+	let ses = Session::owner().with_ns("test-cf-consumer-skip").with_db("test-cf-consumer-skip");
+	// Make more changes to another table than are read in a single batch
+	let sql = "
+		DEFINE TABLE user CHANGEFEED 1h;
+		DEFINE TABLE other CHANGEFEED 1h;
+		DEFINE CONSUMER reader ON TABLE user;
+		CREATE other:1;
+		CREATE other:2;
+		CREATE other:3;
+		CREATE other:4;
+		CREATE other:5;
+		CREATE user:amos;
+	";
+	for res in db.execute(sql, &ses, None).await? {
+		res.result?;
+	}
+	// A batch which only contains changes to other tables is empty
+	let sql = "SHOW CHANGES FOR CONSUMER reader LIMIT 2";
+	let value = db.execute(sql, &ses, None).await?.remove(0).result?;
+	assert_eq!(value, Value::parse("[]"));
+	// The consumer moves past the changes to other tables, without acknowledging anything
+	let mut changes = String::new();
+	for _ in 0..10 {
+		let value = db.execute(sql, &ses, None).await?.remove(0).result?;
+		changes = value.pick(&[Part::from("changes")]).to_string();
+		if changes != "[]" {
+			break;
+		}
+	}
+	assert_eq!(changes, "[[{ update: { id: user:amos } }]]");
+	Ok(())
+}
+
+#[cfg(feature = "http")]
+#[tokio::test]
+async fn changefeed_sinks() -> Result<(), Error> {
+	use wiremock::{
+		matchers::{method, path},
+		Mock, ResponseTemplate,
+	};
+
+	let server = wiremock::MockServer::start().await;
+	let db = new_ds().await?;
+	let ses = Session::owner().with_ns("test-cf-sink").with_db("test-cf-sink");
+	// Enable change feeds, and define a sink after some changes
+	let sql = format!(
+		"
+		DEFINE TABLE user CHANGEFEED 1h;
+		DEFINE TABLE other CHANGEFEED 1h;
+		CREATE user:before;
+		DEFINE SINK hook ON TABLE user URL '{}/hook' RETRY 0;
+		CREATE user:amos;
+		CREATE other:one;
+		CREATE user:jane;
+		",
+		server.uri()
+	);
+	for res in db.execute(&sql, &ses, None).await? {
+		res.result?;
+	}
+	// The sink only delivers changes to its table after it was defined
+	Mock::given(method("POST"))
+		.and(path("/hook"))
+		.respond_with(ResponseTemplate::new(200))
+		.mount(&server)
+		.await;
+	db.sink_process().await?;
+	let requests = server.received_requests().await.unwrap();
+	assert_eq!(requests.len(), 1);
+	let body: serde_json::Value = requests[0].body_json().unwrap();
+	let ids: Vec<_> = body
+		.as_array()
+		.unwrap()
+		.iter()
+		.map(|c| c["changes"][0]["update"]["id"].as_str().unwrap().to_owned())
+		.collect();
+	assert_eq!(ids, ["user:amos", "user:jane"]);
+	// Changes which are not accepted by the endpoint are delivered again
+	server.reset().await;
+	Mock::given(method("POST"))
+		.and(path("/hook"))
+		.respond_with(ResponseTemplate::new(500))
+		.mount(&server)
+		.await;
+	let sql = "CREATE user:tobie";
+	db.execute(sql, &ses, None).await?.remove(0).result?;
+	db.sink_process().await?;
+	assert_eq!(server.received_requests().await.unwrap().len(), 1);
+	server.reset().await;
+	Mock::given(method("POST"))
+		.and(path("/hook"))
+		.respond_with(ResponseTemplate::new(200))
+		.mount(&server)
+		.await;
+	db.sink_process().await?;
+	let requests = server.received_requests().await.unwrap();
+	assert_eq!(requests.len(), 1);
+	let body: serde_json::Value = requests[0].body_json().unwrap();
+	assert_eq!(body[0]["changes"][0]["update"]["id"], "user:tobie");
+	// Once all changes have been delivered, nothing more is sent
+	server.reset().await;
+	db.sink_process().await?;
+	assert_eq!(server.received_requests().await.unwrap().len(), 0);
+	// A transaction with more changes than the batch size is delivered entirely
+	Mock::given(method("POST"))
+		.and(path("/small"))
+		.respond_with(ResponseTemplate::new(200))
+		.mount(&server)
+		.await;
+	let sql = format!(
+		"
+		DEFINE SINK small ON TABLE user URL '{}/small' BATCH 1 RETRY 0;
+		BEGIN;
+		CREATE user:one;
+		CREATE other:two;
+		CREATE user:three;
+		COMMIT;
+		",
+		server.uri()
+	);
+	for res in db.execute(&sql, &ses, None).await? {
+		res.result?;
+	}
+	db.sink_process().await?;
+	let mut ids = Vec::new();
+	for req in server.received_requests().await.unwrap() {
+		if req.url.path() != "/small" {
+			continue;
+		}
+		let body: serde_json::Value = req.body_json().unwrap();
+		for change in body.as_array().unwrap() {
+			for m in change["changes"].as_array().unwrap() {
+				ids.push(m["update"]["id"].as_str().unwrap().to_owned());
+			}
+		}
+	}
+	assert_eq!(ids, ["user:one", "user:three"]);
+	// A sink can not deliver to a network target which is not allowed
+	let db = Datastore::new("memory").await?;
+	let sql = "DEFINE SINK hook ON TABLE user URL 'http://127.0.0.1/hook'";
+	let res = db.execute(sql, &ses, None).await?.remove(0).result;
+	assert!(matches!(res, Err(Error::NetTargetNotAllowed(_))), "{res:?}");
+	Ok(())
+}
+
+#[cfg(feature = "http")]
+#[tokio::test]
+async fn changefeed_sinks_stalled() -> Result<(), Error> {
+	use wiremock::{
+		matchers::{method, path},
+		Mock, ResponseTemplate,
+	};
+
+	let server = wiremock::MockServer::start().await;
+	let db = new_ds().await?;
+	let ses = Session::owner().with_ns("test-cf-sink-stalled").with_db("test-cf-sink-stalled");
+	let now = chrono::Utc::now().timestamp() as u64;
+	// Define a sink whose endpoint does not accept any changes
+	let sql = format!(
+		"
+		DEFINE TABLE user CHANGEFEED 1h;
+		DEFINE SINK hook ON TABLE user URL '{}/hook' RETRY 0;
+		CREATE user:amos;
+		",
+		server.uri()
+	);
+	for res in db.execute(&sql, &ses, None).await? {
+		res.result?;
+	}
+	Mock::given(method("POST"))
+		.and(path("/hook"))
+		.respond_with(ResponseTemplate::new(500))
+		.mount(&server)
+		.await;
+	db.sink_process().await?;
+	// Changes which the sink has not yet delivered are not garbage collected
+	db.changefeed_process_at(now - 3600).await?;
+	db.changefeed_process_at(now).await?;
+	let sql = "SHOW CHANGES FOR TABLE user SINCE 0";
+	let value = db.execute(sql, &ses, None).await?.remove(0).result?;
+	let changes = value.pick(&[Part::from("changes")]).to_string();
+	assert_eq!(changes, "[[{ update: { id: user:amos } }]]");
+	// They are kept even once the sink has failed for longer than the changefeed expiry
+	db.sink_process().await?;
+	db.changefeed_process_at(now + 7200).await?;
+	let value = db.execute(sql, &ses, None).await?.remove(0).result?;
+	let changes = value.pick(&[Part::from("changes")]).to_string();
+	assert_eq!(changes, "[[{ update: { id: user:amos } }]]");
+	// Resetting the sink allows the undelivered changes to be collected
+	let sql = format!("DEFINE SINK OVERWRITE hook ON TABLE user URL '{}/hook'", server.uri());
+	db.execute(&sql, &ses, None).await?.remove(0).result?;
+	db.changefeed_process_at(now + 7200).await?;
+	let sql = "SHOW CHANGES FOR TABLE user SINCE 0";
+	let value = db.execute(sql, &ses, None).await?.remove(0).result?;
+	assert_eq!(value, Value::parse("[]"));
+	Ok(())
+}
@@ -349,6 +349,7 @@ async fn define_statement_analyzer() -> Result<(), Error> {
 			apis: {},
 			buckets: {},
 			configs: {},
+			consumers: {},
 			functions: {
 				stripHtml: "DEFINE FUNCTION fn::stripHtml($html: string) { RETURN string::replace($html, /<[^>]*>/, ''); } PERMISSIONS FULL"
 			},
@@ -356,12 +357,49 @@ async fn define_statement_analyzer() -> Result<(), Error> {
 			params: {},
 			tables: {},
 			sequences: {},
+			sinks: {},
 			users: {},
 		}"#,
 	)?;
 	Ok(())
 }
 
+#[tokio::test]
+async fn define_statement_analyzer_stopwords_synonyms() -> Result<(), Error> {
+	let synonyms = concat!(env!("CARGO_MANIFEST_DIR"), "/../../tests/data/synonyms-en.txt");
+	let sql = format!(
+		r#"
+		DEFINE ANALYZER englishSearch TOKENIZERS blank,class FILTERS lowercase,stopwords(english),synonyms([['tv', 'television'], ['car', 'auto']]);
+		DEFINE ANALYZER englishSynonyms TOKENIZERS blank FILTERS stopwords(['a', 'the']),synonyms('{synonyms}');
+		INFO FOR DB;
+	"#
+	);
+	let mut t = Test::new(&sql).await?;
+	t.expect_size(3)?;
+	t.skip_ok(2)?;
+	t.expect_val(&format!(
+		r#"{{
+			accesses: {{}},
+			analyzers: {{
+				englishSearch: "DEFINE ANALYZER englishSearch TOKENIZERS BLANK,CLASS FILTERS LOWERCASE,STOPWORDS(ENGLISH),SYNONYMS([['tv', 'television'], ['car', 'auto']])",
+				englishSynonyms: "DEFINE ANALYZER englishSynonyms TOKENIZERS BLANK FILTERS STOPWORDS(['a', 'the']),SYNONYMS('{synonyms}')"
+			}},
+			apis: {{}},
+			buckets: {{}},
+			configs: {{}},
+			consumers: {{}},
+			functions: {{}},
+			models: {{}},
+			params: {{}},
+			tables: {{}},
+			sequences: {{}},
+			sinks: {{}},
+			users: {{}},
+		}}"#
+	))?;
+	Ok(())
+}
+
 #[tokio::test]
 async fn define_statement_search_index() -> Result<(), Error> {
 	let sql = r#"
@@ -425,6 +463,96 @@ async fn define_statement_search_index() -> Result<(), Error> {
 	Ok(())
 }
 
+#[tokio::test]
+async fn define_statement_index_statistics() -> Result<(), Error> {
+	let sql = r#"
+		CREATE user:1 SET age = 20;
+		CREATE user:2 SET age = 10;
+		CREATE user:3 SET age = 20;
+		CREATE user:4 SET age = NULL;
+		CREATE user:5 SET age = 30, email = 'tobie@surrealdb.com';
+		DEFINE INDEX age ON user FIELDS age;
+		DEFINE INDEX email ON user FIELDS email UNIQUE;
+		ANALYZE INDEX age ON user;
+		ANALYZE INDEX email ON user;
+		INFO FOR INDEX email ON user;
+	"#;
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 10);
+	//
+	for i in 0..7 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok(), "{}", i);
+	}
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"{
+			distinct: 4,
+			entries: 5,
+			histogram: [
+				{ count: 1, distinct: 1, max: [NULL], min: [NULL] },
+				{ count: 1, distinct: 1, max: [10], min: [10] },
+				{ count: 2, distinct: 1, max: [20], min: [20] },
+				{ count: 1, distinct: 1, max: [30], min: [30] }
+			],
+			nulls: 1,
+			records: 5
+		}",
+	);
+	assert_eq!(format!("{:#}", tmp), format!("{:#}", val));
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"{
+			distinct: 1,
+			entries: 1,
+			histogram: [
+				{ count: 1, distinct: 1, max: ['tobie@surrealdb.com'], min: ['tobie@surrealdb.com'] }
+			],
+			nulls: 0,
+			records: 5
+		}",
+	);
+	assert_eq!(format!("{:#}", tmp), format!("{:#}", val));
+	//
+	let tmp = res.remove(0).result?;
+	check_path(&tmp, &["statistics", "entries"], |v| assert_eq!(v, Value::from(1)));
+	check_path(&tmp, &["statistics", "distinct"], |v| assert_eq!(v, Value::from(1)));
+	Ok(())
+}
+
+#[tokio::test]
+async fn define_statement_hnsw_index_statistics() -> Result<(), Error> {
+	let sql = r#"
+		CREATE pts:1 SET point = [1, 2];
+		CREATE pts:2 SET point = [3, 4];
+		CREATE pts:3 SET point = [5, 6];
+		DEFINE INDEX hnsw_pts ON pts FIELDS point HNSW DIMENSION 2;
+		ANALYZE INDEX hnsw_pts ON pts;
+	"#;
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 5);
+	//
+	for i in 0..4 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok(), "{}", i);
+	}
+	//
+	let tmp = res.remove(0).result?;
+	check_path(&tmp, &["elements"], |v| assert_eq!(v, Value::from(3)));
+	check_path(&tmp, &["next_element_id"], |v| assert_eq!(v, Value::from(3)));
+	check_path(&tmp, &["layers"], |v| {
+		let layer0 = Value::parse("{ edges: 6, level: 0, nodes: 3 }");
+		assert_eq!(format!("{:#}", v.first()), format!("{:#}", layer0));
+	});
+	Ok(())
+}
+
 #[tokio::test]
 async fn define_statement_user_root() -> Result<(), Error> {
 	let sql = "
@@ -679,8 +807,8 @@ async fn permissions_checks_define_function() {
 
 	// Define the expected results for the check statement when the test statement succeeded and when it failed
 	let check_results = [
-        vec!["{ accesses: {  }, analyzers: {  }, apis: {  }, buckets: {  }, configs: {  }, functions: { greet: \"DEFINE FUNCTION fn::greet() { RETURN 'Hello'; } PERMISSIONS FULL\" }, models: {  }, params: {  }, sequences: { }, tables: {  }, users: {  } }"],
-		vec!["{ accesses: {  }, analyzers: {  }, apis: {  }, buckets: {  }, configs: {  }, functions: {  }, models: {  }, params: {  }, sequences: { }, tables: {  }, users: {  } }"]
+        vec!["{ accesses: {  }, analyzers: {  }, apis: {  }, buckets: {  }, configs: {  }, consumers: {  }, functions: { greet: \"DEFINE FUNCTION fn::greet() { RETURN 'Hello'; } PERMISSIONS FULL\" }, models: {  }, params: {  }, sequences: { }, sinks: {  }, tables: {  }, users: {  } }"],
+		vec!["{ accesses: {  }, analyzers: {  }, apis: {  }, buckets: {  }, configs: {  }, consumers: {  }, functions: {  }, models: {  }, params: {  }, sequences: { }, sinks: {  }, tables: {  }, users: {  } }"]
     ];
 
 	let test_cases = [
@@ -721,8 +849,8 @@ async fn permissions_checks_define_analyzer() {
 
 	// Define the expected results for the check statement when the test statement succeeded and when it failed
 	let check_results = [
-        vec!["{ accesses: {  }, analyzers: { analyzer: 'DEFINE ANALYZER analyzer TOKENIZERS BLANK' }, apis: {  }, buckets: {  }, configs: {  }, functions: {  }, models: {  }, params: {  }, tables: {  }, sequences: { }, users: {  } }"],
-		vec!["{ accesses: {  }, analyzers: {  }, apis: {  }, buckets: {  }, configs: {  }, functions: {  }, models: {  }, params: {  }, sequences: {}, tables: {  }, users: {  } }"]
+        vec!["{ accesses: {  }, analyzers: { analyzer: 'DEFINE ANALYZER analyzer TOKENIZERS BLANK' }, apis: {  }, buckets: {  }, configs: {  }, consumers: {  }, functions: {  }, models: {  }, params: {  }, tables: {  }, sequences: { }, sinks: {  }, users: {  } }"],
+		vec!["{ accesses: {  }, analyzers: {  }, apis: {  }, buckets: {  }, configs: {  }, consumers: {  }, functions: {  }, models: {  }, params: {  }, sequences: {}, sinks: {  }, tables: {  }, users: {  } }"]
     ];
 
 	let test_cases = [
@@ -846,8 +974,8 @@ async fn permissions_checks_define_access_db() {
 
 	// Define the expected results for the check statement when the test statement succeeded and when it failed
 	let check_results = [
-        vec!["{ accesses: { access: \"DEFINE ACCESS access ON DATABASE TYPE JWT ALGORITHM HS512 KEY '[REDACTED]' WITH ISSUER KEY '[REDACTED]' DURATION FOR TOKEN 1h, FOR SESSION NONE\" }, analyzers: {  }, apis: {  }, buckets: {  }, configs: {  }, functions: {  }, models: {  }, params: {  }, sequences: { }, tables: {  }, users: {  } }"],
-		vec!["{ accesses: {  }, analyzers: {  }, apis: {  }, buckets: {  }, configs: {  }, functions: {  }, models: {  }, params: {  }, sequences: { }, tables: {  }, users: {  } }"]
+        vec!["{ accesses: { access: \"DEFINE ACCESS access ON DATABASE TYPE JWT ALGORITHM HS512 KEY '[REDACTED]' WITH ISSUER KEY '[REDACTED]' DURATION FOR TOKEN 1h, FOR SESSION NONE\" }, analyzers: {  }, apis: {  }, buckets: {  }, configs: {  }, consumers: {  }, functions: {  }, models: {  }, params: {  }, sequences: { }, sinks: {  }, tables: {  }, users: {  } }"],
+		vec!["{ accesses: {  }, analyzers: {  }, apis: {  }, buckets: {  }, configs: {  }, consumers: {  }, functions: {  }, models: {  }, params: {  }, sequences: { }, sinks: {  }, tables: {  }, users: {  } }"]
     ];
 
 	let test_cases = [
@@ -971,8 +1099,8 @@ async fn permissions_checks_define_user_db() {
 
 	// Define the expected results for the check statement when the test statement succeeded and when it failed
 	let check_results = [
-        vec!["{ accesses: {  }, analyzers: {  }, apis: {  }, buckets: {  }, configs: {  }, functions: {  }, models: {  }, params: {  }, sequences: { }, tables: {  }, users: { user: \"DEFINE USER user ON DATABASE PASSHASH 'secret' ROLES VIEWER DURATION FOR TOKEN 15m, FOR SESSION 6h\" } }"],
-        vec!["{ accesses: {  }, analyzers: {  }, apis: {  }, buckets: {  }, configs: {  }, functions: {  }, models: {  }, params: {  }, sequences: { }, tables: {  }, users: {  } }"]
+        vec!["{ accesses: {  }, analyzers: {  }, apis: {  }, buckets: {  }, configs: {  }, consumers: {  }, functions: {  }, models: {  }, params: {  }, sequences: { }, sinks: {  }, tables: {  }, users: { user: \"DEFINE USER user ON DATABASE PASSHASH 'secret' ROLES VIEWER DURATION FOR TOKEN 15m, FOR SESSION 6h\" } }"],
+        vec!["{ accesses: {  }, analyzers: {  }, apis: {  }, buckets: {  }, configs: {  }, consumers: {  }, functions: {  }, models: {  }, params: {  }, sequences: { }, sinks: {  }, tables: {  }, users: {  } }"]
     ];
 
 	let test_cases = [
@@ -1013,8 +1141,8 @@ async fn permissions_checks_define_access_record() {
 
 	// Define the expected results for the check statement when the test statement succeeded and when it failed
 	let check_results = [
-        vec!["{ accesses: { account: \"DEFINE ACCESS account ON DATABASE TYPE RECORD WITH JWT ALGORITHM HS512 KEY '[REDACTED]' WITH ISSUER KEY '[REDACTED]' DURATION FOR TOKEN 15m, FOR SESSION 12h\" }, analyzers: {  }, apis: {  }, buckets: {  }, configs: {  }, functions: {  }, models: {  }, params: {  }, sequences: { }, tables: {  }, users: {  } }"],
-		vec!["{ accesses: {  }, analyzers: {  }, apis: {  }, buckets: {  }, configs: {  }, functions: {  }, models: {  }, params: {  }, sequences: { }, tables: {  }, users: {  } }"]
+        vec!["{ accesses: { account: \"DEFINE ACCESS account ON DATABASE TYPE RECORD WITH JWT ALGORITHM HS512 KEY '[REDACTED]' WITH ISSUER KEY '[REDACTED]' DURATION FOR TOKEN 15m, FOR SESSION 12h\" }, analyzers: {  }, apis: {  }, buckets: {  }, configs: {  }, consumers: {  }, functions: {  }, models: {  }, params: {  }, sequences: { }, sinks: {  }, tables: {  }, users: {  } }"],
+		vec!["{ accesses: {  }, analyzers: {  }, apis: {  }, buckets: {  }, configs: {  }, consumers: {  }, functions: {  }, models: {  }, params: {  }, sequences: { }, sinks: {  }, tables: {  }, users: {  } }"]
     ];
 
 	let test_cases = [
@@ -1055,8 +1183,8 @@ async fn permissions_checks_define_param() {
 
 	// Define the expected results for the check statement when the test statement succeeded and when it failed
 	let check_results = [
-        vec!["{ accesses: {  }, analyzers: {  }, apis: {  }, buckets: {  }, configs: {  }, functions: {  }, models: {  }, params: { param: \"DEFINE PARAM $param VALUE 'foo' PERMISSIONS FULL\" }, sequences: { }, tables: {  }, users: {  } }"],
-		vec!["{ accesses: {  }, analyzers: {  }, apis: {  }, buckets: {  }, configs: {  }, functions: {  }, models: {  }, params: {  }, sequences: { }, tables: {  }, users: {  } }"]
+        vec!["{ accesses: {  }, analyzers: {  }, apis: {  }, buckets: {  }, configs: {  }, consumers: {  }, functions: {  }, models: {  }, params: { param: \"DEFINE PARAM $param VALUE 'foo' PERMISSIONS FULL\" }, sequences: { }, sinks: {  }, tables: {  }, users: {  } }"],
+		vec!["{ accesses: {  }, analyzers: {  }, apis: {  }, buckets: {  }, configs: {  }, consumers: {  }, functions: {  }, models: {  }, params: {  }, sequences: { }, sinks: {  }, tables: {  }, users: {  } }"]
     ];
 
 	let test_cases = [
@@ -1094,8 +1222,8 @@ async fn permissions_checks_define_table() {
 
 	// Define the expected results for the check statement when the test statement succeeded and when it failed
 	let check_results = [
-        vec!["{ accesses: {  }, analyzers: {  }, apis: {  }, buckets: {  }, configs: {  }, functions: {  }, models: {  }, params: {  }, sequences: { }, tables: { TB: 'DEFINE TABLE TB TYPE ANY SCHEMALESS PERMISSIONS NONE' }, users: {  } }"],
-        vec!["{ accesses: {  }, analyzers: {  }, apis: {  }, buckets: {  }, configs: {  }, functions: {  }, models: {  }, params: {  }, sequences: { }, tables: {  }, users: {  } }"]
+        vec!["{ accesses: {  }, analyzers: {  }, apis: {  }, buckets: {  }, configs: {  }, consumers: {  }, functions: {  }, models: {  }, params: {  }, sequences: { }, sinks: {  }, tables: { TB: 'DEFINE TABLE TB TYPE ANY SCHEMALESS PERMISSIONS NONE' }, users: {  } }"],
+        vec!["{ accesses: {  }, analyzers: {  }, apis: {  }, buckets: {  }, configs: {  }, consumers: {  }, functions: {  }, models: {  }, params: {  }, sequences: { }, sinks: {  }, tables: {  }, users: {  } }"]
     ];
 
 	let test_cases = [
@@ -757,3 +757,298 @@ async fn select_where_matches_analyser_with_mapper() -> Result<(), Error> {
 	t.skip_ok(2)?;
 	Ok(())
 }
+
+#[tokio::test]
+async fn select_where_matches_query_syntax() -> Result<(), Error> {
+	let sql = r#"
+		DEFINE ANALYZER simple TOKENIZERS blank,class FILTERS lowercase;
+		DEFINE INDEX search_idx ON TABLE t COLUMNS text SEARCH ANALYZER simple BM25;
+		CREATE t:1 SET text = 'The quick brown fox jumps';
+		CREATE t:2 SET text = 'The brown quick dog';
+		CREATE t:3 SET text = 'A lazy fox sleeps';
+		CREATE t:4 SET text = 'Quickly running rabbits';
+		SELECT VALUE id FROM t WHERE text @@ '"quick brown"' ORDER BY id;
+		SELECT VALUE id FROM t WHERE text @@ '"brown quick"' ORDER BY id;
+		SELECT VALUE id FROM t WHERE text @@ 'fox OR dog' ORDER BY id;
+		SELECT VALUE id FROM t WHERE text @@ 'fox -lazy' ORDER BY id;
+		SELECT VALUE id FROM t WHERE text @@ 'quick*' ORDER BY id;
+		SELECT VALUE id FROM t WHERE text @@ '(fox OR rabbits) -"quick brown"' ORDER BY id;
+		SELECT VALUE id FROM t WHERE text @@ '-fox' ORDER BY id;
+		SELECT count() FROM t WHERE text @@ '"quick brown"' GROUP ALL;
+	"#;
+	let mut t = Test::new(sql).await?;
+	t.expect_size(14)?;
+	t.skip_ok(6)?;
+	t.expect_val("[t:1]")?;
+	t.expect_val("[t:2]")?;
+	t.expect_val("[t:1, t:2, t:3]")?;
+	t.expect_val("[t:1]")?;
+	t.expect_val("[t:1, t:2, t:4]")?;
+	t.expect_val("[t:3, t:4]")?;
+	t.expect_val("[]")?;
+	t.expect_val("[{ count: 1 }]")?;
+	Ok(())
+}
+
+#[tokio::test]
+async fn select_where_matches_proximity() -> Result<(), Error> {
+	let sql = r#"
+		DEFINE ANALYZER simple TOKENIZERS blank,class FILTERS lowercase;
+		DEFINE INDEX search_idx ON TABLE t COLUMNS text SEARCH ANALYZER simple BM25;
+		CREATE t:1 SET text = 'The quick brown fox jumps';
+		CREATE t:2 SET text = 'The brown quick dog';
+		SELECT VALUE id FROM t WHERE text @@ '"quick fox"' ORDER BY id;
+		SELECT VALUE id FROM t WHERE text @@ '"quick fox"~1' ORDER BY id;
+		SELECT VALUE id FROM t WHERE text @@ '"the dog"~1' ORDER BY id;
+		SELECT VALUE id FROM t WHERE text @@ '"the dog"~2' ORDER BY id;
+		SELECT VALUE id FROM t WHERE text @@ '"fox quick"~5' ORDER BY id;
+		UPDATE t:2 SET text = 'The quick brown cat';
+		SELECT VALUE id FROM t WHERE text @@ '"quick brown"' ORDER BY id;
+		SELECT VALUE id FROM t WHERE text @@ '"the dog"~2' ORDER BY id;
+	"#;
+	let mut t = Test::new(sql).await?;
+	t.expect_size(12)?;
+	t.skip_ok(4)?;
+	t.expect_val("[]")?;
+	t.expect_val("[t:1]")?;
+	t.expect_val("[]")?;
+	t.expect_val("[t:2]")?;
+	// The terms have to be in order
+	t.expect_val("[]")?;
+	t.skip_ok(1)?;
+	t.expect_val("[t:1, t:2]")?;
+	t.expect_val("[]")?;
+	Ok(())
+}
+
+#[tokio::test]
+async fn select_where_matches_fuzzy() -> Result<(), Error> {
+	let sql = r#"
+		DEFINE ANALYZER simple TOKENIZERS blank,class FILTERS lowercase;
+		DEFINE INDEX search_idx ON TABLE t COLUMNS text SEARCH ANALYZER simple BM25;
+		DEFINE INDEX search_idx ON TABLE u COLUMNS text SEARCH ANALYZER simple BM25 FUZZY 1;
+		CREATE t:1, u:1 SET text = 'I receive letters';
+		CREATE t:2, u:2 SET text = 'We recieve parcels';
+		CREATE t:3, u:3 SET text = 'They relieve pain';
+		CREATE t:4, u:4 SET text = 'Nothing to declare';
+		SELECT VALUE id FROM t WHERE text @@ 'recieve' ORDER BY id;
+		SELECT VALUE id FROM t WHERE text @@ 'recieve~1' ORDER BY id;
+		SELECT VALUE id FROM t WHERE text @@ 'recieve~0' ORDER BY id;
+		SELECT VALUE id FROM t WHERE text @@ 'deceive~' ORDER BY id;
+		SELECT VALUE id FROM t WHERE text @@ 'receive~1 -pain' ORDER BY id;
+		SELECT VALUE id FROM (SELECT id, search::score(1) AS score FROM t WHERE text @1@ 'receive~1' ORDER BY score DESC LIMIT 1);
+		SELECT VALUE id FROM u WHERE text @@ 'recieve' ORDER BY id;
+		SELECT VALUE id FROM u WHERE text @@ 'recieve~0' ORDER BY id;
+		SELECT VALUE id FROM u WHERE text @@ 'deceive' ORDER BY id;
+		SELECT VALUE id FROM u WHERE text @@ 'deceive~2' ORDER BY id;
+		(INFO FOR TABLE u).indexes.search_idx;
+	"#;
+	let mut t = Test::new(sql).await?;
+	t.expect_size(18)?;
+	t.skip_ok(7)?;
+	t.expect_val("[t:2]")?;
+	t.expect_val("[t:1, t:2, t:3]")?;
+	t.expect_val("[t:2]")?;
+	t.expect_val("[t:1, t:2]")?;
+	t.expect_val("[t:1, t:2]")?;
+	// The exact match scores higher than the fuzzy matches
+	t.expect_val("[t:1]")?;
+	t.expect_val("[u:1, u:2, u:3]")?;
+	t.expect_val("[u:2]")?;
+	t.expect_val("[u:1]")?;
+	t.expect_val("[u:1, u:2]")?;
+	t.expect_val(
+		"'DEFINE INDEX search_idx ON u FIELDS text SEARCH ANALYZER simple BM25(1.2,0.75) DOC_IDS_ORDER 100 DOC_LENGTHS_ORDER 100 POSTINGS_ORDER 100 TERMS_ORDER 100 DOC_IDS_CACHE 100 DOC_LENGTHS_CACHE 100 POSTINGS_CACHE 100 TERMS_CACHE 100 FUZZY 1'",
+	)?;
+	Ok(())
+}
+
+#[tokio::test]
+async fn select_where_matches_stopwords_and_synonyms() -> Result<(), Error> {
+	let synonyms = concat!(env!("CARGO_MANIFEST_DIR"), "/../../tests/data/synonyms-en.txt");
+	let sql = format!(
+		r#"
+		DEFINE ANALYZER simple TOKENIZERS blank,class FILTERS lowercase,stopwords(english),synonyms('{synonyms}');
+		DEFINE INDEX search_idx ON TABLE t COLUMNS text SEARCH ANALYZER simple BM25 HIGHLIGHTS;
+		CREATE t:1 SET text = 'The new television';
+		CREATE t:2 SET text = 'A TV on the wall';
+		CREATE t:3 SET text = 'The car in the garage';
+		SELECT VALUE id FROM t WHERE text @@ 'tv' ORDER BY id;
+		SELECT VALUE id FROM t WHERE text @@ 'Television' ORDER BY id;
+		SELECT VALUE id FROM t WHERE text @@ 'automobile' ORDER BY id;
+		SELECT VALUE id FROM t WHERE text @@ '"new tv"' ORDER BY id;
+		SELECT VALUE id FROM t WHERE text @@ 'the' ORDER BY id;
+		SELECT VALUE search::highlight('<b>', '</b>', 1) FROM t WHERE text @1@ 'tv';
+		search::analyze('simple', 'The TV in the car');
+	"#
+	);
+	let mut t = Test::new(&sql).await?;
+	t.expect_size(12)?;
+	t.skip_ok(5)?;
+	t.expect_val("[t:1, t:2]")?;
+	t.expect_val("[t:1, t:2]")?;
+	t.expect_val("[t:3]")?;
+	t.expect_val("[t:1]")?;
+	// Stop-words are not indexed
+	t.expect_val("[]")?;
+	t.expect_val("['The new <b>television</b>', 'A <b>TV</b> on the wall']")?;
+	t.expect_val("['tv', 'television', 'car', 'automobile', 'auto']")?;
+	Ok(())
+}
+
+#[tokio::test]
+async fn select_where_matches_synonyms_stage() -> Result<(), Error> {
+	let sql = r#"
+		DEFINE ANALYZER at_query TOKENIZERS blank FILTERS lowercase,synonyms([['tv', 'television']], QUERY);
+		DEFINE ANALYZER at_index TOKENIZERS blank FILTERS lowercase,synonyms([['tv', 'television']], INDEX);
+		DEFINE INDEX query_idx ON TABLE t COLUMNS text SEARCH ANALYZER at_query BM25;
+		DEFINE INDEX index_idx ON TABLE u COLUMNS text SEARCH ANALYZER at_index BM25;
+		CREATE t:1, u:1 SET text = 'The new television';
+		CREATE t:2, u:2 SET text = 'A TV on the wall';
+		SELECT VALUE id FROM t WHERE text @@ 'tv' ORDER BY id;
+		SELECT VALUE id FROM u WHERE text @@ 'tv' ORDER BY id;
+		SELECT VALUE id FROM t WHERE text @@ '"new tv"' ORDER BY id;
+		search::analyze('at_query', 'The TV');
+		search::analyze('at_index', 'The TV');
+		INFO FOR DB;
+	"#;
+	let mut t = Test::new(sql).await?;
+	t.expect_size(12)?;
+	t.skip_ok(6)?;
+	// Either stage is enough to match the synonyms
+	t.expect_val("[t:1, t:2]")?;
+	t.expect_val("[u:1, u:2]")?;
+	// The synonyms of a phrase expanded at query time are alternatives
+	t.expect_val("[t:1]")?;
+	// The synonyms are only indexed when they are expanded at index time
+	t.expect_val("['the', 'tv']")?;
+	t.expect_val("['the', 'tv', 'television']")?;
+	let info = t.next_value()?;
+	assert_eq!(
+		info.pick(&["analyzers".into(), "at_query".into()]),
+		Value::from(
+			"DEFINE ANALYZER at_query TOKENIZERS BLANK FILTERS LOWERCASE,SYNONYMS([['tv', 'television']], QUERY)"
+		)
+	);
+	Ok(())
+}
+
+#[tokio::test]
+async fn select_where_matches_multi_field() -> Result<(), Error> {
+	let sql = r#"
+		DEFINE ANALYZER simple TOKENIZERS blank,class FILTERS lowercase;
+		DEFINE INDEX search_idx ON TABLE product FIELDS title, description SEARCH ANALYZER simple BM25 HIGHLIGHTS BOOSTS(10, 1);
+		CREATE product:1 SET title = 'Kitchen', description = 'A chair with a long description of many words';
+		CREATE product:2 SET title = 'Chair', description = 'kitchen kitchen';
+		CREATE product:3 SET title = 'Lamp', description = 'A desk lamp';
+		CREATE product:4 SET title = 'Sofa', description = 'A large sofa';
+		CREATE product:5 SET title = 'Rug', description = 'A soft rug';
+		RETURN (SELECT id, search::score(1) AS score FROM product WHERE title @1@ 'kitchen' ORDER BY score DESC).id;
+		SELECT VALUE search::highlight('<b>', '</b>', 1) FROM product WHERE title @1@ 'kitchen';
+		SELECT VALUE id FROM product WHERE description @@ 'chair';
+		SELECT VALUE id FROM product WHERE title @@ '"long description"';
+		DEFINE INDEX OVERWRITE search_idx ON TABLE product FIELDS title, description SEARCH ANALYZER simple BM25 HIGHLIGHTS;
+		RETURN (SELECT id, search::score(1) AS score FROM product WHERE title @1@ 'kitchen' ORDER BY score DESC).id;
+	"#;
+	let mut t = Test::new(sql).await?;
+	t.expect_size(13)?;
+	t.skip_ok(7)?;
+	// The boosted title outweighs the repeated term of the description
+	t.expect_val("[product:1, product:2]")?;
+	// The highlights report which field matched
+	t.expect_val(
+		"[{ title: '<b>Kitchen</b>' }, { description: '<b>kitchen</b> <b>kitchen</b>' }]",
+	)?;
+	// Any field of the index searches every field
+	t.expect_val("[product:1, product:2]")?;
+	t.expect_val("[product:1]")?;
+	t.skip_ok(1)?;
+	// Without boosts the repeated term wins
+	t.expect_val("[product:2, product:1]")?;
+	Ok(())
+}
+
+#[tokio::test]
+async fn select_hybrid_search() -> Result<(), Error> {
+	let sql = r#"
+		DEFINE ANALYZER simple TOKENIZERS blank,class FILTERS lowercase;
+		DEFINE INDEX ft_text ON doc FIELDS text SEARCH ANALYZER simple BM25;
+		DEFINE INDEX hnsw_embedding ON doc FIELDS embedding HNSW DIMENSION 2 DIST EUCLIDEAN;
+		CREATE doc:1 SET text = 'rust database', embedding = [0, 0];
+		CREATE doc:2 SET text = 'rust rust rust language', embedding = [10, 10];
+		CREATE doc:3 SET text = 'python database', embedding = [1, 0];
+		CREATE doc:4 SET text = 'cooking recipes', embedding = [0, 2];
+		CREATE doc:5 SET text = 'gardening tips', embedding = [8, 8];
+		CREATE doc:6 SET text = 'travel guide', embedding = [9, 9];
+		RETURN (SELECT id, search::rrf(1) AS score FROM doc WHERE text @1@ 'rust' OR embedding <|2,40|> [0, 0] ORDER BY score DESC).id;
+		SELECT id, search::score(1) > 0 AS matched, vector::distance::knn() AS dist FROM doc WHERE text @1@ 'rust' OR embedding <|2,40|> [0, 0] ORDER BY id;
+		RETURN (SELECT id, search::linear(1, 0.75) AS score FROM doc WHERE text @1@ 'rust' OR embedding <|2,40|> [0, 0] ORDER BY score DESC).id;
+		RETURN (SELECT id, search::linear(1, 0.25) AS score FROM doc WHERE text @1@ 'rust' OR embedding <|2,40|> [0, 0] ORDER BY score DESC).id;
+		SELECT id, search::linear(1, 2) AS score FROM doc WHERE text @1@ 'rust' OR embedding <|2,40|> [0, 0];
+	"#;
+	let mut t = Test::new(sql).await?;
+	t.expect_size(14)?;
+	t.skip_ok(9)?;
+	// doc:1 is ranked by both the full-text and the vector search
+	t.expect_val("[doc:1, doc:2, doc:3]")?;
+	// Both component scores are available for every record
+	t.expect_val(
+		"[
+			{ id: doc:1, matched: true, dist: 0f },
+			{ id: doc:2, matched: true, dist: NONE },
+			{ id: doc:3, matched: false, dist: 1f }
+		]",
+	)?;
+	// The weight favours either the full-text score or the vector distance
+	t.expect_val("[doc:2, doc:1, doc:3]")?;
+	t.expect_val("[doc:1, doc:2, doc:3]")?;
+	t.expect_error("Incorrect arguments for function search::linear(). The weight of the full-text score must be between 0 and 1.")?;
+	Ok(())
+}
+
+#[tokio::test]
+async fn select_hybrid_search_with_phrase_and_bruteforce() -> Result<(), Error> {
+	let sql = r#"
+		DEFINE ANALYZER simple TOKENIZERS blank,class FILTERS lowercase;
+		DEFINE INDEX ft_text ON doc FIELDS text SEARCH ANALYZER simple BM25;
+		DEFINE INDEX hnsw_embedding ON doc FIELDS embedding HNSW DIMENSION 2 DIST EUCLIDEAN;
+		CREATE doc:0 SET text = 'database rust', embedding = [20, 20];
+		CREATE doc:1 SET text = 'rust database', embedding = [0, 0];
+		CREATE doc:2 SET text = 'rust rust rust language', embedding = [10, 10];
+		CREATE doc:3 SET text = 'python database', embedding = [1, 0];
+		SELECT id, search::rrf(1) == 1 / 61f + 1 / 61f AS top FROM doc WHERE text @1@ '"rust database"' OR embedding <|2,40|> [0, 0] ORDER BY id;
+		SELECT id, search::rrf(1) AS score FROM doc WHERE text @1@ 'rust' OR embedding <|2,EUCLIDEAN|> [0, 0];
+		SELECT id, search::linear(1) AS score FROM doc WHERE text @1@ 'rust' OR embedding <|2,EUCLIDEAN|> [0, 0];
+	"#;
+	let mut t = Test::new(sql).await?;
+	t.expect_size(10)?;
+	t.skip_ok(7)?;
+	// The candidate which does not contain the phrase is not ranked
+	t.expect_val(
+		"[
+			{ id: doc:1, top: true },
+			{ id: doc:3, top: false }
+		]",
+	)?;
+	// A brute force KNN search can't be fused
+	t.expect_error("Incorrect arguments for function search::rrf(). The KNN searches of a hybrid search must use a vector index.")?;
+	t.expect_error("Incorrect arguments for function search::linear(). The KNN searches of a hybrid search must use a vector index.")?;
+	Ok(())
+}
+
+#[tokio::test]
+async fn select_hybrid_search_rrf_constant() -> Result<(), Error> {
+	let sql = r"
+		RETURN search::rrf(1, 0);
+		RETURN search::rrf(1, -1);
+		RETURN search::rrf(1, 0.001);
+		RETURN search::rrf(1);
+	";
+	let mut t = Test::new(sql).await?;
+	// The ranking constant must be strictly positive
+	t.expect_error("Incorrect arguments for function search::rrf(). The ranking constant must be a positive number.")?;
+	t.expect_error("Incorrect arguments for function search::rrf(). The ranking constant must be a positive number.")?;
+	t.expect_val("NONE")?;
+	t.expect_val("NONE")?;
+	Ok(())
+}
@@ -31,10 +31,12 @@ async fn define_global_param() -> Result<(), Error> {
 			apis: {},
 			buckets: {},
 			configs: {},
+			consumers: {},
 			functions: {},
 			models: {},
 			params: { test: 'DEFINE PARAM $test VALUE 12345 PERMISSIONS FULL' },
 			sequences: {},
+			sinks: {},
 			tables: {},
 			users: {},
 		}",
@@ -2843,3 +2843,305 @@ async fn select_limit_start_array() -> Result<(), Error> {
 
 	Ok(())
 }
+
+#[tokio::test]
+async fn select_cost_based_index_selection() -> Result<(), Error> {
+	let sql = r"
+		FOR $i IN 0..100 {
+			CREATE type::thing('person', $i) SET
+				status = IF $i < 95 { 'active' } ELSE { 'inactive' },
+				a = $i % 10,
+				b = $i % 4;
+		};
+		DEFINE INDEX status ON person FIELDS status;
+		DEFINE INDEX a ON person FIELDS a;
+		DEFINE INDEX b ON person FIELDS b;
+		ANALYZE INDEX status ON person;
+		ANALYZE INDEX a ON person;
+		ANALYZE INDEX b ON person;
+		SELECT id FROM person WHERE status = 'active' EXPLAIN;
+		SELECT id FROM person WHERE status = 'inactive' EXPLAIN;
+		SELECT id FROM person WHERE a = 3 AND b = 1 EXPLAIN;
+		SELECT id FROM person WHERE a = 3 AND b = 1;
+		SELECT id FROM person WHERE status = 'inactive' OR a = 3 EXPLAIN;
+		SELECT id FROM person WHERE status = 'active' OR a = 3 EXPLAIN;
+	";
+	let mut t = Test::new(sql).await?;
+	t.skip_ok(7)?;
+	// Most records are active, a table scan is cheaper
+	t.expect_val(
+		"[
+			{
+				detail: { direction: 'forward', table: 'person' },
+				operation: 'Iterate Table'
+			},
+			{
+				detail: {
+					candidates: [
+						{ chosen: false, cost: 210f, plan: 'index status', rows: 95 },
+						{ chosen: true, cost: 100f, plan: 'table', rows: 100 }
+					],
+					table: 'person'
+				},
+				operation: 'Cost'
+			},
+			{
+				detail: { type: 'Memory' },
+				operation: 'Collector'
+			}
+		]",
+	)?;
+	// Few records are inactive, the index is cheaper
+	t.expect_val(
+		"[
+			{
+				detail: {
+					plan: { index: 'status', operator: '=', value: 'inactive' },
+					table: 'person'
+				},
+				operation: 'Iterate Index'
+			},
+			{
+				detail: {
+					candidates: [
+						{ chosen: true, cost: 12f, plan: 'index status', rows: 5 },
+						{ chosen: false, cost: 100f, plan: 'table', rows: 100 }
+					],
+					table: 'person'
+				},
+				operation: 'Cost'
+			},
+			{
+				detail: { type: 'Memory' },
+				operation: 'Collector'
+			}
+		]",
+	)?;
+	// The intersection of both indexes is cheaper than each index
+	t.expect_val(
+		"[
+			{
+				detail: {
+					plan: {
+						indexes: [
+							{ index: 'a', operator: '=', value: 3 },
+							{ index: 'b', operator: '=', value: 1 }
+						],
+						operator: 'intersection'
+					},
+					table: 'person'
+				},
+				operation: 'Iterate Index'
+			},
+			{
+				detail: {
+					candidates: [
+						{ chosen: false, cost: 25f, plan: 'index a', rows: 10 },
+						{ chosen: false, cost: 58f, plan: 'index b', rows: 25 },
+						{ chosen: true, cost: 18f, plan: 'intersection a, b', rows: 3 },
+						{ chosen: false, cost: 100f, plan: 'table', rows: 100 }
+					],
+					table: 'person'
+				},
+				operation: 'Cost'
+			},
+			{
+				detail: { type: 'Memory' },
+				operation: 'Collector'
+			}
+		]",
+	)?;
+	t.expect_val(
+		"[{ id: person:13 }, { id: person:33 }, { id: person:53 }, { id: person:73 }, { id: person:93 }]",
+	)?;
+	// The union of the indexes is cheaper
+	t.expect_val(
+		"[
+			{
+				detail: {
+					plan: { index: 'status', operator: '=', value: 'inactive' },
+					table: 'person'
+				},
+				operation: 'Iterate Index'
+			},
+			{
+				detail: {
+					plan: { index: 'a', operator: '=', value: 3 },
+					table: 'person'
+				},
+				operation: 'Iterate Index'
+			},
+			{
+				detail: {
+					candidates: [
+						{ chosen: true, cost: 37f, plan: 'union', rows: 15 },
+						{ chosen: false, cost: 100f, plan: 'table', rows: 100 }
+					],
+					table: 'person'
+				},
+				operation: 'Cost'
+			},
+			{
+				detail: { type: 'Memory' },
+				operation: 'Collector'
+			}
+		]",
+	)?;
+	// The union would return most of the records, a table scan is cheaper
+	t.expect_val(
+		"[
+			{
+				detail: { direction: 'forward', table: 'person' },
+				operation: 'Iterate Table'
+			},
+			{
+				detail: {
+					candidates: [
+						{ chosen: false, cost: 235f, plan: 'union', rows: 100 },
+						{ chosen: true, cost: 100f, plan: 'table', rows: 100 }
+					],
+					table: 'person'
+				},
+				operation: 'Cost'
+			},
+			{
+				detail: { type: 'Memory' },
+				operation: 'Collector'
+			}
+		]",
+	)?;
+	Ok(())
+}
+
+#[tokio::test]
+async fn select_cost_based_index_selection_with_stale_statistics() -> Result<(), Error> {
+	let sql = r"
+		FOR $i IN 0..20 {
+			CREATE type::thing('person', $i) SET status = 'active';
+		};
+		DEFINE INDEX status ON person FIELDS status;
+		DEFINE INDEX email ON person FIELDS email UNIQUE;
+		ANALYZE INDEX status ON person;
+		ANALYZE INDEX email ON person;
+		SELECT id FROM person WHERE status = 'active' EXPLAIN;
+		FOR $i IN 20..100 {
+			CREATE type::thing('person', $i) SET status = 'inactive';
+		};
+		SELECT id FROM person WHERE status = 'active' EXPLAIN;
+	";
+	let mut t = Test::new(sql).await?;
+	t.skip_ok(5)?;
+	// The unique index has no entry, but the table size is known
+	t.expect_val(
+		"[
+			{
+				detail: { direction: 'forward', table: 'person' },
+				operation: 'Iterate Table'
+			},
+			{
+				detail: {
+					candidates: [
+						{ chosen: false, cost: 45f, plan: 'index status', rows: 20 },
+						{ chosen: true, cost: 20f, plan: 'table', rows: 20 }
+					],
+					table: 'person'
+				},
+				operation: 'Cost'
+			},
+			{
+				detail: { type: 'Memory' },
+				operation: 'Collector'
+			}
+		]",
+	)?;
+	t.skip_ok(1)?;
+	// The table has grown since it has been analyzed, the plan is chosen by rules
+	t.expect_val(
+		"[
+			{
+				detail: {
+					plan: { index: 'status', operator: '=', value: 'active' },
+					table: 'person'
+				},
+				operation: 'Iterate Index'
+			},
+			{
+				detail: { type: 'Memory' },
+				operation: 'Collector'
+			}
+		]",
+	)?;
+	Ok(())
+}
+
+#[tokio::test]
+async fn select_expression_index() -> Result<(), Error> {
+	let sql = r"
+		DEFINE INDEX email ON user FIELDS string::lowercase(email) UNIQUE;
+		CREATE user:1 SET email = 'Tobie@SurrealDB.com';
+		CREATE user:2 SET email = 'jaime@surrealdb.com';
+		CREATE user:3 SET email = 'TOBIE@surrealdb.com';
+		SELECT id FROM user WHERE string::lowercase(email) = 'tobie@surrealdb.com' EXPLAIN;
+		SELECT id FROM user WHERE string::lowercase(email) = 'tobie@surrealdb.com';
+		SELECT id FROM user WHERE email = 'tobie@surrealdb.com' EXPLAIN;
+		INFO FOR TABLE user;
+	";
+	let mut t = Test::new(sql).await?;
+	t.skip_ok(3)?;
+	// The index is unique on the lowercase email
+	t.expect_error(
+		"Database index `email` already contains 'tobie@surrealdb.com', with record `user:1`",
+	)?;
+	t.expect_val(
+		"[
+			{
+				detail: {
+					plan: {
+						index: 'email',
+						operator: '=',
+						value: 'tobie@surrealdb.com'
+					},
+					table: 'user'
+				},
+				operation: 'Iterate Index'
+			},
+			{
+				detail: {
+					type: 'Memory'
+				},
+				operation: 'Collector'
+			}
+		]",
+	)?;
+	t.expect_val("[{ id: user:1 }]")?;
+	// The field itself is not indexed
+	t.expect_val(
+		"[
+			{
+				detail: {
+					direction: 'forward',
+					table: 'user'
+				},
+				operation: 'Iterate Table'
+			},
+			{
+				detail: {
+					type: 'Memory'
+				},
+				operation: 'Collector'
+			}
+		]",
+	)?;
+	t.expect_val(
+		"{
+			events: {},
+			fields: {},
+			indexes: {
+				email: 'DEFINE INDEX email ON user FIELDS string::lowercase(email) UNIQUE'
+			},
+			lives: {},
+			tables: {}
+		}",
+	)?;
+	Ok(())
+}
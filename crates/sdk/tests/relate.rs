@@ -285,10 +285,12 @@ async fn relate_enforced() -> Result<(), Error> {
 	apis: {},
 	buckets: {},
 	configs: {},
+	consumers: {},
 	functions: {},
 	models: {},
 	params: {},
 	sequences: {},
+	sinks: {},
 	tables: {
 		a: 'DEFINE TABLE a TYPE ANY SCHEMALESS PERMISSIONS NONE',
 		edge: 'DEFINE TABLE edge TYPE RELATION ENFORCED SCHEMALESS PERMISSIONS NONE'
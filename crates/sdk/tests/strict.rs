@@ -261,10 +261,12 @@ async fn loose_mode_all_ok() -> Result<(), Error> {
 			apis: {},
 			buckets: {},
 			configs: {},
+			consumers: {},
 			functions: {},
 			models: {},
 			params: {},
 			sequences: {},
+			sinks: {},
 			tables: { test: 'DEFINE TABLE test TYPE ANY SCHEMALESS PERMISSIONS NONE' },
 			users: {},
 		}",
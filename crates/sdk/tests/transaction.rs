@@ -2,9 +2,11 @@ mod parse;
 use parse::Parse;
 mod helpers;
 use helpers::new_ds;
-use surrealdb::dbs::Session;
+use std::time::Duration;
+use surrealdb::dbs::{QueryType, Session};
 use surrealdb::err::Error;
 use surrealdb::sql::Value;
+use tokio::time::sleep;
 
 #[tokio::test]
 async fn transaction_basic() -> Result<(), Error> {
@@ -200,3 +202,126 @@ async fn transaction_with_throw_and_return() -> Result<(), Error> {
 	//
 	Ok(())
 }
+
+#[tokio::test]
+async fn interactive_transaction_commit() -> Result<(), Error> {
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let txn = dbs.begin(&ses).await?;
+	dbs.execute_in_transaction(txn, "CREATE person:tobie", &ses, None).await?;
+	dbs.execute_in_transaction(txn, "CREATE person:jaime", &ses, None).await?;
+	// The changes are only visible within the transaction
+	let res = &mut dbs.execute("SELECT VALUE id FROM person", &ses, None).await?;
+	assert_eq!(res.remove(0).result?, Value::parse("[]"));
+	let res =
+		&mut dbs.execute_in_transaction(txn, "SELECT VALUE id FROM person", &ses, None).await?;
+	assert_eq!(res.remove(0).result?, Value::parse("[person:jaime, person:tobie]"));
+	//
+	dbs.commit(txn, &ses).await?;
+	let res = &mut dbs.execute("SELECT VALUE id FROM person", &ses, None).await?;
+	assert_eq!(res.remove(0).result?, Value::parse("[person:jaime, person:tobie]"));
+	// The transaction can't be used once it has been committed
+	let tmp = dbs.execute_in_transaction(txn, "CREATE person:other", &ses, None).await;
+	assert!(matches!(tmp, Err(Error::TxNotFound { .. })));
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn interactive_transaction_live() -> Result<(), Error> {
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test").with_rt(true);
+	let txn = dbs.begin(&ses).await?;
+	let res = &mut dbs.execute_in_transaction(txn, "LIVE SELECT * FROM person", &ses, None).await?;
+	let lqid = res.remove(0).result?;
+	// Live queries only take effect once the transaction commits
+	let res = dbs.commit(txn, &ses).await?;
+	assert_eq!(res.len(), 1);
+	assert!(matches!(res[0].query_type, QueryType::Live));
+	assert_eq!(res[0].result.as_ref().ok(), Some(&lqid));
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn interactive_transaction_cancel() -> Result<(), Error> {
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let txn = dbs.begin(&ses).await?;
+	dbs.execute_in_transaction(txn, "CREATE person:tobie", &ses, None).await?;
+	dbs.cancel(txn, &ses).await?;
+	let res = &mut dbs.execute("SELECT VALUE id FROM person", &ses, None).await?;
+	assert_eq!(res.remove(0).result?, Value::parse("[]"));
+	//
+	let tmp = dbs.commit(txn, &ses).await;
+	assert!(matches!(tmp, Err(Error::TxNotFound { .. })));
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn interactive_transaction_with_throw() -> Result<(), Error> {
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let txn = dbs.begin(&ses).await?;
+	dbs.execute_in_transaction(txn, "CREATE person:tobie", &ses, None).await?;
+	let res = &mut dbs
+		.execute_in_transaction(txn, "THROW 'there was an error'; CREATE person:jaime", &ses, None)
+		.await?;
+	assert_eq!(res.len(), 2);
+	assert!(res.remove(0).result.is_err());
+	assert!(matches!(res.remove(0).result, Err(Error::QueryNotExecuted)));
+	// The transaction is rolled back, and can no longer be used
+	let tmp = dbs.execute_in_transaction(txn, "CREATE person:other", &ses, None).await;
+	assert!(matches!(tmp, Err(Error::TxAborted)));
+	let tmp = dbs.commit(txn, &ses).await;
+	assert!(matches!(tmp, Err(Error::TxAborted)));
+	let res = &mut dbs.execute("SELECT VALUE id FROM person", &ses, None).await?;
+	assert_eq!(res.remove(0).result?, Value::parse("[]"));
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn interactive_transaction_statements() -> Result<(), Error> {
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let txn = dbs.begin(&ses).await?;
+	let res = &mut dbs.execute_in_transaction(txn, "COMMIT", &ses, None).await?;
+	assert!(matches!(res.remove(0).result, Err(Error::QueryNotExecutedDetail { .. })));
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn interactive_transaction_other_user() -> Result<(), Error> {
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let txn = dbs.begin(&ses).await?;
+	// Transactions can only be used by the user which started them
+	let other = Session::viewer().with_ns("test").with_db("test");
+	let tmp = dbs.execute_in_transaction(txn, "CREATE person:tobie", &other, None).await;
+	assert!(matches!(tmp, Err(Error::TxNotFound { .. })));
+	let tmp = dbs.cancel(txn, &other).await;
+	assert!(matches!(tmp, Err(Error::TxNotFound { .. })));
+	dbs.cancel(txn, &ses).await?;
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn interactive_transaction_expiry() -> Result<(), Error> {
+	let dbs = new_ds().await?.with_transaction_timeout(Some(Duration::from_millis(100)));
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let txn = dbs.begin(&ses).await?;
+	dbs.execute_in_transaction(txn, "CREATE person:tobie", &ses, None).await?;
+	// Abandoned transactions are rolled back
+	sleep(Duration::from_millis(200)).await;
+	dbs.interactive_transaction_expire().await?;
+	let tmp = dbs.commit(txn, &ses).await;
+	assert!(matches!(tmp, Err(Error::TxNotFound { .. })));
+	let res = &mut dbs.execute("SELECT VALUE id FROM person", &ses, None).await?;
+	assert_eq!(res.remove(0).result?, Value::parse("[]"));
+	//
+	Ok(())
+}
@@ -0,0 +1,132 @@
+use crate::cli::abstraction::AuthArguments;
+use crate::err::Error;
+use bytes::Bytes;
+use clap::Args;
+use reqwest::{RequestBuilder, Response};
+use surrealdb::engine::any;
+use surrealdb::kvs::{backup, Datastore};
+use surrealdb::opt::Endpoint;
+use tokio::fs::File;
+use tokio::io::{self, AsyncWrite, AsyncWriteExt};
+
+#[derive(Args, Debug)]
+pub struct DatabaseConnectionArguments {
+	#[arg(help = "Database server, or storage path, to take the backup from")]
+	#[arg(short = 'e', long = "endpoint", visible_aliases = ["conn"])]
+	#[arg(default_value = "http://localhost:8000")]
+	#[arg(value_parser = super::validator::endpoint_valid)]
+	pub(crate) endpoint: String,
+}
+
+#[derive(Args, Debug)]
+pub struct BackupCommandArguments {
+	#[arg(help = "Path to the file to write the backup to. Use dash - to write into stdout.")]
+	#[arg(default_value = "-")]
+	#[arg(index = 1)]
+	file: String,
+	#[arg(
+		help = "Whether to only back up the change feeds, as an archive for point-in-time recovery"
+	)]
+	#[arg(long)]
+	changes: bool,
+	#[command(flatten)]
+	conn: DatabaseConnectionArguments,
+	#[command(flatten)]
+	auth: AuthArguments,
+}
+
+pub async fn init(
+	BackupCommandArguments {
+		file,
+		changes,
+		conn: DatabaseConnectionArguments {
+			endpoint,
+		},
+		auth,
+	}: BackupCommandArguments,
+) -> Result<(), Error> {
+	// Open the file to write the backup to
+	let mut output: Box<dyn AsyncWrite + Send + Unpin> = match file.as_str() {
+		"-" => Box::new(io::stdout()),
+		file => Box::new(File::create(file).await?),
+	};
+	// Check the backup while it is written, so that an incomplete backup is never reported as successful
+	let (chk, chunks) = surrealdb::channel::bounded(1);
+	let verify = tokio::spawn(backup::verify(chunks));
+	let endpoint = any::__into_endpoint(&endpoint)?;
+	if endpoint.parse_kind()?.is_local() {
+		// Open the datastore directly
+		debug!("Opening the datastore to back up");
+		let ds = Datastore::new(&local_path(endpoint)).await?;
+		// Start the backup task
+		let (snd, rcv) = surrealdb::channel::bounded(1);
+		let task = match changes {
+			true => tokio::spawn(ds.backup_changes(snd).await?),
+			false => tokio::spawn(ds.backup(snd).await?),
+		};
+		// Write the backup to the output
+		while let Ok(bytes) = rcv.recv().await {
+			output.write_all(&bytes).await?;
+			let _ = chk.send(Ok(Bytes::from(bytes))).await;
+		}
+		task.await.map_err(|e| Error::Other(e.to_string()))??;
+	} else {
+		// Stream the backup from the server
+		debug!("Requesting a backup from the database server");
+		let client = reqwest::Client::new();
+		let req = client.get(remote_url(&endpoint, "/backup")?).query(&[("changes", changes)]);
+		let mut res = send(req, &auth).await?;
+		// Write the backup to the output
+		while let Some(bytes) = res.chunk().await? {
+			output.write_all(&bytes).await?;
+			let _ = chk.send(Ok(bytes)).await;
+		}
+	}
+	output.flush().await?;
+	// Check that the backup matches its manifest
+	drop(chk);
+	let manifest = verify.await.map_err(|e| Error::Other(e.to_string()))??;
+	debug!("Backed up {} keys ({} bytes)", manifest.keys, manifest.bytes);
+	info!("The backup was completed successfully");
+	// Everything OK
+	Ok(())
+}
+
+/// Gets the path of a storage engine, which can be opened as a datastore
+pub(super) fn local_path(endpoint: Endpoint) -> String {
+	if endpoint.path.is_empty() {
+		endpoint.url.to_string()
+	} else {
+		endpoint.path
+	}
+}
+
+/// Gets the HTTP url of a route on a remote database server
+pub(super) fn remote_url(endpoint: &Endpoint, route: &str) -> Result<reqwest::Url, Error> {
+	let mut url = endpoint.url.clone();
+	let scheme = match url.scheme() {
+		"ws" => "http",
+		"wss" => "https",
+		scheme => scheme,
+	}
+	.to_owned();
+	url.set_scheme(&scheme).map_err(|_| Error::Other(format!("Invalid endpoint '{url}'")))?;
+	url.set_path(route);
+	Ok(url)
+}
+
+/// Sends an authenticated request to a remote database server
+pub(super) async fn send(req: RequestBuilder, auth: &AuthArguments) -> Result<Response, Error> {
+	let req = match (&auth.username, &auth.password, &auth.token) {
+		(Some(user), pass, _) => req.basic_auth(user, pass.as_ref()),
+		(_, _, Some(token)) => req.bearer_auth(token),
+		_ => req,
+	};
+	let res = req.send().await?;
+	if !res.status().is_success() {
+		let status = res.status();
+		let body = res.text().await?;
+		return Err(Error::Other(format!("The server responded with status {status}: {body}")));
+	}
+	Ok(res)
+}
@@ -1,12 +1,14 @@
 #![allow(deprecated)]
 
 pub(crate) mod abstraction;
+mod backup;
 mod config;
 mod export;
 mod fix;
 mod import;
 mod isready;
 mod ml;
+mod restore;
 mod sql;
 mod start;
 #[cfg(test)]
@@ -24,6 +26,7 @@ use crate::cli::version_client::VersionClient;
 use crate::cnf::DEBUG_BUILD_WARNING;
 use crate::cnf::{LOGO, PKG_VERSION};
 use crate::env::RELEASE;
+use backup::BackupCommandArguments;
 use clap::{Parser, Subcommand};
 pub use config::CF;
 use export::ExportCommandArguments;
@@ -31,6 +34,7 @@ use fix::FixCommandArguments;
 use import::ImportCommandArguments;
 use isready::IsReadyCommandArguments;
 use ml::MlCommand;
+use restore::RestoreCommandArguments;
 use semver::Version;
 use sql::SqlCommandArguments;
 use start::StartCommandArguments;
@@ -78,10 +82,10 @@ struct Cli {
 enum Commands {
 	#[command(about = "Start the database server")]
 	Start(StartCommandArguments),
-	/* Not implemented yet
-	#[command(about = "Backup data to or from an existing database")]
+	#[command(about = "Take a physical backup of an existing database")]
 	Backup(BackupCommandArguments),
-	*/
+	#[command(about = "Restore a physical backup into an empty database")]
+	Restore(RestoreCommandArguments),
 	#[command(about = "Import a SurrealQL script into an existing database")]
 	Import(ImportCommandArguments),
 	#[command(about = "Export an existing database as a SurrealQL script")]
@@ -148,6 +152,8 @@ pub async fn init() -> ExitCode {
 		Commands::Start(args) => start::init(args).await,
 		Commands::Import(args) => import::init(args).await,
 		Commands::Export(args) => export::init(args).await,
+		Commands::Backup(args) => backup::init(args).await,
+		Commands::Restore(args) => restore::init(args).await,
 		Commands::Version(args) => version::init(args).await,
 		Commands::Upgrade(args) => upgrade::init(args).await,
 		Commands::Sql(args) => sql::init(args).await,
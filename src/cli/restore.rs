@@ -0,0 +1,109 @@
+use super::backup::{local_path, remote_url, send};
+use crate::cli::abstraction::AuthArguments;
+use crate::err::Error;
+use clap::Args;
+use futures::TryStreamExt;
+use reqwest::header::{HeaderValue, ACCEPT};
+use surrealdb::engine::any;
+use surrealdb::kvs::Datastore;
+use surrealdb::sql::statements::ShowSince;
+use surrealdb::sql::Datetime;
+use tokio::fs::File;
+use tokio::io::{self, AsyncRead};
+use tokio_util::io::ReaderStream;
+
+#[derive(Args, Debug)]
+pub struct DatabaseConnectionArguments {
+	#[arg(help = "Database server, or storage path, to restore the backup into")]
+	#[arg(short = 'e', long = "endpoint", visible_aliases = ["conn"])]
+	#[arg(default_value = "http://localhost:8000")]
+	#[arg(value_parser = super::validator::endpoint_valid)]
+	pub(crate) endpoint: String,
+}
+
+#[derive(Args, Debug)]
+pub struct RestoreCommandArguments {
+	#[arg(help = "Path to the backup file to restore. Use dash - to read from stdin.")]
+	#[arg(index = 1)]
+	file: String,
+	#[arg(help = "Paths to archives of change feeds to replay on top of the backup")]
+	#[arg(long = "changes", value_delimiter = ',', requires = "until")]
+	changes: Vec<String>,
+	#[arg(help = "The datetime, or versionstamp, to recover the datastore to")]
+	#[arg(long, requires = "changes", value_parser = until_valid)]
+	until: Option<ShowSince>,
+	#[command(flatten)]
+	conn: DatabaseConnectionArguments,
+	#[command(flatten)]
+	auth: AuthArguments,
+}
+
+pub async fn init(
+	RestoreCommandArguments {
+		file,
+		changes,
+		until,
+		conn: DatabaseConnectionArguments {
+			endpoint,
+		},
+		auth,
+	}: RestoreCommandArguments,
+) -> Result<(), Error> {
+	// Open the file to read the backup from
+	let input: Box<dyn AsyncRead + Send + Unpin> = match file.as_str() {
+		"-" => Box::new(io::stdin()),
+		file => Box::new(File::open(file).await?),
+	};
+	let stream = ReaderStream::new(input);
+	let endpoint = any::__into_endpoint(&endpoint)?;
+	if let Some(until) = until {
+		// Change feeds can only be replayed into a local datastore
+		if !endpoint.parse_kind()?.is_local() {
+			return Err(Error::Other(
+				"Point-in-time recovery can only be performed on a local datastore".to_string(),
+			));
+		}
+		// Open the archives of change feeds
+		let mut archives = Vec::with_capacity(changes.len());
+		for file in changes {
+			let input: Box<dyn AsyncRead + Send + Unpin> = Box::new(File::open(file).await?);
+			archives.push(ReaderStream::new(input).map_err(Into::into));
+		}
+		// Open the datastore directly
+		debug!("Opening the datastore to recover into");
+		let ds = Datastore::new(&local_path(endpoint)).await?;
+		// Restore the backup and replay the change feeds into the datastore
+		let manifest = ds.recover(stream.map_err(Into::into), archives, until).await?;
+		debug!("Restored {} keys ({} bytes)", manifest.keys, manifest.bytes);
+	} else if endpoint.parse_kind()?.is_local() {
+		// Open the datastore directly
+		debug!("Opening the datastore to restore into");
+		let ds = Datastore::new(&local_path(endpoint)).await?;
+		// Restore the backup into the datastore
+		let manifest = ds.restore(stream.map_err(Into::into)).await?;
+		debug!("Restored {} keys ({} bytes)", manifest.keys, manifest.bytes);
+	} else {
+		// Stream the backup to the server
+		debug!("Sending the backup to the database server");
+		let client = reqwest::Client::new();
+		let req = client
+			.post(remote_url(&endpoint, "/restore")?)
+			.header(ACCEPT, HeaderValue::from_static("application/octet-stream"))
+			.body(reqwest::Body::wrap_stream(stream));
+		send(req, &auth).await?;
+	}
+	info!("The backup was restored successfully");
+	// Everything OK
+	Ok(())
+}
+
+/// Parses the datetime, or versionstamp, to recover a datastore to
+fn until_valid(v: &str) -> Result<ShowSince, String> {
+	match v.parse::<u64>() {
+		Ok(vs) => Ok(ShowSince::Versionstamp(vs)),
+		Err(_) => match Datetime::try_from(v) {
+			Ok(ts) => Ok(ShowSince::Timestamp(ts)),
+			Err(_) => Err(format!("'{v}' is not a valid datetime or versionstamp")),
+		},
+	}
+}
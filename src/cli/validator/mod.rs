@@ -18,6 +18,7 @@ pub(crate) fn path_valid(v: &str) -> Result<String, String> {
 		"memory" => Ok(v.to_string()),
 		v if v.starts_with("file:") => Ok(v.to_string()),
 		v if v.starts_with("rocksdb:") => Ok(v.to_string()),
+		v if v.starts_with("rocksdb+versioned:") => Ok(v.to_string()),
 		v if v.starts_with("surrealkv:") => Ok(v.to_string()),
 		v if v.starts_with("surrealkv+versioned:") => Ok(v.to_string()),
 		v if v.starts_with("tikv:") => Ok(v.to_string()),
@@ -70,6 +71,7 @@ pub(crate) fn endpoint_valid(v: &str) -> Result<String, String> {
 		| "fdb"
 		| "mem"
 		| "rocksdb"
+		| "rocksdb+versioned"
 		| "surrealkv"
 		| "surrealkv+versioned"
 		| "file"
@@ -45,6 +45,10 @@ pub static HTTP_MAX_SQL_BODY_SIZE: LazyLock<usize> =
 pub static HTTP_MAX_API_BODY_SIZE: LazyLock<usize> =
 	lazy_env_parse!(bytes, "SURREAL_HTTP_MAX_API_BODY_SIZE", usize, 4 << 20);
 
+/// The maximum HTTP body size of the HTTP /files endpoint (default: 4 GiB)
+pub static HTTP_MAX_FILES_BODY_SIZE: LazyLock<usize> =
+	lazy_env_parse!(bytes, "SURREAL_HTTP_MAX_FILES_BODY_SIZE", usize, 4 << 30);
+
 /// The maximum HTTP body size of the HTTP /rpc endpoint (default: 4 MiB)
 pub static HTTP_MAX_RPC_BODY_SIZE: LazyLock<usize> =
 	lazy_env_parse!(bytes, "SURREAL_HTTP_MAX_RPC_BODY_SIZE", usize, 4 << 20);
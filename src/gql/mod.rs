@@ -6,16 +6,17 @@ use std::{
 };
 
 use async_graphql::{
-	http::{create_multipart_mixed_stream, is_accept_multipart_mixed},
-	Executor, ParseRequestError,
+	http::{create_multipart_mixed_stream, is_accept_multipart_mixed, ALL_WEBSOCKET_PROTOCOLS},
+	Data, Executor, ParseRequestError,
 };
 use async_graphql_axum::{
-	rejection::GraphQLRejection, GraphQLBatchRequest, GraphQLRequest, GraphQLResponse,
+	rejection::GraphQLRejection, GraphQLBatchRequest, GraphQLProtocol, GraphQLRequest,
+	GraphQLResponse, GraphQLWebSocket,
 };
 use axum::{
 	body::{Body, HttpBody},
-	extract::FromRequest,
-	http::{Request as HttpRequest, Response as HttpResponse},
+	extract::{FromRequest, FromRequestParts, WebSocketUpgrade},
+	http::{header, Request as HttpRequest, Response as HttpResponse},
 	response::IntoResponse,
 	BoxError,
 };
@@ -25,6 +26,7 @@ use surrealdb::dbs::capabilities::RouteTarget;
 use surrealdb::dbs::Session;
 use surrealdb::gql::cache::{Invalidator, SchemaCache};
 use surrealdb::gql::error::resolver_error;
+use surrealdb::gql::Subscriptions;
 use surrealdb::kvs::Datastore;
 use tower_service::Service;
 
@@ -35,15 +37,21 @@ use crate::err::Error as SurrealError;
 pub struct GraphQL<I: Invalidator> {
 	cache: SchemaCache<I>,
 	// datastore: Arc<Datastore>,
+	subscriptions: Arc<Subscriptions>,
 }
 
 impl<I: Invalidator> GraphQL<I> {
 	/// Create a GraphQL handler.
-	pub fn new(invalidator: I, datastore: Arc<Datastore>) -> Self {
+	pub fn new(
+		invalidator: I,
+		datastore: Arc<Datastore>,
+		subscriptions: Arc<Subscriptions>,
+	) -> Self {
 		let _ = invalidator;
 		GraphQL {
 			cache: SchemaCache::new(datastore),
 			// datastore,
+			subscriptions,
 		}
 	}
 }
@@ -65,6 +73,7 @@ where
 
 	fn call(&mut self, req: HttpRequest<B>) -> Self::Future {
 		let cache = self.cache.clone();
+		let subscriptions = self.subscriptions.clone();
 		let req = req.map(Body::new);
 
 		Box::pin(async move {
@@ -105,6 +114,33 @@ where
 					return Ok(to_rejection(e).into_response());
 				}
 			};
+			// Subscriptions are served over a WebSocket connection
+			let is_websocket_upgrade = req
+				.headers()
+				.get(header::UPGRADE)
+				.and_then(|value| value.to_str().ok())
+				.is_some_and(|value| value.eq_ignore_ascii_case("websocket"));
+
+			if is_websocket_upgrade {
+				let (mut parts, _) = req.into_parts();
+				let protocol = match GraphQLProtocol::from_request_parts(&mut parts, &()).await {
+					Ok(protocol) => protocol,
+					Err(err) => return Ok(err.into_response()),
+				};
+				let upgrade = match WebSocketUpgrade::from_request_parts(&mut parts, &()).await {
+					Ok(upgrade) => upgrade,
+					Err(err) => return Ok(err.into_response()),
+				};
+				let mut data = Data::default();
+				data.insert(subscriptions);
+				return Ok(upgrade
+					.protocols(ALL_WEBSOCKET_PROTOCOLS)
+					.on_upgrade(move |stream| {
+						GraphQLWebSocket::new(stream, executor, protocol).with_data(data).serve()
+					})
+					.into_response());
+			}
+
 			let is_accept_multipart_mixed = req
 				.headers()
 				.get("accept")
@@ -117,7 +153,7 @@ where
 					Ok(req) => req,
 					Err(err) => return Ok(err.into_response()),
 				};
-				let stream = Executor::execute_stream(&executor, req.0, None);
+				let stream = Executor::execute_stream(&executor, req.0.data(subscriptions), None);
 				let body = Body::from_stream(
 					create_multipart_mixed_stream(stream, Duration::from_secs(30))
 						.map(Ok::<_, std::io::Error>),
@@ -14,7 +14,7 @@ use axum::response::IntoResponse;
 use axum::routing::any;
 use axum::Extension;
 use axum::Router;
-use http::header::CONTENT_TYPE;
+use http::header::{ACCESS_CONTROL_REQUEST_METHOD, CONTENT_TYPE};
 use surrealdb::dbs::capabilities::ExperimentalTarget;
 use surrealdb::dbs::capabilities::RouteTarget;
 use surrealdb::dbs::Session;
@@ -67,6 +67,17 @@ async fn handler(
 		return Err(Error::ForbiddenRoute(RouteTarget::Api.to_string()));
 	}
 
+	// A preflight request is handled for the method which the client intends to use
+	let preflight = method == Method::OPTIONS;
+	let method = if preflight {
+		match headers.get(ACCESS_CONTROL_REQUEST_METHOD).and_then(|v| v.to_str().ok()) {
+			Some(v) => v.parse::<Method>().map_err(|_| Error::NotFound(url.clone()))?,
+			None => return Err(Error::NotFound(url)),
+		}
+	} else {
+		method
+	};
+
 	let method = match method {
 		Method::DELETE => ApiMethod::Delete,
 		Method::GET => ApiMethod::Get,
@@ -80,35 +91,59 @@ async fn handler(
 	let tx = Arc::new(
 		ds.transaction(TransactionType::Write, LockType::Optimistic).await.map_err(Error::from)?,
 	);
-	let apis = tx.all_db_apis(&ns, &db).await.map_err(Error::from)?;
+	let apis = match tx.all_db_apis(&ns, &db).await {
+		Ok(apis) => apis,
+		Err(e) => {
+			tx.cancel().await.map_err(Error::from)?;
+			return Err(Error::from(e));
+		}
+	};
 	let segments: Vec<&str> = path.split('/').filter(|x| !x.is_empty()).collect();
 
-	let (mut res, res_instruction) =
-		if let Some((api, params)) = apis.as_ref().find_api(segments, method) {
-			let invocation = ApiInvocation {
-				params,
-				method,
-				headers,
-				query: query.inner,
-			};
+	let apis = apis.as_ref();
+	let Some((api, params)) = apis.find_api(segments, method) else {
+		tx.cancel().await.map_err(Error::from)?;
+		return Err(Error::NotFound(url));
+	};
+	let invocation = ApiInvocation {
+		params,
+		method,
+		headers,
+		query: query.inner,
+	};
 
-			match invocation
-				.invoke_with_transaction(
-					tx.clone(),
-					ds.clone(),
-					&session,
-					api,
-					ApiBody::from_stream(body.into_data_stream()),
-				)
-				.await
-			{
-				Ok(Some(v)) => v,
-				Err(e) => return Err(Error::from(e)),
-				_ => return Err(Error::NotFound(url)),
-			}
-		} else {
-			return Err(Error::NotFound(url));
+	// Respond to the preflight request without invoking the endpoint
+	if preflight {
+		let res =
+			invocation.preflight_with_transaction(tx.clone(), ds.clone(), &session, api).await;
+		// A preflight request never changes any data
+		tx.cancel().await.map_err(Error::from)?;
+		return match res {
+			Ok(Some(res)) => Ok((res.status, res.headers, Vec::new())),
+			Ok(None) => Err(Error::NotFound(url)),
+			Err(e) => Err(Error::from(e)),
 		};
+	}
+
+	let (mut res, res_instruction) = match invocation
+		.invoke_with_transaction(
+			tx.clone(),
+			ds.clone(),
+			&session,
+			api,
+			ApiBody::from_stream(body.into_data_stream()),
+		)
+		.await
+	{
+		Ok(Some(v)) => v,
+		res => {
+			tx.cancel().await.map_err(Error::from)?;
+			return match res {
+				Err(e) => Err(Error::from(e)),
+				_ => Err(Error::NotFound(url)),
+			};
+		}
+	};
 
 	// Commit the transaction
 	tx.commit().await.map_err(Error::from)?;
@@ -0,0 +1,126 @@
+use super::headers::Accept;
+use super::AppState;
+use crate::err::Error;
+use crate::net::output;
+use axum::body::Body;
+use axum::extract::{DefaultBodyLimit, Query, Request};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Extension, Router};
+use axum_extra::TypedHeader;
+use bytes::Bytes;
+use futures::TryStreamExt;
+use http::StatusCode;
+use serde::Deserialize;
+use surrealdb::dbs::capabilities::RouteTarget;
+use surrealdb::dbs::Session;
+use surrealdb::iam::Action::{Edit, View};
+use surrealdb::iam::ResourceKind::Any;
+
+#[derive(Default, Deserialize, Debug, Clone)]
+struct BackupOptions {
+	/// Whether to only back up the change feeds of every database
+	#[serde(default)]
+	pub changes: bool,
+}
+
+pub(super) fn router<S>() -> Router<S>
+where
+	S: Clone + Send + Sync + 'static,
+{
+	Router::new()
+		.route("/backup", get(backup))
+		// A backup contains the entire datastore, so its size is not limited
+		.route("/restore", post(restore).route_layer(DefaultBodyLimit::disable()))
+}
+
+async fn backup(
+	Extension(state): Extension<AppState>,
+	Extension(session): Extension<Session>,
+	Query(options): Query<BackupOptions>,
+) -> Result<impl IntoResponse, Error> {
+	// Get the datastore reference
+	let db = &state.datastore;
+	// Check if capabilities allow querying the requested HTTP route
+	if !db.allows_http_route(&RouteTarget::Backup) {
+		warn!("Capabilities denied HTTP route request attempt, target: '{}'", &RouteTarget::Backup);
+		return Err(Error::ForbiddenRoute(RouteTarget::Backup.to_string()));
+	}
+	// Check the permissions level
+	db.check(&session, View, Any.on_root())?;
+	// Create a chunked response
+	let (chn, body_stream) = surrealdb::channel::bounded::<Result<Bytes, Error>>(1);
+	let body = Body::from_stream(body_stream);
+	// Create a new bounded channel
+	let (snd, rcv) = surrealdb::channel::bounded(1);
+	// Spawn a new database backup job
+	let task = match options.changes {
+		true => tokio::spawn(db.backup_changes(snd).await?),
+		false => tokio::spawn(db.backup(snd).await?),
+	};
+	// Process all chunk values
+	tokio::spawn(async move {
+		while let Ok(v) = rcv.recv().await {
+			// Stop the backup if the client has gone away
+			if chn.send(Ok(Bytes::from(v))).await.is_err() {
+				break;
+			}
+		}
+		drop(rcv);
+		// Abort the response body if the backup failed, so
+		// that the client does not receive an incomplete backup
+		let err = match task.await {
+			Ok(Ok(())) => return,
+			Ok(Err(e)) => Error::from(e),
+			Err(e) => Error::Other(e.to_string()),
+		};
+		error!("The database backup failed: {err}");
+		let _ = chn.send(Err(err)).await;
+	});
+	// Return the chunked body
+	Ok(Response::builder().status(StatusCode::OK).body(body).unwrap())
+}
+
+async fn restore(
+	Extension(state): Extension<AppState>,
+	Extension(session): Extension<Session>,
+	accept: Option<TypedHeader<Accept>>,
+	request: Request,
+) -> Result<impl IntoResponse, impl IntoResponse> {
+	// Get the datastore reference
+	let db = &state.datastore;
+	// Check if capabilities allow querying the requested HTTP route
+	if !db.allows_http_route(&RouteTarget::Restore) {
+		warn!(
+			"Capabilities denied HTTP route request attempt, target: '{}'",
+			&RouteTarget::Restore
+		);
+		return Err(Error::ForbiddenRoute(RouteTarget::Restore.to_string()));
+	}
+	// Check the permissions level
+	db.check(&session, Edit, Any.on_root())?;
+
+	let body_stream = request
+		.into_body()
+		.into_data_stream()
+		.map_err(|e| surrealdb_core::err::Error::QueryStream(e.to_string()));
+
+	// Restore the backup into the datastore
+	match db.restore(body_stream).await {
+		Ok(res) => {
+			match accept.as_deref() {
+				// Simple serialization
+				Some(Accept::ApplicationJson) => Ok(output::json(&output::simplify(res)?)),
+				Some(Accept::ApplicationCbor) => Ok(output::cbor(&output::simplify(res)?)),
+				// Return nothing
+				Some(Accept::ApplicationOctetStream) => Ok(output::none()),
+				// Internal serialization
+				Some(Accept::Surrealdb) => Ok(output::full(&res)),
+				// An incorrect content-type was requested
+				_ => Err(Error::InvalidType),
+			}
+		}
+		// There was an error when restoring the backup
+		Err(err) => Err(Error::from(err)),
+	}
+}
@@ -0,0 +1,83 @@
+use super::sync::since;
+use super::AppState;
+use crate::err::Error;
+use axum::body::Body;
+use axum::extract::Query;
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::{Extension, Router};
+use bytes::Bytes;
+use futures::StreamExt;
+use http::header::CONTENT_TYPE;
+use http::StatusCode;
+use serde::Deserialize;
+use surrealdb::dbs::capabilities::RouteTarget;
+use surrealdb::dbs::Session;
+use surrealdb::iam::check::check_ns_db;
+use surrealdb::iam::Action::View;
+use surrealdb::iam::ResourceKind::Table;
+use surrealdb::sql::Value;
+
+#[derive(Default, Deserialize, Debug, Clone)]
+struct ChangesOptions {
+	/// The versionstamp, or datetime, from which to stream changes
+	pub since: Option<String>,
+	/// The table to stream changes for, or the whole database if not set
+	pub table: Option<String>,
+}
+
+pub(super) fn router<S>() -> Router<S>
+where
+	S: Clone + Send + Sync + 'static,
+{
+	Router::new().route("/changes", get(handler))
+}
+
+async fn handler(
+	Extension(state): Extension<AppState>,
+	Extension(session): Extension<Session>,
+	Query(query): Query<ChangesOptions>,
+) -> Result<impl IntoResponse, impl IntoResponse> {
+	// Get the datastore reference
+	let db = &state.datastore;
+	// Check if capabilities allow querying the requested HTTP route
+	if !db.allows_http_route(&RouteTarget::Changes) {
+		warn!(
+			"Capabilities denied HTTP route request attempt, target: '{}'",
+			&RouteTarget::Changes
+		);
+		return Err(Error::ForbiddenRoute(RouteTarget::Changes.to_string()));
+	}
+	// Check where the changes should be streamed from
+	let since = since(query.since.as_deref())?;
+	// Ensure a NS and DB are set
+	let (nsv, dbv) = check_ns_db(&session)?;
+	// Check the permissions level
+	db.check(&session, View, Table.on_db(&nsv, &dbv))?;
+	// Create a new bounded channel, so that changes
+	// are only read as quickly as the client receives them
+	let (snd, rcv) = surrealdb::channel::bounded(1);
+	// Spawn a new change feed stream, which stops once the response is dropped
+	let ds = db.clone();
+	tokio::spawn(async move {
+		if let Err(err) = ds.changes(&session, query.table, since, snd.clone()).await {
+			warn!("Error streaming change feed: {err}");
+			// Send the error as the last line of the response, so
+			// that the client can tell that the stream has failed
+			let err = map! { String::from("error") => Value::from(err.to_string()) };
+			let _ = snd.send(Value::from(err)).await;
+		}
+	});
+	// Output each change set, or the error, as a line of JSON
+	let body = Body::from_stream(rcv.map(|v| {
+		let mut line = serde_json::to_vec(&v.into_json()).map_err(|_| Error::Request)?;
+		line.push(b'\n');
+		Ok::<_, Error>(Bytes::from(line))
+	}));
+	// Return the streamed body
+	Ok(Response::builder()
+		.status(StatusCode::OK)
+		.header(CONTENT_TYPE, "application/x-ndjson")
+		.body(body)
+		.unwrap())
+}
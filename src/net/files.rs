@@ -0,0 +1,174 @@
+//! This file defines the endpoints for streaming files to and from buckets.
+use super::AppState;
+use crate::cnf::HTTP_MAX_FILES_BODY_SIZE;
+use crate::err::Error;
+use crate::net::output;
+use axum::body::Body;
+use axum::extract::{DefaultBodyLimit, Path};
+use axum::http::HeaderMap;
+use axum::response::{IntoResponse, Response};
+use axum::routing::options;
+use axum::Extension;
+use axum::Router;
+use futures::{StreamExt, TryStreamExt};
+use http::header::{ACCEPT_RANGES, CONTENT_LENGTH, CONTENT_RANGE, CONTENT_TYPE, ETAG, RANGE};
+use http::StatusCode;
+use surrealdb::buc::FileRange;
+use surrealdb::dbs::capabilities::{ExperimentalTarget, RouteTarget};
+use surrealdb::dbs::Session;
+use surrealdb::iam::check::check_ns_db;
+use tower_http::limit::RequestBodyLimitLayer;
+
+pub(super) fn router<S>() -> Router<S>
+where
+	S: Clone + Send + Sync + 'static,
+{
+	Router::new()
+		.route("/files/:bucket/*key", options(|| async {}).get(get_file).put(put_file))
+		.route_layer(DefaultBodyLimit::disable())
+		.layer(RequestBodyLimitLayer::new(*HTTP_MAX_FILES_BODY_SIZE))
+}
+
+/// This endpoint streams a file from a bucket, with support for `Range` requests.
+/// HEAD requests are handled by this endpoint too, without sending the body.
+async fn get_file(
+	Extension(state): Extension<AppState>,
+	Extension(session): Extension<Session>,
+	Path((bucket, key)): Path<(String, String)>,
+	headers: HeaderMap,
+) -> Result<Response, Error> {
+	// Get the datastore reference
+	let db = &state.datastore;
+	// Check if the route is enabled
+	check_route(db, &bucket, &key)?;
+	// Ensure a NS and DB are set
+	let _ = check_ns_db(&session)?;
+	// Parse the requested range, ignoring unsupported range requests
+	let range = headers.get(RANGE).and_then(|v| v.to_str().ok()).and_then(parse_range);
+	// Stream the file from the bucket
+	let file = match db.get_file_stream(&session, &bucket, &key, range).await {
+		Ok(Some(file)) => file,
+		Ok(None) => return Err(Error::NotFound(format!("/files/{bucket}/{key}"))),
+		Err(surrealdb::err::Error::FileRangeNotSatisfiable(size)) => {
+			return Ok((
+				StatusCode::RANGE_NOT_SATISFIABLE,
+				[(CONTENT_RANGE, format!("bytes */{size}"))],
+			)
+				.into_response());
+		}
+		Err(err) => return Err(Error::from(err)),
+	};
+	// Output the file contents
+	let len = file.range.end - file.range.start;
+	let body = Body::from_stream(file.stream);
+	let content_type = file.content_type.unwrap_or_else(|| "application/octet-stream".to_string());
+	let mut res = (
+		[
+			(CONTENT_TYPE, content_type),
+			(CONTENT_LENGTH, len.to_string()),
+			(ACCEPT_RANGES, "bytes".to_string()),
+		],
+		body,
+	)
+		.into_response();
+	// Output the entity tag, if the file has one
+	if let Some(etag) = file.etag.and_then(|v| format!("\"{v}\"").parse().ok()) {
+		res.headers_mut().insert(ETAG, etag);
+	}
+	// Mark the response as partial content for ranged requests
+	if range.is_some() {
+		*res.status_mut() = StatusCode::PARTIAL_CONTENT;
+		let content_range =
+			format!("bytes {}-{}/{}", file.range.start, file.range.end - 1, file.size);
+		res.headers_mut().insert(
+			CONTENT_RANGE,
+			content_range.parse().map_err(|_| Error::Other("Invalid content range".into()))?,
+		);
+	}
+	Ok(res)
+}
+
+/// This endpoint streams the request body into a file in a bucket.
+async fn put_file(
+	Extension(state): Extension<AppState>,
+	Extension(session): Extension<Session>,
+	Path((bucket, key)): Path<(String, String)>,
+	headers: HeaderMap,
+	body: Body,
+) -> Result<impl IntoResponse, Error> {
+	// Get the datastore reference
+	let db = &state.datastore;
+	// Check if the route is enabled
+	check_route(db, &bucket, &key)?;
+	// Ensure a NS and DB are set
+	let _ = check_ns_db(&session)?;
+	// Store the content type of the request body with the file
+	let content_type = headers.get(CONTENT_TYPE).and_then(|v| v.to_str().ok()).map(String::from);
+	// Stream the request body into the bucket
+	let stream = body.into_data_stream().map_err(|e| e.to_string()).boxed();
+	db.put_file_stream(&session, &bucket, &key, content_type, stream).await?;
+	Ok(output::none())
+}
+
+/// Checks that the experimental capability and the HTTP route are enabled
+fn check_route(db: &surrealdb::kvs::Datastore, bucket: &str, key: &str) -> Result<(), Error> {
+	// Check if the experimental capability is enabled
+	if !db.get_capabilities().allows_experimental(&ExperimentalTarget::Files) {
+		warn!("Experimental capability for files is not enabled");
+		return Err(Error::NotFound(format!("/files/{bucket}/{key}")));
+	}
+	// Check if capabilities allow querying the requested HTTP route
+	if !db.allows_http_route(&RouteTarget::Files) {
+		warn!("Capabilities denied HTTP route request attempt, target: '{}'", &RouteTarget::Files);
+		return Err(Error::ForbiddenRoute(RouteTarget::Files.to_string()));
+	}
+	Ok(())
+}
+
+/// Parses a single `bytes` range from a `Range` header
+fn parse_range(header: &str) -> Option<FileRange> {
+	let spec = header.trim().strip_prefix("bytes=")?;
+	// Multiple ranges are not supported, so the full file is returned instead
+	if spec.contains(',') {
+		return None;
+	}
+	let (start, end) = spec.split_once('-')?;
+	let (start, end) = (start.trim(), end.trim());
+	match (start.is_empty(), end.is_empty()) {
+		// bytes=-500
+		(true, false) => Some(FileRange::Suffix(end.parse().ok()?)),
+		// bytes=500-
+		(false, true) => Some(FileRange::From(start.parse().ok()?)),
+		// bytes=500-999, where the end offset is inclusive
+		(false, false) => {
+			let start = start.parse().ok()?;
+			let end: u64 = end.parse().ok()?;
+			Some(FileRange::Bounded(start, end.checked_add(1)?))
+		}
+		(true, true) => None,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_parse_range() {
+		// A single range, where the end offset is inclusive
+		assert_eq!(parse_range("bytes=0-499"), Some(FileRange::Bounded(0, 500)));
+		assert_eq!(parse_range(" bytes= 500 - 999 "), Some(FileRange::Bounded(500, 1000)));
+		// A suffix range
+		assert_eq!(parse_range("bytes=-500"), Some(FileRange::Suffix(500)));
+		// An open-ended range
+		assert_eq!(parse_range("bytes=9500-"), Some(FileRange::From(9500)));
+		// Multiple ranges are not supported
+		assert_eq!(parse_range("bytes=0-1,4-5"), None);
+		// Invalid ranges are ignored
+		assert_eq!(parse_range("bytes=-"), None);
+		assert_eq!(parse_range("bytes=a-b"), None);
+		assert_eq!(parse_range("bytes=10"), None);
+		assert_eq!(parse_range("items=0-10"), None);
+		assert_eq!(parse_range("bytes=0-18446744073709551615"), None);
+	}
+}
@@ -1,7 +1,10 @@
 mod api;
 mod auth;
+mod backup;
+mod changes;
 pub mod client_ip;
 mod export;
+mod files;
 mod gql;
 pub(crate) mod headers;
 mod health;
@@ -166,6 +169,8 @@ pub async fn init(ds: Arc<Datastore>, ct: CancellationToken) -> Result<(), Error
 				.max_age(Duration::from_secs(86400)),
 		);
 
+	let rpc_state = Arc::new(RpcState::new());
+
 	let axum_app = Router::<Arc<RpcState>>::new()
 		// Redirect until we provide a UI
 		.route("/", get(|| async { Redirect::temporary(cnf::APP_ENDPOINT) }))
@@ -173,16 +178,19 @@ pub async fn init(ds: Arc<Datastore>, ct: CancellationToken) -> Result<(), Error
 		.merge(health::router())
 		.merge(export::router())
 		.merge(import::router())
+		.merge(backup::router())
 		.merge(rpc::router())
 		.merge(version::router())
 		.merge(sync::router())
+		.merge(changes::router())
 		.merge(sql::router())
 		.merge(signin::router())
 		.merge(signup::router())
 		.merge(key::router())
 		.merge(ml::router())
 		.merge(api::router())
-		.merge(gql::router(ds.clone()));
+		.merge(files::router())
+		.merge(gql::router(ds.clone(), rpc_state.gql_subscriptions.clone()));
 
 	if ds.get_capabilities().allows_experimental(&ExperimentalTarget::GraphQL) {
 		warn!("❌🔒IMPORTANT: GraphQL is a pre-release feature with known security flaws. This is not recommended for production use.🔒❌");
@@ -193,8 +201,6 @@ pub async fn init(ds: Arc<Datastore>, ct: CancellationToken) -> Result<(), Error
 	// Get a new server handler
 	let handle = Handle::new();
 
-	let rpc_state = Arc::new(RpcState::new());
-
 	// Setup the graceful shutdown handler
 	let shutdown_handler = graceful_shutdown(rpc_state.clone(), ct.clone(), handle.clone());
 
@@ -1,20 +1,68 @@
+use super::headers::Accept;
+use super::AppState;
+use crate::cnf::HTTP_MAX_IMPORT_BODY_SIZE;
 use crate::err::Error;
-use axum::response::IntoResponse;
+use crate::net::output;
+use axum::body::Body;
+use axum::extract::{DefaultBodyLimit, Query, Request};
+use axum::response::{IntoResponse, Response};
 use axum::routing::get;
 use axum::{Extension, Router};
+use axum_extra::TypedHeader;
+use bytes::Bytes;
+use futures::TryStreamExt;
+use http::StatusCode;
+use serde::Deserialize;
 use surrealdb::dbs::capabilities::RouteTarget;
+use surrealdb::dbs::Session;
+use surrealdb::iam::check::check_ns_db;
+use surrealdb::iam::Action::{Edit, View};
+use surrealdb::iam::ResourceKind::Any;
+use surrealdb::sql::statements::ShowSince;
+use surrealdb::sql::Datetime;
+use tower_http::limit::RequestBodyLimitLayer;
 
-use super::AppState;
+#[derive(Default, Deserialize, Debug, Clone)]
+struct SyncOptions {
+	/// The versionstamp, or datetime, from which to read changes
+	pub since: Option<String>,
+}
+
+impl SyncOptions {
+	fn since(&self) -> Result<ShowSince, Error> {
+		since(self.since.as_deref())
+	}
+}
+
+/// Parses the versionstamp, or datetime, from which to read changes
+pub(super) fn since(v: Option<&str>) -> Result<ShowSince, Error> {
+	match v {
+		None => Ok(ShowSince::Versionstamp(0)),
+		Some(v) => match v.parse::<u64>() {
+			Ok(vs) => Ok(ShowSince::Versionstamp(vs)),
+			Err(_) => match Datetime::try_from(v) {
+				Ok(ts) => Ok(ShowSince::Timestamp(ts)),
+				Err(_) => Err(Error::Request),
+			},
+		},
+	}
+}
 
 pub(super) fn router<S>() -> Router<S>
 where
 	S: Clone + Send + Sync + 'static,
 {
-	Router::new().route("/sync", get(save).post(load))
+	Router::new()
+		.route("/sync", get(save).post(load))
+		.route_layer(DefaultBodyLimit::disable())
+		.layer(RequestBodyLimitLayer::new(*HTTP_MAX_IMPORT_BODY_SIZE))
 }
 
 async fn load(
 	Extension(state): Extension<AppState>,
+	Extension(session): Extension<Session>,
+	accept: Option<TypedHeader<Accept>>,
+	request: Request,
 ) -> Result<impl IntoResponse, impl IntoResponse> {
 	// Get the datastore reference
 	let db = &state.datastore;
@@ -23,12 +71,40 @@ async fn load(
 		warn!("Capabilities denied HTTP route request attempt, target: '{}'", &RouteTarget::Sync);
 		return Err(Error::ForbiddenRoute(RouteTarget::Sync.to_string()));
 	}
+	// Ensure a NS and DB are set
+	let (nsv, dbv) = check_ns_db(&session)?;
+	// Check the permissions level
+	db.check(&session, Edit, Any.on_db(&nsv, &dbv))?;
 
-	Ok("Load")
+	let body_stream = request
+		.into_body()
+		.into_data_stream()
+		.map_err(|e| surrealdb_core::err::Error::QueryStream(e.to_string()));
+
+	// Apply the changes to the database
+	match db.import_stream(&session, body_stream).await {
+		Ok(res) => {
+			match accept.as_deref() {
+				// Simple serialization
+				Some(Accept::ApplicationJson) => Ok(output::json(&output::simplify(res)?)),
+				Some(Accept::ApplicationCbor) => Ok(output::cbor(&output::simplify(res)?)),
+				// Return nothing
+				Some(Accept::ApplicationOctetStream) => Ok(output::none()),
+				// Internal serialization
+				Some(Accept::Surrealdb) => Ok(output::full(&res)),
+				// An incorrect content-type was requested
+				_ => Err(Error::InvalidType),
+			}
+		}
+		// There was an error when applying the changes
+		Err(err) => Err(Error::from(err)),
+	}
 }
 
 async fn save(
 	Extension(state): Extension<AppState>,
+	Extension(session): Extension<Session>,
+	Query(query): Query<SyncOptions>,
 ) -> Result<impl IntoResponse, impl IntoResponse> {
 	// Get the datastore reference
 	let db = &state.datastore;
@@ -37,6 +113,40 @@ async fn save(
 		warn!("Capabilities denied HTTP route request attempt, target: '{}'", &RouteTarget::Sync);
 		return Err(Error::ForbiddenRoute(RouteTarget::Sync.to_string()));
 	}
-
-	Ok("Save")
+	// Check where the changes should be read from
+	let since = query.since()?;
+	// Create a chunked response
+	let (chn, body_stream) = surrealdb::channel::bounded::<Result<Bytes, Error>>(1);
+	let body = Body::from_stream(body_stream);
+	// Ensure a NS and DB are set
+	let (nsv, dbv) = check_ns_db(&session)?;
+	// Check the permissions level
+	db.check(&session, View, Any.on_db(&nsv, &dbv))?;
+	// Create a new bounded channel
+	let (snd, rcv) = surrealdb::channel::bounded(1);
+	// Start the sync task
+	let task = db.sync(&session, since, snd).await?;
+	// Spawn a new change feed export job
+	let task = tokio::spawn(task);
+	// Process all chunk values
+	tokio::spawn(async move {
+		while let Ok(v) = rcv.recv().await {
+			// Stop the sync if the client has gone away
+			if chn.send(Ok(Bytes::from(v))).await.is_err() {
+				break;
+			}
+		}
+		drop(rcv);
+		// Abort the response body if the sync failed, so
+		// that the client does not receive incomplete changes
+		let err = match task.await {
+			Ok(Ok(())) => return,
+			Ok(Err(e)) => Error::from(e),
+			Err(e) => Error::Other(e.to_string()),
+		};
+		error!("The change feed sync failed: {err}");
+		let _ = chn.send(Err(err)).await;
+	});
+	// Return the chunked body
+	Ok(Response::builder().status(StatusCode::OK).body(body).unwrap())
 }
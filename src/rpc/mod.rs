@@ -12,6 +12,7 @@ use opentelemetry::Context as TelemetryContext;
 use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Duration;
+use surrealdb::gql::Subscriptions;
 use surrealdb::kvs::Datastore;
 use tokio::sync::RwLock;
 use tokio_stream::StreamExt;
@@ -31,6 +32,8 @@ pub struct RpcState {
 	pub web_sockets: WebSockets,
 	/// Stores the currently initiated LIVE queries
 	pub live_queries: LiveQueries,
+	/// Stores the currently active GraphQL subscriptions
+	pub gql_subscriptions: Arc<Subscriptions>,
 }
 
 impl RpcState {
@@ -38,6 +41,7 @@ impl RpcState {
 		RpcState {
 			web_sockets: WebSockets::default(),
 			live_queries: LiveQueries::default(),
+			gql_subscriptions: Arc::new(Subscriptions::new()),
 		}
 	}
 }
@@ -93,6 +97,8 @@ pub(crate) async fn notifications(
 							// Pus the future to the pipeline
 							futures.push(future);
 						}
+					} else if let Some(notification) = state.gql_subscriptions.deliver(notification) {
+						trace!("Dropping notification for unknown live query {}", notification.id);
 					}
 				},
 			}
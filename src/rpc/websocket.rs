@@ -6,7 +6,7 @@ use crate::cnf::WEBSOCKET_RESPONSE_FLUSH_PERIOD;
 use crate::cnf::{PKG_NAME, PKG_VERSION};
 use crate::rpc::failure::Failure;
 use crate::rpc::format::WsFormat;
-use crate::rpc::response::{failure, IntoRpcResponse};
+use crate::rpc::response::{failure, success, IntoRpcResponse};
 use crate::rpc::CONN_CLOSED_ERR;
 use crate::telemetry;
 use crate::telemetry::metrics::ws::RequestContext;
@@ -18,6 +18,7 @@ use futures::stream::FuturesUnordered;
 use futures::{Sink, SinkExt, StreamExt};
 use opentelemetry::trace::FutureExt;
 use opentelemetry::Context as TelemetryContext;
+use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Duration;
 use surrealdb::dbs::Session;
@@ -28,12 +29,14 @@ use surrealdb::rpc::format::Format;
 use surrealdb::rpc::Data;
 use surrealdb::rpc::Method;
 use surrealdb::rpc::RpcContext;
+use surrealdb::rpc::RpcError;
+use surrealdb::sql::statements::ShowSince;
 use surrealdb::sql::Array;
 use surrealdb::sql::Value;
 use surrealdb_core::rpc::RpcProtocolV1;
 use surrealdb_core::rpc::RpcProtocolV2;
 use tokio::sync::mpsc::{channel, Receiver, Sender};
-use tokio::sync::Semaphore;
+use tokio::sync::{RwLock, Semaphore};
 use tokio::task::JoinSet;
 use tokio_util::sync::CancellationToken;
 use tracing::Instrument;
@@ -67,6 +70,8 @@ pub struct Websocket {
 	pub(crate) channel: Sender<Message>,
 	/// The GraphQL schema cache stored in advance
 	pub(crate) gql_schema: SchemaCache<Pessimistic>,
+	/// The change feed streams started on this WebSocket connection
+	pub(crate) changefeeds: Arc<RwLock<HashMap<Uuid, CancellationToken>>>,
 }
 
 impl Websocket {
@@ -94,6 +99,7 @@ impl Websocket {
 			session: ArcSwap::from(Arc::new(session)),
 			channel: sender.clone(),
 			gql_schema: SchemaCache::new(datastore.clone()),
+			changefeeds: Arc::new(RwLock::new(HashMap::new())),
 			datastore,
 		});
 		// Add this WebSocket to the list
@@ -385,7 +391,7 @@ impl Websocket {
 							// Otherwise process the request message
 							else {
 								// Process the message
-								Self::process_message(rpc.clone(), req.version, req.method, req.params).await
+								Self::process_message(rpc.clone(), req.version, req.txn, req.method, req.params).await
 									.into_response(req.id)
 									.send(otel_cx.clone(), rpc.format, chn)
 									.with_context(otel_cx.as_ref().clone())
@@ -411,6 +417,7 @@ impl Websocket {
 	async fn process_message(
 		rpc: Arc<Websocket>,
 		version: Option<u8>,
+		txn: Option<Uuid>,
 		method: Method,
 		params: Array,
 	) -> Result<Data, Failure> {
@@ -420,7 +427,7 @@ impl Websocket {
 			return Err(Failure::METHOD_NOT_FOUND);
 		}
 		// Execute the specified method
-		RpcContext::execute(rpc.as_ref(), version, method, params).await.map_err(Into::into)
+		RpcContext::execute(rpc.as_ref(), version, txn, method, params).await.map_err(Into::into)
 	}
 
 	/// Reject a WebSocket message due to server overloading
@@ -504,6 +511,77 @@ impl RpcContext for Websocket {
 		}
 	}
 
+	// ------------------------------
+	// Change feeds
+	// ------------------------------
+
+	/// Change feed streams are enabled on WebSockets
+	const CF_SUPPORT: bool = true;
+
+	/// Handles the start of a change feed stream
+	async fn handle_changes(&self, id: Uuid, tb: Option<String>, since: ShowSince) {
+		// Stop the stream when this WebSocket is closed
+		let canceller = self.canceller.child_token();
+		self.changefeeds.write().await.insert(id, canceller.clone());
+		trace!("Registered change feed stream {id} on websocket {}", self.id);
+		// Clone the values needed by the stream
+		let ds = self.datastore.clone();
+		let session = self.session();
+		let format = self.format;
+		let sender = self.channel.clone();
+		let changefeeds = self.changefeeds.clone();
+		let ws = self.id;
+		// Spawn the change feed stream
+		tokio::spawn(async move {
+			// Only read one change set ahead of the client
+			let (snd, rcv) = surrealdb::channel::bounded(1);
+			// Forward each change set to the client
+			let forward = async {
+				while let Ok(mut value) = rcv.recv().await {
+					if let Value::Object(ref mut o) = value {
+						o.insert("id".to_string(), Value::Uuid(id.into()));
+					}
+					let cx = Arc::new(TelemetryContext::new());
+					// Sending waits for space in the WebSocket channel
+					success(None, value).send(cx, format, sender.clone()).await;
+				}
+			};
+			tokio::select! {
+				//
+				biased;
+				// Check if the stream has been stopped
+				_ = canceller.cancelled() => (),
+				// Read the changes from the datastore
+				res = ds.changes(&session, tb, since, snd) => {
+					if let Err(err) = res {
+						// Send the failure with the id of the stream which failed
+						let cx = Arc::new(TelemetryContext::new());
+						let id = Some(Value::Uuid(id.into()));
+						failure(id, RpcError::from(err).into()).send(cx, format, sender.clone()).await;
+					}
+				},
+				// Send the changes to the client
+				_ = forward => (),
+			}
+			// Unregister the stream once it has stopped
+			if changefeeds.write().await.remove(&id).is_some() {
+				trace!("Unregistered change feed stream {id} on websocket {ws}");
+			}
+		});
+	}
+
+	/// Handles the cancellation of a change feed stream
+	async fn kill_changes(&self, id: &Uuid) -> bool {
+		match self.changefeeds.write().await.remove(id) {
+			Some(canceller) => {
+				canceller.cancel();
+				trace!("Unregistered change feed stream {id} on websocket {}", self.id);
+				true
+			}
+			None => false,
+		}
+	}
+
 	// ------------------------------
 	// GraphQL
 	// ------------------------------
@@ -890,6 +890,114 @@ mod cli_integration {
 		server.finish().unwrap();
 	}
 
+	#[test(tokio::test)]
+	async fn with_backup_file() {
+		let (addr, mut server) = common::start_server_with_defaults().await.unwrap();
+		let creds = format!("--user {USER} --pass {PASS}");
+		let ns = Ulid::new();
+		let db = Ulid::new();
+
+		info!("* Create some data");
+		{
+			let args = format!("sql --conn http://{addr} {creds} --ns {ns} --db {db} --multi");
+			let output = common::run(&args)
+				.input(
+					"DEFINE INDEX name ON person FIELDS name; \
+					CREATE person:tobie SET name = 'Tobie'; \
+					CREATE person:jaime SET name = 'Jaime'; \
+					RELATE person:tobie->knows->person:jaime;\n",
+				)
+				.output()
+				.expect("success");
+			assert!(!output.contains("error"), "{output}");
+		}
+
+		info!("* Take a backup");
+		let backup = common::tmp_file("backup.bin");
+		{
+			let args = format!("backup --conn http://{addr} {creds} {backup}");
+			common::run(&args).output().expect("failed to run backup");
+			server.finish().unwrap();
+		}
+
+		info!("* Restore the backup into a new server");
+		{
+			let (addr, mut server) = common::start_server_with_defaults().await.unwrap();
+			// Anonymous users can't restore a backup
+			let args = format!("restore --conn http://{addr} {backup}");
+			common::run(&args).output().expect_err("anonymous users can't restore");
+			// Root users can restore a backup
+			let args = format!("restore --conn http://{addr} {creds} {backup}");
+			common::run(&args).output().expect("failed to run restore");
+			// The data has been restored
+			let args = format!("sql --conn http://{addr} {creds} --ns {ns} --db {db}");
+			let output = common::run(&args)
+				.input("SELECT id, ->knows->person AS people FROM person WHERE name = 'Tobie'\n")
+				.output()
+				.expect("success");
+			assert!(output.contains("people: [person:jaime]"), "{output}");
+			// A backup can't be restored over existing data
+			let args = format!("restore --conn http://{addr} {creds} {backup}");
+			common::run(&args).output().expect_err("restore into a non-empty datastore");
+			server.finish().unwrap();
+		}
+	}
+
+	#[test(tokio::test)]
+	async fn with_point_in_time_recovery() {
+		let (addr, mut server) = common::start_server_with_defaults().await.unwrap();
+		let creds = format!("--user {USER} --pass {PASS}");
+		let ns = Ulid::new();
+		let db = Ulid::new();
+
+		info!("* Create some data and take a backup");
+		let backup = common::tmp_file("backup.bin");
+		{
+			let args = format!("sql --conn http://{addr} {creds} --ns {ns} --db {db} --multi");
+			let output = common::run(&args)
+				.input("DEFINE TABLE person CHANGEFEED 1d; CREATE person:tobie;\n")
+				.output()
+				.expect("success");
+			assert!(!output.contains("error"), "{output}");
+			let args = format!("backup --conn http://{addr} {creds} {backup}");
+			common::run(&args).output().expect("failed to run backup");
+		}
+
+		info!("* Make further changes and archive the change feeds");
+		let changes = common::tmp_file("changes.bin");
+		{
+			let args = format!("sql --conn http://{addr} {creds} --ns {ns} --db {db} --multi");
+			let output = common::run(&args)
+				.input("CREATE person:jaime; DELETE person;\n")
+				.output()
+				.expect("success");
+			assert!(!output.contains("error"), "{output}");
+			let args = format!("backup --conn http://{addr} {creds} --changes {changes}");
+			common::run(&args).output().expect("failed to archive the change feeds");
+			server.finish().unwrap();
+		}
+
+		info!("* Recover the backup to a point in time");
+		{
+			// The change feeds can be replayed on top of the backup
+			let args = format!(
+				"restore --conn memory {backup} --changes {changes} --until 9999-01-01T00:00:00Z"
+			);
+			common::run(&args).output().expect("failed to run recovery");
+			// A point in time is required to replay the change feeds
+			let args = format!("restore --conn memory {backup} --changes {changes}");
+			common::run(&args).output().expect_err("recovery without a point in time");
+			// The datastore can't be recovered to before the backup was taken
+			let args = format!(
+				"restore --conn memory {backup} --changes {changes} --until 1970-01-01T00:00:00Z"
+			);
+			common::run(&args).output().expect_err("recovery before the backup");
+			// A full backup is not an archive of change feeds
+			let args = format!("restore --conn memory {backup} --changes {backup} --until 1");
+			common::run(&args).output().expect_err("recovery from a full backup");
+		}
+	}
+
 	#[test(tokio::test)]
 	async fn with_import_file() {
 		let ns = Ulid::new();
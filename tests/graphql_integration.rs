@@ -507,4 +507,496 @@ mod graphql_integration {
 
 		Ok(())
 	}
+
+	#[test(tokio::test)]
+	async fn mutations() -> Result<(), Box<dyn std::error::Error>> {
+		let (addr, _server) = common::start_server_gql_without_auth().await.unwrap();
+		let gql_url = &format!("http://{addr}/graphql");
+		let sql_url = &format!("http://{addr}/sql");
+
+		let mut headers = reqwest::header::HeaderMap::new();
+		let ns = Ulid::new().to_string();
+		let db = Ulid::new().to_string();
+		headers.insert("surreal-ns", ns.parse()?);
+		headers.insert("surreal-db", db.parse()?);
+		headers.insert(header::ACCEPT, "application/json".parse()?);
+		let client = reqwest::Client::builder()
+			.connect_timeout(Duration::from_millis(10))
+			.default_headers(headers)
+			.build()?;
+
+		// add schema
+		{
+			let res = client
+				.post(sql_url)
+				.body(
+					r#"
+					DEFINE CONFIG GRAPHQL AUTO;
+					DEFINE TABLE foo SCHEMAFUL;
+					DEFINE FIELD val ON foo TYPE int;
+					DEFINE FIELD name ON foo TYPE option<string>;
+				"#,
+				)
+				.send()
+				.await?;
+			assert_eq!(res.status(), 200);
+		}
+
+		// create, update and upsert records
+		{
+			let res = client
+				.post(gql_url)
+				.body(
+					json!({"query": r#"mutation{
+						createFoo(id: "1", data: {val: 42}){id, val, name},
+						updateFoo(id: "foo:1", data: {name: "bar"}){id, val, name},
+						upsertFoo(id: "2", data: {val: 43}){id, val}
+					}"#})
+					.to_string(),
+				)
+				.send()
+				.await?;
+			assert_eq!(res.status(), 200);
+			let body = res.text().await?;
+			let expected = json!({
+				"data": {
+					"createFoo": {
+						"id": "foo:1",
+						"val": 42,
+						"name": null
+					},
+					"updateFoo": {
+						"id": "foo:1",
+						"val": 42,
+						"name": "bar"
+					},
+					"upsertFoo": {
+						"id": "foo:2",
+						"val": 43
+					}
+				}
+			});
+			assert_eq!(expected.to_string(), body)
+		}
+
+		// records from other tables are rejected
+		{
+			let res = client
+				.post(gql_url)
+				.body(
+					json!({"query": r#"mutation{updateFoo(id: "bar:1", data: {val: 1}){id}}"#})
+						.to_string(),
+				)
+				.send()
+				.await?;
+			assert_eq!(res.status(), 200);
+			let body = res.text().await?;
+			assert!(body.contains("is not in table `foo`"), "body: {body}")
+		}
+
+		// record ranges are rejected
+		for id in ["foo:..", "foo:1..100", ".."] {
+			let res = client
+				.post(gql_url)
+				.body(json!({"query": format!(r#"mutation{{deleteFoo(id: "{id}")}}"#)}).to_string())
+				.send()
+				.await?;
+			assert_eq!(res.status(), 200);
+			let body = res.text().await?;
+			assert!(body.contains("Record ranges are not supported"), "body: {body}")
+		}
+
+		// delete records
+		{
+			let res = client
+				.post(gql_url)
+				.body(
+					json!({"query": r#"mutation{a: deleteFoo(id: "1"), b: deleteFoo(id: "1")}"#})
+						.to_string(),
+				)
+				.send()
+				.await?;
+			assert_eq!(res.status(), 200);
+			let body = res.text().await?;
+			let expected = json!({"data":{"a":true,"b":false}});
+			assert_eq!(expected.to_string(), body)
+		}
+
+		// check the changes were persisted
+		{
+			let res = client
+				.post(gql_url)
+				.body(json!({"query": r#"query{foo{id, val}}"#}).to_string())
+				.send()
+				.await?;
+			assert_eq!(res.status(), 200);
+			let body = res.text().await?;
+			let expected = json!({"data":{"foo":[{"id":"foo:2","val":43}]}});
+			assert_eq!(expected.to_string(), body)
+		}
+
+		Ok(())
+	}
+
+	#[test(tokio::test)]
+	async fn mutations_permissions() -> Result<(), Box<dyn std::error::Error>> {
+		let (addr, _server) = common::start_server_gql().await.unwrap();
+		let gql_url = &format!("http://{addr}/graphql");
+		let sql_url = &format!("http://{addr}/sql");
+		let signup_url = &format!("http://{addr}/signup");
+
+		let mut headers = reqwest::header::HeaderMap::new();
+		let ns = Ulid::new().to_string();
+		let db = Ulid::new().to_string();
+		headers.insert("surreal-ns", ns.parse()?);
+		headers.insert("surreal-db", db.parse()?);
+		headers.insert(header::ACCEPT, "application/json".parse()?);
+		let client = Client::builder()
+			.connect_timeout(Duration::from_millis(10))
+			.default_headers(headers)
+			.build()?;
+
+		// add schema
+		{
+			let res = client
+				.post(sql_url)
+				.basic_auth(USER, Some(PASS))
+				.body(
+					r#"
+					DEFINE CONFIG GRAPHQL AUTO;
+					DEFINE ACCESS user ON DATABASE TYPE RECORD
+					SIGNUP ( CREATE user:test SET email = $email, pass = crypto::argon2::generate($pass) )
+					SIGNIN ( SELECT * FROM user WHERE email = $email AND crypto::argon2::compare(pass, $pass) )
+					DURATION FOR SESSION 60s, FOR TOKEN 1d;
+
+					DEFINE TABLE foo SCHEMAFUL PERMISSIONS
+						FOR select FULL,
+						FOR create, update WHERE owner = $auth.id,
+						FOR delete NONE;
+					DEFINE FIELD owner ON foo TYPE record;
+					DEFINE FIELD val ON foo TYPE int;
+					DEFINE FIELD secret ON foo TYPE option<string> PERMISSIONS FOR select FULL, FOR create, update NONE;
+				"#,
+				)
+				.send()
+				.await?;
+			assert_eq!(res.status(), 200);
+		}
+
+		// sign up as a record user
+		let token = {
+			let req_body = serde_json::to_string(
+				json!({
+					"ns": ns,
+					"db": db,
+					"ac": "user",
+					"email": "user@email.com",
+					"pass": "pass",
+				})
+				.as_object()
+				.unwrap(),
+			)
+			.unwrap();
+
+			let res = client.post(signup_url).body(req_body).send().await?;
+			assert_eq!(res.status(), 200, "body: {}", res.text().await?);
+			let body: serde_json::Value = serde_json::from_str(&res.text().await?).unwrap();
+			body["token"].as_str().unwrap().to_owned()
+		};
+
+		// fields without create permissions are not set
+		{
+			let res = client
+				.post(gql_url)
+				.bearer_auth(&token)
+				.body(
+					json!({"query": r#"mutation{
+						createFoo(id: "1", data: {owner: "user:test", val: 1, secret: "a"}){id, val, secret}
+					}"#})
+					.to_string(),
+				)
+				.send()
+				.await?;
+			assert_eq!(res.status(), 200);
+			let body = res.text().await?;
+			let expected = json!({"data":{"createFoo":{"id":"foo:1","val":1,"secret":null}}});
+			assert_eq!(expected.to_string(), body)
+		}
+
+		// records can't be written without table permissions
+		{
+			let res = client
+				.post(gql_url)
+				.bearer_auth(&token)
+				.body(
+					json!({"query": r#"mutation{
+						createFoo(id: "2", data: {owner: "user:other", val: 2}){id},
+						updateFoo(id: "1", data: {owner: "user:other"}){id},
+						deleteFoo(id: "1")
+					}"#})
+					.to_string(),
+				)
+				.send()
+				.await?;
+			assert_eq!(res.status(), 200);
+			let body = res.text().await?;
+			assert!(!body.contains("foo:2"), "body: {body}");
+		}
+
+		// check only the permitted changes were persisted
+		{
+			let res = client
+				.post(gql_url)
+				.basic_auth(USER, Some(PASS))
+				.body(json!({"query": r#"query{foo{id, val, secret}}"#}).to_string())
+				.send()
+				.await?;
+			assert_eq!(res.status(), 200);
+			let body = res.text().await?;
+			let expected = json!({"data":{"foo":[{"id":"foo:1","val":1,"secret":null}]}});
+			assert_eq!(expected.to_string(), body)
+		}
+
+		Ok(())
+	}
+
+	#[test(tokio::test)]
+	async fn subscriptions() -> Result<(), Box<dyn std::error::Error>> {
+		use futures_util::{SinkExt, StreamExt};
+		use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+		use tokio_tungstenite::tungstenite::Message;
+
+		let (addr, _server) = common::start_server_gql_without_auth().await.unwrap();
+		let sql_url = &format!("http://{addr}/sql");
+
+		let mut headers = reqwest::header::HeaderMap::new();
+		let ns = Ulid::new().to_string();
+		let db = Ulid::new().to_string();
+		headers.insert("surreal-ns", ns.parse()?);
+		headers.insert("surreal-db", db.parse()?);
+		headers.insert(header::ACCEPT, "application/json".parse()?);
+		let client = reqwest::Client::builder()
+			.connect_timeout(Duration::from_millis(10))
+			.default_headers(headers)
+			.build()?;
+
+		// add schema
+		{
+			let res = client
+				.post(sql_url)
+				.body(
+					r#"
+					DEFINE CONFIG GRAPHQL AUTO;
+					DEFINE TABLE foo SCHEMAFUL;
+					DEFINE FIELD val ON foo TYPE int;
+				"#,
+				)
+				.send()
+				.await?;
+			assert_eq!(res.status(), 200);
+		}
+
+		// connect using the graphql-ws protocol
+		let mut req = format!("ws://{addr}/graphql").into_client_request()?;
+		req.headers_mut().insert("surreal-ns", ns.parse()?);
+		req.headers_mut().insert("surreal-db", db.parse()?);
+		req.headers_mut().insert("sec-websocket-protocol", "graphql-transport-ws".parse()?);
+		let (mut socket, _) = tokio_tungstenite::connect_async(req).await?;
+
+		async fn receive<S>(socket: &mut S) -> Result<serde_json::Value, Box<dyn std::error::Error>>
+		where
+			S: futures_util::Stream<Item = Result<Message, tokio_tungstenite::tungstenite::Error>>
+				+ Unpin,
+		{
+			loop {
+				let msg = tokio::time::timeout(Duration::from_secs(5), socket.next())
+					.await?
+					.ok_or("socket closed")??;
+				if let Message::Text(text) = msg {
+					return Ok(serde_json::from_str(&text)?);
+				}
+			}
+		}
+
+		socket.send(Message::Text(json!({"type": "connection_init"}).to_string())).await?;
+		let msg = receive(&mut socket).await?;
+		assert_eq!(msg["type"], "connection_ack", "msg: {msg}");
+
+		socket
+			.send(Message::Text(
+				json!({
+					"id": "1",
+					"type": "subscribe",
+					"payload": {"query": r#"subscription{foo(filter: {val: {ne: 2}}){action, id, record{val}}}"#}
+				})
+				.to_string(),
+			))
+			.await?;
+		// wait for the live query to be registered
+		tokio::time::sleep(Duration::from_millis(500)).await;
+
+		// make changes to the table
+		{
+			let res = client
+				.post(sql_url)
+				.body(
+					r#"
+					CREATE foo:1 SET val = 1;
+					CREATE foo:2 SET val = 2;
+					UPDATE foo:1 SET val = 3;
+					DELETE foo:1;
+				"#,
+				)
+				.send()
+				.await?;
+			assert_eq!(res.status(), 200);
+		}
+
+		let expected = [
+			json!({"action": "CREATE", "id": "foo:1", "record": {"val": 1}}),
+			json!({"action": "UPDATE", "id": "foo:1", "record": {"val": 3}}),
+			json!({"action": "DELETE", "id": "foo:1", "record": {"val": 3}}),
+		];
+		for expected in expected {
+			let msg = receive(&mut socket).await?;
+			assert_eq!(msg["type"], "next", "msg: {msg}");
+			assert_eq!(msg["id"], "1", "msg: {msg}");
+			assert_eq!(msg["payload"]["data"]["foo"], expected, "msg: {msg}");
+		}
+
+		Ok(())
+	}
+
+	#[test(tokio::test)]
+	async fn relations() -> Result<(), Box<dyn std::error::Error>> {
+		let (addr, _server) = common::start_server_gql_without_auth().await.unwrap();
+		let gql_url = &format!("http://{addr}/graphql");
+		let sql_url = &format!("http://{addr}/sql");
+
+		let mut headers = reqwest::header::HeaderMap::new();
+		let ns = Ulid::new().to_string();
+		let db = Ulid::new().to_string();
+		headers.insert("surreal-ns", ns.parse()?);
+		headers.insert("surreal-db", db.parse()?);
+		headers.insert(header::ACCEPT, "application/json".parse()?);
+		let client = reqwest::Client::builder()
+			.connect_timeout(Duration::from_millis(10))
+			.default_headers(headers)
+			.build()?;
+
+		// add schema and data
+		{
+			let res = client
+				.post(sql_url)
+				.body(
+					r#"
+					DEFINE CONFIG GRAPHQL AUTO;
+					DEFINE TABLE person SCHEMAFULL;
+					DEFINE FIELD name ON person TYPE string;
+					DEFINE FIELD friends ON person TYPE option<array<record<person>>>;
+					DEFINE TABLE post SCHEMAFULL;
+					DEFINE FIELD title ON post TYPE string;
+					DEFINE TABLE likes TYPE RELATION IN person OUT post SCHEMAFULL;
+					DEFINE FIELD weight ON likes TYPE int;
+					CREATE person:a SET name = "a", friends = [person:b, person:c];
+					CREATE person:b SET name = "b";
+					CREATE person:c SET name = "c";
+					CREATE post:1 SET title = "one";
+					CREATE post:2 SET title = "two";
+					RELATE person:a->likes->post:1 SET weight = 1;
+					RELATE person:a->likes->post:2 SET weight = 5;
+					RELATE person:b->likes->post:2 SET weight = 3;
+				"#,
+				)
+				.send()
+				.await?;
+			assert_eq!(res.status(), 200);
+		}
+
+		// traverse outgoing edges
+		{
+			let res = client
+				.post(gql_url)
+				.body(
+					json!({"query": r#"query{_get_person(id: "a"){
+						all: _out_likes(order: {desc: weight}){weight, out{title}},
+						filtered: _out_likes(filter: {weight: {eq: 1}}){out{title}}
+					}}"#})
+					.to_string(),
+				)
+				.send()
+				.await?;
+			assert_eq!(res.status(), 200);
+			let body = res.text().await?;
+			let expected = json!({
+				"data": {
+					"_get_person": {
+						"all": [
+							{"weight": 5, "out": {"title": "two"}},
+							{"weight": 1, "out": {"title": "one"}}
+						],
+						"filtered": [
+							{"out": {"title": "one"}}
+						]
+					}
+				}
+			});
+			assert_eq!(expected.to_string(), body)
+		}
+
+		// traverse incoming edges
+		{
+			let res = client
+				.post(gql_url)
+				.body(
+					json!({"query": r#"query{_get_post(id: "post:2"){_in_likes(order: {asc: weight}){in{name}}}}"#})
+						.to_string(),
+				)
+				.send()
+				.await?;
+			assert_eq!(res.status(), 200);
+			let body = res.text().await?;
+			let expected = json!({
+				"data": {
+					"_get_post": {
+						"_in_likes": [
+							{"in": {"name": "b"}},
+							{"in": {"name": "a"}}
+						]
+					}
+				}
+			});
+			assert_eq!(expected.to_string(), body)
+		}
+
+		// follow lists of record links
+		{
+			let res = client
+				.post(gql_url)
+				.body(
+					json!({"query": r#"query{_get_person(id: "a"){
+						friends{name},
+						filtered: friends(filter: {name: {eq: "c"}}){id},
+						limited: friends(limit: 1){id}
+					}}"#})
+					.to_string(),
+				)
+				.send()
+				.await?;
+			assert_eq!(res.status(), 200);
+			let body = res.text().await?;
+			let expected = json!({
+				"data": {
+					"_get_person": {
+						"friends": [{"name": "b"}, {"name": "c"}],
+						"filtered": [{"id": "person:c"}],
+						"limited": [{"id": "person:b"}]
+					}
+				}
+			});
+			assert_eq!(expected.to_string(), body)
+		}
+
+		Ok(())
+	}
 }
@@ -374,6 +374,78 @@ mod http_integration {
 		}
 	}
 
+	#[test(tokio::test)]
+	async fn api_endpoint_middleware() -> Result<(), Box<dyn std::error::Error>> {
+		let (addr, _server) = common::start_server(StartServerArguments {
+			args: "--allow-experimental define_api".to_string(),
+			..Default::default()
+		})
+		.await
+		.unwrap();
+		let ns = Ulid::new().to_string();
+		let db = Ulid::new().to_string();
+
+		// Prepare HTTP client
+		let mut headers = reqwest::header::HeaderMap::new();
+		headers.insert("surreal-ns", ns.parse()?);
+		headers.insert("surreal-db", db.parse()?);
+		headers.insert(header::ACCEPT, "application/json".parse()?);
+		let client = reqwest::Client::builder()
+			.connect_timeout(Duration::from_millis(10))
+			.default_headers(headers)
+			.build()?;
+
+		// Define the endpoints
+		{
+			let res = client
+				.post(format!("http://{addr}/sql"))
+				.basic_auth(USER, Some(PASS))
+				.body(
+					r#"
+					DEFINE API "/signed_in" FOR get MIDDLEWARE api::auth::require() THEN { RETURN { status: 200 } };
+					DEFINE API "/limited" FOR get MIDDLEWARE api::rate_limit(1, 1m) THEN { RETURN { status: 200 } };
+					"#,
+				)
+				.send()
+				.await?;
+			assert_eq!(res.status(), 200, "body: {}", res.text().await?);
+		}
+
+		// An anonymous request to an endpoint requiring authentication returns a 401
+		{
+			let res = client.get(format!("http://{addr}/api/{ns}/{db}/signed_in")).send().await?;
+			assert_eq!(res.status(), 401, "body: {}", res.text().await?);
+		}
+
+		// An authenticated request is invoked
+		{
+			let res = client
+				.get(format!("http://{addr}/api/{ns}/{db}/signed_in"))
+				.basic_auth(USER, Some(PASS))
+				.send()
+				.await?;
+			assert_eq!(res.status(), 200, "body: {}", res.text().await?);
+		}
+
+		// Requests are limited per client IP address
+		{
+			let url = format!("http://{addr}/api/{ns}/{db}/limited");
+			let res = client.get(&url).send().await?;
+			assert_eq!(res.status(), 200, "body: {}", res.text().await?);
+			let res = client.get(&url).send().await?;
+			assert!(res.headers().contains_key(header::RETRY_AFTER));
+			assert_eq!(res.status(), 429, "body: {}", res.text().await?);
+		}
+
+		// An unknown endpoint returns a 404
+		{
+			let res = client.get(format!("http://{addr}/api/{ns}/{db}/unknown")).send().await?;
+			assert_eq!(res.status(), 404, "body: {}", res.text().await?);
+		}
+
+		Ok(())
+	}
+
 	#[test(tokio::test)]
 	async fn export_endpoint() -> Result<(), Box<dyn std::error::Error>> {
 		let (addr, _server) = common::start_server_with_defaults().await.unwrap();
@@ -417,6 +489,92 @@ mod http_integration {
 		Ok(())
 	}
 
+	#[test(tokio::test)]
+	async fn files_endpoint_range() -> Result<(), Box<dyn std::error::Error>> {
+		let (addr, _server) = common::start_server(StartServerArguments {
+			args: "--allow-experimental files".to_string(),
+			..Default::default()
+		})
+		.await
+		.unwrap();
+		let url = &format!("http://{addr}/files/test/a.txt");
+
+		// Prepare HTTP client
+		let mut headers = reqwest::header::HeaderMap::new();
+		headers.insert("surreal-ns", Ulid::new().to_string().parse()?);
+		headers.insert("surreal-db", Ulid::new().to_string().parse()?);
+		headers.insert(header::ACCEPT, "application/json".parse()?);
+		let client = reqwest::Client::builder()
+			.connect_timeout(Duration::from_millis(10))
+			.default_headers(headers)
+			.build()?;
+
+		// Define the bucket and store a file
+		{
+			let res = client
+				.post(format!("http://{addr}/sql"))
+				.basic_auth(USER, Some(PASS))
+				.body(r#"DEFINE BUCKET test BACKEND "memory""#)
+				.send()
+				.await?;
+			assert_eq!(res.status(), 200, "body: {}", res.text().await?);
+			let res = client.put(url).basic_auth(USER, Some(PASS)).body("abcdefgh").send().await?;
+			assert_eq!(res.status(), 200, "body: {}", res.text().await?);
+		}
+
+		// Without a range, the full file is returned
+		{
+			let res = client.get(url).basic_auth(USER, Some(PASS)).send().await?;
+			assert_eq!(res.status(), 200);
+			assert_eq!(res.headers()[header::ACCEPT_RANGES], "bytes");
+			assert_eq!(res.text().await?, "abcdefgh");
+		}
+
+		// Satisfiable ranges return partial content
+		for (range, content_range, body) in [
+			("bytes=1-3", "bytes 1-3/8", "bcd"),
+			("bytes=-3", "bytes 5-7/8", "fgh"),
+			("bytes=6-", "bytes 6-7/8", "gh"),
+			("bytes=4-100", "bytes 4-7/8", "efgh"),
+		] {
+			let res = client
+				.get(url)
+				.basic_auth(USER, Some(PASS))
+				.header(header::RANGE, range)
+				.send()
+				.await?;
+			assert_eq!(res.status(), 206, "range: {range}");
+			assert_eq!(res.headers()[header::CONTENT_RANGE], content_range, "range: {range}");
+			assert_eq!(res.text().await?, body, "range: {range}");
+		}
+
+		// Unsatisfiable ranges return a 416
+		for range in ["bytes=8-", "bytes=20-30", "bytes=3-1", "bytes=-0"] {
+			let res = client
+				.get(url)
+				.basic_auth(USER, Some(PASS))
+				.header(header::RANGE, range)
+				.send()
+				.await?;
+			assert_eq!(res.status(), 416, "range: {range}");
+			assert_eq!(res.headers()[header::CONTENT_RANGE], "bytes */8", "range: {range}");
+		}
+
+		// Unsupported ranges return the full file
+		for range in ["bytes=0-1,4-5", "bytes=a-b", "items=0-1"] {
+			let res = client
+				.get(url)
+				.basic_auth(USER, Some(PASS))
+				.header(header::RANGE, range)
+				.send()
+				.await?;
+			assert_eq!(res.status(), 200, "range: {range}");
+			assert_eq!(res.text().await?, "abcdefgh", "range: {range}");
+		}
+
+		Ok(())
+	}
+
 	#[test(tokio::test)]
 	async fn health_endpoint() -> Result<(), Box<dyn std::error::Error>> {
 		let (addr, _server) = common::start_server_with_defaults().await.unwrap();
@@ -994,8 +1152,8 @@ mod http_integration {
 
 	#[test(tokio::test)]
 	async fn sync_endpoint() -> Result<(), Box<dyn std::error::Error>> {
-		let (addr, _server) = common::start_server_with_defaults().await.unwrap();
-		let url = &format!("http://{addr}/sync");
+		let (primary, _primary) = common::start_server_with_defaults().await.unwrap();
+		let (replica, _replica) = common::start_server_with_defaults().await.unwrap();
 
 		// Prepare HTTP client
 		let mut headers = reqwest::header::HeaderMap::new();
@@ -1007,19 +1165,214 @@ mod http_integration {
 			.default_headers(headers)
 			.build()?;
 
-		// GET
+		// Create some data on the primary
 		{
-			let res = client.get(url).send().await?;
+			let res = client
+				.post(format!("http://{primary}/sql"))
+				.basic_auth(USER, Some(PASS))
+				.body(
+					r#"
+					DEFINE TABLE person CHANGEFEED 1h;
+					DEFINE TABLE knows TYPE RELATION CHANGEFEED 1h;
+					CREATE person:tobie, person:jaime, person:other SET name = 'test';
+					RELATE person:tobie->knows:one->person:jaime SET since = 2016;
+					UPDATE person:tobie SET name = 'Tobie';
+					DELETE person:other;
+				"#,
+				)
+				.send()
+				.await?;
 			assert_eq!(res.status(), 200, "body: {}", res.text().await?);
-			let body = res.text().await?;
-			assert_eq!(body, r#"Save"#, "body: {body}");
 		}
-		// POST
+
+		// When no auth is provided, the endpoint returns a 403
 		{
-			let res = client.post(url).body("").send().await?;
+			let res = client.get(format!("http://{primary}/sync")).send().await?;
+			assert_eq!(res.status(), 403, "body: {}", res.text().await?);
+			let res = client.post(format!("http://{replica}/sync")).body("").send().await?;
+			assert_eq!(res.status(), 403, "body: {}", res.text().await?);
+		}
+
+		// An invalid starting point is rejected
+		{
+			let res = client
+				.get(format!("http://{primary}/sync?since=invalid"))
+				.basic_auth(USER, Some(PASS))
+				.send()
+				.await?;
+			assert_eq!(res.status(), 400, "body: {}", res.text().await?);
+		}
+
+		// Read the changes from the primary
+		let body = {
+			let res = client
+				.get(format!("http://{primary}/sync"))
+				.basic_auth(USER, Some(PASS))
+				.send()
+				.await?;
+			assert_eq!(res.status(), 200, "body: {}", res.text().await?);
+			res.text().await?
+		};
+		assert!(body.contains("-- CHANGE: "), "body: {body}");
+		assert!(body.contains("DELETE person:other;"), "body: {body}");
+
+		// Apply the changes to the replica
+		{
+			let res = client
+				.post(format!("http://{replica}/sync"))
+				.basic_auth(USER, Some(PASS))
+				.body(body)
+				.send()
+				.await?;
+			assert_eq!(res.status(), 200, "body: {}", res.text().await?);
+		}
+
+		// The replica contains the same data as the primary
+		for query in [
+			"SELECT * FROM person",
+			"SELECT * FROM knows",
+			"SELECT ->knows->person AS people FROM person:tobie",
+		] {
+			let mut results = Vec::new();
+			for addr in [&primary, &replica] {
+				let res = client
+					.post(format!("http://{addr}/sql"))
+					.basic_auth(USER, Some(PASS))
+					.body(query)
+					.send()
+					.await?;
+				assert_eq!(res.status(), 200, "body: {}", res.text().await?);
+				let body: serde_json::Value = serde_json::from_str(&res.text().await?)?;
+				results.push(body[0]["result"].clone());
+			}
+			assert_eq!(results[0], results[1], "query: {query}");
+			assert_ne!(results[1], serde_json::json!([]), "query: {query}");
+		}
+
+		// Changes can be read from a specific versionstamp
+		{
+			let res = client
+				.get(format!("http://{primary}/sync?since=18446744073709551615"))
+				.basic_auth(USER, Some(PASS))
+				.send()
+				.await?;
 			assert_eq!(res.status(), 200, "body: {}", res.text().await?);
 			let body = res.text().await?;
-			assert_eq!(body, r#"Load"#, "body: {body}");
+			assert!(!body.contains("-- CHANGE: "), "body: {body}");
+		}
+
+		Ok(())
+	}
+
+	#[test(tokio::test)]
+	async fn changes_endpoint() -> Result<(), Box<dyn std::error::Error>> {
+		let (addr, _server) = common::start_server_with_defaults().await.unwrap();
+
+		// Prepare HTTP client
+		let mut headers = reqwest::header::HeaderMap::new();
+		headers.insert("surreal-ns", Ulid::new().to_string().parse()?);
+		headers.insert("surreal-db", Ulid::new().to_string().parse()?);
+		headers.insert(header::ACCEPT, "application/json".parse()?);
+		let client = reqwest::Client::builder()
+			.connect_timeout(Duration::from_millis(10))
+			.default_headers(headers)
+			.build()?;
+
+		// Reads the next change set from a change feed stream
+		async fn next(
+			res: &mut reqwest::Response,
+			buf: &mut Vec<u8>,
+		) -> Result<serde_json::Value, Box<dyn std::error::Error>> {
+			loop {
+				if let Some(pos) = buf.iter().position(|b| *b == b'\n') {
+					let line: Vec<u8> = buf.drain(..=pos).collect();
+					return Ok(serde_json::from_slice(&line)?);
+				}
+				let chunk = tokio::time::timeout(Duration::from_secs(5), res.chunk()).await??;
+				buf.extend(chunk.ok_or("the change feed stream ended")?);
+			}
+		}
+
+		// Create some data
+		{
+			let res = client
+				.post(format!("http://{addr}/sql"))
+				.basic_auth(USER, Some(PASS))
+				.body("DEFINE TABLE person CHANGEFEED 1h; CREATE person:one; CREATE other:one;")
+				.send()
+				.await?;
+			assert_eq!(res.status(), 200, "body: {}", res.text().await?);
+		}
+
+		// When no auth is provided, the endpoint returns a 403
+		{
+			let res = client.get(format!("http://{addr}/changes")).send().await?;
+			assert_eq!(res.status(), 403, "body: {}", res.text().await?);
+		}
+
+		// An invalid starting point is rejected
+		{
+			let res = client
+				.get(format!("http://{addr}/changes?since=invalid"))
+				.basic_auth(USER, Some(PASS))
+				.send()
+				.await?;
+			assert_eq!(res.status(), 400, "body: {}", res.text().await?);
+		}
+
+		// Existing changes are streamed, followed by new changes once committed
+		let vs = {
+			let mut res = client
+				.get(format!("http://{addr}/changes?table=person"))
+				.basic_auth(USER, Some(PASS))
+				.send()
+				.await?;
+			assert_eq!(res.status(), 200);
+			let mut buf = Vec::new();
+			let change = next(&mut res, &mut buf).await?;
+			assert!(change["changes"][0]["define_table"].is_object(), "change: {change}");
+			let change = next(&mut res, &mut buf).await?;
+			assert_eq!(change["changes"][0]["update"]["id"], "person:one", "change: {change}");
+			// Create some more data while the stream is open
+			let res2 = client
+				.post(format!("http://{addr}/sql"))
+				.basic_auth(USER, Some(PASS))
+				.body("CREATE other:two; CREATE person:two;")
+				.send()
+				.await?;
+			assert_eq!(res2.status(), 200, "body: {}", res2.text().await?);
+			let change = next(&mut res, &mut buf).await?;
+			assert_eq!(change["changes"][0]["update"]["id"], "person:two", "change: {change}");
+			change["versionstamp"].as_u64().ok_or("missing versionstamp")?
+		};
+
+		// Changes can be resumed from a specific versionstamp
+		{
+			let mut res = client
+				.get(format!("http://{addr}/changes?table=person&since={vs}"))
+				.basic_auth(USER, Some(PASS))
+				.send()
+				.await?;
+			assert_eq!(res.status(), 200);
+			let mut buf = Vec::new();
+			let change = next(&mut res, &mut buf).await?;
+			assert_eq!(change["versionstamp"], vs, "change: {change}");
+			assert_eq!(change["changes"][0]["update"]["id"], "person:two", "change: {change}");
+		}
+
+		// A failure while streaming is sent as the last line of the response
+		{
+			let mut res = client
+				.get(format!("http://{addr}/changes?since=1970-01-01T00:00:00Z"))
+				.basic_auth(USER, Some(PASS))
+				.send()
+				.await?;
+			assert_eq!(res.status(), 200);
+			let mut buf = Vec::new();
+			let line = next(&mut res, &mut buf).await?;
+			assert!(line["error"].is_string(), "line: {line}");
+			let end = tokio::time::timeout(Duration::from_secs(5), res.chunk()).await??;
+			assert!(end.is_none(), "the change feed stream did not end");
 		}
 
 		Ok(())
@@ -951,6 +951,60 @@ pub async fn kill(cfg_server: Option<Format>, cfg_format: Format) {
 	server.finish().unwrap();
 }
 
+pub async fn changes_rpc(cfg_server: Option<Format>, cfg_format: Format) {
+	// Setup database server
+	let (addr, mut server) = common::start_server_with_defaults().await.unwrap();
+	// Connect to WebSocket
+	let mut socket = Socket::connect(&addr, cfg_server, cfg_format).await.unwrap();
+	// Authenticate the connection
+	socket.send_message_signin(USER, PASS, None, None, None).await.unwrap();
+	// Specify a namespace and database
+	socket.send_message_use(Some(NS), Some(DB)).await.unwrap();
+	// Create a table with a change feed
+	socket
+		.send_message_query(
+			"DEFINE TABLE tester CHANGEFEED 1h; CREATE tester:one; CREATE other:one;",
+		)
+		.await
+		.unwrap();
+	// Send CHANGES command
+	let res = socket.send_request("changes", json!(["tester"])).await.unwrap();
+	assert!(res.is_object(), "result: {res:?}");
+	assert!(res["result"].is_string(), "result: {res:?}");
+	let id = res["result"].as_str().unwrap();
+	// The existing changes are streamed first
+	let msgs = tokio::time::timeout(Duration::from_secs(1), async {
+		vec![
+			socket.receive_other_message().await.unwrap(),
+			socket.receive_other_message().await.unwrap(),
+		]
+	})
+	.await
+	.unwrap();
+	assert!(msgs.iter().all(|v| v["result"]["id"] == id), "result: {msgs:?}");
+	assert!(msgs[0]["result"]["changes"][0]["define_table"].is_object(), "result: {msgs:?}");
+	assert_eq!(msgs[1]["result"]["changes"][0]["update"]["id"], "tester:one", "result: {msgs:?}");
+	// New changes are streamed once they are committed
+	socket.send_message_query("CREATE other:two; CREATE tester:two;").await.unwrap();
+	let msg = tokio::time::timeout(Duration::from_secs(1), socket.receive_other_message())
+		.await
+		.unwrap()
+		.unwrap();
+	assert_eq!(msg["result"]["id"], id, "result: {msg:?}");
+	assert_eq!(msg["result"]["changes"][0]["update"]["id"], "tester:two", "result: {msg:?}");
+	// Send KILL command
+	let res = socket.send_request("kill", json!([id])).await.unwrap();
+	assert!(res.is_object(), "result: {res:?}");
+	assert!(res["result"].is_null(), "result: {res:?}");
+	// No further changes are streamed
+	socket.send_message_query("CREATE tester:three;").await.unwrap();
+	let res =
+		tokio::time::timeout(Duration::from_millis(500), socket.receive_other_message()).await;
+	assert!(res.is_err(), "result: {res:?}");
+	// Test passed
+	server.finish().unwrap();
+}
+
 pub async fn live_table_removal(cfg_server: Option<Format>, cfg_format: Format) {
 	// Setup database server
 	let (addr, mut server) = common::start_server_with_defaults().await.unwrap();
@@ -2353,6 +2407,8 @@ define_include_tests! {
 	#[test_log::test(tokio::test)]
 	kill,
 	#[test_log::test(tokio::test)]
+	changes_rpc,
+	#[test_log::test(tokio::test)]
 	live_table_removal,
 	#[test_log::test(tokio::test)]
 	live_second_connection,